@@ -1,7 +1,8 @@
+use crate::env_file::CliEnvFileArgs;
 use crate::execute::Execute;
 use anyhow::{anyhow, Result};
 use clap::Args;
-use rain_orderbook_app_settings::{config_source::ConfigSource, Config};
+use rain_orderbook_app_settings::{config_source::ConfigSource, imports::ImportLoaders, Config};
 use rain_orderbook_common::dotrain::RainDocument;
 use rain_orderbook_common::fuzz::FuzzRunner;
 use std::fs::read_to_string;
@@ -16,15 +17,23 @@ pub struct Chart {
         help = "Path to the .rain file specifying the order"
     )]
     dotrain_file: PathBuf,
+
+    #[clap(flatten)]
+    env_file_args: CliEnvFileArgs,
 }
 
 impl Execute for Chart {
     async fn execute(&self) -> Result<()> {
         let dotrain = read_to_string(self.dotrain_file.clone()).map_err(|e| anyhow!(e))?;
         let frontmatter = RainDocument::get_front_matter(&dotrain).unwrap();
-        let config_string = ConfigSource::try_from_string(frontmatter.to_string(), None)
-            .await?
-            .0;
+        let config_string = ConfigSource::try_from_string_with_loaders(
+            frontmatter.to_string(),
+            None,
+            &self.env_file_args.resolver()?,
+            &ImportLoaders::for_files(Some(self.dotrain_file.as_path()), None),
+        )
+        .await?
+        .0;
         let config: Config = config_string.try_into()?;
         let fuzzer = FuzzRunner::new(&dotrain, config, None).await;
         let chart_data = fuzzer.make_chart_data().await?;
@@ -0,0 +1,224 @@
+use crate::env_file::CliEnvFileArgs;
+use crate::execute::Execute;
+use anyhow::{anyhow, Result};
+use clap::{ArgGroup, Args};
+use rain_orderbook_app_settings::{
+    config_source::ConfigSource,
+    imports::ImportLoaders,
+    merge::{ConfigLayers, ConfigProvenance},
+};
+use rain_orderbook_common::dotrain::RainDocument;
+use serde_yaml::Value;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+
+#[derive(Debug, clap::ValueEnum, Clone, Copy, PartialEq)]
+pub enum ConfigPrecedence {
+    /// The frontmatter overrides the settings file
+    Frontmatter,
+    /// The settings file overrides the frontmatter
+    Settings,
+}
+
+#[derive(Args, Clone)]
+#[command(group(ArgGroup::new("input").required(true).multiple(true).args(["dotrain_file", "settings_file"])))]
+pub struct ConfigExplain {
+    #[arg(help = "Dotted path of the value to explain, ie networks.mainnet.rpc")]
+    path: String,
+
+    #[arg(
+        short = 'f',
+        long,
+        help = "Path to the .rain file whose frontmatter to merge"
+    )]
+    dotrain_file: Option<PathBuf>,
+
+    #[arg(short = 'c', long, help = "Path to the settings yaml file to merge")]
+    settings_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = ConfigPrecedence::Frontmatter,
+        help = "Which of the frontmatter and the settings file wins when both define a value"
+    )]
+    precedence: ConfigPrecedence,
+
+    #[clap(flatten)]
+    env_file_args: CliEnvFileArgs,
+}
+
+impl Execute for ConfigExplain {
+    async fn execute(&self) -> Result<()> {
+        let resolver = self.env_file_args.resolver()?;
+        let settings = match &self.settings_file {
+            Some(settings_file) => {
+                Some(read_to_string(settings_file.clone()).map_err(|e| anyhow!(e))?)
+            }
+            None => None,
+        };
+        let (frontmatter, settings) = match &self.dotrain_file {
+            Some(dotrain_file) => {
+                let dotrain = read_to_string(dotrain_file.clone()).map_err(|e| anyhow!(e))?;
+                let frontmatter = RainDocument::get_front_matter(&dotrain)
+                    .unwrap_or("")
+                    .to_string();
+                let loaders = ImportLoaders::for_files(
+                    Some(dotrain_file.as_path()),
+                    self.settings_file.as_deref(),
+                );
+                let (frontmatter, settings) = ConfigSource::try_from_string_with_loaders(
+                    frontmatter,
+                    settings,
+                    &resolver,
+                    &loaders,
+                )
+                .await?;
+                (
+                    Some(frontmatter),
+                    self.settings_file.as_ref().map(|_| settings),
+                )
+            }
+            None => (
+                None,
+                Some(
+                    ConfigSource::try_from_string_with_loaders(
+                        settings.unwrap_or_default(),
+                        None,
+                        &resolver,
+                        &ImportLoaders::for_files(self.settings_file.as_deref(), None),
+                    )
+                    .await?
+                    .0,
+                ),
+            ),
+        };
+
+        let mut layers = vec![];
+        if let Some(settings) = settings {
+            layers.push(("settings", settings));
+        }
+        if let Some(frontmatter) = frontmatter {
+            layers.push(("frontmatter", frontmatter));
+        }
+        if self.precedence == ConfigPrecedence::Settings {
+            layers.reverse();
+        }
+        let (config, provenance) = layers
+            .into_iter()
+            .fold(ConfigLayers::new(), |layers, (name, config)| {
+                layers.with_layer(name, config)
+            })
+            .merge()?;
+
+        let value = serde_yaml::to_value(config.redacted())?;
+        for line in explain(&self.path, &value, &provenance)? {
+            println!("{}", line);
+        }
+        Ok(())
+    }
+}
+
+/// Describes the final value of every leaf at or under the path along with
+/// the layer it came from and the layers it overrides
+fn explain(path: &str, value: &Value, provenance: &ConfigProvenance) -> Result<Vec<String>> {
+    let entries = provenance.explain(path);
+    if entries.is_empty() {
+        return Err(anyhow!("Nothing is defined at '{}'", path));
+    }
+    Ok(entries
+        .into_iter()
+        .map(|(leaf, origin)| {
+            let leaf_value = lookup(value, leaf)
+                .map(|v| match v {
+                    Value::String(s) => s.clone(),
+                    other => serde_yaml::to_string(other)
+                        .unwrap_or_default()
+                        .trim()
+                        .to_string(),
+                })
+                .unwrap_or_default();
+            let mut line = format!("{} = {} (from {}", leaf, leaf_value, origin.layer);
+            if !origin.overridden.is_empty() {
+                line.push_str(&format!(", overrides {}", origin.overridden.join(", ")));
+            }
+            line.push(')');
+            line
+        })
+        .collect())
+}
+
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |value, key| match value {
+        Value::Sequence(seq) => seq.get(key.parse::<usize>().ok()?),
+        other => other.get(key),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{CommandFactory, Parser};
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        explain: ConfigExplain,
+    }
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_cli_args() {
+        let cli = Cli::try_parse_from([
+            "cmd",
+            "networks.mainnet.rpc",
+            "-f",
+            "./some.rain",
+            "-c",
+            "./settings.yml",
+            "--precedence",
+            "settings",
+        ])
+        .unwrap();
+        assert_eq!(cli.explain.path, "networks.mainnet.rpc");
+        assert_eq!(cli.explain.dotrain_file, Some(PathBuf::from("./some.rain")));
+        assert_eq!(cli.explain.precedence, ConfigPrecedence::Settings);
+
+        let cli = Cli::try_parse_from(["cmd", "tokens", "-c", "./settings.yml"]).unwrap();
+        assert_eq!(cli.explain.precedence, ConfigPrecedence::Frontmatter);
+
+        assert!(Cli::try_parse_from(["cmd", "tokens"]).is_err());
+    }
+
+    #[test]
+    fn test_explain() {
+        let settings: ConfigSource = serde_yaml::from_str(
+            "networks:\n  mainnet:\n    rpc: https://mainnet.node\n    chain-id: 1\n    label: Mainnet\n",
+        )
+        .unwrap();
+        let frontmatter: ConfigSource = serde_yaml::from_str(
+            "networks:\n  mainnet:\n    rpc: https://private.node\n    chain-id: 1\n",
+        )
+        .unwrap();
+        let (config, provenance) = ConfigLayers::new()
+            .with_layer("settings", settings)
+            .with_layer("frontmatter", frontmatter)
+            .merge()
+            .unwrap();
+        let value = serde_yaml::to_value(&config).unwrap();
+
+        assert_eq!(
+            explain("networks.mainnet", &value, &provenance).unwrap(),
+            vec![
+                "networks.mainnet.chain-id = 1 (from frontmatter, overrides settings)",
+                "networks.mainnet.label = Mainnet (from settings)",
+                "networks.mainnet.rpc = https://private.node/ (from frontmatter, overrides settings)",
+            ]
+        );
+        assert!(explain("tokens", &value, &provenance).is_err());
+    }
+}
@@ -0,0 +1,153 @@
+use crate::execute::Execute;
+use anyhow::{anyhow, Result};
+use clap::{ArgGroup, Args};
+use rain_orderbook_app_settings::lint::{
+    lint_config, lint_config_strict, ConfigLintDiagnostic, ConfigLintSeverity, ConfigLintSource,
+};
+use rain_orderbook_common::dotrain::RainDocument;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+
+#[derive(Args, Clone)]
+#[command(group(ArgGroup::new("input").required(true).multiple(true).args(["dotrain_file", "settings_file"])))]
+pub struct ConfigLint {
+    #[arg(
+        short = 'f',
+        long,
+        help = "Path to the .rain file whose frontmatter to lint"
+    )]
+    dotrain_file: Option<PathBuf>,
+
+    #[arg(
+        short = 'c',
+        long,
+        help = "Path to the settings yaml file, merged with the frontmatter if a .rain file is given"
+    )]
+    settings_file: Option<PathBuf>,
+
+    #[arg(long, help = "Print the diagnostics as JSON")]
+    json: bool,
+
+    #[arg(long, help = "Also report keys the config format does not know of")]
+    strict: bool,
+}
+
+impl Execute for ConfigLint {
+    async fn execute(&self) -> Result<()> {
+        let settings = match &self.settings_file {
+            Some(settings_file) => {
+                Some(read_to_string(settings_file.clone()).map_err(|e| anyhow!(e))?)
+            }
+            None => None,
+        };
+        let lint = if self.strict {
+            lint_config_strict
+        } else {
+            lint_config
+        };
+        let diagnostics = match &self.dotrain_file {
+            Some(dotrain_file) => {
+                let dotrain = read_to_string(dotrain_file.clone()).map_err(|e| anyhow!(e))?;
+                let frontmatter = RainDocument::get_front_matter(&dotrain).unwrap_or("");
+                lint(frontmatter, settings.as_deref())
+            }
+            None => lint(settings.as_deref().unwrap_or(""), None),
+        };
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&diagnostics)?);
+        } else {
+            for diagnostic in &diagnostics {
+                println!("{}", self.describe(diagnostic));
+            }
+        }
+
+        let errors = diagnostics
+            .iter()
+            .filter(|v| v.severity == ConfigLintSeverity::Error)
+            .count();
+        if errors > 0 {
+            return Err(anyhow!("found {} config error(s)", errors));
+        }
+        Ok(())
+    }
+}
+
+impl ConfigLint {
+    /// Prefixes the diagnostic with the path of the file it belongs to
+    fn describe(&self, diagnostic: &ConfigLintDiagnostic) -> String {
+        let file = match (diagnostic.source, &self.dotrain_file) {
+            (ConfigLintSource::Main, Some(dotrain_file)) => dotrain_file,
+            _ => self
+                .settings_file
+                .as_ref()
+                .or(self.dotrain_file.as_ref())
+                .expect("one of the files is required"),
+        };
+        let mut diagnostic = diagnostic.clone();
+        diagnostic.source = ConfigLintSource::Main;
+        format!("{}: {}", file.display(), diagnostic)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{CommandFactory, Parser};
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        lint: ConfigLint,
+    }
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_cli_args() {
+        let cli =
+            Cli::try_parse_from(["cmd", "-f", "./some.rain", "-c", "./settings.yml"]).unwrap();
+        assert_eq!(cli.lint.dotrain_file, Some(PathBuf::from("./some.rain")));
+        assert_eq!(
+            cli.lint.settings_file,
+            Some(PathBuf::from("./settings.yml"))
+        );
+        assert!(!cli.lint.json);
+        assert!(!cli.lint.strict);
+
+        let cli =
+            Cli::try_parse_from(["cmd", "-c", "./settings.yml", "--json", "--strict"]).unwrap();
+        assert_eq!(cli.lint.dotrain_file, None);
+        assert!(cli.lint.json);
+        assert!(cli.lint.strict);
+
+        assert!(Cli::try_parse_from(["cmd"]).is_err());
+    }
+
+    #[test]
+    fn test_describe() {
+        let lint = Cli::try_parse_from(["cmd", "-f", "./some.rain", "-c", "./settings.yml"])
+            .unwrap()
+            .lint;
+        let mut diagnostic = ConfigLintDiagnostic {
+            severity: ConfigLintSeverity::Error,
+            source: ConfigLintSource::Main,
+            path: "tokens.weth".to_string(),
+            message: "Some error".to_string(),
+            span: None,
+        };
+        assert_eq!(
+            lint.describe(&diagnostic),
+            "./some.rain: error: Some error at tokens.weth"
+        );
+
+        diagnostic.source = ConfigLintSource::Settings;
+        assert_eq!(
+            lint.describe(&diagnostic),
+            "./settings.yml: error: Some error at tokens.weth"
+        );
+    }
+}
@@ -0,0 +1,48 @@
+mod explain;
+mod lint;
+mod schema;
+
+use crate::execute::Execute;
+use anyhow::Result;
+use clap::Parser;
+use explain::ConfigExplain;
+use lint::ConfigLint;
+use schema::ConfigSchema;
+
+#[derive(Parser)]
+pub enum Config {
+    #[command(
+        about = "Report every error of a settings file or a .rain file's frontmatter",
+        alias = "check"
+    )]
+    Lint(ConfigLint),
+
+    #[command(
+        about = "Show where each value under a path comes from when the frontmatter and settings are merged"
+    )]
+    Explain(ConfigExplain),
+
+    #[command(about = "Print the JSON Schema of the settings and frontmatter format")]
+    Schema(ConfigSchema),
+}
+
+impl Execute for Config {
+    async fn execute(&self) -> Result<()> {
+        match self {
+            Config::Lint(lint) => lint.execute().await,
+            Config::Explain(explain) => explain.execute().await,
+            Config::Schema(schema) => schema.execute().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn verify_command() {
+        Config::command().debug_assert();
+    }
+}
@@ -0,0 +1,58 @@
+use crate::execute::Execute;
+use anyhow::Result;
+use clap::Args;
+use rain_orderbook_app_settings::schema::{config_schema, unit_test_schema};
+use std::path::PathBuf;
+
+#[derive(Args, Clone)]
+pub struct ConfigSchema {
+    #[arg(short, long, help = "Write the schema to this file instead of stdout")]
+    output: Option<PathBuf>,
+
+    #[arg(long, help = "Print the schema of a unit test's frontmatter instead")]
+    unit_test: bool,
+}
+
+impl Execute for ConfigSchema {
+    async fn execute(&self) -> Result<()> {
+        let schema = if self.unit_test {
+            unit_test_schema()
+        } else {
+            config_schema()
+        };
+        let schema = serde_json::to_string_pretty(&schema)?;
+        match &self.output {
+            Some(output) => std::fs::write(output, schema + "\n")?,
+            None => println!("{}", schema),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{CommandFactory, Parser};
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        schema: ConfigSchema,
+    }
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_cli_args() {
+        let cli = Cli::try_parse_from(["cmd", "-o", "./schema.json", "--unit-test"]).unwrap();
+        assert_eq!(cli.schema.output, Some(PathBuf::from("./schema.json")));
+        assert!(cli.schema.unit_test);
+
+        let cli = Cli::try_parse_from(["cmd"]).unwrap();
+        assert_eq!(cli.schema.output, None);
+        assert!(!cli.schema.unit_test);
+    }
+}
@@ -0,0 +1,393 @@
+use crate::commands::order::parse_deposit;
+use crate::env_file::CliEnvFileArgs;
+use crate::execute::Execute;
+use crate::journal::CliJournalArgs;
+use crate::preview::{display_preview, CliPreviewArgs};
+use crate::safe::CliSafeArgs;
+use crate::status::display_write_transaction_status;
+use crate::transaction::CliGasFeeSpeed;
+use alloy::primitives::{Address, U256};
+use alloy_ethers_typecast::ethers_address_to_alloy;
+use anyhow::{anyhow, Result};
+use clap::{ArgAction, Args};
+use rain_orderbook_app_settings::imports::ImportLoaders;
+use rain_orderbook_app_settings::order::OrderIO;
+use rain_orderbook_bindings::IOrderBookV4::IO;
+use rain_orderbook_common::dotrain_order::{
+    deploy::{DeployArgs, DeployDeposit},
+    DotrainOrder,
+};
+use rain_orderbook_common::transaction::TransactionArgs;
+use std::collections::HashMap;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+use tracing::info;
+
+/// Approves the deposited tokens, then deposits and adds the order of a
+/// deployment in a single orderbook multicall
+#[derive(Args, Clone)]
+pub struct Deploy {
+    #[arg(
+        short = 'f',
+        long,
+        help = "Path to the .rain file specifying the order"
+    )]
+    dotrain_file: PathBuf,
+
+    #[arg(short = 'c', long, help = "Path to the settings yaml file")]
+    settings_file: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub env_file_args: CliEnvFileArgs,
+
+    #[arg(short = 'e', long, help = "Deployment key to select from frontmatter")]
+    deployment: String,
+
+    #[arg(
+        short = 'd',
+        long = "deposit",
+        value_name = "TOKEN=AMOUNT",
+        value_parser = parse_deposit,
+        help = "Deposit into the vault of the given output token key, can be repeated"
+    )]
+    deposits: Vec<(String, String)>,
+
+    #[arg(
+        long = "deposit-preset",
+        value_name = "TOKEN=INDEX",
+        value_parser = parse_deposit_preset,
+        help = "Deposit one of the gui deposit presets of the given output token key, can be repeated"
+    )]
+    deposit_presets: Vec<(String, usize)>,
+
+    #[arg(
+        long,
+        help = "Derive the order's nonce, secret and vault ids from the owner, deployment and this salt, so redeploying yields the same order"
+    )]
+    salt: Option<String>,
+
+    #[arg(
+        long,
+        help = "Address to simulate from instead of the Ledger wallet, requires --no-broadcast",
+        requires = "no_broadcast",
+        conflicts_with = "safe"
+    )]
+    owner: Option<Address>,
+
+    #[clap(flatten)]
+    safe_args: CliSafeArgs,
+
+    #[clap(flatten)]
+    preview_args: CliPreviewArgs,
+
+    #[clap(flatten)]
+    journal_args: CliJournalArgs,
+
+    #[arg(
+        long,
+        help = "Derivation index of the Ledger wallet address to use",
+        default_value = "0"
+    )]
+    derivation_index: Option<usize>,
+
+    #[arg(
+        short = 'p',
+        long,
+        help = "Max priority fee per gas (in wei)",
+        conflicts_with("gas_fee_speed")
+    )]
+    max_priority_fee_per_gas: Option<U256>,
+
+    #[arg(
+        short,
+        long,
+        help = "Max fee per gas (in wei)",
+        conflicts_with("gas_fee_speed")
+    )]
+    max_fee_per_gas: Option<U256>,
+
+    #[arg(
+        short,
+        long,
+        help = "Chooses sensible gas fees for a desired transaction speed.",
+        default_value = "medium"
+    )]
+    gas_fee_speed: Option<CliGasFeeSpeed>,
+
+    /// Do NOT broadcast the transactions to the network, only simulate them
+    #[arg(long, action = ArgAction::SetTrue)]
+    no_broadcast: bool,
+}
+
+fn parse_deposit_preset(value: &str) -> Result<(String, usize), String> {
+    let (token, index) = parse_deposit(value)?;
+    let index = index
+        .parse()
+        .map_err(|_| format!("expected TOKEN=INDEX, got: {}", value))?;
+    Ok((token, index))
+}
+
+impl Deploy {
+    fn to_deploy_deposits(&self) -> Result<HashMap<String, DeployDeposit>> {
+        let mut deposits = HashMap::new();
+        let amounts = self
+            .deposits
+            .iter()
+            .map(|(token, amount)| (token, DeployDeposit::Amount(amount.clone())));
+        let presets = self
+            .deposit_presets
+            .iter()
+            .map(|(token, index)| (token, DeployDeposit::Preset(*index)));
+        for (token, deposit) in amounts.chain(presets) {
+            if deposits.insert(token.clone(), deposit).is_some() {
+                return Err(anyhow!("token {} is deposited more than once", token));
+            }
+        }
+        Ok(deposits)
+    }
+}
+
+fn display_vaults(kind: &str, ios: &[IO], order_ios: &[OrderIO]) {
+    for (io, order_io) in ios.iter().zip(order_ios) {
+        match &order_io.token.symbol {
+            Some(symbol) => info!("{} vault: {} {} ({})", kind, io.vaultId, symbol, io.token),
+            None => info!("{} vault: {} {}", kind, io.vaultId, io.token),
+        }
+    }
+}
+
+impl Execute for Deploy {
+    async fn execute(&self) -> Result<()> {
+        let dotrain = read_to_string(self.dotrain_file.clone()).map_err(|e| anyhow!(e))?;
+        let settings = match &self.settings_file {
+            Some(settings_file) => {
+                Some(read_to_string(settings_file.clone()).map_err(|e| anyhow!(e))?)
+            }
+            None => None,
+        };
+        let loaders = ImportLoaders::for_files(
+            Some(self.dotrain_file.as_path()),
+            self.settings_file.as_deref(),
+        );
+        let order = DotrainOrder::new_with_loaders(
+            dotrain,
+            settings,
+            &self.env_file_args.resolver()?,
+            &loaders,
+        )
+        .await?;
+        let deployment = order
+            .config()
+            .deployments
+            .get(&self.deployment)
+            .ok_or(anyhow!("specified deployment is undefined!"))?
+            .clone();
+        let network = deployment
+            .order
+            .orderbook
+            .as_ref()
+            .ok_or(anyhow!("deployment has no orderbook"))?
+            .network
+            .clone();
+
+        let tx_args = TransactionArgs {
+            derivation_index: self.derivation_index,
+            chain_id: Some(network.chain_id),
+            max_priority_fee_per_gas: self.max_priority_fee_per_gas,
+            max_fee_per_gas: self.max_fee_per_gas,
+            gas_fee_speed: self.gas_fee_speed.clone().map(|g| g.into()),
+            journal: self.journal_args.flow()?,
+            ..TransactionArgs::for_network(&network).await?
+        };
+        let owner = match self.safe_args.safe.or(self.owner) {
+            Some(owner) => owner,
+            None => ethers_address_to_alloy(
+                tx_args
+                    .clone()
+                    .try_into_ledger_client()
+                    .await?
+                    .client
+                    .address(),
+            ),
+        };
+
+        let transactions = order
+            .prepare_deployment(
+                &self.deployment,
+                DeployArgs {
+                    owner,
+                    deposits: self.to_deploy_deposits()?,
+                    salt: self.salt.clone(),
+                },
+            )
+            .await?;
+
+        display_preview(&transactions.preview().await?);
+
+        if self.safe_args.safe.is_some() {
+            self.safe_args
+                .propose(
+                    "Deploy order",
+                    network.chain_id,
+                    tx_args.rpc_url.clone(),
+                    transactions.safe_transactions(),
+                )
+                .await?;
+        } else if !self.no_broadcast {
+            if !self.preview_args.confirm()? {
+                info!("Aborted, no transactions sent");
+                return Ok(());
+            }
+            info!(
+                "----- Approve {} token(s), deposit and add order -----",
+                transactions.approvals.len()
+            );
+            transactions
+                .execute(
+                    tx_args,
+                    display_write_transaction_status,
+                    display_write_transaction_status,
+                )
+                .await?;
+        }
+
+        info!("Owner: {}", owner);
+        info!("Order hash: {}", transactions.order_hash());
+        display_vaults(
+            "Input",
+            &transactions.add_order.config.validInputs,
+            &deployment.order.inputs,
+        );
+        display_vaults(
+            "Output",
+            &transactions.add_order.config.validOutputs,
+            &deployment.order.outputs,
+        );
+        for deposit in &transactions.deposits {
+            info!(
+                "Deposit: {} of {} into vault {}",
+                deposit.amount, deposit.token, deposit.vaultId
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        deploy: Deploy,
+    }
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_cli_args() {
+        let owner = Address::random();
+        let cli = Cli::try_parse_from([
+            "cmd",
+            "-f",
+            "./some.rain",
+            "-e",
+            "some-deployment",
+            "-d",
+            "token1=10.5",
+            "--deposit-preset",
+            "token2=1",
+            "--salt",
+            "v1",
+            "--owner",
+            &owner.to_string(),
+            "--no-broadcast",
+        ])
+        .unwrap();
+        assert_eq!(cli.deploy.deployment, "some-deployment");
+        assert_eq!(cli.deploy.owner, Some(owner));
+        assert_eq!(cli.deploy.salt, Some("v1".to_string()));
+        assert_eq!(
+            cli.deploy.to_deploy_deposits().unwrap(),
+            HashMap::from([
+                (
+                    "token1".to_string(),
+                    DeployDeposit::Amount("10.5".to_string())
+                ),
+                ("token2".to_string(), DeployDeposit::Preset(1)),
+            ])
+        );
+
+        // an owner is only used to simulate
+        assert!(Cli::try_parse_from([
+            "cmd",
+            "-f",
+            "./some.rain",
+            "-e",
+            "some-deployment",
+            "--owner",
+            &owner.to_string(),
+        ])
+        .is_err());
+
+        // a safe owner is proposed to rather than simulated from a given owner
+        let cli = Cli::try_parse_from([
+            "cmd",
+            "-f",
+            "./some.rain",
+            "-e",
+            "some-deployment",
+            "--safe",
+            &owner.to_string(),
+            "--safe-nonce",
+            "4",
+        ])
+        .unwrap();
+        assert_eq!(cli.deploy.safe_args.safe, Some(owner));
+        assert!(Cli::try_parse_from([
+            "cmd",
+            "-f",
+            "./some.rain",
+            "-e",
+            "some-deployment",
+            "--safe",
+            &owner.to_string(),
+            "--owner",
+            &owner.to_string(),
+            "--no-broadcast",
+        ])
+        .is_err());
+
+        let cli = Cli::try_parse_from([
+            "cmd",
+            "-f",
+            "./some.rain",
+            "-e",
+            "some-deployment",
+            "-d",
+            "token1=1",
+            "--deposit-preset",
+            "token1=0",
+        ])
+        .unwrap();
+        assert!(cli.deploy.to_deploy_deposits().is_err());
+
+        assert!(Cli::try_parse_from([
+            "cmd",
+            "-f",
+            "./some.rain",
+            "-e",
+            "some-deployment",
+            "--deposit-preset",
+            "token1=first",
+        ])
+        .is_err());
+    }
+}
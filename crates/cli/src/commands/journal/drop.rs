@@ -0,0 +1,85 @@
+use super::CliJournalFileArgs;
+use crate::execute::Execute;
+use anyhow::Result;
+use clap::Args;
+use rain_orderbook_common::journal::JournalStatus;
+use tracing::info;
+
+#[derive(Args, Clone)]
+pub struct CliJournalDropArgs {
+    #[arg(long, help = "Id of the flow of the dropped transaction")]
+    flow: String,
+
+    #[arg(long, help = "Step of the flow of the dropped transaction")]
+    step: usize,
+
+    #[clap(flatten)]
+    file_args: CliJournalFileArgs,
+}
+
+impl Execute for CliJournalDropArgs {
+    async fn execute(&self) -> Result<()> {
+        let journal = self.file_args.open()?;
+        let pending = journal.pending_attempt(&self.flow, self.step)?;
+        journal.set_status(
+            &pending.flow,
+            pending.step,
+            pending.attempt,
+            JournalStatus::Failed("Dropped".to_string()),
+        )?;
+        info!(
+            "Step {} of flow {} at nonce {} marked as dropped",
+            pending.step, pending.flow, pending.nonce
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{Address, Bytes, U256};
+    use rain_orderbook_common::journal::{JournalEntry, TransactionJournal};
+    use std::path::PathBuf;
+
+    #[tokio::test]
+    async fn test_drop() {
+        let path = std::env::temp_dir().join(format!("journal-drop-{}.json", std::process::id()));
+        let journal = TransactionJournal::open(&path).unwrap();
+        journal
+            .insert(JournalEntry {
+                flow: "flow".to_string(),
+                step: 0,
+                attempt: 0,
+                label: "withdraw2".to_string(),
+                chain_id: 1,
+                from: Address::random(),
+                to: Address::random(),
+                calldata: Bytes::new(),
+                nonce: U256::from(1),
+                max_fee_per_gas: None,
+                max_priority_fee_per_gas: None,
+                status: JournalStatus::Sent,
+                updated_at: 0,
+            })
+            .unwrap();
+
+        let args = CliJournalDropArgs {
+            flow: "flow".to_string(),
+            step: 0,
+            file_args: CliJournalFileArgs {
+                journal: Some(PathBuf::from(&path)),
+            },
+        };
+        args.execute().await.unwrap();
+        // a dropped step is no longer pending
+        assert!(args.execute().await.is_err());
+
+        let journal = TransactionJournal::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            journal.entries()[0].status,
+            JournalStatus::Failed("Dropped".to_string())
+        );
+    }
+}
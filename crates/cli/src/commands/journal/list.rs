@@ -0,0 +1,98 @@
+use super::CliJournalFileArgs;
+use crate::execute::Execute;
+use anyhow::Result;
+use clap::Args;
+use comfy_table::Table;
+use rain_orderbook_common::journal::{JournalEntry, JournalStatus};
+use tracing::info;
+
+#[derive(Args, Clone)]
+pub struct CliJournalListArgs {
+    #[arg(long, help = "Only the transactions of the flow with this id")]
+    flow: Option<String>,
+
+    #[clap(flatten)]
+    file_args: CliJournalFileArgs,
+}
+
+impl Execute for CliJournalListArgs {
+    async fn execute(&self) -> Result<()> {
+        let journal = self.file_args.open()?;
+        let entries = match &self.flow {
+            Some(flow) => journal.flow_entries(flow),
+            None => journal.entries(),
+        };
+        info!("\n{}", build_table(&entries));
+        Ok(())
+    }
+}
+
+fn display_status(status: &JournalStatus) -> String {
+    match status {
+        JournalStatus::Prepared => "Prepared".to_string(),
+        JournalStatus::Signing => "Signing".to_string(),
+        JournalStatus::Sent => "Sent".to_string(),
+        JournalStatus::Confirmed(hash) => format!("Confirmed {}", hash),
+        JournalStatus::Failed(error) => format!("Failed: {}", error),
+        JournalStatus::Replaced => "Replaced".to_string(),
+    }
+}
+
+fn build_table(entries: &[JournalEntry]) -> Table {
+    let mut table = Table::new();
+    table
+        .load_preset(comfy_table::presets::UTF8_FULL)
+        .set_content_arrangement(comfy_table::ContentArrangement::Dynamic)
+        .set_header(vec![
+            "Flow", "Step", "Attempt", "Call", "Chain", "From", "Nonce", "Status", "Updated",
+        ]);
+
+    for entry in entries {
+        let updated_at = chrono::DateTime::from_timestamp_millis(entry.updated_at)
+            .map(|time| time.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+            .unwrap_or_default();
+        table.add_row(vec![
+            entry.flow.clone(),
+            entry.step.to_string(),
+            entry.attempt.to_string(),
+            entry.label.clone(),
+            entry.chain_id.to_string(),
+            entry.from.to_string(),
+            entry.nonce.to_string(),
+            display_status(&entry.status),
+            updated_at,
+        ]);
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{Address, Bytes, B256, U256};
+
+    #[test]
+    fn test_build_table() {
+        let hash = B256::random();
+        let entry = JournalEntry {
+            flow: "20240101-000000000".to_string(),
+            step: 1,
+            attempt: 0,
+            label: "deposit2".to_string(),
+            chain_id: 137,
+            from: Address::random(),
+            to: Address::random(),
+            calldata: Bytes::new(),
+            nonce: U256::from(4),
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            status: JournalStatus::Confirmed(hash),
+            updated_at: 0,
+        };
+        let table = build_table(&[entry]).to_string();
+        assert!(table.contains("deposit2"));
+        assert!(table.contains(&format!("Confirmed {}", hash)));
+        assert!(table.contains("1970-01-01 00:00:00 UTC"));
+    }
+}
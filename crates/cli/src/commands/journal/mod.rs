@@ -0,0 +1,71 @@
+mod drop;
+mod list;
+mod replace;
+
+use crate::execute::Execute;
+use crate::journal::default_journal_path;
+use anyhow::Result;
+use clap::{Args, Parser};
+use drop::CliJournalDropArgs;
+use list::CliJournalListArgs;
+use rain_orderbook_common::journal::TransactionJournal;
+use replace::{CliJournalCancelArgs, CliJournalSpeedUpArgs};
+use std::path::PathBuf;
+
+#[derive(Parser)]
+pub enum Journal {
+    #[command(about = "List the transactions recorded in the journal")]
+    List(CliJournalListArgs),
+
+    #[command(about = "Resend a stuck transaction at its nonce with higher fees")]
+    SpeedUp(CliJournalSpeedUpArgs),
+
+    #[command(about = "Replace a stuck transaction at its nonce with an empty call to the sender")]
+    Cancel(CliJournalCancelArgs),
+
+    #[command(
+        about = "Mark a pending transaction that is no longer known to the network as failed, so its flow can be resumed"
+    )]
+    Drop(CliJournalDropArgs),
+}
+
+impl Execute for Journal {
+    async fn execute(&self) -> Result<()> {
+        match self {
+            Journal::List(list) => list.execute().await,
+            Journal::SpeedUp(speed_up) => speed_up.execute().await,
+            Journal::Cancel(cancel) => cancel.execute().await,
+            Journal::Drop(drop) => drop.execute().await,
+        }
+    }
+}
+
+#[derive(Args, Clone, Default)]
+pub struct CliJournalFileArgs {
+    #[arg(
+        long,
+        help = "Path to the transaction journal, defaults to ~/.rain-orderbook/journal.json"
+    )]
+    pub journal: Option<PathBuf>,
+}
+
+impl CliJournalFileArgs {
+    pub fn open(&self) -> Result<TransactionJournal> {
+        let path = match &self.journal {
+            Some(path) => path.clone(),
+            None => default_journal_path()?,
+        };
+        Ok(TransactionJournal::open(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn verify_command() {
+        Journal::command().debug_assert();
+    }
+}
@@ -0,0 +1,136 @@
+use super::CliJournalFileArgs;
+use crate::execute::Execute;
+use anyhow::Result;
+use clap::Args;
+use rain_orderbook_common::journal::{JournalFlow, Replacement};
+use rain_orderbook_common::transaction::TransactionArgs;
+use std::sync::Arc;
+use tracing::info;
+
+/// Arguments selecting the stuck step of a flow and the wallet that sent it
+#[derive(Args, Clone)]
+pub struct CliJournalReplaceArgs {
+    #[arg(long, help = "Id of the flow of the stuck transaction")]
+    flow: String,
+
+    #[arg(long, help = "Step of the flow of the stuck transaction")]
+    step: usize,
+
+    #[arg(
+        short,
+        long,
+        help = "Derivation index of the Ledger wallet address that sent the transaction",
+        default_value = "0"
+    )]
+    derivation_index: Option<usize>,
+
+    #[arg(short, long, help = "RPC URL")]
+    rpc_url: String,
+
+    #[clap(flatten)]
+    file_args: CliJournalFileArgs,
+}
+
+impl CliJournalReplaceArgs {
+    async fn replace(&self, replacement: Replacement) -> Result<()> {
+        let flow = JournalFlow::resume(Arc::new(self.file_args.open()?), self.flow.clone());
+        let transaction_args = TransactionArgs {
+            derivation_index: self.derivation_index,
+            rpc_url: self.rpc_url.clone(),
+            journal: Some(flow.clone()),
+            ..Default::default()
+        };
+        transaction_args
+            .replace_journaled(self.step, replacement)
+            .await?;
+
+        if let Some(entry) = flow.last_entry() {
+            info!(
+                "Step {} of flow {} replaced by attempt {} at nonce {}: {:?}",
+                entry.step, entry.flow, entry.attempt, entry.nonce, entry.status
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Args, Clone)]
+pub struct CliJournalSpeedUpArgs {
+    #[clap(flatten)]
+    replace_args: CliJournalReplaceArgs,
+}
+
+impl Execute for CliJournalSpeedUpArgs {
+    async fn execute(&self) -> Result<()> {
+        self.replace_args.replace(Replacement::SpeedUp).await
+    }
+}
+
+#[derive(Args, Clone)]
+pub struct CliJournalCancelArgs {
+    #[clap(flatten)]
+    replace_args: CliJournalReplaceArgs,
+}
+
+impl Execute for CliJournalCancelArgs {
+    async fn execute(&self) -> Result<()> {
+        self.replace_args.replace(Replacement::Cancel).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{CommandFactory, Parser};
+    use std::path::PathBuf;
+
+    #[derive(Parser)]
+    enum Cli {
+        SpeedUp(CliJournalSpeedUpArgs),
+        Cancel(CliJournalCancelArgs),
+    }
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_cli_args() {
+        let cli = Cli::try_parse_from([
+            "cmd",
+            "speed-up",
+            "--flow",
+            "20240101-000000000",
+            "--step",
+            "2",
+            "-r",
+            "https://some-rpc.com",
+            "--journal",
+            "./journal.json",
+        ])
+        .unwrap();
+        let Cli::SpeedUp(speed_up) = cli else {
+            panic!("expected speed-up");
+        };
+        let args = speed_up.replace_args;
+        assert_eq!(args.flow, "20240101-000000000");
+        assert_eq!(args.step, 2);
+        assert_eq!(args.derivation_index, Some(0));
+        assert_eq!(args.rpc_url, "https://some-rpc.com");
+        assert_eq!(
+            args.file_args.journal,
+            Some(PathBuf::from("./journal.json"))
+        );
+
+        assert!(Cli::try_parse_from([
+            "cmd",
+            "cancel",
+            "--flow",
+            "20240101-000000000",
+            "-r",
+            "https://some-rpc.com",
+        ])
+        .is_err());
+    }
+}
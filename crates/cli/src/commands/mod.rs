@@ -1,13 +1,18 @@
 mod analytics;
 mod chart;
+mod config;
+mod deploy;
+mod journal;
 mod order;
 mod quote;
+mod strategy;
 mod subgraph;
 mod trade;
 mod vault;
 mod words;
 
 pub use self::{
-    analytics::Analytics, chart::Chart, order::Order, subgraph::Subgraph, trade::Trade,
-    vault::Vault, words::Words,
+    analytics::Analytics, chart::Chart, config::Config, deploy::Deploy, journal::Journal,
+    order::Order, quote::Quote, strategy::Strategy, subgraph::Subgraph, trade::Trade, vault::Vault,
+    words::Words,
 };
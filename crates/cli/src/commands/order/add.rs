@@ -1,17 +1,27 @@
 use crate::{
-    execute::Execute, status::display_write_transaction_status, transaction::CliTransactionArgs,
+    env_file::CliEnvFileArgs,
+    execute::Execute,
+    preview::{display_preview, CliPreviewArgs},
+    safe::CliSafeArgs,
+    status::display_write_transaction_status,
+    transaction::CliTransactionArgs,
 };
+use alloy::primitives::Address;
 use anyhow::{anyhow, Result};
 use clap::{ArgAction, Args};
-use rain_orderbook_app_settings::Config;
+use rain_orderbook_app_settings::{config_source::ConfigSource, imports::ImportLoaders, Config};
 use rain_orderbook_common::add_order::AddOrderArgs;
-use rain_orderbook_common::frontmatter::parse_frontmatter;
+use rain_orderbook_common::derivation::OrderDerivation;
+use rain_orderbook_common::dotrain::RainDocument;
+use rain_orderbook_common::preview::PreviewBuilder;
+use rain_orderbook_common::safe::SafeTransaction;
+use rain_orderbook_common::token_metadata::{enrich_token_metadata, TokenInfoCache};
 use rain_orderbook_common::transaction::TransactionArgs;
 use rain_orderbook_common::GH_COMMIT_SHA;
 use std::fs::read_to_string;
 use std::ops::Deref;
 use std::path::PathBuf;
-use tracing::info;
+use tracing::{info, warn};
 
 #[derive(Args, Clone)]
 pub struct CliOrderAddArgs {
@@ -28,6 +38,15 @@ pub struct CliOrderAddArgs {
     #[clap(flatten)]
     pub transaction_args: CliTransactionArgs,
 
+    #[clap(flatten)]
+    pub env_file_args: CliEnvFileArgs,
+
+    #[clap(flatten)]
+    pub safe_args: CliSafeArgs,
+
+    #[clap(flatten)]
+    pub preview_args: CliPreviewArgs,
+
     /// Do NOT broadcast the transaction to the network, only simulate the transaction
     #[arg(long, action = ArgAction::SetTrue)]
     pub no_broadcast: bool,
@@ -35,12 +54,36 @@ pub struct CliOrderAddArgs {
     /// Skips checking the `raindex-version` against this cli app current version
     #[arg(long, action = ArgAction::SetTrue)]
     pub skip_version_check: bool,
+
+    /// Derives the nonce, secret and vault ids from the owner, the deployment
+    /// and this salt instead of picking random ones, so the order hash is
+    /// known ahead and the order is not added twice
+    #[arg(long)]
+    pub salt: Option<String>,
+
+    /// Embeds the dotrain source in the order meta so the order can be
+    /// audited against it later
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub embed_source: bool,
 }
 
 impl CliOrderAddArgs {
-    async fn to_add_order_args(&self) -> Result<AddOrderArgs> {
+    async fn to_add_order_args(&self, owner: Option<Address>) -> Result<AddOrderArgs> {
         let text = read_to_string(&self.dotrain_file).map_err(|e| anyhow!(e))?;
-        let config: Config = parse_frontmatter(text.clone()).await?.try_into()?;
+        let frontmatter = RainDocument::get_front_matter(&text).unwrap_or("");
+        let (mut config_source, _) = ConfigSource::try_from_string_with_loaders(
+            frontmatter.to_string(),
+            None,
+            &self.env_file_args.resolver()?,
+            &ImportLoaders::for_files(Some(self.dotrain_file.as_path()), None),
+        )
+        .await?;
+        for warning in
+            enrich_token_metadata(&mut config_source, None, TokenInfoCache::global(), None).await
+        {
+            warn!("{}", warning);
+        }
+        let config: Config = config_source.try_into()?;
 
         if !self.skip_version_check {
             if let Some(ver) = config.raindex_version {
@@ -62,26 +105,65 @@ impl CliOrderAddArgs {
             .get(&self.deployment)
             .ok_or(anyhow!("specified deployment is undefined!"))?;
 
-        Ok(
-            AddOrderArgs::new_from_deployment(text.clone(), config_deployment.deref().clone())
-                .await?,
-        )
+        let deployment = config_deployment.deref().clone();
+        let add_order_args = match (&self.salt, owner) {
+            (Some(salt), Some(owner)) => Ok(AddOrderArgs::new_from_deployment_derived(
+                text.clone(),
+                deployment,
+                OrderDerivation {
+                    owner,
+                    deployment: self.deployment.clone(),
+                    salt: salt.clone(),
+                },
+            )
+            .await?),
+            (Some(_), None) => Err(anyhow!("deriving the order requires the owner address")),
+            (None, _) => Ok(AddOrderArgs::new_from_deployment(text.clone(), deployment).await?),
+        }?;
+        if self.embed_source {
+            return Ok(add_order_args.with_embedded_source(None));
+        }
+        Ok(add_order_args)
     }
 }
 
 impl Execute for CliOrderAddArgs {
     async fn execute(&self) -> Result<()> {
-        let add_order_args: AddOrderArgs = self.clone().to_add_order_args().await?;
-        let mut tx_args: TransactionArgs = self.transaction_args.clone().into();
+        let mut tx_args: TransactionArgs = self.transaction_args.clone().try_into()?;
         tx_args.try_fill_chain_id().await?;
+        let sender = self.safe_args.sender(&tx_args).await?;
+        let add_order_args: AddOrderArgs = self.clone().to_add_order_args(Some(sender)).await?;
+        let add_order = add_order_args
+            .try_into_call(tx_args.rpc_url.clone())
+            .await?;
+        if add_order_args.derivation.is_some() {
+            info!("Order hash: {}", add_order_args.order_hash(&add_order)?);
+        }
+        add_order_args.ensure_not_live(&tx_args, &add_order).await?;
 
-        info!("----- Simulating Transaction -----");
-        add_order_args
-            .simulate_execute(tx_args.clone(), None)
+        let mut preview = PreviewBuilder::new(tx_args.rpc_url.clone(), sender).await?;
+        preview
+            .call(tx_args.orderbook_address, add_order.clone())
             .await?;
-        info!("----- Finished Simulation Successfully -----");
+        display_preview(&preview.finish().await?);
+
+        if self.safe_args.safe.is_some() {
+            return self
+                .safe_args
+                .propose(
+                    "Add order",
+                    tx_args.chain_id.unwrap_or_default(),
+                    tx_args.rpc_url.clone(),
+                    vec![SafeTransaction::new(tx_args.orderbook_address, &add_order)],
+                )
+                .await;
+        }
 
         if !self.no_broadcast {
+            if !self.preview_args.confirm()? {
+                info!("Aborted, no transactions sent");
+                return Ok(());
+            }
             info!("----- Add Order -----");
             add_order_args
                 .execute(tx_args, |status| {
@@ -97,6 +179,7 @@ impl Execute for CliOrderAddArgs {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::journal::CliJournalArgs;
     use alloy::primitives::{Address, U256};
     use rain_orderbook_bindings::IOrderBookV4::IO;
     use std::{collections::HashMap, str::FromStr};
@@ -113,6 +196,11 @@ mod tests {
             dotrain_file: dotrain_path.into(),
             deployment: "some-deployment".to_string(),
             skip_version_check: false,
+            salt: None,
+            embed_source: false,
+            safe_args: CliSafeArgs::default(),
+            preview_args: CliPreviewArgs::default(),
+            env_file_args: CliEnvFileArgs::default(),
             transaction_args: CliTransactionArgs {
                 orderbook_address: Address::random(),
                 derivation_index: None,
@@ -121,10 +209,11 @@ mod tests {
                 max_fee_per_gas: None,
                 max_priority_fee_per_gas: None,
                 gas_fee_speed: None,
+                journal_args: CliJournalArgs::default(),
             },
         };
 
-        let result = cli_order_add_args.to_add_order_args().await.unwrap();
+        let result = cli_order_add_args.to_add_order_args(None).await.unwrap();
         let expected = AddOrderArgs {
             dotrain: get_dotrain(GH_COMMIT_SHA),
             inputs: vec![IO {
@@ -139,6 +228,8 @@ mod tests {
             }],
             deployer: Address::from_str("0xF14E09601A47552De6aBd3A0B165607FaFd2B5Ba").unwrap(),
             bindings: HashMap::new(),
+            derivation: None,
+            embedded_source: None,
         };
         assert_eq!(result, expected);
 
@@ -158,6 +249,11 @@ mod tests {
             dotrain_file: dotrain_path.into(),
             deployment: "some-deployment".to_string(),
             skip_version_check: false,
+            salt: None,
+            embed_source: false,
+            safe_args: CliSafeArgs::default(),
+            preview_args: CliPreviewArgs::default(),
+            env_file_args: CliEnvFileArgs::default(),
             transaction_args: CliTransactionArgs {
                 orderbook_address: Address::random(),
                 derivation_index: None,
@@ -166,9 +262,10 @@ mod tests {
                 max_fee_per_gas: None,
                 max_priority_fee_per_gas: None,
                 gas_fee_speed: None,
+                journal_args: CliJournalArgs::default(),
             },
         };
-        let result = cli_order_add_args.to_add_order_args().await;
+        let result = cli_order_add_args.to_add_order_args(None).await;
 
         // rm test file
         std::fs::remove_file(dotrain_path).unwrap();
@@ -1,8 +1,11 @@
+use crate::env_file::CliEnvFileArgs;
 use crate::execute::Execute;
 use crate::output::{output, SupportedOutputEncoding};
 use alloy::sol_types::SolCall;
 use anyhow::{anyhow, Result};
 use clap::Parser;
+use rain_orderbook_app_settings::imports::ImportLoaders;
+use rain_orderbook_app_settings::rpc::RpcProvider;
 use rain_orderbook_common::add_order::AddOrderArgs;
 use rain_orderbook_common::dotrain_order::DotrainOrder;
 use std::fs::read_to_string;
@@ -21,6 +24,9 @@ pub struct AddOrderCalldata {
     #[arg(short = 'c', long, help = "Path to the settings yaml file")]
     settings_file: Option<PathBuf>,
 
+    #[clap(flatten)]
+    pub env_file_args: CliEnvFileArgs,
+
     #[arg(short = 'e', long, help = "Deployment key to select from frontmatter")]
     deployment: String,
 
@@ -37,7 +43,17 @@ impl Execute for AddOrderCalldata {
             }
             None => None,
         };
-        let order = DotrainOrder::new(dotrain, settings).await?;
+        let loaders = ImportLoaders::for_files(
+            Some(self.dotrain_file.as_path()),
+            self.settings_file.as_deref(),
+        );
+        let order = DotrainOrder::new_with_loaders(
+            dotrain,
+            settings,
+            &self.env_file_args.resolver()?,
+            &loaders,
+        )
+        .await?;
         let dotrain_string = order.dotrain().to_string();
 
         let config_deployment = order
@@ -47,13 +63,16 @@ impl Execute for AddOrderCalldata {
             .ok_or(anyhow!("specified deployment is undefined!"))?;
 
         let add_order_args =
-            AddOrderArgs::new_from_deployment(dotrain_string, config_deployment.deref().clone())
-                .await;
+            &AddOrderArgs::new_from_deployment(dotrain_string, config_deployment.deref().clone())
+                .await?;
 
-        let add_order_calldata = add_order_args?
-            .try_into_call(config_deployment.scenario.deployer.network.rpc.to_string())
-            .await?
-            .abi_encode();
+        let add_order_calldata =
+            RpcProvider::for_network(&config_deployment.scenario.deployer.network)
+                .with_failover(|rpc_url| async move {
+                    Ok::<_, anyhow::Error>(add_order_args.try_into_call(rpc_url.to_string()).await?)
+                })
+                .await?
+                .abi_encode();
 
         output(&None, self.encoding.clone(), &add_order_calldata)?;
 
@@ -240,6 +259,7 @@ _ _: 0 0;
         let add_order_calldata = AddOrderCalldata {
             dotrain_file: dotrain_path.into(),
             settings_file: None,
+            env_file_args: CliEnvFileArgs::default(),
             deployment: "some-deployment".to_string(),
             encoding: SupportedOutputEncoding::Hex,
         };
@@ -1,7 +1,9 @@
+use crate::env_file::CliEnvFileArgs;
 use crate::execute::Execute;
 use crate::output::{output, SupportedOutputEncoding};
 use anyhow::{anyhow, Result};
 use clap::Args;
+use rain_orderbook_app_settings::imports::ImportLoaders;
 use rain_orderbook_common::dotrain_order::DotrainOrder;
 use std::fs::read_to_string;
 use std::path::PathBuf;
@@ -19,6 +21,9 @@ pub struct Compose {
     #[arg(short = 'c', long, help = "Path to the settings yaml file")]
     settings_file: Option<PathBuf>,
 
+    #[clap(flatten)]
+    pub env_file_args: CliEnvFileArgs,
+
     // the name of the scenrio to use
     #[arg(short = 's', long, help = "The name of the scenario to use")]
     scenario: String,
@@ -42,7 +47,17 @@ impl Execute for Compose {
             None => None,
         };
 
-        let order = DotrainOrder::new(dotrain, settings).await?;
+        let loaders = ImportLoaders::for_files(
+            Some(self.dotrain_file.as_path()),
+            self.settings_file.as_deref(),
+        );
+        let order = DotrainOrder::new_with_loaders(
+            dotrain,
+            settings,
+            &self.env_file_args.resolver()?,
+            &loaders,
+        )
+        .await?;
 
         let rainlang = if self.post {
             order
@@ -74,6 +89,7 @@ mod tests {
         let compose = Compose {
             dotrain_file: dotrain_path.into(),
             settings_file: None,
+            env_file_args: CliEnvFileArgs::default(),
             scenario: "some-scenario".to_string(),
             encoding: SupportedOutputEncoding::Hex,
             post: false,
@@ -95,6 +111,7 @@ mod tests {
         let compose = Compose {
             dotrain_file: dotrain_path.into(),
             settings_file: None,
+            env_file_args: CliEnvFileArgs::default(),
             scenario: "some-other-scenario".to_string(),
             encoding: SupportedOutputEncoding::Hex,
             post: false,
@@ -0,0 +1,165 @@
+use crate::execute::Execute;
+use crate::output::{output, SupportedOutputEncoding};
+use crate::subgraph::CliSubgraphArgs;
+use alloy::primitives::{hex::encode_prefixed, Address, B256};
+use anyhow::{anyhow, Result};
+use clap::Args;
+use rain_orderbook_common::export_order::{ExportNetwork, ExportOrderArgs};
+use rain_orderbook_common::subgraph::SubgraphArgs;
+use rain_orderbook_subgraph_client::{
+    types::common::{Bytes, OrdersListFilterArgs},
+    PaginationArgs,
+};
+use std::path::PathBuf;
+use url::Url;
+
+/// Reconstructs a .rain file from a live order so it can be edited,
+/// redeployed or fuzzed locally
+#[derive(Args, Clone)]
+pub struct CliOrderExportArgs {
+    #[arg(help = "Hash of the Order to export")]
+    order_hash: B256,
+
+    #[clap(flatten)]
+    pub subgraph_args: CliSubgraphArgs,
+
+    #[arg(
+        short = 'n',
+        long,
+        help = "Name of the network the Order is on, used as the key of the generated frontmatter"
+    )]
+    network: String,
+
+    #[arg(short = 'r', long, help = "RPC url of the network")]
+    rpc_url: Url,
+
+    #[arg(long, help = "Chain id of the network")]
+    chain_id: u64,
+
+    #[arg(
+        short = 'd',
+        long,
+        help = "Address of the deployer to parse the exported rainlang with"
+    )]
+    deployer: Address,
+
+    #[arg(
+        short,
+        long,
+        help = "Write the .rain file to this path instead of stdout"
+    )]
+    output: Option<PathBuf>,
+}
+
+impl Execute for CliOrderExportArgs {
+    async fn execute(&self) -> Result<()> {
+        let subgraph_args: SubgraphArgs = self.subgraph_args.clone().into();
+        let order = subgraph_args
+            .to_subgraph_client()
+            .await?
+            .orders_list(
+                OrdersListFilterArgs {
+                    owners: vec![],
+                    active: None,
+                    order_hash: Some(Bytes(encode_prefixed(self.order_hash))),
+                },
+                PaginationArgs {
+                    page: 1,
+                    page_size: 1,
+                },
+            )
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(anyhow!("order {} not found", self.order_hash))?;
+
+        let dotrain = ExportOrderArgs {
+            order,
+            network: ExportNetwork {
+                name: self.network.clone(),
+                rpc: self.rpc_url.clone(),
+                chain_id: self.chain_id,
+                deployer: self.deployer,
+                subgraph: Url::parse(&self.subgraph_args.subgraph_url)?,
+            },
+        }
+        .to_dotrain()?;
+
+        output(
+            &self.output,
+            SupportedOutputEncoding::Binary,
+            dotrain.as_bytes(),
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{CommandFactory, Parser};
+    use httpmock::MockServer;
+    use serde_json::json;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        export: CliOrderExportArgs,
+    }
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_cli_args() {
+        let order_hash = B256::random();
+        let deployer = Address::random();
+        let cli = Cli::try_parse_from([
+            "cmd",
+            &order_hash.to_string(),
+            "-s",
+            "https://some-subgraph.com",
+            "-n",
+            "base",
+            "-r",
+            "https://some-rpc.com",
+            "--chain-id",
+            "8453",
+            "-d",
+            &deployer.to_string(),
+            "-o",
+            "./order.rain",
+        ])
+        .unwrap();
+        assert_eq!(cli.export.order_hash, order_hash);
+        assert_eq!(cli.export.network, "base");
+        assert_eq!(cli.export.chain_id, 8453);
+        assert_eq!(cli.export.deployer, deployer);
+        assert_eq!(cli.export.output, Some(PathBuf::from("./order.rain")));
+    }
+
+    #[tokio::test]
+    async fn test_execute_order_not_found() {
+        let sg_server = MockServer::start();
+        sg_server.mock(|_when, then| {
+            then.json_body_obj(&json!({"data": {"orders": []}}));
+        });
+
+        let export = CliOrderExportArgs {
+            order_hash: B256::random(),
+            subgraph_args: CliSubgraphArgs {
+                subgraph_url: sg_server.url("/sg"),
+            },
+            network: "base".to_string(),
+            rpc_url: Url::parse("https://some-rpc.com").unwrap(),
+            chain_id: 8453,
+            deployer: Address::random(),
+            output: None,
+        };
+        let err = export.execute().await.unwrap_err();
+        assert!(err.to_string().contains("not found"));
+    }
+}
@@ -1,6 +1,8 @@
+use crate::env_file::CliEnvFileArgs;
 use crate::execute::Execute;
 use anyhow::{anyhow, Result};
 use clap::{ArgAction, Parser};
+use rain_orderbook_app_settings::imports::ImportLoaders;
 use rain_orderbook_common::dotrain_order::DotrainOrder;
 use std::fs::read_to_string;
 use std::path::PathBuf;
@@ -17,6 +19,9 @@ pub struct Filter {
     #[arg(short = 'c', long, value_name = "PATH")]
     settings_file: Option<PathBuf>,
 
+    #[clap(flatten)]
+    pub env_file_args: CliEnvFileArgs,
+
     /// List of deployment keys to include in the output .rain frontmatter
     #[arg(short = 'e', long, value_name = "DEPLOYMENT", num_args = 1..)]
     deployments: Vec<String>,
@@ -42,11 +47,18 @@ impl Execute for Filter {
         };
 
         // generate new dotrain order instance with cleaned up frontmatter
-        let order = DotrainOrder::new_with_frontmatter_filtered_by_deployment(
+        let loaders = ImportLoaders::for_files(
+            Some(self.dotrain_file.as_path()),
+            self.settings_file.as_deref(),
+        );
+        let order = DotrainOrder::new_with_loaders(
             dotrain,
-            self.deployments.clone(),
             settings,
+            &self.env_file_args.resolver()?,
+            &loaders,
         )
+        .await?
+        .filter_by_deployment(self.deployments.clone())
         .await?;
 
         // handle output
@@ -149,6 +161,7 @@ _ _: 0 0;
         let filter = Filter {
             dotrain_file: dotrain_path.into(),
             settings_file: Some(settings_path.into()),
+            env_file_args: CliEnvFileArgs::default(),
             deployments: vec!["some-deployment".to_string()],
             output: None,
             stdout: true,
@@ -165,6 +178,7 @@ _ _: 0 0;
         let filter = Filter {
             dotrain_file: "./bad-path/test.rain".into(),
             settings_file: None,
+            env_file_args: CliEnvFileArgs::default(),
             deployments: vec!["some-deployment".to_string()],
             output: None,
             stdout: true,
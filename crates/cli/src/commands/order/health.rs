@@ -0,0 +1,209 @@
+use crate::{execute::Execute, subgraph::CliSubgraphArgs};
+use alloy::primitives::{Address, B256};
+use anyhow::Result;
+use clap::{ArgGroup, Args};
+use comfy_table::Table;
+use rain_orderbook_common::{
+    order_health::{
+        get_order_health, get_owner_orders_health, OrderHealthReport, OrderProblemSeverity,
+    },
+    subgraph::SubgraphArgs,
+};
+use tracing::info;
+
+#[derive(Debug, clap::ValueEnum, Clone, PartialEq)]
+pub enum HealthOutputFormat {
+    Table,
+    Json,
+}
+
+#[derive(Args, Clone)]
+#[command(group(ArgGroup::new("target").required(true).args(["order_hash", "owner"])))]
+pub struct CliOrderHealthArgs {
+    #[arg(long, requires = "orderbook", help = "Hash of the Order to check")]
+    order_hash: Option<B256>,
+
+    #[arg(long, help = "Orderbook contract address of the Order")]
+    orderbook: Option<Address>,
+
+    #[arg(long, help = "Check all the Orders of this owner")]
+    owner: Option<Address>,
+
+    #[clap(flatten)]
+    subgraph_args: CliSubgraphArgs,
+
+    #[arg(
+        short,
+        long,
+        help = "RPC URL to quote the Orders and read the tokens with"
+    )]
+    rpc_url: String,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = HealthOutputFormat::Table,
+        help = "Output format of the report"
+    )]
+    format: HealthOutputFormat,
+}
+
+impl Execute for CliOrderHealthArgs {
+    async fn execute(&self) -> Result<()> {
+        let subgraph_args: SubgraphArgs = self.subgraph_args.clone().into();
+        let client = subgraph_args.to_subgraph_client().await?;
+
+        let reports = match (self.order_hash, self.orderbook, self.owner) {
+            (Some(order_hash), Some(orderbook), _) => {
+                vec![get_order_health(&client, &self.rpc_url, orderbook, order_hash).await?]
+            }
+            (_, _, Some(owner)) => get_owner_orders_health(&client, &self.rpc_url, owner).await?,
+            _ => anyhow::bail!("requires either '--order-hash' and '--orderbook' or '--owner'"),
+        };
+
+        match self.format {
+            HealthOutputFormat::Table => {
+                for report in &reports {
+                    info!("{}", report_title(report));
+                    info!("\n{}", build_table(report));
+                }
+            }
+            HealthOutputFormat::Json => println!("{}", serde_json::to_string_pretty(&reports)?),
+        }
+
+        Ok(())
+    }
+}
+
+fn report_title(report: &OrderHealthReport) -> String {
+    let status = if !report.active {
+        "❌ inactive"
+    } else if report.is_healthy() {
+        "✅ healthy"
+    } else {
+        "⚠️  unhealthy"
+    };
+    format!(
+        "Order {} on {} - {}",
+        report.order_hash, report.orderbook, status
+    )
+}
+
+fn build_table(report: &OrderHealthReport) -> Table {
+    let mut table = Table::new();
+    table
+        .load_preset(comfy_table::presets::UTF8_FULL)
+        .set_content_arrangement(comfy_table::ContentArrangement::Dynamic)
+        .set_header(vec!["Severity", "Problem"]);
+
+    for problem in &report.problems {
+        let severity = match problem.severity {
+            OrderProblemSeverity::Critical => "Critical",
+            OrderProblemSeverity::Warning => "Warning",
+            OrderProblemSeverity::Info => "Info",
+        };
+        table.add_row(vec![severity, problem.message.as_str()]);
+    }
+    if report.problems.is_empty() {
+        table.add_row(vec!["-", "No problems found"]);
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{CommandFactory, Parser};
+    use rain_orderbook_common::order_health::{OrderProblem, OrderProblemKind};
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        health: CliOrderHealthArgs,
+    }
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_cli_args() {
+        let order_hash = B256::random();
+        let orderbook = Address::random();
+        let cli = Cli::try_parse_from([
+            "cmd",
+            "--order-hash",
+            &order_hash.to_string(),
+            "--orderbook",
+            &orderbook.to_string(),
+            "-s",
+            "https://sg.com",
+            "-r",
+            "https://rpc.com",
+            "--format",
+            "json",
+        ])
+        .unwrap();
+        assert_eq!(cli.health.order_hash, Some(order_hash));
+        assert_eq!(cli.health.orderbook, Some(orderbook));
+        assert_eq!(cli.health.owner, None);
+        assert_eq!(cli.health.format, HealthOutputFormat::Json);
+
+        let owner = Address::random();
+        let cli = Cli::try_parse_from([
+            "cmd",
+            "--owner",
+            &owner.to_string(),
+            "-s",
+            "https://sg.com",
+            "-r",
+            "https://rpc.com",
+        ])
+        .unwrap();
+        assert_eq!(cli.health.owner, Some(owner));
+        assert_eq!(cli.health.format, HealthOutputFormat::Table);
+
+        // order hash requires the orderbook
+        assert!(Cli::try_parse_from([
+            "cmd",
+            "--order-hash",
+            &order_hash.to_string(),
+            "-s",
+            "https://sg.com",
+            "-r",
+            "https://rpc.com",
+        ])
+        .is_err());
+
+        // either an order or an owner is required
+        assert!(
+            Cli::try_parse_from(["cmd", "-s", "https://sg.com", "-r", "https://rpc.com"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_report_title() {
+        let mut report = OrderHealthReport {
+            order_hash: "0x01".to_string(),
+            orderbook: "0x02".to_string(),
+            owner: "0x03".to_string(),
+            active: true,
+            vaults: vec![],
+            quotes: vec![],
+            problems: vec![],
+        };
+        assert_eq!(report_title(&report), "Order 0x01 on 0x02 - ✅ healthy");
+
+        report.problems.push(OrderProblem {
+            severity: OrderProblemSeverity::Warning,
+            kind: OrderProblemKind::EmptyOutputVault("0x04".to_string()),
+            message: "Output vault 1 of T is empty".to_string(),
+        });
+        assert_eq!(report_title(&report), "Order 0x01 on 0x02 - ⚠️  unhealthy");
+
+        report.active = false;
+        assert_eq!(report_title(&report), "Order 0x01 on 0x02 - ❌ inactive");
+    }
+}
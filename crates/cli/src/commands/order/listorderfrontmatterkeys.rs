@@ -1,7 +1,9 @@
+use crate::env_file::CliEnvFileArgs;
 use crate::execute::Execute;
 use crate::output::{output, SupportedOutputEncoding};
 use anyhow::{anyhow, Result};
 use clap::Parser;
+use rain_orderbook_app_settings::imports::ImportLoaders;
 use rain_orderbook_common::dotrain_order::DotrainOrder;
 use std::fs::read_to_string;
 use std::path::PathBuf;
@@ -24,6 +26,9 @@ pub struct ListOrderFrontmatterKeys {
     #[arg(short = 'c', long, help = "Path to the settings yaml file")]
     settings_file: Option<PathBuf>,
 
+    #[clap(flatten)]
+    pub env_file_args: CliEnvFileArgs,
+
     #[arg(short = 'k', long, help = "Key Type", default_value = "deployment")]
     key_type: KeyType,
 
@@ -41,7 +46,17 @@ impl Execute for ListOrderFrontmatterKeys {
             None => None,
         };
 
-        let order = DotrainOrder::new(dotrain, settings).await?;
+        let loaders = ImportLoaders::for_files(
+            Some(self.dotrain_file.as_path()),
+            self.settings_file.as_deref(),
+        );
+        let order = DotrainOrder::new_with_loaders(
+            dotrain,
+            settings,
+            &self.env_file_args.resolver()?,
+            &loaders,
+        )
+        .await?;
 
         let keys_string = match self.key_type {
             KeyType::Deployment => {
@@ -204,6 +219,7 @@ _ _: 0 0;
         let keys = ListOrderFrontmatterKeys {
             dotrain_file: dotrain_path.into(),
             settings_file: None,
+            env_file_args: CliEnvFileArgs::default(),
             key_type: KeyType::Deployment,
             encoding: SupportedOutputEncoding::Binary,
         };
@@ -224,6 +240,7 @@ _ _: 0 0;
         let keys = ListOrderFrontmatterKeys {
             dotrain_file: dotrain_path.into(),
             settings_file: None,
+            env_file_args: CliEnvFileArgs::default(),
             key_type: KeyType::Scenario,
             encoding: SupportedOutputEncoding::Binary,
         };
@@ -2,11 +2,15 @@ mod add;
 mod calldata;
 mod compose;
 mod detail;
+mod export;
 mod filter;
+mod health;
 mod list;
 mod listorderfrontmatterkeys;
 mod orderbook_address;
+mod quote;
 mod remove;
+mod replace;
 
 use crate::commands::order::orderbook_address::OrderbookAddress;
 use crate::execute::Execute;
@@ -16,11 +20,16 @@ use calldata::AddOrderCalldata;
 use clap::Parser;
 use compose::Compose;
 use filter::Filter;
+use health::CliOrderHealthArgs;
 use listorderfrontmatterkeys::ListOrderFrontmatterKeys;
+pub(crate) use quote::parse_deposit;
+use quote::CliOrderQuoteArgs;
 
 use detail::CliOrderDetailArgs;
+use export::CliOrderExportArgs;
 use list::CliOrderListArgs;
 use remove::CliOrderRemoveArgs;
+use replace::CliOrderReplaceArgs;
 
 #[derive(Parser)]
 pub enum Order {
@@ -30,12 +39,21 @@ pub enum Order {
     #[command(about = "View an Order", alias = "view")]
     Detail(CliOrderDetailArgs),
 
+    #[command(about = "Reconstruct a .rain file from an Order")]
+    Export(CliOrderExportArgs),
+
     #[command(about = "Create an Order", alias = "add")]
     Create(CliOrderAddArgs),
 
     #[command(about = "Remove an Order", alias = "rm")]
     Remove(CliOrderRemoveArgs),
 
+    #[command(
+        about = "Replace an Order with a deployment in a single multicall, keeping its vaults",
+        alias = "upgrade"
+    )]
+    Replace(CliOrderReplaceArgs),
+
     #[command(about = "Compose a .rain order file to Rainlang", alias = "comp")]
     Compose(Compose),
 
@@ -51,6 +69,18 @@ pub enum Order {
     )]
     OrderbookAddress(OrderbookAddress),
 
+    #[command(
+        about = "Quote a deployment of a .rain file on a fork without deploying it",
+        alias = "q"
+    )]
+    Quote(CliOrderQuoteArgs),
+
+    #[command(
+        about = "Report the problems of an Order or all Orders of an owner",
+        alias = "doctor"
+    )]
+    Health(CliOrderHealthArgs),
+
     #[command(about = "Get frontmatter keys from a dotrain file", alias = "keys")]
     ListOrderFrontmatterKeys(ListOrderFrontmatterKeys),
     #[command()]
@@ -62,11 +92,15 @@ impl Execute for Order {
         match self {
             Order::List(list) => list.execute().await,
             Order::Detail(detail) => detail.execute().await,
+            Order::Export(export) => export.execute().await,
             Order::Create(create) => create.execute().await,
             Order::Remove(remove) => remove.execute().await,
+            Order::Replace(replace) => replace.execute().await,
             Order::Compose(compose) => compose.execute().await,
             Order::Calldata(calldata) => calldata.execute().await,
             Order::OrderbookAddress(orderbook_address) => orderbook_address.execute().await,
+            Order::Quote(quote) => quote.execute().await,
+            Order::Health(health) => health.execute().await,
             Order::ListOrderFrontmatterKeys(keys) => keys.execute().await,
             Order::Filter(filter) => filter.execute().await,
         }
@@ -1,7 +1,9 @@
+use crate::env_file::CliEnvFileArgs;
 use crate::execute::Execute;
 use crate::output::{output, SupportedOutputEncoding};
 use anyhow::{anyhow, Result};
 use clap::Parser;
+use rain_orderbook_app_settings::imports::ImportLoaders;
 use rain_orderbook_common::dotrain_order::DotrainOrder;
 use std::fs::read_to_string;
 use std::path::PathBuf;
@@ -18,6 +20,9 @@ pub struct OrderbookAddress {
     #[arg(short = 'c', long, help = "Path to the settings yaml file")]
     settings_file: Option<PathBuf>,
 
+    #[clap(flatten)]
+    pub env_file_args: CliEnvFileArgs,
+
     #[arg(short = 'e', long, help = "Deployment key to select from frontmatter")]
     deployment: String,
 
@@ -34,7 +39,17 @@ impl Execute for OrderbookAddress {
             }
             None => None,
         };
-        let order = DotrainOrder::new(dotrain, settings).await?;
+        let loaders = ImportLoaders::for_files(
+            Some(self.dotrain_file.as_path()),
+            self.settings_file.as_deref(),
+        );
+        let order = DotrainOrder::new_with_loaders(
+            dotrain,
+            settings,
+            &self.env_file_args.resolver()?,
+            &loaders,
+        )
+        .await?;
         let order_config = order.config().clone();
         let deployment_ref = order_config
             .deployments
@@ -185,6 +200,7 @@ _ _: 0 0;
         let orderbook_adress = OrderbookAddress {
             dotrain_file: dotrain_path.into(),
             settings_file: None,
+            env_file_args: CliEnvFileArgs::default(),
             deployment: "some-deployment".to_string(),
             encoding: SupportedOutputEncoding::Hex,
         };
@@ -205,6 +221,7 @@ _ _: 0 0;
         let orderbook_adress = OrderbookAddress {
             dotrain_file: dotrain_path.into(),
             settings_file: None,
+            env_file_args: CliEnvFileArgs::default(),
             deployment: "some-deployment".to_string(),
             encoding: SupportedOutputEncoding::Hex,
         };
@@ -0,0 +1,194 @@
+use crate::env_file::CliEnvFileArgs;
+use crate::execute::Execute;
+use alloy::primitives::Address;
+use anyhow::{anyhow, Result};
+use clap::Args;
+use rain_orderbook_app_settings::imports::ImportLoaders;
+use rain_orderbook_common::dotrain_order::{quote::DeploymentQuoteArgs, DotrainOrder};
+use rain_orderbook_quote::cli::{build_csv, build_table, OutputFormat, QuoterResultRow};
+use std::fs::read_to_string;
+use std::path::PathBuf;
+use tracing::info;
+
+#[derive(Args, Clone)]
+pub struct CliOrderQuoteArgs {
+    #[arg(
+        short = 'f',
+        long,
+        help = "Path to the .rain file specifying the order"
+    )]
+    dotrain_file: PathBuf,
+
+    #[arg(short = 'c', long, help = "Path to the settings yaml file")]
+    settings_file: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub env_file_args: CliEnvFileArgs,
+
+    #[arg(short = 'e', long, help = "Deployment key to select from frontmatter")]
+    deployment: String,
+
+    #[arg(
+        long,
+        help = "Address to add the order and deposit from on a fork, otherwise the order is quoted with a single eth_call"
+    )]
+    owner: Option<Address>,
+
+    #[arg(
+        short = 'd',
+        long = "deposit",
+        value_name = "TOKEN=AMOUNT",
+        value_parser = parse_deposit,
+        help = "Deposit into the vault of the given output token key before quoting, can be repeated, defaults to the first preset of each of the deployment's gui deposits"
+    )]
+    deposits: Vec<(String, String)>,
+
+    #[arg(short, long, help = "Block number to quote at, defaults to latest")]
+    block_number: Option<u64>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = OutputFormat::Table,
+        help = "Output format of the quotes"
+    )]
+    format: OutputFormat,
+}
+
+pub(crate) fn parse_deposit(value: &str) -> Result<(String, String), String> {
+    let (token, amount) = value
+        .split_once('=')
+        .ok_or(format!("expected TOKEN=AMOUNT, got: {}", value))?;
+    Ok((token.trim().to_string(), amount.trim().to_string()))
+}
+
+impl Execute for CliOrderQuoteArgs {
+    async fn execute(&self) -> Result<()> {
+        let dotrain = read_to_string(self.dotrain_file.clone()).map_err(|e| anyhow!(e))?;
+        let settings = match &self.settings_file {
+            Some(settings_file) => {
+                Some(read_to_string(settings_file.clone()).map_err(|e| anyhow!(e))?)
+            }
+            None => None,
+        };
+        let loaders = ImportLoaders::for_files(
+            Some(self.dotrain_file.as_path()),
+            self.settings_file.as_deref(),
+        );
+        let order = DotrainOrder::new_with_loaders(
+            dotrain,
+            settings,
+            &self.env_file_args.resolver()?,
+            &loaders,
+        )
+        .await?;
+
+        let deposits = if self.deposits.is_empty() {
+            order.preset_deposits(&self.deployment)
+        } else {
+            self.deposits.iter().cloned().collect()
+        };
+        let result = match self.owner {
+            Some(owner) => {
+                order
+                    .quote_deployment(
+                        &self.deployment,
+                        DeploymentQuoteArgs {
+                            owner: Some(owner),
+                            deposits,
+                            block_number: self.block_number,
+                        },
+                    )
+                    .await?
+            }
+            None => {
+                order
+                    .quote_deployment_with_rpc(&self.deployment, &deposits, self.block_number, None)
+                    .await?
+            }
+        };
+        let rows = result
+            .quotes
+            .iter()
+            .map(|(quote_target, quote_result)| {
+                QuoterResultRow::new(Some(quote_target), quote_result, &result.tokens)
+            })
+            .collect::<Vec<_>>();
+
+        match self.format {
+            OutputFormat::Table => {
+                info!("Quoted at block {}", result.block_number);
+                info!("\n{}", build_table(&rows));
+            }
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&rows)?),
+            OutputFormat::Csv => println!("{}", build_csv(&rows)?),
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        quote: CliOrderQuoteArgs,
+    }
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_cli_args() {
+        let owner = Address::random();
+        let cli = Cli::try_parse_from([
+            "cmd",
+            "-f",
+            "./some.rain",
+            "-e",
+            "some-deployment",
+            "--owner",
+            &owner.to_string(),
+            "-d",
+            "token1=10.5",
+            "--deposit",
+            "token2 = 3",
+            "-b",
+            "123",
+            "--format",
+            "csv",
+        ])
+        .unwrap();
+        assert_eq!(cli.quote.dotrain_file, PathBuf::from("./some.rain"));
+        assert_eq!(cli.quote.settings_file, None);
+        assert_eq!(cli.quote.deployment, "some-deployment");
+        assert_eq!(cli.quote.owner, Some(owner));
+        assert_eq!(
+            cli.quote.deposits,
+            vec![
+                ("token1".to_string(), "10.5".to_string()),
+                ("token2".to_string(), "3".to_string()),
+            ]
+        );
+        assert_eq!(cli.quote.block_number, Some(123));
+        assert_eq!(cli.quote.format, OutputFormat::Csv);
+
+        assert!(Cli::try_parse_from([
+            "cmd",
+            "-f",
+            "./some.rain",
+            "-e",
+            "some-deployment",
+            "-d",
+            "token1",
+        ])
+        .is_err());
+    }
+}
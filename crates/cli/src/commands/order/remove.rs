@@ -1,10 +1,17 @@
 use crate::{
-    execute::Execute, status::display_write_transaction_status, subgraph::CliSubgraphArgs,
+    execute::Execute,
+    preview::{display_preview, CliPreviewArgs},
+    safe::CliSafeArgs,
+    status::display_write_transaction_status,
+    subgraph::CliSubgraphArgs,
     transaction::CliTransactionArgs,
 };
 use anyhow::Result;
 use clap::Args;
+use rain_orderbook_bindings::IOrderBookV4::removeOrder2Call;
+use rain_orderbook_common::preview::PreviewBuilder;
 use rain_orderbook_common::remove_order::RemoveOrderArgs;
+use rain_orderbook_common::safe::SafeTransaction;
 use rain_orderbook_common::subgraph::SubgraphArgs;
 use rain_orderbook_common::transaction::TransactionArgs;
 use tracing::info;
@@ -19,6 +26,12 @@ pub struct CliOrderRemoveArgs {
 
     #[clap(flatten)]
     pub transaction_args: CliTransactionArgs,
+
+    #[clap(flatten)]
+    pub safe_args: CliSafeArgs,
+
+    #[clap(flatten)]
+    pub preview_args: CliPreviewArgs,
 }
 
 impl Execute for CliOrderRemoveArgs {
@@ -31,9 +44,40 @@ impl Execute for CliOrderRemoveArgs {
             .await?;
         let remove_order_args: RemoveOrderArgs = order.into();
 
-        let mut tx_args: TransactionArgs = self.transaction_args.clone().into();
+        let mut tx_args: TransactionArgs = self.transaction_args.clone().try_into()?;
         tx_args.try_fill_chain_id().await?;
 
+        let remove_order: removeOrder2Call = remove_order_args.clone().try_into()?;
+
+        let mut preview = PreviewBuilder::new(
+            tx_args.rpc_url.clone(),
+            self.safe_args.sender(&tx_args).await?,
+        )
+        .await?;
+        preview
+            .call(tx_args.orderbook_address, remove_order.clone())
+            .await?;
+        display_preview(&preview.finish().await?);
+
+        if self.safe_args.safe.is_some() {
+            return self
+                .safe_args
+                .propose(
+                    "Remove order",
+                    tx_args.chain_id.unwrap_or_default(),
+                    tx_args.rpc_url.clone(),
+                    vec![SafeTransaction::new(
+                        tx_args.orderbook_address,
+                        &remove_order,
+                    )],
+                )
+                .await;
+        }
+        if !self.preview_args.confirm()? {
+            info!("Aborted, no transactions sent");
+            return Ok(());
+        }
+
         info!("----- Remove Order -----");
         remove_order_args
             .execute(tx_args, |status| {
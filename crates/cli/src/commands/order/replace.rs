@@ -0,0 +1,322 @@
+use crate::env_file::CliEnvFileArgs;
+use crate::execute::Execute;
+use crate::journal::CliJournalArgs;
+use crate::preview::{display_preview, CliPreviewArgs};
+use crate::safe::CliSafeArgs;
+use crate::status::display_write_transaction_status;
+use crate::subgraph::CliSubgraphArgs;
+use crate::transaction::CliGasFeeSpeed;
+use alloy::primitives::{hex::encode_prefixed, B256, U256};
+use alloy_ethers_typecast::ethers_address_to_alloy;
+use anyhow::{anyhow, Result};
+use clap::{ArgAction, Args};
+use rain_orderbook_app_settings::imports::ImportLoaders;
+use rain_orderbook_common::dotrain_order::{replace::ReplaceArgs, DotrainOrder};
+use rain_orderbook_common::subgraph::SubgraphArgs;
+use rain_orderbook_common::transaction::TransactionArgs;
+use rain_orderbook_subgraph_client::{types::Id, utils::make_order_id};
+use std::fs::read_to_string;
+use std::path::PathBuf;
+use tracing::info;
+
+/// Replaces a live order with the order of a deployment in a single
+/// orderbook multicall, keeping its vaults and their balances
+#[derive(Args, Clone)]
+pub struct CliOrderReplaceArgs {
+    #[arg(short = 'i', long, help = "Hash of the live Order to replace")]
+    order_hash: B256,
+
+    #[arg(
+        short = 'f',
+        long,
+        help = "Path to the .rain file specifying the new order"
+    )]
+    dotrain_file: PathBuf,
+
+    #[arg(short = 'c', long, help = "Path to the settings yaml file")]
+    settings_file: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub env_file_args: CliEnvFileArgs,
+
+    #[arg(short = 'e', long, help = "Deployment key to select from frontmatter")]
+    deployment: String,
+
+    #[arg(
+        long,
+        help = "Path to the .rain file the live Order was deployed from, to show how its bindings change"
+    )]
+    previous_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Derive the new order's nonce, secret and vault ids from the owner, deployment and this salt, as when deploying it"
+    )]
+    salt: Option<String>,
+
+    #[clap(flatten)]
+    pub subgraph_args: CliSubgraphArgs,
+
+    #[arg(
+        long,
+        help = "Derivation index of the Ledger wallet address to use",
+        default_value = "0"
+    )]
+    derivation_index: Option<usize>,
+
+    #[arg(
+        short = 'p',
+        long,
+        help = "Max priority fee per gas (in wei)",
+        conflicts_with("gas_fee_speed")
+    )]
+    max_priority_fee_per_gas: Option<U256>,
+
+    #[arg(
+        short,
+        long,
+        help = "Max fee per gas (in wei)",
+        conflicts_with("gas_fee_speed")
+    )]
+    max_fee_per_gas: Option<U256>,
+
+    #[arg(
+        short,
+        long,
+        help = "Chooses sensible gas fees for a desired transaction speed.",
+        default_value = "medium"
+    )]
+    gas_fee_speed: Option<CliGasFeeSpeed>,
+
+    #[clap(flatten)]
+    safe_args: CliSafeArgs,
+
+    #[clap(flatten)]
+    preview_args: CliPreviewArgs,
+
+    #[clap(flatten)]
+    journal_args: CliJournalArgs,
+
+    /// Do NOT broadcast the transactions to the network, only show the
+    /// difference and simulate them
+    #[arg(long, action = ArgAction::SetTrue)]
+    no_broadcast: bool,
+}
+
+impl Execute for CliOrderReplaceArgs {
+    async fn execute(&self) -> Result<()> {
+        let settings = match &self.settings_file {
+            Some(settings_file) => {
+                Some(read_to_string(settings_file.clone()).map_err(|e| anyhow!(e))?)
+            }
+            None => None,
+        };
+        let dotrain = read_to_string(self.dotrain_file.clone()).map_err(|e| anyhow!(e))?;
+        let resolver = self.env_file_args.resolver()?;
+        let loaders = ImportLoaders::for_files(
+            Some(self.dotrain_file.as_path()),
+            self.settings_file.as_deref(),
+        );
+        let order =
+            DotrainOrder::new_with_loaders(dotrain, settings.clone(), &resolver, &loaders).await?;
+        let deployment = order
+            .config()
+            .deployments
+            .get(&self.deployment)
+            .ok_or(anyhow!("specified deployment is undefined!"))?
+            .clone();
+        let orderbook = deployment
+            .order
+            .orderbook
+            .as_ref()
+            .ok_or(anyhow!("deployment has no orderbook"))?
+            .clone();
+
+        let previous_bindings = match &self.previous_file {
+            Some(previous_file) => {
+                let previous = read_to_string(previous_file.clone()).map_err(|e| anyhow!(e))?;
+                let loaders = ImportLoaders::for_files(
+                    Some(previous_file.as_path()),
+                    self.settings_file.as_deref(),
+                );
+                let previous =
+                    DotrainOrder::new_with_loaders(previous, settings, &resolver, &loaders).await?;
+                let previous_deployment = previous
+                    .config()
+                    .deployments
+                    .get(&self.deployment)
+                    .ok_or(anyhow!(
+                        "specified deployment is undefined in the previous file!"
+                    ))?;
+                Some(previous_deployment.scenario.bindings.clone())
+            }
+            None => None,
+        };
+
+        let subgraph_args: SubgraphArgs = self.subgraph_args.clone().into();
+        let order_id = make_order_id(orderbook.address, U256::from_be_bytes(self.order_hash.0));
+        let live_order = subgraph_args
+            .to_subgraph_client()
+            .await?
+            .order_detail(Id::new(encode_prefixed(order_id)))
+            .await?;
+
+        let replacement = order
+            .prepare_replacement(
+                &self.deployment,
+                ReplaceArgs {
+                    order: live_order,
+                    previous_bindings,
+                    salt: self.salt.clone(),
+                },
+            )
+            .await?;
+
+        info!("----- Order Difference -----");
+        info!("\n{}", replacement.diff);
+        for vault_move in &replacement.moves {
+            info!(
+                "Move: {} of {} from vault {} into vault {}",
+                vault_move.amount,
+                vault_move.token,
+                vault_move.from_vault_id,
+                vault_move.to_vault_id
+            );
+        }
+
+        display_preview(&replacement.preview().await?);
+
+        if let Some(safe) = self.safe_args.safe {
+            if safe != replacement.owner {
+                return Err(anyhow!(
+                    "Safe {} is not the owner {} of the order",
+                    safe,
+                    replacement.owner
+                ));
+            }
+            replacement.check_balances().await?;
+            self.safe_args
+                .propose(
+                    "Replace order",
+                    orderbook.network.chain_id,
+                    replacement.rpc_url.clone(),
+                    replacement.safe_transactions(),
+                )
+                .await?;
+            info!("Would remove order once executed: {}", self.order_hash);
+            info!(
+                "Would add order once executed: {}",
+                replacement.order_hash()
+            );
+        } else if self.no_broadcast {
+            info!("Would remove order: {}", self.order_hash);
+            info!("Would add order: {}", replacement.order_hash());
+        } else {
+            if !self.preview_args.confirm()? {
+                info!("Aborted, no transactions sent");
+                return Ok(());
+            }
+            let tx_args = TransactionArgs {
+                derivation_index: self.derivation_index,
+                chain_id: Some(orderbook.network.chain_id),
+                max_priority_fee_per_gas: self.max_priority_fee_per_gas,
+                max_fee_per_gas: self.max_fee_per_gas,
+                gas_fee_speed: self.gas_fee_speed.clone().map(|g| g.into()),
+                journal: self.journal_args.flow()?,
+                ..TransactionArgs::for_network(&orderbook.network).await?
+            };
+            let sender = ethers_address_to_alloy(
+                tx_args
+                    .clone()
+                    .try_into_ledger_client()
+                    .await?
+                    .client
+                    .address(),
+            );
+            if sender != replacement.owner {
+                return Err(anyhow!(
+                    "Ledger wallet {} is not the owner {} of the order",
+                    sender,
+                    replacement.owner
+                ));
+            }
+
+            info!(
+                "----- Approve {} token(s), remove and add order -----",
+                replacement.approvals.len()
+            );
+            replacement
+                .execute(
+                    tx_args,
+                    display_write_transaction_status,
+                    display_write_transaction_status,
+                )
+                .await?;
+            info!("Removed order: {}", self.order_hash);
+            info!("Added order: {}", replacement.order_hash());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        replace: CliOrderReplaceArgs,
+    }
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_cli_args() {
+        let order_hash = B256::random();
+        let cli = Cli::try_parse_from([
+            "cmd",
+            "-i",
+            &order_hash.to_string(),
+            "-f",
+            "./some.rain",
+            "-e",
+            "some-deployment",
+            "--previous-file",
+            "./previous.rain",
+            "--salt",
+            "v2",
+            "-s",
+            "https://some-subgraph.com",
+            "--no-broadcast",
+        ])
+        .unwrap();
+        assert_eq!(cli.replace.order_hash, order_hash);
+        assert_eq!(cli.replace.deployment, "some-deployment");
+        assert_eq!(
+            cli.replace.previous_file,
+            Some(PathBuf::from("./previous.rain"))
+        );
+        assert_eq!(cli.replace.salt, Some("v2".to_string()));
+        assert!(cli.replace.no_broadcast);
+
+        assert!(Cli::try_parse_from([
+            "cmd",
+            "-i",
+            "not-a-hash",
+            "-f",
+            "./some.rain",
+            "-e",
+            "some-deployment",
+            "-s",
+            "https://some-subgraph.com",
+        ])
+        .is_err());
+    }
+}
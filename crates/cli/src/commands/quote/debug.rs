@@ -0,0 +1,212 @@
+use crate::{execute::Execute, subgraph::CliSubgraphArgs};
+use alloy::primitives::{hex::encode_prefixed, utils::format_units, Address, B256, U256};
+use anyhow::Result;
+use clap::Args;
+use comfy_table::Table;
+use rain_orderbook_app_settings::rpc::RpcProvider;
+use rain_orderbook_bindings::IOrderBookV4::Quote;
+use rain_orderbook_common::{
+    annotated_trace::{AnnotatedSourceTrace, AnnotatedTrace},
+    meta::TryDecodeRainlangSource,
+    subgraph::SubgraphArgs,
+};
+use rain_orderbook_quote::{NewQuoteDebugger, QuoteDebugger, QuoteTarget};
+use rain_orderbook_subgraph_client::{types::Id, utils::make_order_id};
+use reqwest::Url;
+use std::{path::PathBuf, str::FromStr};
+use tracing::info;
+
+#[derive(Args, Clone)]
+pub struct QuoteDebug {
+    #[arg(long, help = "Hash of the Order to debug")]
+    pub order_hash: B256,
+
+    #[arg(long, help = "Orderbook contract address of the Order")]
+    pub orderbook: Address,
+
+    #[arg(long, help = "Index of the Order's input IO", default_value = "0")]
+    pub input_io_index: u32,
+
+    #[arg(long, help = "Index of the Order's output IO", default_value = "0")]
+    pub output_io_index: u32,
+
+    #[clap(flatten)]
+    pub subgraph_args: CliSubgraphArgs,
+
+    #[arg(short, long, help = "RPC URL to fork from")]
+    pub rpc_url: String,
+
+    #[arg(short, long, help = "Block number to fork at, defaults to latest")]
+    pub block_number: Option<u64>,
+
+    #[arg(short, long, help = "Path to write the annotated trace into as JSON")]
+    pub output: Option<PathBuf>,
+}
+
+impl Execute for QuoteDebug {
+    async fn execute(&self) -> Result<()> {
+        let subgraph_args: SubgraphArgs = self.subgraph_args.clone().into();
+        let order = subgraph_args
+            .to_subgraph_client()
+            .await?
+            .order_detail(Id::new(encode_prefixed(make_order_id(
+                self.orderbook,
+                self.order_hash.into(),
+            ))))
+            .await?;
+        let rainlang = order
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.try_decode_rainlangsource().ok());
+
+        let quote_target = QuoteTarget {
+            orderbook: self.orderbook,
+            quote_config: Quote {
+                order: order.try_into()?,
+                inputIOIndex: U256::from(self.input_io_index),
+                outputIOIndex: U256::from(self.output_io_index),
+                signedContext: vec![],
+            },
+        };
+
+        let mut debugger = QuoteDebugger::new(NewQuoteDebugger {
+            rpc: RpcProvider::for_url(Url::from_str(&self.rpc_url)?),
+            fork_block_number: self.block_number,
+        })
+        .await?;
+        let (result, error) = debugger.debug(quote_target).await?;
+
+        let trace = AnnotatedTrace::new(
+            &result,
+            rainlang,
+            error.map(|v| match v {
+                Ok(e) => e.to_string(),
+                Err(e) => e.to_string(),
+            }),
+        );
+
+        for source in &trace.sources {
+            info!("{}\n{}", source_title(source), build_table(source));
+        }
+        match &trace.error {
+            Some(error) => info!("❌  Quote reverted: {}", error),
+            None => info!("✅  Quote succeeded"),
+        }
+
+        if let Some(output) = &self.output {
+            std::fs::write(output, serde_json::to_string_pretty(&trace)?)?;
+            info!("Trace written to {}", output.display());
+        }
+
+        Ok(())
+    }
+}
+
+fn source_title(source: &AnnotatedSourceTrace) -> String {
+    let mut title = format!("Source {}", source.source_index);
+    if let Some(name) = &source.name {
+        title.push_str(&format!(" ({})", name));
+    }
+    if let Some(parent) = source.parent_source_index {
+        title.push_str(&format!(", called by source {}", parent));
+    }
+    if source.reverted {
+        title.push_str(" - REVERTED");
+    }
+    title
+}
+
+fn build_table(source: &AnnotatedSourceTrace) -> Table {
+    let mut table = Table::new();
+    table
+        .load_preset(comfy_table::presets::UTF8_FULL)
+        .set_content_arrangement(comfy_table::ContentArrangement::Dynamic)
+        .set_header(vec!["Item", "Word", "Value", "Value (18 decimals)"]);
+
+    for item in &source.items {
+        let name = if item.reverted {
+            format!("❌ {}", item.name)
+        } else {
+            item.name.clone()
+        };
+        let (value, value_18) = match item.value {
+            Some(v) => (v.to_string(), format_units(v, 18).unwrap_or_default()),
+            None if item.reverted => ("reverted".to_string(), String::new()),
+            None => ("-".to_string(), String::new()),
+        };
+        table.add_row(vec![name, item.word.clone(), value, value_18]);
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rain_orderbook_common::annotated_trace::AnnotatedStackItem;
+
+    #[test]
+    fn test_source_title() {
+        let mut source = AnnotatedSourceTrace {
+            source_index: 2,
+            parent_source_index: Some(0),
+            name: Some("some-source".to_string()),
+            items: vec![],
+            reverted: false,
+        };
+        assert_eq!(
+            source_title(&source),
+            "Source 2 (some-source), called by source 0"
+        );
+
+        source.parent_source_index = None;
+        source.name = None;
+        source.reverted = true;
+        assert_eq!(source_title(&source), "Source 2 - REVERTED");
+    }
+
+    #[test]
+    fn test_build_table() {
+        let source = AnnotatedSourceTrace {
+            source_index: 0,
+            parent_source_index: None,
+            name: Some("calculate-io".to_string()),
+            items: vec![
+                AnnotatedStackItem {
+                    name: "amount".to_string(),
+                    word: "16".to_string(),
+                    value: Some(U256::from(16)),
+                    reverted: false,
+                },
+                AnnotatedStackItem {
+                    name: "price".to_string(),
+                    word: "sub(16 52)".to_string(),
+                    value: None,
+                    reverted: true,
+                },
+            ],
+            reverted: true,
+        };
+        let rows = build_table(&source)
+            .row_iter()
+            .map(|row| row.cell_iter().map(|v| v.content()).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            vec![
+                vec![
+                    "amount".to_string(),
+                    "16".to_string(),
+                    "16".to_string(),
+                    "0.000000000000000016".to_string(),
+                ],
+                vec![
+                    "❌ price".to_string(),
+                    "sub(16 52)".to_string(),
+                    "reverted".to_string(),
+                    "".to_string(),
+                ],
+            ]
+        );
+    }
+}
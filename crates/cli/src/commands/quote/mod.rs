@@ -1,8 +1,118 @@
+mod debug;
+
 use crate::execute::Execute;
-use rain_orderbook_quote::cli::Quoter;
+use anyhow::{anyhow, Result};
+use clap::{Args, Subcommand};
+use debug::QuoteDebug;
+use rain_orderbook_quote::cli::{Quoter, QuoterSeries};
+
+/// Quote orders, or debug an order's quote or quote it over a block range
+/// with its subcommands
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+pub struct Quote {
+    #[command(subcommand)]
+    pub command: Option<QuoteCommand>,
+
+    #[command(flatten)]
+    pub quoter: Option<Quoter>,
+}
+
+#[derive(Subcommand)]
+pub enum QuoteCommand {
+    #[command(about = "Debug an Order's quote on a fork with its full evaluation traces")]
+    Debug(QuoteDebug),
+
+    #[command(about = "Quote Orders at every Nth block of a block range")]
+    Series(QuoterSeries),
+}
+
+impl Execute for Quote {
+    async fn execute(&self) -> Result<()> {
+        match (&self.command, &self.quoter) {
+            (Some(QuoteCommand::Debug(debug)), _) => debug.execute().await,
+            (Some(QuoteCommand::Series(series)), _) => series.execute().await,
+            (None, Some(quoter)) => quoter.execute().await,
+            (None, None) => Err(anyhow!("expected quote arguments or a subcommand")),
+        }
+    }
+}
 
 impl Execute for Quoter {
-    async fn execute(&self) -> anyhow::Result<()> {
+    async fn execute(&self) -> Result<()> {
+        self.run().await.map(|_| ())
+    }
+}
+
+impl Execute for QuoterSeries {
+    async fn execute(&self) -> Result<()> {
         self.run().await.map(|_| ())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{CommandFactory, Parser};
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        quote: Quote,
+    }
+
+    #[test]
+    fn verify_command() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_parse_quote_args() {
+        let cli = Cli::try_parse_from([
+            "cmd",
+            "--rpc",
+            "https://rpc.com",
+            "--target",
+            "0x0000000000000000000000000000000000000000",
+            "0",
+            "0",
+            "0x",
+        ])
+        .unwrap();
+        assert!(cli.quote.command.is_none());
+        assert!(cli.quote.quoter.is_some());
+
+        let cli = Cli::try_parse_from([
+            "cmd",
+            "debug",
+            "--rpc-url",
+            "https://rpc.com",
+            "--subgraph-url",
+            "https://sg.com",
+            "--orderbook",
+            "0x0000000000000000000000000000000000000000",
+            "--order-hash",
+            "0x0000000000000000000000000000000000000000000000000000000000000001",
+        ])
+        .unwrap();
+        assert!(matches!(cli.quote.command, Some(QuoteCommand::Debug(_))));
+        assert!(cli.quote.quoter.is_none());
+
+        let cli = Cli::try_parse_from([
+            "cmd",
+            "series",
+            "--rpc",
+            "https://rpc.com",
+            "--target",
+            "0x0000000000000000000000000000000000000000",
+            "0",
+            "0",
+            "0x",
+            "--block-range",
+            "100..",
+        ])
+        .unwrap();
+        assert!(matches!(cli.quote.command, Some(QuoteCommand::Series(_))));
+        assert!(cli.quote.quoter.is_none());
+    }
+}
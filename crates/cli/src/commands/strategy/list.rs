@@ -0,0 +1,76 @@
+use crate::execute::Execute;
+use anyhow::Result;
+use clap::Args;
+use comfy_table::Table;
+use rain_orderbook_common::strategy::{StrategyManifest, StrategyRegistry};
+use std::path::PathBuf;
+use tracing::info;
+
+#[derive(Args, Clone)]
+pub struct CliStrategyListArgs {
+    #[arg(long, help = "Path to a directory of strategy packages")]
+    registry: PathBuf,
+}
+
+impl Execute for CliStrategyListArgs {
+    async fn execute(&self) -> Result<()> {
+        let registry = StrategyRegistry::load_dir(&self.registry)?;
+        info!("\n{}", build_table(&registry.list()));
+        Ok(())
+    }
+}
+
+fn build_table(manifests: &[&StrategyManifest]) -> Table {
+    let mut table = Table::new();
+    table
+        .load_preset(comfy_table::presets::UTF8_FULL)
+        .set_content_arrangement(comfy_table::ContentArrangement::Dynamic)
+        .set_header(vec!["Name", "Version", "Description", "Parameters"]);
+
+    for manifest in manifests {
+        let parameters = manifest
+            .parameters
+            .iter()
+            .map(|parameter| match &parameter.default {
+                Some(default) => format!("{} = {}", parameter.name, default),
+                None => parameter.name.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        table.add_row(vec![
+            manifest.name.clone(),
+            manifest.version.clone(),
+            manifest.description.clone(),
+            parameters,
+        ]);
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_table() {
+        let manifest: StrategyManifest = serde_yaml::from_str(
+            r#"
+name: grid
+version: 0.3.1
+description: Buys and sells along a price grid
+dotrain: grid.rain
+parameters:
+    - name: levels
+      default: "5"
+    - name: spacing
+"#,
+        )
+        .unwrap();
+        let table = build_table(&[&manifest]).to_string();
+        assert!(table.contains("grid"));
+        assert!(table.contains("0.3.1"));
+        assert!(table.contains("levels = 5"));
+        assert!(table.contains("spacing"));
+    }
+}
@@ -0,0 +1,121 @@
+mod list;
+mod pack;
+mod render;
+mod validate;
+
+use crate::execute::Execute;
+use anyhow::{anyhow, Result};
+use clap::{ArgGroup, Args, Parser};
+use list::CliStrategyListArgs;
+use pack::CliStrategyPackArgs;
+use rain_orderbook_common::strategy::{StrategyPackage, StrategyRegistry};
+use render::CliStrategyRenderArgs;
+use std::path::PathBuf;
+use validate::CliStrategyValidateArgs;
+
+#[derive(Parser)]
+pub enum Strategy {
+    #[command(about = "List the strategy packages of a registry directory")]
+    List(CliStrategyListArgs),
+
+    #[command(
+        about = "Check the manifests of strategy packages and run their tests",
+        alias = "test"
+    )]
+    Validate(CliStrategyValidateArgs),
+
+    #[command(
+        about = "Render a strategy package into a .rain file and settings for the given parameter values"
+    )]
+    Render(CliStrategyRenderArgs),
+
+    #[command(about = "Pack a strategy package directory into a single file archive")]
+    Pack(CliStrategyPackArgs),
+}
+
+impl Execute for Strategy {
+    async fn execute(&self) -> Result<()> {
+        match self {
+            Strategy::List(list) => list.execute().await,
+            Strategy::Validate(validate) => validate.execute().await,
+            Strategy::Render(render) => render.execute().await,
+            Strategy::Pack(pack) => pack.execute().await,
+        }
+    }
+}
+
+/// Where to load the strategy packages from, a single package or the
+/// packages of a registry directory
+#[derive(Args, Clone, Default)]
+#[command(group(ArgGroup::new("source").required(true).args(["package", "registry"])))]
+pub struct CliStrategySourceArgs {
+    #[arg(long, help = "Path to a strategy package directory or archive")]
+    pub package: Option<PathBuf>,
+
+    #[arg(long, help = "Path to a directory of strategy packages")]
+    pub registry: Option<PathBuf>,
+
+    #[arg(
+        long,
+        requires = "registry",
+        help = "Name of the strategy in the registry"
+    )]
+    pub name: Option<String>,
+
+    #[arg(
+        long,
+        requires = "name",
+        help = "Version of the strategy, defaults to its latest version"
+    )]
+    pub strategy_version: Option<String>,
+}
+
+impl CliStrategySourceArgs {
+    /// The package, or the named package of the registry
+    pub fn package(&self) -> Result<StrategyPackage> {
+        if let Some(package) = &self.package {
+            return Ok(StrategyPackage::load(package)?);
+        }
+        let name = self
+            .name
+            .as_ref()
+            .ok_or(anyhow!("--name is required with --registry"))?;
+        Ok(self
+            .load_registry()?
+            .get(name, self.strategy_version.as_deref())?
+            .clone())
+    }
+
+    /// The package, the named package of the registry or else all the
+    /// packages of the registry
+    pub fn packages(&self) -> Result<Vec<StrategyPackage>> {
+        if self.package.is_some() || self.name.is_some() {
+            return Ok(vec![self.package()?]);
+        }
+        Ok(self
+            .load_registry()?
+            .packages()
+            .into_iter()
+            .cloned()
+            .collect())
+    }
+
+    fn load_registry(&self) -> Result<StrategyRegistry> {
+        let registry = self
+            .registry
+            .as_ref()
+            .ok_or(anyhow!("--registry is required"))?;
+        Ok(StrategyRegistry::load_dir(registry)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn verify_command() {
+        Strategy::command().debug_assert();
+    }
+}
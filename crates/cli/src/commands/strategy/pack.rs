@@ -0,0 +1,32 @@
+use crate::execute::Execute;
+use crate::output::{output, SupportedOutputEncoding};
+use anyhow::Result;
+use clap::Args;
+use rain_orderbook_common::strategy::StrategyPackage;
+use std::path::PathBuf;
+
+#[derive(Args, Clone)]
+pub struct CliStrategyPackArgs {
+    #[arg(help = "Path to the strategy package directory")]
+    package: PathBuf,
+
+    #[arg(
+        short,
+        long,
+        help = "Write the archive to this path instead of stdout, conventionally with the .rainstrategy extension"
+    )]
+    output: Option<PathBuf>,
+}
+
+impl Execute for CliStrategyPackArgs {
+    async fn execute(&self) -> Result<()> {
+        let package = StrategyPackage::load_dir(&self.package)?;
+        package.validate()?;
+        output(
+            &self.output,
+            SupportedOutputEncoding::Binary,
+            package.to_archive()?.as_bytes(),
+        )?;
+        Ok(())
+    }
+}
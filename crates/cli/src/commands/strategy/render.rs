@@ -0,0 +1,110 @@
+use super::CliStrategySourceArgs;
+use crate::execute::Execute;
+use crate::output::{output, SupportedOutputEncoding};
+use anyhow::Result;
+use clap::Args;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[derive(Args, Clone)]
+pub struct CliStrategyRenderArgs {
+    #[clap(flatten)]
+    source_args: CliStrategySourceArgs,
+
+    #[arg(
+        long = "param",
+        value_name = "NAME=VALUE",
+        value_parser = parse_param,
+        help = "Value of a parameter of the strategy, can be repeated"
+    )]
+    params: Vec<(String, String)>,
+
+    #[arg(
+        short,
+        long,
+        help = "Write the .rain file to this path instead of stdout"
+    )]
+    output: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Write the rendered settings of the strategy to this path"
+    )]
+    settings_output: Option<PathBuf>,
+}
+
+fn parse_param(value: &str) -> Result<(String, String), String> {
+    let (name, value) = value
+        .split_once('=')
+        .ok_or(format!("expected NAME=VALUE, got: {}", value))?;
+    Ok((name.trim().to_string(), value.trim().to_string()))
+}
+
+impl Execute for CliStrategyRenderArgs {
+    async fn execute(&self) -> Result<()> {
+        let package = self.source_args.package()?;
+        package.validate()?;
+        let values: HashMap<String, String> = self.params.iter().cloned().collect();
+        let rendered = package.render(&values)?;
+        // the strategy must make an order with the values
+        package.instantiate(&values).await?;
+
+        if let (Some(settings), Some(settings_output)) = (&rendered.settings, &self.settings_output)
+        {
+            std::fs::write(settings_output, settings)?;
+        }
+        output(
+            &self.output,
+            SupportedOutputEncoding::Binary,
+            rendered.dotrain.as_bytes(),
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{CommandFactory, Parser};
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        render: CliStrategyRenderArgs,
+    }
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_cli_args() {
+        let cli = Cli::try_parse_from([
+            "cmd",
+            "--package",
+            "./dca.rainstrategy",
+            "--param",
+            "amount=10",
+            "--param",
+            "period = 3600",
+            "-o",
+            "./dca.rain",
+        ])
+        .unwrap();
+        assert_eq!(
+            cli.render.source_args.package,
+            Some(PathBuf::from("./dca.rainstrategy"))
+        );
+        assert_eq!(
+            cli.render.params,
+            vec![
+                ("amount".to_string(), "10".to_string()),
+                ("period".to_string(), "3600".to_string()),
+            ]
+        );
+        assert_eq!(cli.render.output, Some(PathBuf::from("./dca.rain")));
+
+        assert!(Cli::try_parse_from(["cmd", "--package", "./dca", "--param", "amount"]).is_err());
+    }
+}
@@ -0,0 +1,87 @@
+use super::CliStrategySourceArgs;
+use crate::execute::Execute;
+use anyhow::{anyhow, Result};
+use clap::Args;
+
+#[derive(Args, Clone)]
+pub struct CliStrategyValidateArgs {
+    #[clap(flatten)]
+    source_args: CliStrategySourceArgs,
+
+    #[arg(long, help = "Only check the manifests, without running the tests")]
+    skip_tests: bool,
+}
+
+impl Execute for CliStrategyValidateArgs {
+    async fn execute(&self) -> Result<()> {
+        let mut failures = 0;
+        for package in self.source_args.packages()? {
+            let id = format!("{}@{}", package.manifest.name, package.manifest.version);
+            if let Err(e) = package.validate() {
+                println!("{}: {}", id, e);
+                failures += 1;
+                continue;
+            }
+            if self.skip_tests {
+                println!("{}: ok", id);
+                continue;
+            }
+            for (test, result) in package.run_tests().await {
+                match result {
+                    Ok(()) => println!("{} {}: ok", id, test),
+                    Err(e) => {
+                        println!("{} {}: {}", id, test, e);
+                        failures += 1;
+                    }
+                }
+            }
+        }
+
+        if failures > 0 {
+            return Err(anyhow!("found {} failure(s)", failures));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{CommandFactory, Parser};
+    use std::path::PathBuf;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        validate: CliStrategyValidateArgs,
+    }
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_cli_args() {
+        let cli = Cli::try_parse_from([
+            "cmd",
+            "--registry",
+            "./strategies",
+            "--name",
+            "dca",
+            "--strategy-version",
+            "1.0.0",
+            "--skip-tests",
+        ])
+        .unwrap();
+        let source_args = cli.validate.source_args;
+        assert_eq!(source_args.registry, Some(PathBuf::from("./strategies")));
+        assert_eq!(source_args.name, Some("dca".to_string()));
+        assert_eq!(source_args.strategy_version, Some("1.0.0".to_string()));
+        assert!(cli.validate.skip_tests);
+
+        assert!(Cli::try_parse_from(["cmd"]).is_err());
+        assert!(Cli::try_parse_from(["cmd", "--package", "./dca", "--registry", "./"]).is_err());
+        assert!(Cli::try_parse_from(["cmd", "--package", "./dca", "--name", "dca"]).is_err());
+    }
+}
@@ -0,0 +1,317 @@
+use crate::commands::order::parse_deposit;
+use crate::execute::Execute;
+use crate::preview::{display_preview, CliPreviewArgs};
+use crate::safe::CliSafeArgs;
+use crate::status::display_write_transaction_status;
+use crate::subgraph::CliSubgraphArgs;
+use crate::transaction::CliTransactionArgs;
+use alloy::primitives::{Address, B256, U256};
+use anyhow::Result;
+use clap::{ArgAction, Args};
+use rain_orderbook_common::bulk_vault::{
+    prepare_bulk_vault_operation, BulkVaultOperation, VaultFilter,
+};
+use rain_orderbook_common::subgraph::SubgraphArgs;
+use rain_orderbook_common::transaction::TransactionArgs;
+use std::collections::HashMap;
+use tracing::info;
+
+/// Arguments shared by the bulk vault commands, selecting the vaults of the
+/// sender on the orderbook and how the multicall is sent
+#[derive(Args, Clone)]
+pub struct CliBulkVaultArgs {
+    #[arg(
+        long = "token",
+        help = "Only the vaults of this token address, can be repeated"
+    )]
+    tokens: Vec<Address>,
+
+    #[arg(long, help = "Only the vaults used by the Order with this hash")]
+    order_hash: Option<B256>,
+
+    #[arg(long, action = ArgAction::SetTrue, help = "Skip the vaults with a zero balance")]
+    hide_zero_balance: bool,
+
+    #[arg(
+        long,
+        action = ArgAction::SetTrue,
+        help = "Only the vaults that no active Order uses"
+    )]
+    inactive_orders_only: bool,
+
+    #[clap(flatten)]
+    subgraph_args: CliSubgraphArgs,
+
+    #[clap(flatten)]
+    transaction_args: CliTransactionArgs,
+
+    #[clap(flatten)]
+    safe_args: CliSafeArgs,
+
+    #[clap(flatten)]
+    preview_args: CliPreviewArgs,
+
+    /// Do NOT broadcast the transactions to the network, only preview them
+    #[arg(long, action = ArgAction::SetTrue)]
+    no_broadcast: bool,
+}
+
+impl From<&CliBulkVaultArgs> for VaultFilter {
+    fn from(val: &CliBulkVaultArgs) -> Self {
+        VaultFilter {
+            tokens: val.tokens.clone(),
+            order_hash: val.order_hash,
+            hide_zero_balance: val.hide_zero_balance,
+            inactive_orders_only: val.inactive_orders_only,
+        }
+    }
+}
+
+impl CliBulkVaultArgs {
+    /// Builds the multicall of the operation over the selected vaults,
+    /// previews it and then proposes it to the Safe or sends it
+    async fn run(&self, name: &str, operation: BulkVaultOperation) -> Result<()> {
+        let mut tx_args: TransactionArgs = self.transaction_args.clone().try_into()?;
+        tx_args.try_fill_chain_id().await?;
+        let owner = self.safe_args.sender(&tx_args).await?;
+
+        let subgraph_args: SubgraphArgs = self.subgraph_args.clone().into();
+        let transactions = prepare_bulk_vault_operation(
+            &subgraph_args.to_subgraph_client().await?,
+            owner,
+            tx_args.orderbook_address,
+            tx_args.rpc_url.clone(),
+            &VaultFilter::from(self),
+            &operation,
+        )
+        .await?;
+
+        for withdrawal in &transactions.withdrawals {
+            if withdrawal.targetAmount == U256::MAX {
+                info!(
+                    "Withdraw: all of {} from vault {}",
+                    withdrawal.token, withdrawal.vaultId
+                );
+            } else {
+                info!(
+                    "Withdraw: {} of {} from vault {}",
+                    withdrawal.targetAmount, withdrawal.token, withdrawal.vaultId
+                );
+            }
+        }
+        for deposit in &transactions.deposits {
+            info!(
+                "Deposit: {} of {} into vault {}",
+                deposit.amount, deposit.token, deposit.vaultId
+            );
+        }
+        display_preview(&transactions.preview().await?);
+
+        if self.safe_args.safe.is_some() {
+            transactions.check_balances().await?;
+            return self
+                .safe_args
+                .propose(
+                    name,
+                    tx_args.chain_id.unwrap_or_default(),
+                    tx_args.rpc_url.clone(),
+                    transactions.safe_transactions(),
+                )
+                .await;
+        }
+        if self.no_broadcast {
+            return Ok(());
+        }
+        if !self.preview_args.confirm()? {
+            info!("Aborted, no transactions sent");
+            return Ok(());
+        }
+
+        info!(
+            "----- Approve {} token(s), {} -----",
+            transactions.approvals.len(),
+            name.to_lowercase()
+        );
+        transactions
+            .execute(
+                tx_args,
+                display_write_transaction_status,
+                display_write_transaction_status,
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(Args, Clone)]
+pub struct CliVaultWithdrawAllArgs {
+    #[clap(flatten)]
+    bulk_args: CliBulkVaultArgs,
+}
+
+impl Execute for CliVaultWithdrawAllArgs {
+    async fn execute(&self) -> Result<()> {
+        self.bulk_args
+            .run("Withdraw all", BulkVaultOperation::WithdrawAll)
+            .await
+    }
+}
+
+#[derive(Args, Clone)]
+pub struct CliVaultSweepArgs {
+    #[arg(
+        long = "from",
+        help = "The ID of the vaults to move the balances out of"
+    )]
+    from_vault_id: U256,
+
+    #[arg(long = "to", help = "The ID of the vaults to move the balances into")]
+    to_vault_id: U256,
+
+    #[clap(flatten)]
+    bulk_args: CliBulkVaultArgs,
+}
+
+impl Execute for CliVaultSweepArgs {
+    async fn execute(&self) -> Result<()> {
+        self.bulk_args
+            .run(
+                "Sweep",
+                BulkVaultOperation::Sweep {
+                    from_vault_id: self.from_vault_id,
+                    to_vault_id: self.to_vault_id,
+                },
+            )
+            .await
+    }
+}
+
+#[derive(Args, Clone)]
+pub struct CliVaultTopUpArgs {
+    #[arg(
+        long = "target",
+        value_name = "TOKEN=AMOUNT",
+        value_parser = parse_target,
+        required = true,
+        help = "Balance to top up the vaults of the token address to, in human readable units, can be repeated"
+    )]
+    targets: Vec<(Address, String)>,
+
+    #[clap(flatten)]
+    bulk_args: CliBulkVaultArgs,
+}
+
+fn parse_target(value: &str) -> Result<(Address, String), String> {
+    let (token, amount) = parse_deposit(value)?;
+    let token = token
+        .parse::<Address>()
+        .map_err(|e| format!("invalid token address {}: {}", token, e))?;
+    Ok((token, amount))
+}
+
+impl Execute for CliVaultTopUpArgs {
+    async fn execute(&self) -> Result<()> {
+        self.bulk_args
+            .run(
+                "Top up",
+                BulkVaultOperation::TopUp {
+                    targets: HashMap::from_iter(self.targets.clone()),
+                },
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{CommandFactory, Parser};
+
+    #[derive(Parser)]
+    enum Cli {
+        WithdrawAll(CliVaultWithdrawAllArgs),
+        Sweep(CliVaultSweepArgs),
+        TopUp(CliVaultTopUpArgs),
+    }
+
+    const COMMON_ARGS: [&str; 6] = [
+        "-s",
+        "https://some-subgraph.com",
+        "-o",
+        "0x0000000000000000000000000000000000000001",
+        "-r",
+        "https://some-rpc.com",
+    ];
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_withdraw_all_args() {
+        let token = Address::random();
+        let order_hash = B256::random();
+        let mut args = vec![
+            "cmd".to_string(),
+            "withdraw-all".to_string(),
+            "--token".to_string(),
+            token.to_string(),
+            "--order-hash".to_string(),
+            order_hash.to_string(),
+            "--hide-zero-balance".to_string(),
+            "--inactive-orders-only".to_string(),
+            "--no-broadcast".to_string(),
+        ];
+        args.extend(COMMON_ARGS.map(String::from));
+        let Cli::WithdrawAll(cli) = Cli::try_parse_from(args).unwrap() else {
+            panic!("expected withdraw-all");
+        };
+        assert_eq!(
+            VaultFilter::from(&cli.bulk_args),
+            VaultFilter {
+                tokens: vec![token],
+                order_hash: Some(order_hash),
+                hide_zero_balance: true,
+                inactive_orders_only: true,
+            }
+        );
+        assert!(cli.bulk_args.no_broadcast);
+    }
+
+    #[test]
+    fn test_sweep_args() {
+        let mut args = vec!["cmd", "sweep", "--from", "1", "--to", "0x02"];
+        args.extend(COMMON_ARGS);
+        let Cli::Sweep(cli) = Cli::try_parse_from(args).unwrap() else {
+            panic!("expected sweep");
+        };
+        assert_eq!(cli.from_vault_id, U256::from(1));
+        assert_eq!(cli.to_vault_id, U256::from(2));
+        assert_eq!(VaultFilter::from(&cli.bulk_args), VaultFilter::default());
+
+        let mut args = vec!["cmd", "sweep", "--from", "1"];
+        args.extend(COMMON_ARGS);
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn test_top_up_args() {
+        let token = Address::random();
+        let target = format!("{}=10.5", token);
+        let mut args = vec!["cmd", "top-up", "--target", &target];
+        args.extend(COMMON_ARGS);
+        let Cli::TopUp(cli) = Cli::try_parse_from(args).unwrap() else {
+            panic!("expected top-up");
+        };
+        assert_eq!(cli.targets, vec![(token, "10.5".to_string())]);
+
+        let mut args = vec!["cmd", "top-up", "--target", "usdc=10"];
+        args.extend(COMMON_ARGS);
+        assert!(Cli::try_parse_from(args).is_err());
+
+        let mut args = vec!["cmd", "top-up"];
+        args.extend(COMMON_ARGS);
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+}
@@ -1,10 +1,18 @@
 use crate::{
-    execute::Execute, status::display_write_transaction_status, transaction::CliTransactionArgs,
+    execute::Execute,
+    preview::{display_preview, CliPreviewArgs},
+    safe::CliSafeArgs,
+    status::display_write_transaction_status,
+    transaction::CliTransactionArgs,
 };
 use alloy::primitives::{Address, U256};
 use anyhow::Result;
 use clap::Args;
-use rain_orderbook_common::{deposit::DepositArgs, transaction::TransactionArgs};
+use rain_orderbook_bindings::{IOrderBookV4::deposit2Call, IERC20::approveCall};
+use rain_orderbook_common::{
+    deposit::DepositArgs, preview::PreviewBuilder, safe::SafeTransaction,
+    transaction::TransactionArgs,
+};
 use tracing::info;
 
 #[derive(Args, Clone)]
@@ -20,6 +28,12 @@ pub struct CliVaultDepositArgs {
 
     #[clap(flatten)]
     pub transaction_args: CliTransactionArgs,
+
+    #[clap(flatten)]
+    pub safe_args: CliSafeArgs,
+
+    #[clap(flatten)]
+    pub preview_args: CliPreviewArgs,
 }
 
 impl From<CliVaultDepositArgs> for DepositArgs {
@@ -34,10 +48,46 @@ impl From<CliVaultDepositArgs> for DepositArgs {
 
 impl Execute for CliVaultDepositArgs {
     async fn execute(&self) -> Result<()> {
-        let mut tx_args: TransactionArgs = self.transaction_args.clone().into();
+        let mut tx_args: TransactionArgs = self.transaction_args.clone().try_into()?;
         tx_args.try_fill_chain_id().await?;
         let deposit_args: DepositArgs = self.clone().into();
 
+        let approve = approveCall {
+            spender: tx_args.orderbook_address,
+            amount: deposit_args.amount,
+        };
+        let deposit: deposit2Call = deposit_args.clone().into();
+
+        let mut preview = PreviewBuilder::new(
+            tx_args.rpc_url.clone(),
+            self.safe_args.sender(&tx_args).await?,
+        )
+        .await?;
+        preview.call(self.token, approve.clone()).await?;
+        preview
+            .call(tx_args.orderbook_address, deposit.clone())
+            .await?;
+        display_preview(&preview.finish().await?);
+
+        if self.safe_args.safe.is_some() {
+            return self
+                .safe_args
+                .propose(
+                    "Deposit",
+                    tx_args.chain_id.unwrap_or_default(),
+                    tx_args.rpc_url.clone(),
+                    vec![
+                        SafeTransaction::new(self.token, &approve),
+                        SafeTransaction::new(tx_args.orderbook_address, &deposit),
+                    ],
+                )
+                .await;
+        }
+        if !self.preview_args.confirm()? {
+            info!("Aborted, no transactions sent");
+            return Ok(());
+        }
+
         info!("----- Approve ERC20 token spend -----");
         deposit_args
             .execute_approve(tx_args.clone(), |status| {
@@ -1,3 +1,4 @@
+mod bulk;
 mod deposit;
 mod detail;
 mod list;
@@ -6,6 +7,7 @@ mod withdraw;
 
 use crate::execute::Execute;
 use anyhow::Result;
+use bulk::{CliVaultSweepArgs, CliVaultTopUpArgs, CliVaultWithdrawAllArgs};
 use clap::Parser;
 use deposit::CliVaultDepositArgs;
 use detail::CliVaultDetailArgs;
@@ -21,6 +23,15 @@ pub enum Vault {
     #[command(about = "Withdraw tokens from a Vault")]
     Withdraw(CliVaultWithdrawArgs),
 
+    #[command(about = "Withdraw the whole balance of the matching Vaults in a single multicall")]
+    WithdrawAll(CliVaultWithdrawAllArgs),
+
+    #[command(about = "Move the balances of the matching Vaults from one Vault ID to another")]
+    Sweep(CliVaultSweepArgs),
+
+    #[command(about = "Top up the matching Vaults to target balances in a single multicall")]
+    TopUp(CliVaultTopUpArgs),
+
     #[command(about = "List all Vaults", alias = "ls")]
     List(CliVaultListArgs),
 
@@ -36,6 +47,9 @@ impl Execute for Vault {
         match self {
             Vault::Deposit(deposit) => deposit.execute().await,
             Vault::Withdraw(withdraw) => withdraw.execute().await,
+            Vault::WithdrawAll(withdraw_all) => withdraw_all.execute().await,
+            Vault::Sweep(sweep) => sweep.execute().await,
+            Vault::TopUp(top_up) => top_up.execute().await,
             Vault::List(list) => list.execute().await,
             Vault::Detail(detail) => detail.execute().await,
             Vault::ListBalanceChanges(list_balance_changes) => list_balance_changes.execute().await,
@@ -1,8 +1,16 @@
 use crate::status::display_write_transaction_status;
-use crate::{execute::Execute, transaction::CliTransactionArgs};
+use crate::{
+    execute::Execute,
+    preview::{display_preview, CliPreviewArgs},
+    safe::CliSafeArgs,
+    transaction::CliTransactionArgs,
+};
 use alloy::primitives::{Address, U256};
 use anyhow::Result;
 use clap::Args;
+use rain_orderbook_bindings::IOrderBookV4::withdraw2Call;
+use rain_orderbook_common::preview::PreviewBuilder;
+use rain_orderbook_common::safe::SafeTransaction;
 use rain_orderbook_common::transaction::TransactionArgs;
 use rain_orderbook_common::withdraw::WithdrawArgs;
 use tracing::info;
@@ -20,6 +28,12 @@ pub struct CliVaultWithdrawArgs {
 
     #[clap(flatten)]
     transaction_args: CliTransactionArgs,
+
+    #[clap(flatten)]
+    safe_args: CliSafeArgs,
+
+    #[clap(flatten)]
+    preview_args: CliPreviewArgs,
 }
 
 impl From<CliVaultWithdrawArgs> for WithdrawArgs {
@@ -34,10 +48,38 @@ impl From<CliVaultWithdrawArgs> for WithdrawArgs {
 
 impl Execute for CliVaultWithdrawArgs {
     async fn execute(&self) -> Result<()> {
-        let mut tx_args: TransactionArgs = self.transaction_args.clone().into();
+        let mut tx_args: TransactionArgs = self.transaction_args.clone().try_into()?;
         tx_args.try_fill_chain_id().await?;
         let withdraw_args: WithdrawArgs = self.clone().into();
 
+        let withdraw: withdraw2Call = withdraw_args.clone().into();
+
+        let mut preview = PreviewBuilder::new(
+            tx_args.rpc_url.clone(),
+            self.safe_args.sender(&tx_args).await?,
+        )
+        .await?;
+        preview
+            .call(tx_args.orderbook_address, withdraw.clone())
+            .await?;
+        display_preview(&preview.finish().await?);
+
+        if self.safe_args.safe.is_some() {
+            return self
+                .safe_args
+                .propose(
+                    "Withdraw",
+                    tx_args.chain_id.unwrap_or_default(),
+                    tx_args.rpc_url.clone(),
+                    vec![SafeTransaction::new(tx_args.orderbook_address, &withdraw)],
+                )
+                .await;
+        }
+        if !self.preview_args.confirm()? {
+            info!("Aborted, no transactions sent");
+            return Ok(());
+        }
+
         info!("----- Withdraw tokens from Vault -----");
         withdraw_args
             .execute(tx_args, |status| {
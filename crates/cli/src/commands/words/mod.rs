@@ -1,7 +1,10 @@
+use crate::env_file::CliEnvFileArgs;
 use crate::execute::Execute;
 use anyhow::{anyhow, Result};
 use clap::{ArgAction, Args, Parser};
 use csv::Writer;
+use rain_orderbook_app_settings::imports::ImportLoaders;
+use rain_orderbook_app_settings::rpc::RpcProvider;
 use rain_orderbook_common::dotrain_order::{AuthoringMetaV2, DotrainOrder, WordsResult};
 use reqwest::Url;
 use std::{fs::read_to_string, path::PathBuf, str::FromStr, sync::Arc};
@@ -45,6 +48,9 @@ pub struct Words {
     /// Print the result on console (send result to std out)
     #[arg(long, action = ArgAction::SetTrue)]
     pub stdout: bool,
+
+    #[clap(flatten)]
+    pub env_file_args: CliEnvFileArgs,
 }
 
 /// Group of possible input files, at least one of dotrain file or
@@ -92,7 +98,17 @@ impl Execute for Words {
             }
             None => None,
         };
-        let mut order = DotrainOrder::new(dotrain, settings).await?;
+        let loaders = ImportLoaders::for_files(
+            self.input.dotrain_file.as_deref(),
+            self.input.settings_file.as_deref(),
+        );
+        let mut order = DotrainOrder::new_with_loaders(
+            dotrain,
+            settings,
+            &self.env_file_args.resolver()?,
+            &loaders,
+        )
+        .await?;
 
         let results = if let Some(deployer_key) = &self.source.deployer {
             // get deployer from order config
@@ -116,13 +132,20 @@ impl Execute for Words {
                 })
                 .ok_or(anyhow!("undefined metaboard subgraph url"))?;
 
-            AuthoringMetaV2::fetch_for_contract(
-                deployer.address,
-                deployer.network.rpc.to_string(),
-                metaboard_url,
-            )
-            .await?
-            .words
+            let metaboard_url = &metaboard_url;
+            RpcProvider::for_network(&deployer.network)
+                .with_failover(|rpc_url| async move {
+                    Ok::<_, anyhow::Error>(
+                        AuthoringMetaV2::fetch_for_contract(
+                            deployer.address,
+                            rpc_url.to_string(),
+                            metaboard_url.clone(),
+                        )
+                        .await?,
+                    )
+                })
+                .await?
+                .words
         } else if let Some(scenario) = &self.source.scenario {
             // set the cli given metaboard url into the config
             if let Some(v) = &self.metaboard_subgraph {
@@ -297,6 +320,7 @@ deployers:
             metaboard_subgraph: None,
             output: None,
             stdout: true,
+            env_file_args: CliEnvFileArgs::default(),
         };
 
         // should execute successfully
@@ -346,6 +370,7 @@ deployers:
             metaboard_subgraph: None,
             output: None,
             stdout: true,
+            env_file_args: CliEnvFileArgs::default(),
         };
 
         // should execute successfully
@@ -401,6 +426,7 @@ deployers:
             metaboard_subgraph: None,
             output: None,
             stdout: true,
+            env_file_args: CliEnvFileArgs::default(),
         };
 
         // should execute successfully
@@ -456,6 +482,7 @@ scenarios:
             metaboard_subgraph: Some(server.url("/sg").to_string()),
             output: None,
             stdout: true,
+            env_file_args: CliEnvFileArgs::default(),
         };
 
         // should execute successfully
@@ -518,6 +545,7 @@ scenarios:
             metaboard_subgraph: None,
             output: None,
             stdout: true,
+            env_file_args: CliEnvFileArgs::default(),
         };
 
         // should execute successfully
@@ -612,6 +640,7 @@ deployments:
             metaboard_subgraph: Some(server.url("/sg").to_string()),
             output: None,
             stdout: true,
+            env_file_args: CliEnvFileArgs::default(),
         };
 
         // should execute successfully
@@ -675,6 +704,7 @@ deployers:
             metaboard_subgraph: None,
             output: None,
             stdout: true,
+            env_file_args: CliEnvFileArgs::default(),
         };
 
         // should fail
@@ -0,0 +1,50 @@
+use anyhow::Result;
+use clap::Args;
+use rain_orderbook_app_settings::interpolation::{ChainResolver, DotEnvResolver, EnvResolver};
+use std::path::PathBuf;
+
+#[derive(Args, Clone, Debug, Default, PartialEq)]
+pub struct CliEnvFileArgs {
+    #[arg(
+        long,
+        help = "Path to a .env file to resolve the ${VAR} references of the config from, the process environment takes precedence"
+    )]
+    pub env_file: Option<PathBuf>,
+}
+
+impl CliEnvFileArgs {
+    /// Resolves variables from the process environment, falling back to the
+    /// env file if one is given
+    pub fn resolver(&self) -> Result<ChainResolver> {
+        let resolver = ChainResolver::new().with(EnvResolver);
+        Ok(match &self.env_file {
+            Some(path) => resolver.with(DotEnvResolver::from_file(path)?),
+            None => resolver,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rain_orderbook_app_settings::interpolation::VariableResolver;
+    use std::fs::write;
+
+    #[test]
+    fn test_resolver_reads_env_file() {
+        let path = std::env::temp_dir().join(format!("cli-env-file-{}.env", std::process::id()));
+        write(&path, "CLI_ENV_FILE_TEST_KEY=from-file\n").unwrap();
+
+        let args = CliEnvFileArgs {
+            env_file: Some(path.clone()),
+        };
+        let resolver = args.resolver().unwrap();
+        assert_eq!(
+            resolver.resolve("CLI_ENV_FILE_TEST_KEY"),
+            Some("from-file".to_string())
+        );
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(args.resolver().is_err());
+    }
+}
@@ -0,0 +1,152 @@
+use anyhow::{anyhow, Result};
+use clap::{ArgAction, Args};
+use rain_orderbook_common::journal::{JournalFlow, TransactionJournal};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::info;
+
+#[derive(Args, Clone, Default)]
+pub struct CliJournalArgs {
+    #[arg(
+        long,
+        help = "Path to the transaction journal, defaults to ~/.rain-orderbook/journal.json"
+    )]
+    pub journal: Option<PathBuf>,
+
+    #[arg(
+        long,
+        action = ArgAction::SetTrue,
+        conflicts_with_all = ["journal", "resume"],
+        help = "Do not record the transactions in the journal"
+    )]
+    pub no_journal: bool,
+
+    #[arg(
+        long,
+        help = "Id of a journal flow to resume, skipping the transactions it already confirmed"
+    )]
+    pub resume: Option<String>,
+
+    #[arg(
+        long,
+        action = ArgAction::SetTrue,
+        requires = "resume",
+        help = "Send the unconfirmed steps of the resumed flow again with their journaled calldata, even when it differs from the calls of this run"
+    )]
+    pub keep_journal_calldata: bool,
+}
+
+impl CliJournalArgs {
+    pub fn path(&self) -> Result<PathBuf> {
+        match &self.journal {
+            Some(path) => Ok(path.clone()),
+            None => default_journal_path(),
+        }
+    }
+
+    /// The flow the transactions of the command are recorded in, a new one
+    /// unless resuming
+    pub fn flow(&self) -> Result<Option<JournalFlow>> {
+        if self.no_journal {
+            return Ok(None);
+        }
+        let journal = Arc::new(TransactionJournal::open(self.path()?)?);
+        let flow = match &self.resume {
+            Some(id) => {
+                if journal.flow_entries(id).is_empty() {
+                    return Err(anyhow!("no flow {} in the journal", id));
+                }
+                JournalFlow::resume(journal, id.clone())
+                    .with_keep_calldata(self.keep_journal_calldata)
+            }
+            None => JournalFlow::new(journal),
+        };
+        info!(
+            "Journal flow: {} (resume with --resume {})",
+            flow.id(),
+            flow.id()
+        );
+        Ok(Some(flow))
+    }
+}
+
+pub fn default_journal_path() -> Result<PathBuf> {
+    let home = std::env::var_os("HOME").ok_or(anyhow!(
+        "HOME is not set, pass the journal path with --journal"
+    ))?;
+    Ok(PathBuf::from(home)
+        .join(".rain-orderbook")
+        .join("journal.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{CommandFactory, Parser};
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        journal_args: CliJournalArgs,
+    }
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_cli_args() {
+        let cli = Cli::try_parse_from([
+            "cmd",
+            "--journal",
+            "./journal.json",
+            "--resume",
+            "20240101-000000000",
+        ])
+        .unwrap();
+        assert_eq!(
+            cli.journal_args.path().unwrap(),
+            PathBuf::from("./journal.json")
+        );
+        assert_eq!(
+            cli.journal_args.resume,
+            Some("20240101-000000000".to_string())
+        );
+
+        assert!(!cli.journal_args.keep_journal_calldata);
+
+        assert!(Cli::try_parse_from(["cmd", "--no-journal", "--resume", "flow"]).is_err());
+        assert!(Cli::try_parse_from(["cmd", "--keep-journal-calldata"]).is_err());
+        assert!(
+            Cli::try_parse_from(["cmd", "--resume", "flow", "--keep-journal-calldata"])
+                .unwrap()
+                .journal_args
+                .keep_journal_calldata
+        );
+        assert!(Cli::try_parse_from(["cmd", "--no-journal"])
+            .unwrap()
+            .journal_args
+            .flow()
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_flow() {
+        let path = std::env::temp_dir().join(format!("cli-journal-{}.json", std::process::id()));
+        let journal_args = CliJournalArgs {
+            journal: Some(path.clone()),
+            ..Default::default()
+        };
+        let flow = journal_args.flow().unwrap().unwrap();
+        assert_eq!(flow.journal().path(), path);
+
+        // only flows in the journal can be resumed
+        let resume_args = CliJournalArgs {
+            resume: Some(flow.id().to_string()),
+            ..journal_args
+        };
+        assert!(resume_args.flow().is_err());
+    }
+}
@@ -1,12 +1,18 @@
-use crate::commands::{Analytics, Chart, Order, Subgraph, Trade, Vault, Words};
+use crate::commands::{
+    Analytics, Chart, Config, Deploy, Journal, Order, Quote, Strategy, Subgraph, Trade, Vault,
+    Words,
+};
 use crate::execute::Execute;
 use anyhow::Result;
 use clap::Subcommand;
-use rain_orderbook_quote::cli::Quoter;
 
 mod commands;
+mod env_file;
 mod execute;
+mod journal;
 mod output;
+mod preview;
+mod safe;
 mod status;
 mod subgraph;
 mod transaction;
@@ -27,12 +33,23 @@ pub enum Orderbook {
 
     Chart(Chart),
 
-    Quote(Quoter),
+    Quote(Quote),
 
     Words(Words),
 
     #[command(subcommand)]
     Analytics(Analytics),
+
+    #[command(subcommand)]
+    Config(Config),
+
+    Deploy(Deploy),
+
+    #[command(subcommand)]
+    Journal(Journal),
+
+    #[command(subcommand)]
+    Strategy(Strategy),
 }
 
 impl Orderbook {
@@ -46,6 +63,10 @@ impl Orderbook {
             Orderbook::Subgraph(subgraph) => subgraph.execute().await,
             Orderbook::Words(words) => words.execute().await,
             Orderbook::Analytics(analytics) => analytics.execute().await,
+            Orderbook::Config(config) => config.execute().await,
+            Orderbook::Deploy(deploy) => deploy.execute().await,
+            Orderbook::Journal(journal) => journal.execute().await,
+            Orderbook::Strategy(strategy) => strategy.execute().await,
         }
     }
 }
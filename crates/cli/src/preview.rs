@@ -0,0 +1,83 @@
+use anyhow::Result;
+use clap::{ArgAction, Args};
+use rain_orderbook_common::preview::TransactionPreview;
+use std::io::{BufRead, Write};
+use tracing::info;
+
+#[derive(Args, Clone, Default)]
+pub struct CliPreviewArgs {
+    /// Send the transactions after the preview without asking for confirmation
+    #[arg(short = 'y', long, action = ArgAction::SetTrue)]
+    pub yes: bool,
+}
+
+impl CliPreviewArgs {
+    /// Asks to confirm sending the previewed transactions, unless --yes is set
+    pub fn confirm(&self) -> Result<bool> {
+        if self.yes {
+            return Ok(true);
+        }
+        ask(std::io::stdin().lock(), std::io::stderr())
+    }
+}
+
+pub fn display_preview(preview: &TransactionPreview) {
+    info!("----- Transaction Preview -----");
+    info!("\n{}", preview);
+}
+
+fn ask(mut input: impl BufRead, mut output: impl Write) -> Result<bool> {
+    write!(output, "Send the transactions? [y/N] ")?;
+    output.flush()?;
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{CommandFactory, Parser};
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        preview_args: CliPreviewArgs,
+    }
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_confirm() {
+        assert!(Cli::try_parse_from(["cmd", "-y"])
+            .unwrap()
+            .preview_args
+            .confirm()
+            .unwrap());
+        assert!(!Cli::try_parse_from(["cmd"]).unwrap().preview_args.yes);
+    }
+
+    #[test]
+    fn test_ask() {
+        for (answer, expected) in [
+            ("y\n", true),
+            ("YES\n", true),
+            ("n\n", false),
+            ("\n", false),
+            ("", false),
+        ] {
+            let mut output = vec![];
+            assert_eq!(ask(answer.as_bytes(), &mut output).unwrap(), expected);
+            assert_eq!(
+                String::from_utf8(output).unwrap(),
+                "Send the transactions? [y/N] "
+            );
+        }
+    }
+}
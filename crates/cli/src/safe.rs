@@ -0,0 +1,157 @@
+use crate::output::{output, SupportedOutputEncoding};
+use alloy::primitives::{Address, U256};
+use alloy_ethers_typecast::ethers_address_to_alloy;
+use anyhow::{anyhow, Result};
+use clap::Args;
+use rain_orderbook_common::safe::{SafeBatch, SafeTransaction};
+use rain_orderbook_common::transaction::TransactionArgs;
+use std::path::PathBuf;
+use tracing::info;
+
+#[derive(Args, Clone, Default)]
+pub struct CliSafeArgs {
+    #[arg(
+        long,
+        help = "Address of the Safe sending the transactions, outputs a Safe Transaction Builder batch instead of sending them with the Ledger wallet"
+    )]
+    pub safe: Option<Address>,
+
+    #[arg(
+        long,
+        requires = "safe",
+        help = "Nonce to propose the Safe transaction at, defaults to the current nonce of the Safe"
+    )]
+    pub safe_nonce: Option<U256>,
+
+    #[arg(
+        long,
+        requires = "safe",
+        help = "Write the Safe Transaction Builder batch to this path instead of stdout"
+    )]
+    pub safe_output: Option<PathBuf>,
+}
+
+impl CliSafeArgs {
+    /// Address the transactions are sent from, the Safe if one is given or
+    /// else the Ledger wallet
+    pub async fn sender(&self, transaction_args: &TransactionArgs) -> Result<Address> {
+        match self.safe {
+            Some(safe) => Ok(safe),
+            None => Ok(ethers_address_to_alloy(
+                transaction_args
+                    .clone()
+                    .try_into_ledger_client()
+                    .await?
+                    .client
+                    .address(),
+            )),
+        }
+    }
+
+    /// Outputs the transactions as a Safe Transaction Builder batch and logs
+    /// the hash the Safe owners sign
+    pub async fn propose(
+        &self,
+        name: &str,
+        chain_id: u64,
+        rpc_url: String,
+        transactions: Vec<SafeTransaction>,
+    ) -> Result<()> {
+        let safe = self.safe.ok_or(anyhow!("no Safe address given"))?;
+        let nonce = match self.safe_nonce {
+            Some(nonce) => nonce,
+            None => SafeBatch::read_nonce(rpc_url, safe).await?,
+        };
+        let batch = SafeBatch {
+            transactions,
+            ..SafeBatch::new(chain_id, safe, nonce)
+        };
+        let json =
+            batch.to_transaction_builder_json(name, chrono::Utc::now().timestamp_millis())?;
+
+        info!("----- Safe Transaction -----");
+        info!("Safe: {}", safe);
+        info!("Nonce: {}", nonce);
+        info!("SafeTx hash: {}", batch.safe_tx_hash()?);
+        output(
+            &self.safe_output,
+            SupportedOutputEncoding::Binary,
+            json.as_bytes(),
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{CommandFactory, Parser};
+    use rain_orderbook_bindings::IOrderBookV4::withdraw2Call;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        safe_args: CliSafeArgs,
+    }
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_cli_args() {
+        let safe = Address::random();
+        let cli = Cli::try_parse_from([
+            "cmd",
+            "--safe",
+            &safe.to_string(),
+            "--safe-nonce",
+            "12",
+            "--safe-output",
+            "./batch.json",
+        ])
+        .unwrap();
+        assert_eq!(cli.safe_args.safe, Some(safe));
+        assert_eq!(cli.safe_args.safe_nonce, Some(U256::from(12)));
+        assert_eq!(
+            cli.safe_args.safe_output,
+            Some(PathBuf::from("./batch.json"))
+        );
+
+        assert!(Cli::try_parse_from(["cmd", "--safe-nonce", "12"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_propose() {
+        let path = "./test_safe_batch.json";
+        let safe_args = CliSafeArgs {
+            safe: Some(Address::random()),
+            safe_nonce: Some(U256::from(3)),
+            safe_output: Some(path.into()),
+        };
+        let orderbook = Address::random();
+        let withdraw = withdraw2Call {
+            token: Address::random(),
+            vaultId: U256::from(1),
+            targetAmount: U256::from(10),
+            tasks: vec![],
+        };
+        safe_args
+            .propose(
+                "Withdraw",
+                137,
+                "https://some-rpc.com".to_string(),
+                vec![SafeTransaction::new(orderbook, &withdraw)],
+            )
+            .await
+            .unwrap();
+
+        let batch: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(batch["chainId"], "137");
+        assert_eq!(batch["meta"]["name"], "Withdraw");
+        assert_eq!(batch["transactions"].as_array().unwrap().len(), 1);
+    }
+}
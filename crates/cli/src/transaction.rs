@@ -1,5 +1,7 @@
+use crate::journal::CliJournalArgs;
 use alloy::primitives::{Address, U256};
 use alloy_ethers_typecast::gas_fee_middleware::GasFeeSpeed;
+use anyhow::Result;
 use clap::Args;
 use rain_orderbook_common::transaction::TransactionArgs;
 
@@ -45,11 +47,16 @@ pub struct CliTransactionArgs {
         default_value = "medium"
     )]
     pub gas_fee_speed: Option<CliGasFeeSpeed>,
+
+    #[clap(flatten)]
+    pub journal_args: CliJournalArgs,
 }
 
-impl From<CliTransactionArgs> for TransactionArgs {
-    fn from(val: CliTransactionArgs) -> Self {
-        TransactionArgs {
+impl TryFrom<CliTransactionArgs> for TransactionArgs {
+    type Error = anyhow::Error;
+
+    fn try_from(val: CliTransactionArgs) -> Result<Self> {
+        Ok(TransactionArgs {
             orderbook_address: val.orderbook_address,
             derivation_index: val.derivation_index,
             chain_id: val.chain_id,
@@ -57,7 +64,8 @@ impl From<CliTransactionArgs> for TransactionArgs {
             max_priority_fee_per_gas: val.max_priority_fee_per_gas,
             max_fee_per_gas: val.max_fee_per_gas,
             gas_fee_speed: val.gas_fee_speed.map(|g| g.into()),
-        }
+            journal: val.journal_args.flow()?,
+        })
     }
 }
 
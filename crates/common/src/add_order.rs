@@ -1,15 +1,18 @@
 use crate::{
+    derivation::OrderDerivation,
     dotrain_order::DotrainOrderError,
+    meta::{EmbeddedSource, GuiStateMeta},
     rainlang::compose_to_rainlang,
+    token_metadata::TokenInfoCache,
     transaction::{TransactionArgs, TransactionArgsError},
 };
-use alloy::primitives::{hex::FromHexError, private::rand, Address, U256};
-use alloy::sol_types::SolCall;
+use alloy::primitives::{hex::FromHexError, keccak256, private::rand, Address, B256, U256};
+use alloy::sol_types::{SolCall, SolValue};
+#[cfg(not(target_family = "wasm"))]
+use alloy_ethers_typecast::transaction::WriteTransactionStatus;
 use alloy_ethers_typecast::transaction::{
     ReadContractParameters, ReadableClientError, ReadableClientHttp, WritableClientError,
 };
-#[cfg(not(target_family = "wasm"))]
-use alloy_ethers_typecast::transaction::{WriteTransaction, WriteTransactionStatus};
 use dotrain::error::ComposeError;
 use rain_interpreter_dispair::{DISPair, DISPairError};
 #[cfg(not(target_family = "wasm"))]
@@ -22,9 +25,15 @@ use rain_metadata::{
     ContentEncoding, ContentLanguage, ContentType, Error as RainMetaError, KnownMagic,
     RainMetaDocumentV1Item,
 };
-use rain_orderbook_app_settings::deployment::Deployment;
+use rain_orderbook_app_settings::{
+    deployment::Deployment,
+    rpc::{RpcProvider, RpcProviderError},
+    token::Token,
+};
 use rain_orderbook_bindings::{
-    IOrderBookV4::{addOrder2Call, EvaluableV3, OrderConfigV3, TaskV1, IO},
+    IOrderBookV4::{
+        addOrder2Call, orderExistsCall, EvaluableV3, OrderConfigV3, OrderV3, TaskV1, IO,
+    },
     ERC20::decimalsCall,
 };
 use serde::{Deserialize, Serialize};
@@ -60,6 +69,14 @@ pub enum AddOrderArgsError {
     #[cfg(not(target_family = "wasm"))]
     #[error(transparent)]
     ForkCallError(#[from] ForkCallError),
+    #[error(transparent)]
+    RpcProviderError(#[from] RpcProviderError),
+    #[error("Order hash can only be predicted for derived orders")]
+    NotDerived,
+    #[error("Order {0} is already live on the orderbook")]
+    OrderExists(B256),
+    #[error(transparent)]
+    SerdeJsonError(#[from] serde_json::Error),
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -70,64 +87,82 @@ pub struct AddOrderArgs {
     pub outputs: Vec<IO>,
     pub deployer: Address,
     pub bindings: HashMap<String, String>,
+    /// Derives the nonce, secret and vault ids instead of picking random ones
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub derivation: Option<OrderDerivation>,
+    /// Source embedded in the order meta next to the composed rainlang
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedded_source: Option<EmbeddedSource>,
 }
 
 impl AddOrderArgs {
+    /// Decimals of a token, taken from the token info cache or read from
+    /// chain when the config does not set them
+    async fn token_decimals(token: &Token) -> Result<u8, AddOrderArgsError> {
+        if let Some(decimals) = token.decimals {
+            return Ok(decimals);
+        }
+        if let Some(info) = TokenInfoCache::global().get(token.network.chain_id, token.address) {
+            return Ok(info.decimals);
+        }
+        RpcProvider::for_network(&token.network)
+            .with_failover(|rpc_url| async move {
+                let client = ReadableClientHttp::new_from_url(rpc_url.to_string())?;
+                let parameters = ReadContractParameters {
+                    address: token.address,
+                    call: decimalsCall {},
+                    block_number: None,
+                    gas: None,
+                };
+                Ok::<_, AddOrderArgsError>(client.read(parameters).await?._0)
+            })
+            .await
+    }
+
     /// create a new  instance from Deployment
     pub async fn new_from_deployment(
         dotrain: String,
         deployment: Deployment,
     ) -> Result<AddOrderArgs, AddOrderArgsError> {
-        let random_vault_id: U256 = rand::random();
+        Self::from_deployment(dotrain, deployment, None).await
+    }
+
+    /// create a new instance from Deployment whose nonce, secret and unset
+    /// vault ids are derived, so the same derivation always yields the same
+    /// order
+    pub async fn new_from_deployment_derived(
+        dotrain: String,
+        deployment: Deployment,
+        derivation: OrderDerivation,
+    ) -> Result<AddOrderArgs, AddOrderArgsError> {
+        Self::from_deployment(dotrain, deployment, Some(derivation)).await
+    }
+
+    async fn from_deployment(
+        dotrain: String,
+        deployment: Deployment,
+        derivation: Option<OrderDerivation>,
+    ) -> Result<AddOrderArgs, AddOrderArgsError> {
+        let default_vault_id = match &derivation {
+            Some(derivation) => derivation.vault_id(),
+            None => rand::random(),
+        };
         let mut inputs = vec![];
         for input in &deployment.order.inputs {
-            if let Some(decimals) = input.token.decimals {
-                inputs.push(IO {
-                    token: input.token.address,
-                    vaultId: input.vault_id.unwrap_or(random_vault_id),
-                    decimals,
-                });
-            } else {
-                let client = ReadableClientHttp::new_from_url(input.token.network.rpc.to_string())?;
-                let parameters = ReadContractParameters {
-                    address: input.token.address,
-                    call: decimalsCall {},
-                    block_number: None,
-                    gas: None,
-                };
-                let decimals = client.read(parameters).await?._0;
-                inputs.push(IO {
-                    token: input.token.address,
-                    vaultId: input.vault_id.unwrap_or(random_vault_id),
-                    decimals,
-                });
-            }
+            inputs.push(IO {
+                token: input.token.address,
+                vaultId: input.vault_id.unwrap_or(default_vault_id),
+                decimals: Self::token_decimals(&input.token).await?,
+            });
         }
 
         let mut outputs = vec![];
         for output in &deployment.order.outputs {
-            if let Some(decimals) = output.token.decimals {
-                outputs.push(IO {
-                    token: output.token.address,
-                    vaultId: output.vault_id.unwrap_or(random_vault_id),
-                    decimals,
-                });
-            } else {
-                let client =
-                    ReadableClientHttp::new_from_url(output.token.network.rpc.to_string())?;
-                let parameters = ReadContractParameters {
-                    address: output.token.address,
-                    call: decimalsCall {},
-                    block_number: None,
-                    gas: None,
-                };
-                let decimals = client.read(parameters).await?._0;
-                outputs.push(IO {
-                    token: output.token.address,
-                    vaultId: output.vault_id.unwrap_or(random_vault_id),
-                    decimals,
-                });
-            }
+            outputs.push(IO {
+                token: output.token.address,
+                vaultId: output.vault_id.unwrap_or(default_vault_id),
+                decimals: Self::token_decimals(&output.token).await?,
+            });
         }
 
         Ok(AddOrderArgs {
@@ -136,9 +171,21 @@ impl AddOrderArgs {
             outputs,
             deployer: deployment.scenario.deployer.address,
             bindings: deployment.scenario.bindings.to_owned(),
+            derivation,
+            embedded_source: None,
         })
     }
 
+    /// Embeds the dotrain, without its networks, and the given gui state in
+    /// the order meta, so the order can be audited against its source and
+    /// reopened in the gui
+    pub fn with_embedded_source(self, gui_state: Option<GuiStateMeta>) -> Self {
+        AddOrderArgs {
+            embedded_source: Some(EmbeddedSource::new(&self.dotrain, gui_state)),
+            ..self
+        }
+    }
+
     /// Read parser address from deployer contract, then call parser to parse rainlang into bytecode and constants
     async fn try_parse_rainlang(
         &self,
@@ -160,20 +207,21 @@ impl AddOrderArgs {
         Ok(rainlang_parsed.bytecode.into())
     }
 
-    /// Generate RainlangSource meta
+    /// Generate RainlangSource meta, followed by the embedded source if any
     fn try_generate_meta(&self, rainlang: String) -> Result<Vec<u8>, AddOrderArgsError> {
-        let meta_doc = RainMetaDocumentV1Item {
+        let mut meta_docs = vec![RainMetaDocumentV1Item {
             payload: ByteBuf::from(rainlang.as_bytes()),
             magic: KnownMagic::RainlangSourceV1,
             content_type: ContentType::OctetStream,
             content_encoding: ContentEncoding::None,
             content_language: ContentLanguage::None,
-        };
-        let meta_doc_bytes = RainMetaDocumentV1Item::cbor_encode_seq(
-            &vec![meta_doc],
-            KnownMagic::RainMetaDocumentV1,
-        )
-        .map_err(AddOrderArgsError::RainMetaError)?;
+        }];
+        if let Some(embedded_source) = &self.embedded_source {
+            meta_docs.extend(embedded_source.to_meta_items()?);
+        }
+        let meta_doc_bytes =
+            RainMetaDocumentV1Item::cbor_encode_seq(&meta_docs, KnownMagic::RainMetaDocumentV1)
+                .map_err(AddOrderArgsError::RainMetaError)?;
 
         Ok(meta_doc_bytes)
     }
@@ -239,28 +287,88 @@ impl AddOrderArgs {
                     bytecode: bytecode.into(),
                 },
                 meta: meta.into(),
-                nonce: alloy::primitives::private::rand::random::<U256>().into(),
-                secret: alloy::primitives::private::rand::random::<U256>().into(),
+                nonce: match &self.derivation {
+                    Some(derivation) => derivation.nonce(),
+                    None => rand::random::<U256>().into(),
+                },
+                secret: match &self.derivation {
+                    Some(derivation) => derivation.secret(),
+                    None => rand::random::<U256>().into(),
+                },
             },
             tasks: vec![post_task],
         })
     }
 
+    /// Hash of the order the call adds, known ahead of sending it for
+    /// derived orders as their owner is part of the derivation
+    pub fn order_hash(&self, call: &addOrder2Call) -> Result<B256, AddOrderArgsError> {
+        let derivation = self
+            .derivation
+            .as_ref()
+            .ok_or(AddOrderArgsError::NotDerived)?;
+        let order = OrderV3 {
+            owner: derivation.owner,
+            evaluable: call.config.evaluable.clone(),
+            validInputs: call.config.validInputs.clone(),
+            validOutputs: call.config.validOutputs.clone(),
+            nonce: call.config.nonce,
+        };
+        Ok(keccak256(order.abi_encode()))
+    }
+
+    /// Predicts the hash of the order before it is added. The order hash
+    /// covers the evaluable, whose bytecode comes from the parser of the
+    /// deployer, so the rpc is needed to read the deployer and parse the
+    /// rainlang. Use [`AddOrderArgs::order_hash`] to hash an already built
+    /// call without reading from the chain
+    pub async fn predict_order_hash(&self, rpc_url: String) -> Result<B256, AddOrderArgsError> {
+        let call = self.try_into_call(rpc_url).await?;
+        self.order_hash(&call)
+    }
+
+    /// Errors if a derived order is already live on the orderbook, as adding
+    /// it again would not change anything
+    #[cfg(not(target_family = "wasm"))]
+    pub async fn ensure_not_live(
+        &self,
+        transaction_args: &TransactionArgs,
+        call: &addOrder2Call,
+    ) -> Result<(), AddOrderArgsError> {
+        if self.derivation.is_none() {
+            return Ok(());
+        }
+        let order_hash = self.order_hash(call)?;
+        let client = ReadableClientHttp::new_from_url(transaction_args.rpc_url.clone())?;
+        let parameters = ReadContractParameters {
+            address: transaction_args.orderbook_address,
+            call: orderExistsCall {
+                orderHash: order_hash,
+            },
+            block_number: None,
+            gas: None,
+        };
+        if client.read(parameters).await?.exists {
+            return Err(AddOrderArgsError::OrderExists(order_hash));
+        }
+        Ok(())
+    }
+
     #[cfg(not(target_family = "wasm"))]
     pub async fn execute<S: Fn(WriteTransactionStatus<addOrder2Call>)>(
         &self,
         transaction_args: TransactionArgs,
         transaction_status_changed: S,
     ) -> Result<(), AddOrderArgsError> {
-        let ledger_client = transaction_args.clone().try_into_ledger_client().await?;
-
         let add_order_call = self.try_into_call(transaction_args.clone().rpc_url).await?;
-        let params = transaction_args
-            .try_into_write_contract_parameters(add_order_call, transaction_args.orderbook_address)
+        self.ensure_not_live(&transaction_args, &add_order_call)
             .await?;
-
-        WriteTransaction::new(ledger_client.client, params, 4, transaction_status_changed)
-            .execute()
+        transaction_args
+            .send(
+                add_order_call,
+                transaction_args.orderbook_address,
+                transaction_status_changed,
+            )
             .await?;
 
         Ok(())
@@ -303,6 +411,7 @@ impl AddOrderArgs {
         )
         .await?;
         let call = self.try_into_call(transaction_args.rpc_url.clone()).await?;
+        self.ensure_not_live(&transaction_args, &call).await?;
         forker
             .alloy_call_committing(
                 Address::from(from_address),
@@ -319,8 +428,10 @@ impl AddOrderArgs {
 #[cfg(test)]
 mod tests {
     use crate::dotrain_order::DotrainOrder;
+    use crate::meta::{TryDecodeEmbeddedSource, TryDecodeRainlangSource};
 
     use super::*;
+    use alloy::primitives::hex::encode_prefixed;
     use rain_orderbook_app_settings::{
         deployer::Deployer,
         network::Network,
@@ -328,8 +439,9 @@ mod tests {
         scenario::Scenario,
         token::Token,
     };
+    use rain_orderbook_subgraph_client::types::common::Bytes;
     use rain_orderbook_test_fixtures::LocalEvm;
-    use std::sync::Arc;
+    use std::{collections::BTreeMap, sync::Arc};
     use url::Url;
 
     #[test]
@@ -351,6 +463,8 @@ price: 2e18;
             outputs: vec![],
             bindings: HashMap::new(),
             deployer: Address::default(),
+            derivation: None,
+            embedded_source: None,
         };
 
         let meta_bytes = args.try_generate_meta(dotrain_body).unwrap();
@@ -369,6 +483,47 @@ price: 2e18;
         );
     }
 
+    #[test]
+    fn test_try_generate_meta_with_embedded_source() {
+        let gui_state = GuiStateMeta {
+            deployment_name: "some-deployment".to_string(),
+            state: "H4sIAAAAAAAA_w".to_string(),
+            select_tokens: BTreeMap::from([("token1".to_string(), Address::random())]),
+        };
+        let args = AddOrderArgs {
+            dotrain: "networks:\n  some: https://some-rpc.com/key\nsome: frontmatter\n---\n#calculate-io\n_: 1;".into(),
+            inputs: vec![],
+            outputs: vec![],
+            bindings: HashMap::new(),
+            deployer: Address::default(),
+            derivation: None,
+            embedded_source: None,
+        }
+        .with_embedded_source(Some(gui_state.clone()));
+
+        let meta = Bytes(encode_prefixed(
+            args.try_generate_meta("_: 1;".to_string()).unwrap(),
+        ));
+        assert_eq!(meta.try_decode_rainlangsource().unwrap(), "_: 1;");
+        assert_eq!(
+            meta.try_decode_embedded_source().unwrap(),
+            Some(EmbeddedSource {
+                dotrain: "some: frontmatter\n---\n#calculate-io\n_: 1;".to_string(),
+                gui_state: Some(gui_state),
+            })
+        );
+
+        let meta = Bytes(encode_prefixed(
+            AddOrderArgs {
+                embedded_source: None,
+                ..args
+            }
+            .try_generate_meta("_: 1;".to_string())
+            .unwrap(),
+        ));
+        assert_eq!(meta.try_decode_embedded_source().unwrap(), None);
+    }
+
     #[test]
     fn test_order_config_v2_validity() {
         let inputs = vec![
@@ -430,6 +585,7 @@ price: 2e18;
         let network = Network {
             name: "test-network".to_string(),
             rpc: Url::parse("https://some-rpc.com").unwrap(),
+            rpcs: vec![],
             chain_id: 137,
             label: None,
             network_id: None,
@@ -448,6 +604,7 @@ price: 2e18;
             runs: None,
             blocks: None,
             deployer: deployer_arc.clone(),
+            parameters: HashMap::new(),
         };
         let token1 = Token {
             address: Address::default(),
@@ -528,6 +685,7 @@ _ _: 0 0;
         let network = Network {
             name: "test-network".to_string(),
             rpc: Url::parse(&local_evm.url()).unwrap(),
+            rpcs: vec![],
             chain_id: 137,
             label: None,
             network_id: None,
@@ -546,6 +704,7 @@ _ _: 0 0;
             runs: None,
             blocks: None,
             deployer: deployer_arc.clone(),
+            parameters: HashMap::new(),
         };
         let token1 = Token {
             address: Address::default(),
@@ -660,6 +819,7 @@ _ _: 0 0;
         let network = Network {
             name: "test-network".to_string(),
             rpc: Url::parse("https://some-rpc.com").unwrap(),
+            rpcs: vec![],
             chain_id: 137,
             label: None,
             network_id: None,
@@ -678,6 +838,7 @@ _ _: 0 0;
             runs: None,
             blocks: None,
             deployer: deployer_arc.clone(),
+            parameters: HashMap::new(),
         };
         let token1 = Token {
             address: Address::default(),
@@ -912,4 +1073,112 @@ _ _: 16 52;
             .await
             .expect_err("expected to fail but resolved");
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_derived_order() {
+        let local_evm = LocalEvm::new_with_tokens(2).await;
+
+        let orderbook = &local_evm.orderbook;
+        let owner = local_evm.signer_wallets[0].default_signer().address();
+        let token1 = local_evm.tokens[0].clone();
+        let token2 = local_evm.tokens[1].clone();
+
+        let dotrain = format!(
+            r#"
+networks:
+    some-key:
+        rpc: {rpc_url}
+        chain-id: 123
+        network-id: 123
+        currency: ETH
+deployers:
+    some-key:
+        address: {deployer}
+tokens:
+    t1:
+        network: some-key
+        address: {token2}
+        decimals: 18
+    t2:
+        network: some-key
+        address: {token1}
+        decimals: 18
+orders:
+    some-key:
+        inputs:
+            - token: t1
+        outputs:
+            - token: t2
+              vault-id: 0x01
+scenarios:
+    some-key:
+deployments:
+    some-key:
+        scenario: some-key
+        order: some-key
+---
+#calculate-io
+_ _: 16 52;
+#handle-add-order
+:;
+#handle-io
+:;
+"#,
+            rpc_url = local_evm.url(),
+            deployer = local_evm.deployer.address(),
+            token1 = token1.address(),
+            token2 = token2.address(),
+        );
+
+        let order = DotrainOrder::new(dotrain.clone(), None).await.unwrap();
+        let deployment = order.config().deployments["some-key"].as_ref().clone();
+        let derivation = OrderDerivation {
+            owner,
+            deployment: "some-key".to_string(),
+            salt: "salt".to_string(),
+        };
+        let args = AddOrderArgs::new_from_deployment_derived(
+            dotrain.clone(),
+            deployment.clone(),
+            derivation.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(args.inputs[0].vaultId, derivation.vault_id());
+        assert_eq!(args.outputs[0].vaultId, U256::from(1));
+
+        // the same derivation yields the same order
+        let call = args.try_into_call(local_evm.url()).await.unwrap();
+        assert_eq!(call.config.nonce, derivation.nonce());
+        assert_eq!(call.config.secret, derivation.secret());
+        let order_hash = args.predict_order_hash(local_evm.url()).await.unwrap();
+        assert_eq!(args.order_hash(&call).unwrap(), order_hash);
+
+        let transaction_args = TransactionArgs {
+            orderbook_address: *orderbook.address(),
+            rpc_url: local_evm.url(),
+            ..Default::default()
+        };
+        args.simulate_execute(transaction_args.clone(), Some(owner))
+            .await
+            .unwrap();
+
+        let (event, _) = local_evm.add_order(&call.abi_encode(), owner).await;
+        assert_eq!(event.orderHash, order_hash);
+
+        // a live order is not added again
+        let err = args
+            .simulate_execute(transaction_args, Some(owner))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AddOrderArgsError::OrderExists(hash) if hash == order_hash));
+
+        let random = AddOrderArgs::new_from_deployment(dotrain, deployment)
+            .await
+            .unwrap();
+        assert!(matches!(
+            random.order_hash(&call),
+            Err(AddOrderArgsError::NotDerived)
+        ));
+    }
 }
@@ -0,0 +1,425 @@
+use alloy::primitives::U256;
+use rain_interpreter_eval::trace::RainEvalResult;
+use serde::{Deserialize, Serialize};
+
+/// A line of a composed rainlang source, ie `lhs items: rhs items`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct RainlangLine {
+    pub lhs: Vec<String>,
+    pub rhs: Vec<String>,
+}
+
+impl RainlangLine {
+    /// Returns the rhs word(s) that produce the lhs item at the given index,
+    /// if rhs items do not map 1:1 to lhs items the whole rhs is returned
+    pub fn word_of(&self, lhs_index: usize) -> String {
+        if self.lhs.len() == self.rhs.len() {
+            self.rhs[lhs_index].clone()
+        } else {
+            self.rhs.join(" ")
+        }
+    }
+
+    /// Returns the indexes of the sources this line calls with `call<>` word
+    fn called_sources(&self, sources: &[RainlangSource]) -> Vec<usize> {
+        self.rhs
+            .iter()
+            .filter_map(|word| {
+                let operand = word.strip_prefix("call<")?.split('>').next()?.trim();
+                match operand.strip_prefix('\'') {
+                    Some(name) => sources.iter().position(|v| v.name.as_deref() == Some(name)),
+                    None => operand.parse::<usize>().ok(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// A source of a composed rainlang text
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct RainlangSource {
+    pub name: Option<String>,
+    pub lines: Vec<RainlangLine>,
+}
+
+/// Parses a composed rainlang text (such as the one embedded in an order's
+/// meta as RainlangSourceV1) into its sources, lines and lhs/rhs items.
+/// Source names are read from the `/* <index>. <name> */` comments that
+/// precede each source in a composed rainlang.
+pub fn parse_rainlang_sources(rainlang: &str) -> Vec<RainlangSource> {
+    let mut sources = vec![];
+    let mut name = None;
+    let mut text = String::new();
+    let mut depth = 0i32;
+    let mut in_string = false;
+    let mut chars = rainlang.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            text.push(c);
+            if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match c {
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut comment = String::new();
+                while let Some(c) = chars.next() {
+                    if c == '*' && chars.peek() == Some(&'/') {
+                        chars.next();
+                        break;
+                    }
+                    comment.push(c);
+                }
+                if text.trim().is_empty() {
+                    if let Some((index, source_name)) = comment.trim().split_once(". ") {
+                        if index.trim().parse::<usize>().is_ok() {
+                            name = Some(source_name.trim().to_string());
+                        }
+                    }
+                }
+            }
+            '"' => {
+                in_string = true;
+                text.push(c);
+            }
+            '(' | '<' => {
+                depth += 1;
+                text.push(c);
+            }
+            ')' | '>' => {
+                depth -= 1;
+                text.push(c);
+            }
+            ';' if depth == 0 => {
+                sources.push(RainlangSource {
+                    name: name.take(),
+                    lines: parse_lines(&text),
+                });
+                text.clear();
+            }
+            _ => text.push(c),
+        }
+    }
+
+    sources
+}
+
+/// Splits the given text by the given delimiter ignoring the delimiters that
+/// are nested in parens, operands or strings
+fn split_top_level(text: &str, is_delimiter: impl Fn(char) -> bool) -> Vec<String> {
+    let mut items = vec![];
+    let mut item = String::new();
+    let mut depth = 0i32;
+    let mut in_string = false;
+    for c in text.chars() {
+        match c {
+            '"' => in_string = !in_string,
+            '(' | '<' if !in_string => depth += 1,
+            ')' | '>' if !in_string => depth -= 1,
+            _ => {}
+        }
+        if !in_string && depth == 0 && is_delimiter(c) {
+            items.push(std::mem::take(&mut item));
+        } else {
+            item.push(c);
+        }
+    }
+    items.push(item);
+    items
+        .into_iter()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+fn parse_lines(source: &str) -> Vec<RainlangLine> {
+    split_top_level(source, |c| c == ',')
+        .iter()
+        .map(|line| {
+            let (lhs, rhs) = line.split_once(':').unwrap_or(("", line));
+            RainlangLine {
+                lhs: lhs.split_whitespace().map(str::to_string).collect(),
+                rhs: split_top_level(rhs, char::is_whitespace),
+            }
+        })
+        .collect()
+}
+
+/// A stack item of an evaluated source annotated with its lhs name and the
+/// rhs word that produced it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotatedStackItem {
+    pub name: String,
+    pub word: String,
+    /// None if the source did not finish its evaluation
+    pub value: Option<U256>,
+    /// Marks the earliest item of a reverted source that may have reverted
+    pub reverted: bool,
+}
+
+/// Stack of an evaluated source annotated with its rainlang
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotatedSourceTrace {
+    pub source_index: u16,
+    pub parent_source_index: Option<u16>,
+    pub name: Option<String>,
+    pub items: Vec<AnnotatedStackItem>,
+    pub reverted: bool,
+}
+
+/// Traces of an evaluation annotated with the rainlang that was evaluated
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotatedTrace {
+    pub rainlang: Option<String>,
+    pub sources: Vec<AnnotatedSourceTrace>,
+    pub error: Option<String>,
+}
+
+impl AnnotatedTrace {
+    /// Annotates the traces of the given eval result with lhs names and rhs
+    /// words of the given rainlang, if the evaluation reverted (ie an error is
+    /// given) the entrypoint source is appended as reverted with its earliest
+    /// item that may have reverted marked, which is the first item after the
+    /// last `call<>` whose callee source finished its evaluation
+    pub fn new(result: &RainEvalResult, rainlang: Option<String>, error: Option<String>) -> Self {
+        let parsed_sources = rainlang
+            .as_deref()
+            .map(parse_rainlang_sources)
+            .unwrap_or_default();
+
+        let mut sources: Vec<AnnotatedSourceTrace> = result
+            .traces
+            .iter()
+            .map(|trace| {
+                let source = parsed_sources.get(trace.source_index as usize);
+                let lhs_items: Vec<(String, String)> = source
+                    .map(|v| {
+                        v.lines
+                            .iter()
+                            .flat_map(|line| {
+                                line.lhs
+                                    .iter()
+                                    .enumerate()
+                                    .map(|(i, name)| (name.clone(), line.word_of(i)))
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                // stack is in reverse order of lhs items
+                let items = trace
+                    .stack
+                    .iter()
+                    .rev()
+                    .enumerate()
+                    .map(|(i, value)| {
+                        let (name, word) = lhs_items
+                            .get(i)
+                            .cloned()
+                            .unwrap_or((format!("#{}", i), String::new()));
+                        AnnotatedStackItem {
+                            name,
+                            word,
+                            value: Some(*value),
+                            reverted: false,
+                        }
+                    })
+                    .collect();
+
+                AnnotatedSourceTrace {
+                    source_index: trace.source_index,
+                    parent_source_index: (trace.source_index != trace.parent_source_index)
+                        .then_some(trace.parent_source_index),
+                    name: source.and_then(|v| v.name.clone()),
+                    items,
+                    reverted: false,
+                }
+            })
+            .collect();
+
+        if error.is_some() && !sources.iter().any(|v| v.source_index == 0) {
+            let source = parsed_sources.first().cloned().unwrap_or_default();
+            let completed_line = source
+                .lines
+                .iter()
+                .rposition(|line| {
+                    line.called_sources(&parsed_sources)
+                        .iter()
+                        .any(|callee| sources.iter().any(|v| v.source_index as usize == *callee))
+                })
+                .map(|v| v + 1)
+                .unwrap_or(0);
+            let items = source
+                .lines
+                .iter()
+                .enumerate()
+                .flat_map(|(line_index, line)| {
+                    line.lhs
+                        .iter()
+                        .enumerate()
+                        .map(move |(i, name)| AnnotatedStackItem {
+                            name: name.clone(),
+                            word: line.word_of(i),
+                            value: None,
+                            reverted: line_index == completed_line && i == 0,
+                        })
+                })
+                .collect();
+            sources.push(AnnotatedSourceTrace {
+                source_index: 0,
+                parent_source_index: None,
+                name: source.name,
+                items,
+                reverted: true,
+            });
+        }
+
+        Self {
+            rainlang,
+            sources,
+            error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rain_interpreter_eval::trace::RainSourceTrace;
+
+    const RAINLANG: &str = r#"/* 0. calculate-io */
+amount price: 16 52,
+current-time: call<2>(),
+_: sub(16 52),
+_ _: amount price;
+
+/* 1. handle-io */
+:;
+
+/* 2. some-source */
+_: 1;"#;
+
+    #[test]
+    fn test_parse_rainlang_sources() {
+        let result = parse_rainlang_sources(RAINLANG);
+        let expected = vec![
+            RainlangSource {
+                name: Some("calculate-io".to_string()),
+                lines: vec![
+                    RainlangLine {
+                        lhs: vec!["amount".to_string(), "price".to_string()],
+                        rhs: vec!["16".to_string(), "52".to_string()],
+                    },
+                    RainlangLine {
+                        lhs: vec!["current-time".to_string()],
+                        rhs: vec!["call<2>()".to_string()],
+                    },
+                    RainlangLine {
+                        lhs: vec!["_".to_string()],
+                        rhs: vec!["sub(16 52)".to_string()],
+                    },
+                    RainlangLine {
+                        lhs: vec!["_".to_string(), "_".to_string()],
+                        rhs: vec!["amount".to_string(), "price".to_string()],
+                    },
+                ],
+            },
+            RainlangSource {
+                name: Some("handle-io".to_string()),
+                lines: vec![RainlangLine {
+                    lhs: vec![],
+                    rhs: vec![],
+                }],
+            },
+            RainlangSource {
+                name: Some("some-source".to_string()),
+                lines: vec![RainlangLine {
+                    lhs: vec!["_".to_string()],
+                    rhs: vec!["1".to_string()],
+                }],
+            },
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_annotated_trace() {
+        let result = RainEvalResult {
+            reverted: false,
+            stack: vec![],
+            writes: vec![],
+            traces: vec![RainSourceTrace {
+                parent_source_index: 0,
+                source_index: 0,
+                stack: vec![U256::from(52), U256::from(16)],
+            }],
+        };
+        let annotated =
+            AnnotatedTrace::new(&result, Some("amount price: 16 52;".to_string()), None);
+        assert_eq!(
+            annotated.sources,
+            vec![AnnotatedSourceTrace {
+                source_index: 0,
+                parent_source_index: None,
+                name: None,
+                items: vec![
+                    AnnotatedStackItem {
+                        name: "amount".to_string(),
+                        word: "16".to_string(),
+                        value: Some(U256::from(16)),
+                        reverted: false,
+                    },
+                    AnnotatedStackItem {
+                        name: "price".to_string(),
+                        word: "52".to_string(),
+                        value: Some(U256::from(52)),
+                        reverted: false,
+                    },
+                ],
+                reverted: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_annotated_trace_reverted() {
+        let result = RainEvalResult {
+            reverted: true,
+            stack: vec![],
+            writes: vec![],
+            traces: vec![RainSourceTrace {
+                parent_source_index: 0,
+                source_index: 2,
+                stack: vec![U256::from(1)],
+            }],
+        };
+        let annotated = AnnotatedTrace::new(
+            &result,
+            Some(RAINLANG.to_string()),
+            Some("underflow".to_string()),
+        );
+
+        assert_eq!(annotated.sources.len(), 2);
+        assert_eq!(annotated.sources[0].name, Some("some-source".to_string()));
+        assert_eq!(annotated.sources[0].parent_source_index, Some(0));
+        assert_eq!(annotated.sources[0].items[0].value, Some(U256::from(1)));
+
+        let reverted_source = &annotated.sources[1];
+        assert!(reverted_source.reverted);
+        assert_eq!(reverted_source.name, Some("calculate-io".to_string()));
+        let reverted_items = reverted_source
+            .items
+            .iter()
+            .filter(|v| v.reverted)
+            .collect::<Vec<_>>();
+        assert_eq!(reverted_items.len(), 1);
+        assert_eq!(reverted_items[0].word, "sub(16 52)");
+        assert!(reverted_source.items.iter().all(|v| v.value.is_none()));
+    }
+}
@@ -0,0 +1,748 @@
+use crate::{
+    dotrain_order::deploy::{
+        deposit_approvals, execute_multicall, multicall_safe_transactions, preview_multicall,
+        read_vault_balances, DeployError,
+    },
+    preview::TransactionPreview,
+    safe::SafeTransaction,
+    transaction::TransactionArgs,
+};
+use alloy::{
+    primitives::{
+        hex::{encode_prefixed, FromHexError},
+        ruint::ParseError,
+        utils::{parse_units, UnitsError},
+        Address, Bytes, B256, U256,
+    },
+    sol_types::SolCall,
+};
+use alloy_ethers_typecast::transaction::WriteTransactionStatus;
+use rain_orderbook_bindings::{
+    IOrderBookV4::{deposit2Call, withdraw2Call},
+    OrderBook::multicallCall,
+    IERC20::approveCall,
+};
+use rain_orderbook_subgraph_client::{
+    types::common::{Bytes as SgBytes, Vault, VaultsListFilterArgs},
+    OrderbookSubgraphClient, OrderbookSubgraphClientError, PaginationArgs,
+};
+use std::{collections::HashMap, str::FromStr};
+use thiserror::Error;
+
+const OWNER_VAULTS_PAGE_SIZE: u16 = 200;
+
+#[derive(Debug, Error)]
+pub enum BulkVaultError {
+    #[error("None of the matching vaults need a withdraw or a deposit")]
+    NothingToDo,
+
+    #[error("Decimals of token {0} are unknown")]
+    MissingDecimals(Address),
+
+    #[error("Cannot sweep vault {0} into itself")]
+    SweepIntoSameVault(U256),
+
+    #[error(
+        "Balance of vault {vault_id} of token {token} changed from {expected} to {actual} since the operation was prepared"
+    )]
+    VaultBalanceChanged {
+        token: Address,
+        vault_id: U256,
+        expected: U256,
+        actual: U256,
+    },
+
+    #[error(transparent)]
+    DeployError(#[from] DeployError),
+
+    #[error(transparent)]
+    SubgraphClientError(#[from] OrderbookSubgraphClientError),
+
+    #[error(transparent)]
+    FromHexError(#[from] FromHexError),
+
+    #[error(transparent)]
+    ParseError(#[from] ParseError),
+
+    #[error(transparent)]
+    UnitsError(#[from] UnitsError),
+}
+
+/// Selects which of the owner's vaults a bulk operation applies to, every
+/// criterion that is set must match
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct VaultFilter {
+    /// Only the vaults of these tokens, all tokens if empty
+    pub tokens: Vec<Address>,
+    /// Only the vaults used by the order with this hash
+    pub order_hash: Option<B256>,
+    /// Skip the vaults with a zero balance
+    pub hide_zero_balance: bool,
+    /// Only the vaults no active order uses
+    pub inactive_orders_only: bool,
+}
+
+/// A vault of the owner as indexed by the subgraph
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnerVault {
+    pub token: Address,
+    pub vault_id: U256,
+    /// Balance indexed by the subgraph, replaced by the balance read from
+    /// the orderbook when preparing an operation
+    pub balance: U256,
+    pub decimals: Option<u8>,
+    /// Hashes of the orders using the vault along with whether they are
+    /// active
+    pub orders: Vec<(B256, bool)>,
+}
+
+/// What to do with the balances of the selected vaults
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkVaultOperation {
+    /// Withdraw the whole balance of every vault, whatever it is when the
+    /// withdrawal executes
+    WithdrawAll,
+    /// Withdraw the whole balance of the vaults with the given id and
+    /// deposit it into the vault with the other id for the same token
+    Sweep {
+        from_vault_id: U256,
+        to_vault_id: U256,
+    },
+    /// Deposit into every vault of the given tokens whose balance is under
+    /// the target, keyed by token with targets in human readable units
+    TopUp { targets: HashMap<Address, String> },
+}
+
+/// The orderbook multicall of a bulk vault operation, its withdrawals
+/// followed by its deposits, along with the approvals the deposits need
+#[derive(Debug, Clone)]
+pub struct BulkVaultTransactions {
+    pub owner: Address,
+    pub orderbook: Address,
+    pub rpc_url: String,
+    pub approvals: Vec<(Address, approveCall)>,
+    pub withdrawals: Vec<withdraw2Call>,
+    pub deposits: Vec<deposit2Call>,
+    /// The vaults the amounts of the calls were derived from, with the
+    /// balances they were derived from
+    pub balances: Vec<OwnerVault>,
+}
+
+impl TryFrom<&Vault> for OwnerVault {
+    type Error = BulkVaultError;
+
+    fn try_from(vault: &Vault) -> Result<Self, Self::Error> {
+        let mut orders: Vec<(B256, bool)> = vec![];
+        for order in vault.orders_as_output.iter().chain(&vault.orders_as_input) {
+            let order_hash = B256::from_str(&order.order_hash.0)?;
+            if !orders.iter().any(|(hash, _)| *hash == order_hash) {
+                orders.push((order_hash, order.active));
+            }
+        }
+        Ok(OwnerVault {
+            token: Address::from_str(&vault.token.address.0)?,
+            vault_id: U256::from_str(&vault.vault_id.0)?,
+            balance: U256::from_str(&vault.balance.0)?,
+            decimals: vault
+                .token
+                .decimals
+                .as_ref()
+                .and_then(|decimals| decimals.0.parse().ok()),
+            orders,
+        })
+    }
+}
+
+impl VaultFilter {
+    pub fn matches(&self, vault: &OwnerVault) -> bool {
+        let used_by_order = match self.order_hash {
+            Some(order_hash) => vault.orders.iter().any(|(hash, _)| *hash == order_hash),
+            None => true,
+        };
+        (self.tokens.is_empty() || self.tokens.contains(&vault.token))
+            && used_by_order
+            && !(self.hide_zero_balance && vault.balance.is_zero())
+            && !(self.inactive_orders_only && vault.orders.iter().any(|(_, active)| *active))
+    }
+}
+
+impl BulkVaultOperation {
+    /// The withdrawals and deposits of the operation over the given vaults,
+    /// vaults with nothing to withdraw or deposit are skipped
+    pub fn calls(
+        &self,
+        vaults: &[OwnerVault],
+    ) -> Result<(Vec<withdraw2Call>, Vec<deposit2Call>), BulkVaultError> {
+        let withdraw = |vault: &OwnerVault, target_amount: U256| withdraw2Call {
+            token: vault.token,
+            vaultId: vault.vault_id,
+            targetAmount: target_amount,
+            tasks: vec![],
+        };
+        let deposit = |token: Address, vault_id: U256, amount: U256| deposit2Call {
+            token,
+            vaultId: vault_id,
+            amount,
+            tasks: vec![],
+        };
+        let non_zero = |vault: &&OwnerVault| !vault.balance.is_zero();
+
+        match self {
+            BulkVaultOperation::WithdrawAll => Ok((
+                // the orderbook caps the withdrawal at the vault balance
+                vaults
+                    .iter()
+                    .filter(non_zero)
+                    .map(|vault| withdraw(vault, U256::MAX))
+                    .collect(),
+                vec![],
+            )),
+            BulkVaultOperation::Sweep {
+                from_vault_id,
+                to_vault_id,
+            } => {
+                if from_vault_id == to_vault_id {
+                    return Err(BulkVaultError::SweepIntoSameVault(*from_vault_id));
+                }
+                let withdrawals = vaults
+                    .iter()
+                    .filter(non_zero)
+                    .filter(|vault| vault.vault_id == *from_vault_id)
+                    .map(|vault| withdraw(vault, vault.balance))
+                    .collect::<Vec<_>>();
+                // a single deposit per token of everything withdrawn
+                let mut deposits: Vec<deposit2Call> = vec![];
+                for withdrawal in &withdrawals {
+                    match deposits
+                        .iter_mut()
+                        .find(|existing| existing.token == withdrawal.token)
+                    {
+                        Some(existing) => existing.amount += withdrawal.targetAmount,
+                        None => deposits.push(deposit(
+                            withdrawal.token,
+                            *to_vault_id,
+                            withdrawal.targetAmount,
+                        )),
+                    }
+                }
+                Ok((withdrawals, deposits))
+            }
+            BulkVaultOperation::TopUp { targets } => {
+                let mut deposits = vec![];
+                for vault in vaults {
+                    let Some(target) = targets.get(&vault.token) else {
+                        continue;
+                    };
+                    let decimals = vault
+                        .decimals
+                        .ok_or(BulkVaultError::MissingDecimals(vault.token))?;
+                    let target: U256 = parse_units(target, decimals)?.into();
+                    if vault.balance < target {
+                        deposits.push(deposit(vault.token, vault.vault_id, target - vault.balance));
+                    }
+                }
+                Ok((vec![], deposits))
+            }
+        }
+    }
+
+    /// The vaults whose balances the amounts of the calls are derived from,
+    /// withdrawing all is capped by the orderbook instead
+    fn balance_dependent(&self, vaults: &[OwnerVault]) -> Vec<OwnerVault> {
+        vaults
+            .iter()
+            .filter(|vault| match self {
+                BulkVaultOperation::WithdrawAll => false,
+                BulkVaultOperation::Sweep { from_vault_id, .. } => vault.vault_id == *from_vault_id,
+                BulkVaultOperation::TopUp { targets } => targets.contains_key(&vault.token),
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// Reads all the vaults of the given owner from the subgraph
+pub async fn get_owner_vaults(
+    client: &OrderbookSubgraphClient,
+    owner: Address,
+) -> Result<Vec<Vault>, BulkVaultError> {
+    let mut vaults = vec![];
+    let mut page = 1;
+    loop {
+        let page_data = client
+            .vaults_list(
+                VaultsListFilterArgs {
+                    owners: vec![SgBytes(encode_prefixed(owner))],
+                    hide_zero_balance: false,
+                },
+                PaginationArgs {
+                    page,
+                    page_size: OWNER_VAULTS_PAGE_SIZE,
+                },
+            )
+            .await?;
+        if page_data.is_empty() {
+            break;
+        }
+        vaults.extend(page_data);
+        page += 1;
+    }
+    Ok(vaults)
+}
+
+/// Builds the multicall of a bulk operation over the vaults of the owner
+/// on the given orderbook that match the filter, with their balances read
+/// from the orderbook, reading the owner's allowances to only approve the
+/// tokens the deposits need
+pub async fn prepare_bulk_vault_operation(
+    client: &OrderbookSubgraphClient,
+    owner: Address,
+    orderbook: Address,
+    rpc_url: String,
+    filter: &VaultFilter,
+    operation: &BulkVaultOperation,
+) -> Result<BulkVaultTransactions, BulkVaultError> {
+    let mut owner_vaults = vec![];
+    for vault in get_owner_vaults(client, owner).await? {
+        if Address::from_str(&vault.orderbook.id.0)? == orderbook {
+            owner_vaults.push(OwnerVault::try_from(&vault)?);
+        }
+    }
+
+    // the subgraph may lag behind, the amounts moved are the balances on chain
+    let balances = read_vault_balances(
+        owner,
+        orderbook,
+        &rpc_url,
+        &owner_vaults
+            .iter()
+            .map(|vault| (vault.token, vault.vault_id))
+            .collect::<Vec<_>>(),
+    )
+    .await?;
+    let vaults = owner_vaults
+        .into_iter()
+        .zip(balances)
+        .map(|(vault, balance)| OwnerVault { balance, ..vault })
+        .filter(|vault| filter.matches(vault))
+        .collect::<Vec<_>>();
+
+    let (withdrawals, deposits) = operation.calls(&vaults)?;
+    if withdrawals.is_empty() && deposits.is_empty() {
+        return Err(BulkVaultError::NothingToDo);
+    }
+    let approvals = deposit_approvals(owner, orderbook, &rpc_url, &deposits).await?;
+
+    Ok(BulkVaultTransactions {
+        owner,
+        orderbook,
+        rpc_url,
+        approvals,
+        withdrawals,
+        deposits,
+        balances: operation.balance_dependent(&vaults),
+    })
+}
+
+impl BulkVaultTransactions {
+    /// The orderbook multicall of the withdrawals followed by the deposits,
+    /// so withdrawn balances can be deposited again
+    pub fn multicall(&self) -> multicallCall {
+        let mut data = self
+            .withdrawals
+            .iter()
+            .map(|withdrawal| Bytes::from(withdrawal.abi_encode()))
+            .collect::<Vec<_>>();
+        data.extend(
+            self.deposits
+                .iter()
+                .map(|deposit| Bytes::from(deposit.abi_encode())),
+        );
+        multicallCall { data }
+    }
+
+    /// Previews the approvals and the multicall sent from the owner
+    pub async fn preview(&self) -> Result<TransactionPreview, BulkVaultError> {
+        Ok(preview_multicall(
+            self.owner,
+            self.orderbook,
+            &self.rpc_url,
+            &self.approvals,
+            self.multicall(),
+        )
+        .await?)
+    }
+
+    /// Reads the balances the amounts of the calls were derived from again,
+    /// failing if any changed since, as a sweep would then deposit tokens of
+    /// the wallet or leave some behind, and a top up would miss its target
+    pub async fn check_balances(&self) -> Result<(), BulkVaultError> {
+        let balances = read_vault_balances(
+            self.owner,
+            self.orderbook,
+            &self.rpc_url,
+            &self
+                .balances
+                .iter()
+                .map(|vault| (vault.token, vault.vault_id))
+                .collect::<Vec<_>>(),
+        )
+        .await?;
+        for (vault, balance) in self.balances.iter().zip(balances) {
+            if balance != vault.balance {
+                return Err(BulkVaultError::VaultBalanceChanged {
+                    token: vault.token,
+                    vault_id: vault.vault_id,
+                    expected: vault.balance,
+                    actual: balance,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// The approvals and the multicall as the calls of a Safe batch, for
+    /// owners that are a Safe rather than a ledger wallet. Their balances
+    /// can only be checked when the batch is proposed, not when it executes
+    pub fn safe_transactions(&self) -> Vec<SafeTransaction> {
+        multicall_safe_transactions(self.orderbook, &self.approvals, self.multicall())
+    }
+
+    /// Checks the balances and then sends the approvals and the multicall
+    /// with the ledger wallet of the transaction args
+    pub async fn execute<A, M>(
+        &self,
+        transaction_args: TransactionArgs,
+        approval_status_changed: A,
+        multicall_status_changed: M,
+    ) -> Result<(), BulkVaultError>
+    where
+        A: Fn(WriteTransactionStatus<approveCall>),
+        M: Fn(WriteTransactionStatus<multicallCall>),
+    {
+        self.check_balances().await?;
+        Ok(execute_multicall(
+            TransactionArgs {
+                orderbook_address: self.orderbook,
+                rpc_url: self.rpc_url.clone(),
+                ..transaction_args
+            },
+            &self.approvals,
+            self.multicall(),
+            approval_status_changed,
+            multicall_status_changed,
+        )
+        .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{primitives::utils::parse_ether, sol_types::SolValue};
+    use alloy_ethers_typecast::{multicall::IMulticall3::Result as MulticallResult, rpc::Response};
+    use httpmock::{Method::POST, MockServer};
+    use rain_orderbook_subgraph_client::types::common::{BigInt, Erc20, OrderAsIO, Orderbook};
+
+    fn owner_vault(token: u8, vault_id: u64, balance: &str, orders: Vec<(u8, bool)>) -> OwnerVault {
+        OwnerVault {
+            token: Address::repeat_byte(token),
+            vault_id: U256::from(vault_id),
+            balance: parse_ether(balance).unwrap(),
+            decimals: Some(18),
+            orders: orders
+                .into_iter()
+                .map(|(hash, active)| (B256::repeat_byte(hash), active))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_owner_vault_from_subgraph_vault() {
+        let order = |hash: u8, active: bool| OrderAsIO {
+            id: SgBytes("0x01".into()),
+            order_hash: SgBytes(B256::repeat_byte(hash).to_string()),
+            active,
+        };
+        let vault = Vault {
+            id: SgBytes("0x01".into()),
+            owner: SgBytes("0x02".into()),
+            vault_id: BigInt("16".into()),
+            balance: BigInt("1000".into()),
+            token: Erc20 {
+                id: SgBytes(Address::repeat_byte(0x11).to_string()),
+                address: SgBytes(Address::repeat_byte(0x11).to_string()),
+                name: None,
+                symbol: None,
+                decimals: Some(BigInt("6".into())),
+            },
+            orderbook: Orderbook {
+                id: SgBytes(Address::repeat_byte(0x22).to_string()),
+            },
+            orders_as_output: vec![order(1, true)],
+            orders_as_input: vec![order(1, true), order(2, false)],
+            balance_changes: vec![],
+        };
+        assert_eq!(
+            OwnerVault::try_from(&vault).unwrap(),
+            OwnerVault {
+                token: Address::repeat_byte(0x11),
+                vault_id: U256::from(16),
+                balance: U256::from(1000),
+                decimals: Some(6),
+                orders: vec![(B256::repeat_byte(1), true), (B256::repeat_byte(2), false)],
+            }
+        );
+    }
+
+    #[test]
+    fn test_vault_filter() {
+        let vault = owner_vault(1, 1, "0", vec![(1, true), (2, false)]);
+        assert!(VaultFilter::default().matches(&vault));
+        assert!(VaultFilter {
+            tokens: vec![Address::repeat_byte(2), Address::repeat_byte(1)],
+            order_hash: Some(B256::repeat_byte(2)),
+            ..Default::default()
+        }
+        .matches(&vault));
+        assert!(!VaultFilter {
+            tokens: vec![Address::repeat_byte(2)],
+            ..Default::default()
+        }
+        .matches(&vault));
+        assert!(!VaultFilter {
+            order_hash: Some(B256::repeat_byte(3)),
+            ..Default::default()
+        }
+        .matches(&vault));
+        assert!(!VaultFilter {
+            hide_zero_balance: true,
+            ..Default::default()
+        }
+        .matches(&vault));
+
+        let inactive_only = VaultFilter {
+            inactive_orders_only: true,
+            ..Default::default()
+        };
+        assert!(!inactive_only.matches(&vault));
+        assert!(inactive_only.matches(&owner_vault(1, 1, "0", vec![(2, false)])));
+        assert!(inactive_only.matches(&owner_vault(1, 1, "0", vec![])));
+    }
+
+    #[test]
+    fn test_withdraw_all() {
+        let vaults = vec![
+            owner_vault(1, 1, "10", vec![]),
+            owner_vault(1, 2, "0", vec![]),
+            owner_vault(2, 1, "5", vec![]),
+        ];
+        let (withdrawals, deposits) = BulkVaultOperation::WithdrawAll.calls(&vaults).unwrap();
+        assert!(deposits.is_empty());
+        assert_eq!(
+            withdrawals,
+            vec![
+                withdraw2Call {
+                    token: Address::repeat_byte(1),
+                    vaultId: U256::from(1),
+                    targetAmount: U256::MAX,
+                    tasks: vec![],
+                },
+                withdraw2Call {
+                    token: Address::repeat_byte(2),
+                    vaultId: U256::from(1),
+                    targetAmount: U256::MAX,
+                    tasks: vec![],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sweep() {
+        let vaults = vec![
+            owner_vault(1, 1, "10", vec![]),
+            owner_vault(1, 2, "3", vec![]),
+            owner_vault(2, 1, "5", vec![]),
+            owner_vault(2, 3, "7", vec![]),
+        ];
+        let (withdrawals, deposits) = BulkVaultOperation::Sweep {
+            from_vault_id: U256::from(1),
+            to_vault_id: U256::from(2),
+        }
+        .calls(&vaults)
+        .unwrap();
+        // the exact balances are withdrawn so they can all be deposited
+        assert_eq!(
+            withdrawals
+                .iter()
+                .map(|withdrawal| (withdrawal.vaultId, withdrawal.targetAmount))
+                .collect::<Vec<_>>(),
+            vec![
+                (U256::from(1), parse_ether("10").unwrap()),
+                (U256::from(1), parse_ether("5").unwrap()),
+            ]
+        );
+        assert_eq!(
+            deposits,
+            vec![
+                deposit2Call {
+                    token: Address::repeat_byte(1),
+                    vaultId: U256::from(2),
+                    amount: parse_ether("10").unwrap(),
+                    tasks: vec![],
+                },
+                deposit2Call {
+                    token: Address::repeat_byte(2),
+                    vaultId: U256::from(2),
+                    amount: parse_ether("5").unwrap(),
+                    tasks: vec![],
+                },
+            ]
+        );
+
+        assert!(matches!(
+            BulkVaultOperation::Sweep {
+                from_vault_id: U256::from(1),
+                to_vault_id: U256::from(1),
+            }
+            .calls(&vaults),
+            Err(BulkVaultError::SweepIntoSameVault(vault_id)) if vault_id == U256::from(1)
+        ));
+    }
+
+    #[test]
+    fn test_top_up() {
+        let vaults = vec![
+            owner_vault(1, 1, "10", vec![]),
+            owner_vault(1, 2, "3", vec![]),
+            owner_vault(2, 1, "0", vec![]),
+        ];
+        let operation = BulkVaultOperation::TopUp {
+            targets: HashMap::from([(Address::repeat_byte(1), "5.5".to_string())]),
+        };
+        let (withdrawals, deposits) = operation.calls(&vaults).unwrap();
+        assert!(withdrawals.is_empty());
+        assert_eq!(
+            deposits,
+            vec![deposit2Call {
+                token: Address::repeat_byte(1),
+                vaultId: U256::from(2),
+                amount: parse_ether("2.5").unwrap(),
+                tasks: vec![],
+            }]
+        );
+
+        let mut unknown_decimals = vaults[1].clone();
+        unknown_decimals.decimals = None;
+        assert!(matches!(
+            operation.calls(&[unknown_decimals]),
+            Err(BulkVaultError::MissingDecimals(token)) if token == Address::repeat_byte(1)
+        ));
+    }
+
+    #[test]
+    fn test_multicall() {
+        let transactions = BulkVaultTransactions {
+            owner: Address::repeat_byte(1),
+            orderbook: Address::repeat_byte(2),
+            rpc_url: "https://some-rpc.com".to_string(),
+            approvals: vec![],
+            withdrawals: vec![withdraw2Call {
+                token: Address::repeat_byte(3),
+                vaultId: U256::from(1),
+                targetAmount: U256::from(10),
+                tasks: vec![],
+            }],
+            deposits: vec![deposit2Call {
+                token: Address::repeat_byte(3),
+                vaultId: U256::from(2),
+                amount: U256::from(10),
+                tasks: vec![],
+            }],
+            balances: vec![],
+        };
+        let multicall = transactions.multicall();
+        assert_eq!(multicall.data.len(), 2);
+        assert_eq!(
+            withdraw2Call::abi_decode(&multicall.data[0], true).unwrap(),
+            transactions.withdrawals[0]
+        );
+        assert_eq!(
+            deposit2Call::abi_decode(&multicall.data[1], true).unwrap(),
+            transactions.deposits[0]
+        );
+        assert_eq!(transactions.safe_transactions().len(), 1);
+    }
+
+    #[test]
+    fn test_balance_dependent() {
+        let vaults = vec![
+            owner_vault(1, 1, "10", vec![]),
+            owner_vault(1, 2, "3", vec![]),
+            owner_vault(2, 1, "0", vec![]),
+        ];
+        assert!(BulkVaultOperation::WithdrawAll
+            .balance_dependent(&vaults)
+            .is_empty());
+        assert_eq!(
+            BulkVaultOperation::Sweep {
+                from_vault_id: U256::from(1),
+                to_vault_id: U256::from(2),
+            }
+            .balance_dependent(&vaults),
+            vec![vaults[0].clone(), vaults[2].clone()]
+        );
+        assert_eq!(
+            BulkVaultOperation::TopUp {
+                targets: HashMap::from([(Address::repeat_byte(1), "5".to_string())]),
+            }
+            .balance_dependent(&vaults),
+            vec![vaults[0].clone(), vaults[1].clone()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_balances() {
+        let server = MockServer::start_async().await;
+        let mut balance_mock = server.mock(|when, then| {
+            when.method(POST).path("/rpc");
+            then.json_body_obj(&vault_balances_response(parse_ether("10").unwrap()));
+        });
+        let transactions = BulkVaultTransactions {
+            owner: Address::repeat_byte(1),
+            orderbook: Address::repeat_byte(2),
+            rpc_url: server.url("/rpc"),
+            approvals: vec![],
+            withdrawals: vec![],
+            deposits: vec![],
+            balances: vec![owner_vault(3, 1, "10", vec![])],
+        };
+        transactions.check_balances().await.unwrap();
+
+        // an order traded out of the vault since
+        balance_mock.delete();
+        server.mock(|when, then| {
+            when.method(POST).path("/rpc");
+            then.json_body_obj(&vault_balances_response(parse_ether("4").unwrap()));
+        });
+        assert!(matches!(
+            transactions.check_balances().await,
+            Err(BulkVaultError::VaultBalanceChanged { expected, actual, .. })
+                if expected == parse_ether("10").unwrap() && actual == parse_ether("4").unwrap()
+        ));
+    }
+
+    fn vault_balances_response(balance: U256) -> serde_json::Value {
+        let results = vec![MulticallResult {
+            success: true,
+            returnData: balance.abi_encode().into(),
+        }];
+        serde_json::from_str(
+            &Response::new_success(1, &encode_prefixed(results.abi_encode()))
+                .to_json_string()
+                .unwrap(),
+        )
+        .unwrap()
+    }
+}
@@ -6,10 +6,7 @@ use alloy_ethers_typecast::transaction::{
     ReadableClientError, WritableClientError,
 };
 #[cfg(not(target_family = "wasm"))]
-use alloy_ethers_typecast::{
-    ethers_address_to_alloy,
-    transaction::{WriteTransaction, WriteTransactionStatus},
-};
+use alloy_ethers_typecast::{ethers_address_to_alloy, transaction::WriteTransactionStatus};
 use rain_orderbook_bindings::{
     IOrderBookV4::deposit2Call,
     IERC20::{allowanceCall, approveCall},
@@ -96,12 +93,8 @@ impl DepositArgs {
                 spender: transaction_args.orderbook_address,
                 amount: self.amount - current_allowance,
             };
-            let params = transaction_args
-                .try_into_write_contract_parameters(approve_call, self.token)
-                .await?;
-
-            WriteTransaction::new(ledger_client.client, params, 4, transaction_status_changed)
-                .execute()
+            transaction_args
+                .send(approve_call, self.token, transaction_status_changed)
                 .await?;
         }
 
@@ -127,15 +120,13 @@ impl DepositArgs {
         transaction_args: TransactionArgs,
         transaction_status_changed: S,
     ) -> Result<(), DepositError> {
-        let ledger_client = transaction_args.clone().try_into_ledger_client().await?;
-
         let deposit_call: deposit2Call = self.clone().into();
-        let params = transaction_args
-            .try_into_write_contract_parameters(deposit_call, transaction_args.orderbook_address)
-            .await?;
-
-        WriteTransaction::new(ledger_client.client, params, 4, transaction_status_changed)
-            .execute()
+        transaction_args
+            .send(
+                deposit_call,
+                transaction_args.orderbook_address,
+                transaction_status_changed,
+            )
             .await?;
 
         Ok(())
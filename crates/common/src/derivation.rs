@@ -0,0 +1,79 @@
+use alloy::primitives::{keccak256, Address, B256, U256};
+use alloy::sol_types::SolValue;
+use serde::{Deserialize, Serialize};
+
+/// Derives the nonce, secret and vault ids of an order from its owner, the
+/// name of its deployment and a user chosen salt, so that deploying the same
+/// deployment again yields the same order and order hash
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderDerivation {
+    pub owner: Address,
+    pub deployment: String,
+    pub salt: String,
+}
+
+impl OrderDerivation {
+    fn derive(&self, purpose: &str) -> B256 {
+        keccak256(
+            (
+                self.owner,
+                self.deployment.clone(),
+                self.salt.clone(),
+                purpose.to_string(),
+            )
+                .abi_encode(),
+        )
+    }
+
+    pub fn nonce(&self) -> B256 {
+        self.derive("nonce")
+    }
+
+    pub fn secret(&self) -> B256 {
+        self.derive("secret")
+    }
+
+    /// Vault id of the inputs and outputs that do not set one
+    pub fn vault_id(&self) -> U256 {
+        U256::from_be_bytes(self.derive("vault-id").0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derivation() {
+        let derivation = OrderDerivation {
+            owner: Address::with_last_byte(1),
+            deployment: "some-deployment".to_string(),
+            salt: "1".to_string(),
+        };
+        assert_eq!(derivation.nonce(), derivation.clone().nonce());
+        assert_ne!(derivation.nonce(), derivation.secret());
+        assert_ne!(
+            U256::from_be_bytes(derivation.nonce().0),
+            derivation.vault_id()
+        );
+
+        for other in [
+            OrderDerivation {
+                owner: Address::with_last_byte(2),
+                ..derivation.clone()
+            },
+            OrderDerivation {
+                deployment: "other-deployment".to_string(),
+                ..derivation.clone()
+            },
+            OrderDerivation {
+                salt: "2".to_string(),
+                ..derivation.clone()
+            },
+        ] {
+            assert_ne!(derivation.nonce(), other.nonce());
+            assert_ne!(derivation.secret(), other.secret());
+            assert_ne!(derivation.vault_id(), other.vault_id());
+        }
+    }
+}
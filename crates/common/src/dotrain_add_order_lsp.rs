@@ -4,6 +4,8 @@ use crate::add_order::ORDERBOOK_ORDER_ENTRYPOINTS;
 use crate::rainlang::parse_rainlang_on_fork;
 #[cfg(not(target_family = "wasm"))]
 use alloy::primitives::Address;
+#[cfg(not(target_family = "wasm"))]
+use dotrain::RainDocument;
 use dotrain::Rebind;
 #[cfg(not(target_family = "wasm"))]
 use dotrain::{
@@ -15,6 +17,8 @@ use dotrain_lsp::{
     RainLanguageServices,
 };
 use once_cell::sync::Lazy;
+#[cfg(not(target_family = "wasm"))]
+use rain_orderbook_app_settings::lint::{lint_config, ConfigLintSource};
 use std::collections::HashMap;
 
 /// static lang services instance
@@ -56,21 +60,20 @@ impl DotrainAddOrderLsp {
         LANG_SERVICES.do_complete(&self.text_document, position, None, self.rebinds.clone())
     }
 
-    /// get problems for a given text document item
+    /// get problems for a given text document item, including the lint
+    /// diagnostics of its frontmatter merged with the given settings
     #[cfg(not(target_family = "wasm"))]
     pub async fn problems(
         &self,
         rpc_url: &str,
         block_number: Option<u64>,
         deployer: Option<Address>,
+        settings: Option<&str>,
     ) -> Vec<Problem> {
         let rain_document =
             LANG_SERVICES.new_rain_document(&self.text_document, self.rebinds.clone());
-        let mut bindings_problems = rain_document
-            .bindings_problems()
-            .iter()
-            .map(|&v| v.clone())
-            .collect::<Vec<_>>();
+        let mut bindings_problems = self.config_problems(settings);
+        bindings_problems.extend(rain_document.bindings_problems().iter().map(|&v| v.clone()));
         let top_problems = rain_document.problems();
         if !top_problems.is_empty() {
             bindings_problems.extend(top_problems.to_vec());
@@ -118,4 +121,22 @@ impl DotrainAddOrderLsp {
             bindings_problems
         }
     }
+
+    /// get lint diagnostics of the text document's frontmatter as problems,
+    /// those of the settings are positioned at the start of the document
+    #[cfg(not(target_family = "wasm"))]
+    fn config_problems(&self, settings: Option<&str>) -> Vec<Problem> {
+        let frontmatter = RainDocument::get_front_matter(&self.text_document.text).unwrap_or("");
+        lint_config(frontmatter, settings)
+            .into_iter()
+            .map(|diagnostic| Problem {
+                msg: diagnostic.to_string(),
+                position: match (diagnostic.source, diagnostic.span) {
+                    (ConfigLintSource::Main, Some(span)) => [span.start, span.end],
+                    _ => [0, 0],
+                },
+                code: ErrorCode::InvalidRainDocument,
+            })
+            .collect()
+    }
 }
@@ -2,7 +2,9 @@ use super::*;
 use crate::{
     add_order::{AddOrderArgs, AddOrderArgsError},
     deposit::{DepositArgs, DepositError},
-    transaction::{TransactionArgs, WritableTransactionExecuteError},
+    derivation::OrderDerivation,
+    meta::GuiStateMeta,
+    transaction::{TransactionArgs, TransactionArgsError, WritableTransactionExecuteError},
 };
 use alloy::{
     hex::FromHexError,
@@ -64,8 +66,7 @@ impl DotrainOrder {
                 };
                 let transaction_args = TransactionArgs {
                     orderbook_address: orderbook.address,
-                    rpc_url: orderbook.network.rpc.to_string(),
-                    ..Default::default()
+                    ..TransactionArgs::for_network(&orderbook.network).await?
                 };
 
                 let allowance = deposit_args
@@ -124,24 +125,44 @@ impl DotrainOrder {
         Ok(calldatas)
     }
 
+    /// Generates the add order calldata of a deployment, embedding the
+    /// dotrain and the given gui state in the order meta if one is given,
+    /// and deriving the nonce and secret if a derivation is given
     pub async fn generate_add_order_calldata(
         &mut self,
         deployment_name: &str,
+        gui_state: Option<GuiStateMeta>,
+        derivation: Option<OrderDerivation>,
     ) -> Result<Bytes, DotrainOrderCalldataError> {
         let deployment = self.get_deployment(deployment_name)?;
         let orderbook = self.get_orderbook(deployment_name)?;
 
-        let calldata = AddOrderArgs::new_from_deployment(
-            self.dotrain().to_string(),
-            deployment.as_ref().to_owned(),
-        )
-        .await?
-        .get_add_order_calldata(TransactionArgs {
-            orderbook_address: orderbook.address,
-            rpc_url: orderbook.network.rpc.to_string(),
-            ..Default::default()
-        })
-        .await?;
+        let mut add_order_args = match derivation {
+            Some(derivation) => {
+                AddOrderArgs::new_from_deployment_derived(
+                    self.dotrain().to_string(),
+                    deployment.as_ref().to_owned(),
+                    derivation,
+                )
+                .await?
+            }
+            None => {
+                AddOrderArgs::new_from_deployment(
+                    self.dotrain().to_string(),
+                    deployment.as_ref().to_owned(),
+                )
+                .await?
+            }
+        };
+        if gui_state.is_some() {
+            add_order_args = add_order_args.with_embedded_source(gui_state);
+        }
+        let calldata = add_order_args
+            .get_add_order_calldata(TransactionArgs {
+                orderbook_address: orderbook.address,
+                ..TransactionArgs::for_network(&orderbook.network).await?
+            })
+            .await?;
 
         Ok(Bytes::copy_from_slice(&calldata))
     }
@@ -172,4 +193,7 @@ pub enum DotrainOrderCalldataError {
 
     #[error(transparent)]
     AddOrderArgsError(#[from] AddOrderArgsError),
+
+    #[error(transparent)]
+    TransactionArgsError(#[from] TransactionArgsError),
 }
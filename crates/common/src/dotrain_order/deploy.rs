@@ -0,0 +1,654 @@
+use super::*;
+use crate::{
+    add_order::{AddOrderArgs, AddOrderArgsError},
+    deposit::{DepositArgs, DepositError},
+    derivation::OrderDerivation,
+    preview::{PreviewBuilder, PreviewError, TransactionPreview},
+    safe::SafeTransaction,
+    transaction::{TransactionArgs, TransactionArgsError},
+};
+use alloy::{
+    primitives::{
+        hex::FromHex,
+        keccak256,
+        utils::{parse_units, UnitsError},
+        Bytes, B256,
+    },
+    sol_types::{SolCall, SolValue},
+};
+use alloy_ethers_typecast::{
+    multicall::{
+        IMulticall3::{aggregate3Call, Call3},
+        MULTICALL3_ADDRESS,
+    },
+    transaction::{ReadContractParameters, WritableClientError, WriteTransactionStatus},
+};
+use rain_orderbook_app_settings::{
+    deployment::Deployment,
+    orderbook::Orderbook,
+    rpc::{RpcProvider, RpcProviderError},
+};
+use rain_orderbook_bindings::{
+    IOrderBookV4::{addOrder2Call, deposit2Call, vaultBalanceCall, OrderV3},
+    OrderBook::multicallCall,
+    IERC20::approveCall,
+};
+
+/// Amount to deposit into the vault of an order output
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeployDeposit {
+    /// Amount in human readable units
+    Amount(String),
+    /// Index of one of the deposit presets of the deployment's gui
+    Preset(usize),
+}
+
+/// Arguments for deploying a deployment along with its deposits
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DeployArgs {
+    /// Address that adds the order and makes the deposits
+    pub owner: Address,
+    /// Deposits keyed by their token key, each deposit goes into the vault
+    /// of the matching order output
+    pub deposits: HashMap<String, DeployDeposit>,
+    /// Salt to derive the order's nonce, secret and vault ids from along
+    /// with the owner and deployment name, random ones are used if not set
+    pub salt: Option<String>,
+}
+
+/// The transactions that deploy a deployment, the approvals followed by a
+/// single orderbook multicall that deposits and adds the order
+#[derive(Debug, Clone)]
+pub struct DeploymentTransactions {
+    pub owner: Address,
+    pub orderbook: Address,
+    pub rpc_url: String,
+    /// Approvals of the deposited tokens not yet approved for the orderbook,
+    /// with the token they are sent to
+    pub approvals: Vec<(Address, approveCall)>,
+    pub deposits: Vec<deposit2Call>,
+    pub add_order: addOrder2Call,
+}
+
+#[derive(Debug, Error)]
+pub enum DeployError {
+    #[error("Deployment not found {0}")]
+    DeploymentNotFound(String),
+
+    #[error("Orderbook not found")]
+    OrderbookNotFound,
+
+    #[error("Deposit token {0} is not an output of the order")]
+    DepositTokenNotFound(String),
+
+    #[error("No deposit preset {1} for token {0} in the gui of the deployment")]
+    DepositPresetNotFound(String, usize),
+
+    #[error(transparent)]
+    AddOrderArgsError(#[from] AddOrderArgsError),
+
+    #[error(transparent)]
+    DepositError(#[from] DepositError),
+
+    #[error(transparent)]
+    UnitsError(#[from] UnitsError),
+
+    #[error(transparent)]
+    RpcProviderError(#[from] RpcProviderError),
+
+    #[error(transparent)]
+    TransactionArgsError(#[from] TransactionArgsError),
+
+    #[error(transparent)]
+    WritableClientError(#[from] WritableClientError),
+
+    #[error(transparent)]
+    ReadableClientError(#[from] ReadableClientError),
+
+    #[error(transparent)]
+    FromHexError(#[from] FromHexError),
+
+    #[error(transparent)]
+    SolTypesError(#[from] alloy::sol_types::Error),
+
+    #[error(transparent)]
+    PreviewError(#[from] PreviewError),
+}
+
+impl DotrainOrder {
+    /// Builds the transactions that deploy the given deployment with the
+    /// given deposits, reading the owner's allowances to only approve the
+    /// tokens that need it
+    pub async fn prepare_deployment(
+        &self,
+        deployment_name: &str,
+        args: DeployArgs,
+    ) -> Result<DeploymentTransactions, DeployError> {
+        let deployment = self
+            .config
+            .deployments
+            .get(deployment_name)
+            .ok_or(DeployError::DeploymentNotFound(deployment_name.to_string()))?;
+        let orderbook = deployment
+            .order
+            .orderbook
+            .clone()
+            .ok_or(DeployError::OrderbookNotFound)?;
+
+        // every read of the deployment is made on the same rpc, and made
+        // again on the next one if it fails midway
+        RpcProvider::for_network(&orderbook.network)
+            .with_failover(|rpc_url| {
+                self.prepare_deployment_on(
+                    deployment_name,
+                    deployment,
+                    &orderbook,
+                    &args,
+                    rpc_url.to_string(),
+                )
+            })
+            .await
+    }
+
+    async fn prepare_deployment_on(
+        &self,
+        deployment_name: &str,
+        deployment: &Deployment,
+        orderbook: &Orderbook,
+        args: &DeployArgs,
+        rpc_url: String,
+    ) -> Result<DeploymentTransactions, DeployError> {
+        let add_order_args = match &args.salt {
+            Some(salt) => {
+                AddOrderArgs::new_from_deployment_derived(
+                    self.dotrain.clone(),
+                    deployment.clone(),
+                    OrderDerivation {
+                        owner: args.owner,
+                        deployment: deployment_name.to_string(),
+                        salt: salt.clone(),
+                    },
+                )
+                .await?
+            }
+            None => {
+                AddOrderArgs::new_from_deployment(self.dotrain.clone(), deployment.clone()).await?
+            }
+        };
+        let add_order = add_order_args.try_into_call(rpc_url.clone()).await?;
+        add_order_args
+            .ensure_not_live(
+                &TransactionArgs {
+                    orderbook_address: orderbook.address,
+                    rpc_url: rpc_url.clone(),
+                    ..Default::default()
+                },
+                &add_order,
+            )
+            .await?;
+
+        // resolve the deposits into the order's output vaults
+        let mut deposits = vec![];
+        for (token_key, deposit) in &args.deposits {
+            let io = self
+                .config
+                .tokens
+                .get(token_key)
+                .and_then(|token| {
+                    add_order
+                        .config
+                        .validOutputs
+                        .iter()
+                        .find(|io| io.token == token.address)
+                })
+                .ok_or(DeployError::DepositTokenNotFound(token_key.clone()))?;
+            let amount = match deposit {
+                DeployDeposit::Amount(amount) => amount.clone(),
+                DeployDeposit::Preset(index) => self
+                    .gui_deposit_preset(deployment_name, token_key, *index)
+                    .ok_or(DeployError::DepositPresetNotFound(
+                        token_key.clone(),
+                        *index,
+                    ))?,
+            };
+            let amount: U256 = parse_units(&amount, io.decimals)?.into();
+            if !amount.is_zero() {
+                deposits.push(deposit2Call {
+                    token: io.token,
+                    vaultId: io.vaultId,
+                    amount,
+                    tasks: vec![],
+                });
+            }
+        }
+
+        let approvals =
+            deposit_approvals(args.owner, orderbook.address, &rpc_url, &deposits).await?;
+
+        Ok(DeploymentTransactions {
+            owner: args.owner,
+            orderbook: orderbook.address,
+            rpc_url,
+            approvals,
+            deposits,
+            add_order,
+        })
+    }
+
+    /// Deposit amount of the given preset of the deployment's gui
+    fn gui_deposit_preset(
+        &self,
+        deployment_name: &str,
+        token_key: &str,
+        index: usize,
+    ) -> Option<String> {
+        self.config
+            .gui
+            .as_ref()?
+            .deployments
+            .iter()
+            .find(|deployment| deployment.deployment_name == deployment_name)?
+            .deposits
+            .iter()
+            .find(|deposit| deposit.token_name == token_key)?
+            .presets
+            .get(index)
+            .cloned()
+    }
+}
+
+/// Approvals of the total deposited amount of each token whose allowance
+/// for the orderbook is lower, approve sets the allowance rather than
+/// adding to it
+pub(crate) async fn deposit_approvals(
+    owner: Address,
+    orderbook: Address,
+    rpc_url: &str,
+    deposits: &[deposit2Call],
+) -> Result<Vec<(Address, approveCall)>, DeployError> {
+    let mut totals: Vec<(Address, U256)> = vec![];
+    for deposit in deposits {
+        match totals.iter_mut().find(|(token, _)| *token == deposit.token) {
+            Some((_, total)) => *total += deposit.amount,
+            None => totals.push((deposit.token, deposit.amount)),
+        }
+    }
+    let mut approvals = vec![];
+    for (token, amount) in totals {
+        let allowance = DepositArgs {
+            token,
+            vault_id: U256::ZERO,
+            amount,
+        }
+        .read_allowance(
+            owner,
+            TransactionArgs {
+                orderbook_address: orderbook,
+                rpc_url: rpc_url.to_string(),
+                ..Default::default()
+            },
+        )
+        .await?;
+        if allowance < amount {
+            approvals.push((
+                token,
+                approveCall {
+                    spender: orderbook,
+                    amount,
+                },
+            ));
+        }
+    }
+    Ok(approvals)
+}
+
+/// Reads the current balances of the given vaults of the owner, as
+/// (token, vault id) pairs, from the orderbook in a single multicall
+pub(crate) async fn read_vault_balances(
+    owner: Address,
+    orderbook: Address,
+    rpc_url: &str,
+    vaults: &[(Address, U256)],
+) -> Result<Vec<U256>, DeployError> {
+    if vaults.is_empty() {
+        return Ok(vec![]);
+    }
+    let client = ReadableClient::new_from_url(rpc_url.to_string())?;
+    let parameters = ReadContractParameters {
+        gas: None,
+        address: Address::from_hex(MULTICALL3_ADDRESS)?,
+        block_number: None,
+        call: aggregate3Call {
+            calls: vaults
+                .iter()
+                .map(|(token, vault_id)| Call3 {
+                    allowFailure: false,
+                    target: orderbook,
+                    callData: vaultBalanceCall {
+                        owner,
+                        token: *token,
+                        vaultId: *vault_id,
+                    }
+                    .abi_encode()
+                    .into(),
+                })
+                .collect(),
+        },
+    };
+    let result = client.read(parameters).await?;
+    let mut balances = vec![];
+    for res in result.returnData {
+        balances.push(vaultBalanceCall::abi_decode_returns(&res.returnData, true)?._0);
+    }
+    Ok(balances)
+}
+
+/// Sends the approvals and the multicall from the owner on a fork of the
+/// latest block, decoding what they do
+pub(crate) async fn preview_multicall(
+    owner: Address,
+    orderbook: Address,
+    rpc_url: &str,
+    approvals: &[(Address, approveCall)],
+    multicall: multicallCall,
+) -> Result<TransactionPreview, DeployError> {
+    let mut preview = PreviewBuilder::new(rpc_url.to_string(), owner).await?;
+    for (token, approval) in approvals {
+        preview.call(*token, approval.clone()).await?;
+    }
+    preview.call(orderbook, multicall).await?;
+    Ok(preview.finish().await?)
+}
+
+/// The approvals followed by the multicall, as the calls of a Safe batch
+pub(crate) fn multicall_safe_transactions(
+    orderbook: Address,
+    approvals: &[(Address, approveCall)],
+    multicall: multicallCall,
+) -> Vec<SafeTransaction> {
+    let mut transactions = approvals
+        .iter()
+        .map(|(token, approval)| SafeTransaction::new(*token, approval))
+        .collect::<Vec<_>>();
+    transactions.push(SafeTransaction::new(orderbook, &multicall));
+    transactions
+}
+
+/// Sends the approvals and then the multicall with the ledger wallet of the
+/// transaction args
+pub(crate) async fn execute_multicall<A, M>(
+    transaction_args: TransactionArgs,
+    approvals: &[(Address, approveCall)],
+    multicall: multicallCall,
+    approval_status_changed: A,
+    multicall_status_changed: M,
+) -> Result<(), DeployError>
+where
+    A: Fn(WriteTransactionStatus<approveCall>),
+    M: Fn(WriteTransactionStatus<multicallCall>),
+{
+    for (token, approval) in approvals {
+        transaction_args
+            .send(approval.clone(), *token, &approval_status_changed)
+            .await?;
+    }
+    transaction_args
+        .send(
+            multicall,
+            transaction_args.orderbook_address,
+            multicall_status_changed,
+        )
+        .await?;
+
+    Ok(())
+}
+
+impl DeploymentTransactions {
+    /// The orderbook multicall adding the order followed by the deposits
+    pub fn multicall(&self) -> multicallCall {
+        let mut data = vec![Bytes::from(self.add_order.abi_encode())];
+        data.extend(
+            self.deposits
+                .iter()
+                .map(|deposit| Bytes::from(deposit.abi_encode())),
+        );
+        multicallCall { data }
+    }
+
+    /// The order as it is stored by the orderbook once added
+    pub fn order(&self) -> OrderV3 {
+        OrderV3 {
+            owner: self.owner,
+            evaluable: self.add_order.config.evaluable.clone(),
+            validInputs: self.add_order.config.validInputs.clone(),
+            validOutputs: self.add_order.config.validOutputs.clone(),
+            nonce: self.add_order.config.nonce,
+        }
+    }
+
+    pub fn order_hash(&self) -> B256 {
+        keccak256(self.order().abi_encode())
+    }
+
+    /// Previews the approvals and the multicall sent from the owner
+    pub async fn preview(&self) -> Result<TransactionPreview, DeployError> {
+        preview_multicall(
+            self.owner,
+            self.orderbook,
+            &self.rpc_url,
+            &self.approvals,
+            self.multicall(),
+        )
+        .await
+    }
+
+    /// The approvals and the multicall as the calls of a Safe batch, for
+    /// owners that are a Safe rather than a ledger wallet
+    pub fn safe_transactions(&self) -> Vec<SafeTransaction> {
+        multicall_safe_transactions(self.orderbook, &self.approvals, self.multicall())
+    }
+
+    /// Sends the approvals and then the multicall with the ledger wallet of
+    /// the transaction args
+    pub async fn execute<A, M>(
+        &self,
+        transaction_args: TransactionArgs,
+        approval_status_changed: A,
+        multicall_status_changed: M,
+    ) -> Result<(), DeployError>
+    where
+        A: Fn(WriteTransactionStatus<approveCall>),
+        M: Fn(WriteTransactionStatus<multicallCall>),
+    {
+        execute_multicall(
+            TransactionArgs {
+                orderbook_address: self.orderbook,
+                rpc_url: self.rpc_url.clone(),
+                ..transaction_args
+            },
+            &self.approvals,
+            self.multicall(),
+            approval_status_changed,
+            multicall_status_changed,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::utils::parse_ether;
+    use rain_orderbook_test_fixtures::LocalEvm;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+    async fn test_prepare_deployment() {
+        let local_evm = LocalEvm::new_with_tokens(2).await;
+
+        let orderbook = &local_evm.orderbook;
+        let token1_holder = local_evm.signer_wallets[0].default_signer().address();
+        let token1 = local_evm.tokens[0].clone();
+        let token2 = local_evm.tokens[1].clone();
+
+        let dotrain = format!(
+            r#"
+networks:
+    some-key:
+        rpc: {rpc_url}
+        chain-id: 123
+        network-id: 123
+        currency: ETH
+subgraphs:
+    some-key: https://www.some-sg.com
+deployers:
+    some-key:
+        address: {deployer}
+tokens:
+    t1:
+        network: some-key
+        address: {token2}
+        decimals: 18
+        label: Token2
+        symbol: Token2
+    t2:
+        network: some-key
+        address: {token1}
+        decimals: 18
+        label: Token1
+        symbol: Token1
+orderbooks:
+    some-key:
+        address: {orderbook}
+orders:
+    some-key:
+        inputs:
+            - token: t1
+        outputs:
+            - token: t2
+              vault-id: 0x01
+        orderbook: some-key
+scenarios:
+    some-key:
+deployments:
+    some-key:
+        scenario: some-key
+        order: some-key
+gui:
+    name: Gui
+    description: Description
+    deployments:
+        - deployment: some-key
+          name: Deployment
+          description: Description
+          deposits:
+            - token: t2
+              presets:
+                - "10"
+                - "1000"
+          fields: []
+---
+#calculate-io
+amount price: 16 52;
+#handle-add-order
+:;
+#handle-io
+:;
+"#,
+            rpc_url = local_evm.url(),
+            orderbook = orderbook.address(),
+            deployer = local_evm.deployer.address(),
+            token1 = token1.address(),
+            token2 = token2.address(),
+        );
+        let order = DotrainOrder::new(dotrain, None).await.unwrap();
+
+        let transactions = order
+            .prepare_deployment(
+                "some-key",
+                DeployArgs {
+                    owner: token1_holder,
+                    deposits: HashMap::from([("t2".to_string(), DeployDeposit::Preset(1))]),
+                    salt: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(transactions.orderbook, *orderbook.address());
+        assert_eq!(
+            transactions.deposits,
+            vec![deposit2Call {
+                token: *token1.address(),
+                vaultId: U256::from(1),
+                amount: parse_ether("1000").unwrap(),
+                tasks: vec![],
+            }]
+        );
+        assert_eq!(
+            transactions.approvals,
+            vec![(
+                *token1.address(),
+                approveCall {
+                    spender: *orderbook.address(),
+                    amount: parse_ether("1000").unwrap(),
+                }
+            )]
+        );
+        assert_eq!(transactions.multicall().data.len(), 2);
+        assert_eq!(transactions.order().owner, token1_holder);
+        transactions.preview().await.unwrap();
+
+        // a salt derives the same order every time
+        let derived_args = DeployArgs {
+            owner: token1_holder,
+            deposits: HashMap::new(),
+            salt: Some("salt".to_string()),
+        };
+        let derived = order
+            .prepare_deployment("some-key", derived_args.clone())
+            .await
+            .unwrap();
+        let again = order
+            .prepare_deployment("some-key", derived_args)
+            .await
+            .unwrap();
+        assert_eq!(derived.order_hash(), again.order_hash());
+        assert_eq!(
+            derived.add_order.config.validInputs[0].vaultId,
+            OrderDerivation {
+                owner: token1_holder,
+                deployment: "some-key".to_string(),
+                salt: "salt".to_string(),
+            }
+            .vault_id()
+        );
+
+        let err = order
+            .prepare_deployment(
+                "some-key",
+                DeployArgs {
+                    owner: token1_holder,
+                    deposits: HashMap::from([("t2".to_string(), DeployDeposit::Preset(2))]),
+                    salt: None,
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DeployError::DepositPresetNotFound(token, 2) if token == "t2"));
+
+        let err = order
+            .prepare_deployment(
+                "some-key",
+                DeployArgs {
+                    owner: token1_holder,
+                    deposits: HashMap::from([(
+                        "t1".to_string(),
+                        DeployDeposit::Amount("1".to_string()),
+                    )]),
+                    salt: None,
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DeployError::DepositTokenNotFound(token) if token == "t1"));
+    }
+}
@@ -403,13 +403,17 @@ impl DotrainOrder {
             }
         }
 
-        // reserialize the new config and construct a new dotrain order with existing dotrain body
-        let mut new_dotrain = serde_yaml::to_string(&new_config_source)
+        // reserialize the new config and construct a new dotrain order with existing dotrain body,
+        // interpolated values are written back as their variables and resolved again from the
+        // values they had in this instance
+        new_config_source.secrets = config_source.secrets.clone();
+        let mut new_dotrain = new_config_source
+            .to_yaml_string()
             .map_err(|e| DotrainOrderError::CleanUnusedFrontmatterError(e.to_string()))?;
         new_dotrain.push_str("\n---\n");
         new_dotrain.push_str(self.dotrain.split("---").nth(1).unwrap());
 
-        Self::new(new_dotrain, None).await
+        Self::new_with_resolver(new_dotrain, None, &config_source.secrets).await
     }
 }
 
@@ -3,9 +3,11 @@ use std::collections::HashMap;
 use crate::GH_COMMIT_SHA;
 use crate::{
     add_order::{ORDERBOOK_ADDORDER_POST_TASK_ENTRYPOINTS, ORDERBOOK_ORDER_ENTRYPOINTS},
+    derivation::OrderDerivation,
     rainlang::compose_to_rainlang,
+    token_metadata::{enrich_token_metadata, TokenInfoCache, TokenMetadataWarnings},
 };
-use alloy::primitives::{private::rand, Address, U256};
+use alloy::primitives::{hex::FromHexError, private::rand, Address, U256};
 use alloy_ethers_typecast::transaction::{ReadableClient, ReadableClientError};
 use dotrain::{error::ComposeError, RainDocument};
 use futures::future::join_all;
@@ -13,7 +15,10 @@ use rain_interpreter_parser::{ParserError, ParserV2};
 pub use rain_metadata::types::authoring::v2::*;
 use rain_orderbook_app_settings::{
     config_source::{ConfigSource, ConfigSourceError},
-    merge::MergeError,
+    imports::ImportLoaders,
+    interpolation::{ConfigVariables, EnvResolver, VariableResolver},
+    merge::{ConfigLayers, MergeError, MergePrecedence},
+    rpc::{RpcProvider, RpcProviderError},
     Config, ParseConfigSourceError,
 };
 #[cfg(target_family = "wasm")]
@@ -23,7 +28,12 @@ use thiserror::Error;
 use typeshare::typeshare;
 
 pub mod calldata;
+#[cfg(not(target_family = "wasm"))]
+pub mod deploy;
 pub mod filter;
+pub mod quote;
+#[cfg(not(target_family = "wasm"))]
+pub mod replace;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(target_family = "wasm", wasm_bindgen)]
@@ -85,6 +95,12 @@ pub enum DotrainOrderError {
 
     #[error("Invalid index for vault ID")]
     InvalidVaultIdIndex,
+
+    #[error(transparent)]
+    FromHexError(#[from] FromHexError),
+
+    #[error(transparent)]
+    RpcProviderError(#[from] RpcProviderError),
 }
 
 #[cfg(target_family = "wasm")]
@@ -133,17 +149,43 @@ impl DotrainOrder {
         dotrain: String,
         config: Option<String>,
     ) -> Result<DotrainOrder, DotrainOrderError> {
-        let frontmatter = RainDocument::get_front_matter(&dotrain)
-            .unwrap_or("")
-            .to_string();
-        let (mut frontmatter_config, config_string) =
-            ConfigSource::try_from_string(frontmatter, config).await?;
-        frontmatter_config.merge(config_string)?;
-        Ok(Self {
-            dotrain,
-            config_source: frontmatter_config.clone(),
-            config: frontmatter_config.try_into()?,
-        })
+        Self::new_with_resolver(dotrain, config, &EnvResolver).await
+    }
+
+    /// Same as `create` but resolves the `${VAR}` references of the
+    /// frontmatter and config from the given variables
+    #[cfg_attr(target_family = "wasm", wasm_bindgen(js_name = "createWithVariables"))]
+    pub async fn new_with_variables(
+        dotrain: String,
+        config: Option<String>,
+        variables: ConfigVariables,
+    ) -> Result<DotrainOrder, DotrainOrderError> {
+        Self::new_with_resolver(dotrain, config, &variables).await
+    }
+
+    /// Fills in the missing decimals, symbol and label of the config's tokens
+    /// from chain and checks the configured decimals of the tokens its orders
+    /// use, returns a warning for every mismatch or token that could not be read.
+    /// Only the tokens of the given networks are read if any are given
+    #[cfg_attr(target_family = "wasm", wasm_bindgen(js_name = "enrichTokenMetadata"))]
+    pub async fn enrich_token_metadata(
+        &mut self,
+        networks: Option<Vec<String>>,
+        multicall_address: Option<String>,
+    ) -> Result<TokenMetadataWarnings, DotrainOrderError> {
+        let multicall_address = multicall_address
+            .map(|v| v.parse::<Address>())
+            .transpose()?;
+        let mut config_source = self.config_source.clone();
+        let warnings = enrich_token_metadata(
+            &mut config_source,
+            networks.as_deref(),
+            TokenInfoCache::global(),
+            multicall_address,
+        )
+        .await;
+        self.update_config_source(config_source)?;
+        Ok(TokenMetadataWarnings(warnings))
     }
 
     // get this instance's dotrain string
@@ -219,6 +261,92 @@ impl DotrainOrder {
 }
 
 impl DotrainOrder {
+    /// Parses the dotrain frontmatter merged with the given config, resolving
+    /// their `${VAR}` references with the given resolver
+    pub async fn new_with_resolver(
+        dotrain: String,
+        config: Option<String>,
+        resolver: &dyn VariableResolver,
+    ) -> Result<DotrainOrder, DotrainOrderError> {
+        Self::new_with_loaders(dotrain, config, resolver, &ImportLoaders::default()).await
+    }
+
+    /// Same as `new_with_resolver` but loads the imports of the frontmatter
+    /// and config with the given loaders, so their relative paths resolve
+    /// from the files they were read from
+    pub async fn new_with_loaders(
+        dotrain: String,
+        config: Option<String>,
+        resolver: &dyn VariableResolver,
+        loaders: &ImportLoaders,
+    ) -> Result<DotrainOrder, DotrainOrderError> {
+        let frontmatter = RainDocument::get_front_matter(&dotrain)
+            .unwrap_or("")
+            .to_string();
+        let (mut frontmatter_config, config_string) =
+            ConfigSource::try_from_string_with_loaders(frontmatter, config, resolver, loaders)
+                .await?;
+        frontmatter_config.merge(config_string)?;
+        Ok(Self {
+            dotrain,
+            config_source: frontmatter_config.clone(),
+            config: frontmatter_config.try_into()?,
+        })
+    }
+
+    /// Same as `new_with_loaders` but deep merges the frontmatter and the
+    /// config field by field rather than rejecting what both define, the
+    /// precedence decides which wins, `Ours` being the frontmatter. The
+    /// winning one only needs to define the fields it overrides as it is
+    /// validated once merged, so it can not use imports, remote networks and
+    /// tokens, nor anchors of the other one
+    pub async fn new_with_precedence(
+        dotrain: String,
+        config: Option<String>,
+        resolver: &dyn VariableResolver,
+        loaders: &ImportLoaders,
+        precedence: MergePrecedence,
+    ) -> Result<DotrainOrder, DotrainOrderError> {
+        let Some(config) = config else {
+            return Self::new_with_loaders(dotrain, None, resolver, loaders).await;
+        };
+        let frontmatter = RainDocument::get_front_matter(&dotrain)
+            .unwrap_or("")
+            .to_string();
+        let (base, base_loader, base_name, overrides, overrides_name) = match precedence {
+            MergePrecedence::Ours => (
+                config,
+                &loaders.top_config,
+                "settings",
+                frontmatter,
+                "frontmatter",
+            ),
+            MergePrecedence::Theirs => (
+                frontmatter,
+                &loaders.main,
+                "frontmatter",
+                config,
+                "settings",
+            ),
+        };
+        let base_loaders = ImportLoaders {
+            main: base_loader.clone(),
+            ..Default::default()
+        };
+        let (base, _) =
+            ConfigSource::try_from_string_with_loaders(base, None, resolver, &base_loaders).await?;
+        let (overrides, secrets) = ConfigSource::try_partial_from_string(&overrides, resolver)?;
+        let (config_source, _) = ConfigLayers::new()
+            .with_layer(base_name, base)
+            .with_partial_layer(overrides_name, overrides, secrets)
+            .merge()?;
+        Ok(Self {
+            dotrain,
+            config_source: config_source.clone(),
+            config: config_source.try_into()?,
+        })
+    }
+
     /// get this instance's config
     pub fn config(&self) -> &Config {
         &self.config
@@ -260,9 +388,13 @@ impl DotrainOrder {
             .compose_scenario_to_rainlang(scenario.to_string())
             .await?;
 
-        let client = ReadableClient::new_from_url(deployer.network.rpc.clone().to_string())?;
-        let pragmas = parser.parse_pragma_text(&rainlang, client).await?;
-        Ok(pragmas)
+        let (parser, rainlang) = (&parser, &rainlang);
+        RpcProvider::for_network(&deployer.network)
+            .with_failover(|rpc_url| async move {
+                let client = ReadableClient::new_from_url(rpc_url.to_string())?;
+                Ok::<_, DotrainOrderError>(parser.parse_pragma_text(rainlang, client).await?)
+            })
+            .await
     }
 
     pub async fn get_contract_authoring_meta_v2_for_scenario(
@@ -278,17 +410,25 @@ impl DotrainOrder {
             .deployer
             .network;
 
-        let rpc = &network.rpc;
         let metaboard = self
             .config
             .metaboards
             .get(&network.name)
             .ok_or_else(|| DotrainOrderError::MetaboardNotFound(network.name.clone()))?
-            .clone();
-        Ok(
-            AuthoringMetaV2::fetch_for_contract(address, rpc.to_string(), metaboard.to_string())
-                .await?,
-        )
+            .to_string();
+        let metaboard = &metaboard;
+        RpcProvider::for_network(network)
+            .with_failover(|rpc_url| async move {
+                Ok::<_, DotrainOrderError>(
+                    AuthoringMetaV2::fetch_for_contract(
+                        address,
+                        rpc_url.to_string(),
+                        metaboard.to_string(),
+                    )
+                    .await?,
+                )
+            })
+            .await
     }
 
     pub async fn get_deployer_words_for_scenario(
@@ -454,10 +594,14 @@ impl DotrainOrder {
         Ok(())
     }
 
+    /// Sets the vault id of the inputs and outputs of a deployment that do
+    /// not set one, to the custom vault id if given, else to the vault id of
+    /// the derivation if given, else to a random one
     pub fn populate_vault_ids(
         &mut self,
         deployment_name: &str,
         custom_vault_id: Option<U256>,
+        derivation: Option<&OrderDerivation>,
     ) -> Result<(), DotrainOrderError> {
         let deployment = self
             .config_source
@@ -474,7 +618,9 @@ impl DotrainOrder {
             .ok_or(DotrainOrderError::OrderNotFound(deployment.order.clone()))?
             .clone();
 
-        let vault_id = custom_vault_id.unwrap_or(rand::random());
+        let vault_id = custom_vault_id
+            .or(derivation.map(OrderDerivation::vault_id))
+            .unwrap_or_else(rand::random);
 
         let new_inputs = order
             .inputs
@@ -752,6 +898,72 @@ networks:
         );
     }
 
+    #[tokio::test]
+    async fn test_config_merge_frontmatter_overrides_settings() {
+        let server = mock_server(vec![]);
+        let dotrain = |network: &str| {
+            format!(
+                r#"
+networks:
+  mainnet:
+{network}
+---
+#calculate-io
+_ _: 00;
+
+#handle-io
+:;"#
+            )
+        };
+        let settings = format!(
+            r#"
+networks:
+    mainnet:
+        rpc: {rpc_url}
+        chain-id: 1
+        currency: ETH"#,
+            rpc_url = server.url("/rpc-mainnet"),
+        );
+
+        // the strict merge rejects what both define
+        let whole_network = format!("    rpc: {}\n    chain-id: 1", server.url("/rpc-private"));
+        let err = DotrainOrder::new(dotrain(&whole_network), Some(settings.clone()))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            DotrainOrderError::MergeConfigError(MergeError::NetworkCollision(ref v)) if v == "mainnet"
+        ));
+
+        // the frontmatter only overrides the rpc of the settings' network
+        let rpc_only = format!("    rpc: {}", server.url("/rpc-private"));
+        let dotrain_order = DotrainOrder::new_with_precedence(
+            dotrain(&rpc_only),
+            Some(settings.clone()),
+            &EnvResolver,
+            &ImportLoaders::default(),
+            MergePrecedence::Ours,
+        )
+        .await
+        .unwrap();
+        let mainnet = dotrain_order.config.networks.get("mainnet").unwrap();
+        assert_eq!(mainnet.rpc.to_string(), server.url("/rpc-private"));
+        assert_eq!(mainnet.chain_id, 1);
+        assert_eq!(mainnet.currency, Some("ETH".to_string()));
+
+        let dotrain_order = DotrainOrder::new_with_precedence(
+            dotrain(&whole_network),
+            Some(settings),
+            &EnvResolver,
+            &ImportLoaders::default(),
+            MergePrecedence::Theirs,
+        )
+        .await
+        .unwrap();
+        let mainnet = dotrain_order.config.networks.get("mainnet").unwrap();
+        assert_eq!(mainnet.rpc.to_string(), server.url("/rpc-mainnet"));
+    }
+
     #[tokio::test]
     async fn test_get_pragmas_for_scenario() {
         let pragma_addresses = vec![Address::random()];
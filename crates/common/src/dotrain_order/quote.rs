@@ -0,0 +1,844 @@
+use super::*;
+use crate::add_order::{AddOrderArgs, AddOrderArgsError};
+use alloy::{
+    hex::{decode, encode_prefixed, FromHex},
+    primitives::{
+        keccak256,
+        utils::{parse_units, UnitsError},
+        B256, U64,
+    },
+    sol_types::{SolCall, SolValue},
+};
+#[cfg(not(target_family = "wasm"))]
+use alloy_ethers_typecast::transaction::ReadableClientHttp;
+use alloy_ethers_typecast::{
+    multicall::{
+        IMulticall3::{aggregate3Call, Call3},
+        MULTICALL3_ADDRESS,
+    },
+    transaction::ReadContractParameters,
+};
+#[cfg(not(target_family = "wasm"))]
+use rain_interpreter_eval::{
+    error::ForkCallError,
+    fork::{Forker, NewForkedEvm},
+};
+use rain_orderbook_app_settings::{
+    deployment::Deployment,
+    orderbook::Orderbook,
+    rpc::{json_rpc, RpcProvider, RpcProviderError},
+    token::Token,
+};
+use rain_orderbook_bindings::{
+    IOrderBookV4::{addOrder2Call, deposit2Call, quoteCall, OrderV3, Quote},
+    IERC20::{approveCall, balanceOfCall},
+};
+#[cfg(not(target_family = "wasm"))]
+use rain_orderbook_quote::{error::FailedQuote, OrderQuoteValue};
+use rain_orderbook_quote::{rpc::decode_quote_result, QuoteResult, QuoteTarget, QuoteToken};
+use serde_json::{json, Map, Value};
+use std::{collections::HashMap, sync::Arc};
+use url::Url;
+
+/// Number of storage slots probed for the balances mapping of a token whose
+/// balance is credited to the multicall contract
+const MAX_BALANCE_SLOT: u64 = 20;
+
+/// Arguments for quoting a deployment on a fork
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DeploymentQuoteArgs {
+    /// Address that adds the order and makes the deposits on the fork, it
+    /// needs to hold enough of the deposited tokens at the fork block, a
+    /// random address is used if not provided
+    pub owner: Option<Address>,
+    /// Deposit amounts in human readable units keyed by their token key,
+    /// each deposit goes into the vault of the matching order output
+    pub deposits: HashMap<String, String>,
+    /// Block number to fork at, defaults to latest
+    pub block_number: Option<u64>,
+}
+
+/// Quotes of all IO pairs of a deployment's order
+#[derive(Debug)]
+pub struct DeploymentQuotes {
+    /// Block number the order was quoted at
+    pub block_number: u64,
+    /// The order's IO tokens with the symbols specified in the config
+    pub tokens: Vec<QuoteToken>,
+    /// Quote target of each IO pair of the order along with its result
+    pub quotes: Vec<(QuoteTarget, QuoteResult)>,
+}
+
+#[derive(Debug, Error)]
+pub enum DeploymentQuoteError {
+    #[error("Deployment not found {0}")]
+    DeploymentNotFound(String),
+
+    #[error("Orderbook not found")]
+    OrderbookNotFound,
+
+    #[error("Deposit token {0} is not an output of the order")]
+    DepositTokenNotFound(String),
+
+    #[error("Depositing requires the owner address holding the tokens")]
+    MissingOwner,
+
+    #[error("Cannot find the balances of token {0} to credit its deposit")]
+    BalanceSlotNotFound(Address),
+
+    #[error("Failed to call with state overrides: {0}")]
+    OverrideCallError(String),
+
+    #[error(transparent)]
+    AddOrderArgsError(#[from] AddOrderArgsError),
+
+    #[cfg(not(target_family = "wasm"))]
+    #[error(transparent)]
+    ForkCallError(#[from] ForkCallError),
+
+    #[error(transparent)]
+    ReadableClientError(#[from] ReadableClientError),
+
+    #[error(transparent)]
+    UnitsError(#[from] UnitsError),
+
+    #[error(transparent)]
+    RpcProviderError(#[from] RpcProviderError),
+
+    #[error(transparent)]
+    UrlParseError(#[from] url::ParseError),
+
+    #[error(transparent)]
+    AbiDecodeError(#[from] alloy::sol_types::Error),
+}
+
+/// The deployment's order ready to be added, along with what it is quoted with
+struct PreparedQuote {
+    deployment: Arc<Deployment>,
+    orderbook: Arc<Orderbook>,
+    rpc_url: String,
+    add_order_args: AddOrderArgs,
+    add_order_call: addOrder2Call,
+}
+
+impl PreparedQuote {
+    /// The order as it is stored by the orderbook once the owner adds it
+    fn order(&self, owner: Address) -> OrderV3 {
+        OrderV3 {
+            owner,
+            evaluable: self.add_order_call.config.evaluable.clone(),
+            validInputs: self.add_order_call.config.validInputs.clone(),
+            validOutputs: self.add_order_call.config.validOutputs.clone(),
+            nonce: self.add_order_call.config.nonce,
+        }
+    }
+
+    /// Quote targets of every IO pair of the order that trades distinct tokens
+    fn quote_targets(&self, owner: Address) -> Vec<QuoteTarget> {
+        let order = self.order(owner);
+        let mut quote_targets = vec![];
+        for input_index in 0..order.validInputs.len() {
+            for output_index in 0..order.validOutputs.len() {
+                if order.validInputs[input_index].token == order.validOutputs[output_index].token {
+                    continue;
+                }
+                quote_targets.push(QuoteTarget {
+                    orderbook: self.orderbook.address,
+                    quote_config: Quote {
+                        order: order.clone(),
+                        inputIOIndex: U256::from(input_index),
+                        outputIOIndex: U256::from(output_index),
+                        signedContext: vec![],
+                    },
+                });
+            }
+        }
+        quote_targets
+    }
+
+    /// Resolves deposit amounts in human readable units keyed by their token
+    /// key into deposits into the vaults of the matching order outputs
+    fn deposits(
+        &self,
+        tokens: &HashMap<String, Arc<Token>>,
+        deposits: &HashMap<String, String>,
+    ) -> Result<Vec<deposit2Call>, DeploymentQuoteError> {
+        let mut resolved = vec![];
+        for (token_key, amount) in deposits {
+            let io = tokens
+                .get(token_key)
+                .and_then(|token| {
+                    self.add_order_args
+                        .outputs
+                        .iter()
+                        .find(|io| io.token == token.address)
+                })
+                .ok_or(DeploymentQuoteError::DepositTokenNotFound(
+                    token_key.clone(),
+                ))?;
+            let amount: U256 = parse_units(amount, io.decimals)?.into();
+            if !amount.is_zero() {
+                resolved.push(deposit2Call {
+                    token: io.token,
+                    vaultId: io.vaultId,
+                    amount,
+                    tasks: vec![],
+                });
+            }
+        }
+        Ok(resolved)
+    }
+
+    /// The order's IO tokens with the symbols specified in the config
+    fn tokens(&self) -> Vec<QuoteToken> {
+        let mut tokens: Vec<QuoteToken> = vec![];
+        for (io, order_io) in self
+            .add_order_args
+            .inputs
+            .iter()
+            .zip(&self.deployment.order.inputs)
+            .chain(
+                self.add_order_args
+                    .outputs
+                    .iter()
+                    .zip(&self.deployment.order.outputs),
+            )
+        {
+            if !tokens.iter().any(|v| v.address == io.token) {
+                tokens.push(QuoteToken {
+                    address: io.token,
+                    symbol: order_io.token.symbol.clone(),
+                    decimals: Some(io.decimals),
+                });
+            }
+        }
+        tokens
+    }
+}
+
+impl DotrainOrder {
+    /// The rpcs of the network the given deployment's order is added on
+    fn quote_rpc(&self, deployment_name: &str) -> Result<Arc<RpcProvider>, DeploymentQuoteError> {
+        let orderbook = self
+            .config
+            .deployments
+            .get(deployment_name)
+            .ok_or(DeploymentQuoteError::DeploymentNotFound(
+                deployment_name.to_string(),
+            ))?
+            .order
+            .orderbook
+            .clone()
+            .ok_or(DeploymentQuoteError::OrderbookNotFound)?;
+        Ok(RpcProvider::for_network(&orderbook.network))
+    }
+
+    async fn prepare_quote(
+        &self,
+        deployment_name: &str,
+        rpc_url: String,
+    ) -> Result<PreparedQuote, DeploymentQuoteError> {
+        let deployment = self
+            .config
+            .deployments
+            .get(deployment_name)
+            .ok_or(DeploymentQuoteError::DeploymentNotFound(
+                deployment_name.to_string(),
+            ))?
+            .clone();
+        let orderbook = deployment
+            .order
+            .orderbook
+            .clone()
+            .ok_or(DeploymentQuoteError::OrderbookNotFound)?;
+
+        let add_order_args =
+            AddOrderArgs::new_from_deployment(self.dotrain.clone(), deployment.as_ref().to_owned())
+                .await?;
+        let add_order_call = add_order_args.try_into_call(rpc_url.clone()).await?;
+
+        Ok(PreparedQuote {
+            deployment,
+            orderbook,
+            rpc_url,
+            add_order_args,
+            add_order_call,
+        })
+    }
+
+    /// Quotes every IO pair of the given deployment's order without it being
+    /// deployed, by adding the order along with the given deposits into a
+    /// fork of the deployment's network and quoting it there
+    #[cfg(not(target_family = "wasm"))]
+    pub async fn quote_deployment(
+        &self,
+        deployment_name: &str,
+        args: DeploymentQuoteArgs,
+    ) -> Result<DeploymentQuotes, DeploymentQuoteError> {
+        // the whole quote is made on the same rpc, and made again on the
+        // next one if it fails midway
+        self.quote_rpc(deployment_name)?
+            .with_failover(|rpc_url| {
+                self.quote_deployment_on(deployment_name, &args, rpc_url.to_string())
+            })
+            .await
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    async fn quote_deployment_on(
+        &self,
+        deployment_name: &str,
+        args: &DeploymentQuoteArgs,
+        rpc_url: String,
+    ) -> Result<DeploymentQuotes, DeploymentQuoteError> {
+        let prepared = self.prepare_quote(deployment_name, rpc_url).await?;
+        let orderbook = prepared.orderbook.address;
+
+        let deposits = prepared.deposits(&self.config.tokens, &args.deposits)?;
+        let owner = match args.owner {
+            Some(owner) => owner,
+            None if deposits.is_empty() => Address::random(),
+            None => return Err(DeploymentQuoteError::MissingOwner),
+        };
+
+        let block_number = match args.block_number {
+            Some(block_number) => block_number,
+            None => {
+                ReadableClientHttp::new_from_url(prepared.rpc_url.clone())?
+                    .get_block_number()
+                    .await?
+            }
+        };
+        let mut forker = Forker::new_with_fork(
+            NewForkedEvm {
+                fork_url: prepared.rpc_url.clone(),
+                fork_block_number: Some(block_number),
+            },
+            None,
+            None,
+        )
+        .await?;
+
+        for deposit in deposits {
+            forker
+                .alloy_call_committing(
+                    owner,
+                    deposit.token,
+                    approveCall {
+                        spender: orderbook,
+                        amount: deposit.amount,
+                    },
+                    U256::ZERO,
+                    true,
+                )
+                .await?;
+            forker
+                .alloy_call_committing(owner, orderbook, deposit, U256::ZERO, true)
+                .await?;
+        }
+        forker
+            .alloy_call_committing(
+                owner,
+                orderbook,
+                prepared.add_order_call.clone(),
+                U256::ZERO,
+                true,
+            )
+            .await?;
+
+        let mut quotes = vec![];
+        for quote_target in prepared.quote_targets(owner) {
+            let quote_result = forker
+                .alloy_call(
+                    Address::ZERO,
+                    orderbook,
+                    quoteCall {
+                        quoteConfig: quote_target.quote_config.clone(),
+                    },
+                    true,
+                )
+                .await
+                .map_err(|e| match e {
+                    ForkCallError::AbiDecodedError(v) => FailedQuote::RevertError(v),
+                    other => FailedQuote::CorruptReturnData(other.to_string()),
+                })
+                .and_then(|v| {
+                    if v.typed_return.exists {
+                        Ok(OrderQuoteValue::from(v.typed_return))
+                    } else {
+                        Err(FailedQuote::NonExistent)
+                    }
+                });
+            quotes.push((quote_target, quote_result));
+        }
+
+        Ok(DeploymentQuotes {
+            block_number,
+            tokens: prepared.tokens(),
+            quotes,
+        })
+    }
+
+    /// Deposits of the given deployment's gui, the first preset amount of
+    /// each of its deposits keyed by their token key, the deployment's order
+    /// can be quoted with these when no deposits are chosen
+    pub fn preset_deposits(&self, deployment_name: &str) -> HashMap<String, String> {
+        self.config
+            .gui
+            .iter()
+            .flat_map(|gui| &gui.deployments)
+            .filter(|deployment| deployment.deployment_name == deployment_name)
+            .flat_map(|deployment| &deployment.deposits)
+            .filter_map(|deposit| {
+                deposit
+                    .presets
+                    .first()
+                    .map(|amount| (deposit.token_name.clone(), amount.clone()))
+            })
+            .collect()
+    }
+
+    /// Quotes every IO pair of the given deployment's order without it being
+    /// deployed and without a fork, so it also runs in wasm. A single
+    /// `eth_call` of the multicall contract makes the given deposits, adds
+    /// the order and then quotes it, so the multicall contract is the order's
+    /// owner. The deposited tokens are credited to the multicall contract by
+    /// overriding their balances in the call's state, so no holder of them
+    /// is needed. Without deposits the max outputs are capped at zero by the
+    /// empty vaults, while the ratios are those the order would trade at
+    pub async fn quote_deployment_with_rpc(
+        &self,
+        deployment_name: &str,
+        deposits: &HashMap<String, String>,
+        block_number: Option<u64>,
+        multicall_address: Option<Address>,
+    ) -> Result<DeploymentQuotes, DeploymentQuoteError> {
+        self.quote_rpc(deployment_name)?
+            .with_failover(|rpc_url| {
+                self.quote_deployment_with_rpc_on(
+                    deployment_name,
+                    deposits,
+                    block_number,
+                    multicall_address,
+                    rpc_url.to_string(),
+                )
+            })
+            .await
+    }
+
+    async fn quote_deployment_with_rpc_on(
+        &self,
+        deployment_name: &str,
+        deposits: &HashMap<String, String>,
+        block_number: Option<u64>,
+        multicall_address: Option<Address>,
+        rpc_url: String,
+    ) -> Result<DeploymentQuotes, DeploymentQuoteError> {
+        let prepared = self.prepare_quote(deployment_name, rpc_url).await?;
+        let deposits = prepared.deposits(&self.config.tokens, deposits)?;
+        let orderbook = prepared.orderbook.address;
+        let multicall_address =
+            multicall_address.unwrap_or(Address::from_hex(MULTICALL3_ADDRESS).unwrap());
+        let client = ReadableClient::new_from_url(prepared.rpc_url.clone())?;
+        let block_number = match block_number {
+            Some(block_number) => block_number,
+            None => client.get_block_number().await?,
+        };
+
+        let mut calls = vec![];
+        for deposit in &deposits {
+            calls.push(Call3 {
+                allowFailure: false,
+                target: deposit.token,
+                callData: approveCall {
+                    spender: orderbook,
+                    amount: deposit.amount,
+                }
+                .abi_encode()
+                .into(),
+            });
+            calls.push(Call3 {
+                allowFailure: false,
+                target: orderbook,
+                callData: deposit.abi_encode().into(),
+            });
+        }
+        calls.push(Call3 {
+            allowFailure: false,
+            target: orderbook,
+            callData: prepared.add_order_call.abi_encode().into(),
+        });
+        let quote_targets = prepared.quote_targets(multicall_address);
+        calls.extend(quote_targets.iter().map(|quote_target| {
+            Call3 {
+                allowFailure: true,
+                target: orderbook,
+                callData: quoteCall {
+                    quoteConfig: quote_target.quote_config.clone(),
+                }
+                .abi_encode()
+                .into(),
+            }
+        }));
+
+        let return_data = if deposits.is_empty() {
+            client
+                .read(ReadContractParameters {
+                    gas: None,
+                    address: multicall_address,
+                    block_number: Some(U64::from(block_number)),
+                    call: aggregate3Call { calls },
+                })
+                .await?
+                .returnData
+        } else {
+            // not every rpc supports state overrides, so they are only
+            // sent when there is something to credit
+            let url = Url::parse(&prepared.rpc_url)?;
+            let block = format!("0x{:x}", block_number);
+            let mut amounts: HashMap<Address, U256> = HashMap::new();
+            for deposit in &deposits {
+                *amounts.entry(deposit.token).or_default() += deposit.amount;
+            }
+            let mut overrides = Map::new();
+            for (token, amount) in amounts {
+                let slot = find_balance_slot(&url, token, multicall_address, &block).await?;
+                overrides.insert(token.to_string(), state_diff(slot, B256::from(amount)));
+            }
+            let data = call_with_overrides(
+                &url,
+                multicall_address,
+                aggregate3Call { calls }.abi_encode(),
+                &block,
+                &Value::Object(overrides),
+            )
+            .await?;
+            aggregate3Call::abi_decode_returns(&data, true)?.returnData
+        };
+
+        let mut quotes = vec![];
+        for (quote_target, result) in quote_targets
+            .into_iter()
+            .zip(return_data.iter().skip(1 + 2 * deposits.len()))
+        {
+            quotes.push((quote_target, decode_quote_result(result).await));
+        }
+
+        Ok(DeploymentQuotes {
+            block_number,
+            tokens: prepared.tokens(),
+            quotes,
+        })
+    }
+}
+
+/// Makes an `eth_call` at the given block with the given state overrides
+async fn call_with_overrides(
+    url: &Url,
+    to: Address,
+    data: Vec<u8>,
+    block: &str,
+    overrides: &Value,
+) -> Result<Vec<u8>, DeploymentQuoteError> {
+    let result = json_rpc(
+        url,
+        "eth_call",
+        json!([{ "to": to, "data": encode_prefixed(data) }, block, overrides]),
+    )
+    .await
+    .map_err(DeploymentQuoteError::OverrideCallError)?;
+    result
+        .as_str()
+        .and_then(|v| decode(v).ok())
+        .ok_or(DeploymentQuoteError::OverrideCallError(format!(
+            "unexpected result {}",
+            result
+        )))
+}
+
+/// State override setting the given storage slot of an account
+fn state_diff(slot: B256, value: B256) -> Value {
+    json!({ "stateDiff": { encode_prefixed(slot): encode_prefixed(value) } })
+}
+
+/// Storage slot of the holder's balance in the given token, found by
+/// overriding the candidate slots of the solidity and vyper layouts of a
+/// balances mapping until `balanceOf` reads back the overridden value
+async fn find_balance_slot(
+    url: &Url,
+    token: Address,
+    holder: Address,
+    block: &str,
+) -> Result<B256, DeploymentQuoteError> {
+    let probe = B256::from(U256::from(0x5eed_u64));
+    let balance_of = balanceOfCall { account: holder }.abi_encode();
+    for index in 0..MAX_BALANCE_SLOT {
+        let index = U256::from(index);
+        for slot in [
+            keccak256((holder, index).abi_encode()),
+            keccak256((index, holder).abi_encode()),
+        ] {
+            let overrides = json!({ token.to_string(): state_diff(slot, probe) });
+            let balance =
+                call_with_overrides(url, token, balance_of.clone(), block, &overrides).await?;
+            if balance == probe.as_slice() {
+                return Ok(slot);
+            }
+        }
+    }
+    Err(DeploymentQuoteError::BalanceSlotNotFound(token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::utils::parse_ether;
+    use rain_orderbook_test_fixtures::LocalEvm;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+    async fn test_quote_deployment() {
+        let local_evm = LocalEvm::new_with_tokens(2).await;
+
+        let orderbook = &local_evm.orderbook;
+        let token1_holder = local_evm.signer_wallets[0].default_signer().address();
+        let token1 = local_evm.tokens[0].clone();
+        let token2 = local_evm.tokens[1].clone();
+
+        let dotrain = format!(
+            r#"
+networks:
+    some-key:
+        rpc: {rpc_url}
+        chain-id: 123
+        network-id: 123
+        currency: ETH
+subgraphs:
+    some-key: https://www.some-sg.com
+deployers:
+    some-key:
+        address: {deployer}
+tokens:
+    t1:
+        network: some-key
+        address: {token2}
+        decimals: 18
+        label: Token2
+        symbol: Token2
+    t2:
+        network: some-key
+        address: {token1}
+        decimals: 18
+        label: Token1
+        symbol: Token1
+orderbooks:
+    some-key:
+        address: {orderbook}
+orders:
+    some-key:
+        inputs:
+            - token: t1
+        outputs:
+            - token: t2
+              vault-id: 0x01
+        orderbook: some-key
+scenarios:
+    some-key:
+deployments:
+    some-key:
+        scenario: some-key
+        order: some-key
+gui:
+    name: Test
+    description: Test
+    deployments:
+        - deployment: some-key
+          name: Test
+          description: Test
+          deposits:
+              - token: t2
+                presets:
+                    - "1000"
+                    - "2000"
+          fields: []
+---
+#calculate-io
+amount price: 16 52;
+#handle-add-order
+:;
+#handle-io
+:;
+"#,
+            rpc_url = local_evm.url(),
+            orderbook = orderbook.address(),
+            deployer = local_evm.deployer.address(),
+            token1 = token1.address(),
+            token2 = token2.address(),
+        );
+        let order = DotrainOrder::new(dotrain, None).await.unwrap();
+        assert_eq!(
+            order.preset_deposits("some-key"),
+            HashMap::from([("t2".to_string(), "1000".to_string())])
+        );
+        assert!(order.preset_deposits("other-key").is_empty());
+
+        // without deposits the max output is capped by the empty vault
+        let result = order
+            .quote_deployment("some-key", DeploymentQuoteArgs::default())
+            .await
+            .unwrap();
+        assert_eq!(result.quotes.len(), 1);
+        assert_eq!(
+            result.quotes[0].1.as_ref().unwrap(),
+            &OrderQuoteValue {
+                max_output: U256::ZERO,
+                ratio: parse_ether("52").unwrap(),
+            }
+        );
+        assert_eq!(
+            result.tokens,
+            vec![
+                QuoteToken {
+                    address: *token2.address(),
+                    symbol: Some("Token2".to_string()),
+                    decimals: Some(18),
+                },
+                QuoteToken {
+                    address: *token1.address(),
+                    symbol: Some("Token1".to_string()),
+                    decimals: Some(18),
+                },
+            ]
+        );
+
+        let result = order
+            .quote_deployment(
+                "some-key",
+                DeploymentQuoteArgs {
+                    owner: Some(token1_holder),
+                    deposits: HashMap::from([("t2".to_string(), "1000".to_string())]),
+                    block_number: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.quotes.len(), 1);
+        let (quote_target, quote_result) = &result.quotes[0];
+        assert_eq!(quote_target.quote_config.order.owner, token1_holder);
+        assert_eq!(
+            quote_result.as_ref().unwrap(),
+            &OrderQuoteValue {
+                max_output: parse_ether("16").unwrap(),
+                ratio: parse_ether("52").unwrap(),
+            }
+        );
+
+        // quoting with a single eth_call, the multicall contract owns the order
+        let result = order
+            .quote_deployment_with_rpc(
+                "some-key",
+                &HashMap::new(),
+                None,
+                Some(*local_evm.multicall3.address()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.quotes.len(), 1);
+        let (quote_target, quote_result) = &result.quotes[0];
+        assert_eq!(
+            quote_target.quote_config.order.owner,
+            *local_evm.multicall3.address()
+        );
+        assert_eq!(
+            quote_result.as_ref().unwrap(),
+            &OrderQuoteValue {
+                max_output: U256::ZERO,
+                ratio: parse_ether("52").unwrap(),
+            }
+        );
+        assert_eq!(result.tokens.len(), 2);
+
+        // the deposits are credited to the multicall contract, so no holder
+        // of the tokens is needed
+        let result = order
+            .quote_deployment_with_rpc(
+                "some-key",
+                &order.preset_deposits("some-key"),
+                None,
+                Some(*local_evm.multicall3.address()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.quotes.len(), 1);
+        assert_eq!(
+            result.quotes[0].1.as_ref().unwrap(),
+            &OrderQuoteValue {
+                max_output: parse_ether("16").unwrap(),
+                ratio: parse_ether("52").unwrap(),
+            }
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+    async fn test_quote_deployment_errors() {
+        let dotrain = r#"
+networks:
+    some-key:
+        rpc: https://some-rpc.com
+        chain-id: 123
+subgraphs:
+    some-key: https://www.some-sg.com
+deployers:
+    some-key:
+        address: 0x1234567890123456789012345678901234567890
+tokens:
+    t1:
+        network: some-key
+        address: 0x1234567890123456789012345678901234567891
+        decimals: 18
+orderbooks:
+    some-key:
+        address: 0x1234567890123456789012345678901234567892
+orders:
+    some-key:
+        inputs:
+            - token: t1
+        outputs:
+            - token: t1
+scenarios:
+    some-key:
+deployments:
+    some-key:
+        scenario: some-key
+        order: some-key
+---
+#calculate-io
+_ _: 0 0;
+#handle-add-order
+:;
+#handle-io
+:;
+"#;
+        let order = DotrainOrder::new(dotrain.to_string(), None).await.unwrap();
+        let err = order
+            .quote_deployment("other-key", DeploymentQuoteArgs::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            DeploymentQuoteError::DeploymentNotFound(v) if v == "other-key"
+        ));
+
+        // the order does not specify an orderbook to be added to
+        let err = order
+            .quote_deployment("some-key", DeploymentQuoteArgs::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DeploymentQuoteError::OrderbookNotFound));
+
+        let err = order
+            .quote_deployment_with_rpc("some-key", &HashMap::new(), None, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DeploymentQuoteError::OrderbookNotFound));
+    }
+}
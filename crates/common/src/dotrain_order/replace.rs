@@ -0,0 +1,764 @@
+use super::{
+    deploy::{
+        deposit_approvals, execute_multicall, multicall_safe_transactions, preview_multicall,
+        read_vault_balances, DeployError,
+    },
+    *,
+};
+use crate::{
+    add_order::{AddOrderArgs, AddOrderArgsError},
+    derivation::OrderDerivation,
+    meta::TryDecodeRainlangSource,
+    preview::TransactionPreview,
+    remove_order::RemoveOrderArgs,
+    safe::SafeTransaction,
+    transaction::TransactionArgs,
+};
+use alloy::{
+    primitives::{keccak256, Bytes, B256},
+    sol_types::{SolCall, SolValue},
+};
+use alloy_ethers_typecast::transaction::WriteTransactionStatus;
+use rain_orderbook_app_settings::{deployment::Deployment, order::OrderIO, orderbook::Orderbook};
+use rain_orderbook_bindings::{
+    IOrderBookV4::{addOrder2Call, deposit2Call, removeOrder2Call, withdraw2Call, OrderV3, IO},
+    OrderBook::multicallCall,
+    IERC20::approveCall,
+};
+use rain_orderbook_subgraph_client::types::{common::Order, order_detail_traits::OrderDetailError};
+use std::fmt;
+
+/// Arguments for replacing a live order with a deployment
+#[derive(Debug, Clone)]
+pub struct ReplaceArgs {
+    /// The live order to replace, as indexed by the subgraph
+    pub order: Order,
+    /// Bindings the live order was deployed with, to diff against the
+    /// bindings of the deployment
+    pub previous_bindings: Option<HashMap<String, String>>,
+    /// Salt to derive the new order's nonce, secret and vault ids from
+    /// along with the owner and deployment name, as when deploying
+    pub salt: Option<String>,
+}
+
+/// A balance moved out of a vault of the replaced order into the vault the
+/// new order uses for the same token
+#[derive(Debug, Clone, PartialEq)]
+pub struct VaultMove {
+    pub token: Address,
+    pub from_vault_id: U256,
+    pub to_vault_id: U256,
+    pub amount: U256,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine {
+    Unchanged(String),
+    Removed(String),
+    Added(String),
+}
+
+/// A binding whose value differs between the replaced and the new order,
+/// None if it is not bound on that side
+#[derive(Debug, Clone, PartialEq)]
+pub struct BindingChange {
+    pub name: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// Difference between the replaced order and the order replacing it
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct OrderDiff {
+    pub removed_inputs: Vec<IO>,
+    pub added_inputs: Vec<IO>,
+    pub removed_outputs: Vec<IO>,
+    pub added_outputs: Vec<IO>,
+    /// Empty if the previous bindings are not known
+    pub bindings: Vec<BindingChange>,
+    pub rainlang: Vec<DiffLine>,
+}
+
+/// The orderbook multicall that removes a live order, adds the order of a
+/// deployment and moves the balances of the vaults the new order no longer
+/// uses, along with the approvals the moves need
+#[derive(Debug, Clone)]
+pub struct OrderReplacement {
+    pub owner: Address,
+    pub orderbook: Address,
+    pub rpc_url: String,
+    pub approvals: Vec<(Address, approveCall)>,
+    pub remove_order: removeOrder2Call,
+    pub add_order: addOrder2Call,
+    pub moves: Vec<VaultMove>,
+    pub diff: OrderDiff,
+}
+
+#[derive(Debug, Error)]
+pub enum ReplaceError {
+    #[error("Deployment not found {0}")]
+    DeploymentNotFound(String),
+
+    #[error("Orderbook not found")]
+    OrderbookNotFound,
+
+    #[error("Order {0} is not active")]
+    OrderNotActive(String),
+
+    #[error("Order is on orderbook {0}, not on the orderbook of the deployment")]
+    OrderbookMismatch(Address),
+
+    #[error(
+        "Balance of vault {vault_id} of token {token} changed from {expected} to {actual} since the replacement was prepared"
+    )]
+    VaultBalanceChanged {
+        token: Address,
+        vault_id: U256,
+        expected: U256,
+        actual: U256,
+    },
+
+    #[error(transparent)]
+    AddOrderArgsError(#[from] AddOrderArgsError),
+
+    #[error(transparent)]
+    DeployError(#[from] DeployError),
+
+    #[error(transparent)]
+    OrderDetailError(#[from] OrderDetailError),
+
+    #[error(transparent)]
+    FromHexError(#[from] FromHexError),
+
+    #[error(transparent)]
+    RpcProviderError(#[from] RpcProviderError),
+}
+
+impl DotrainOrder {
+    /// Builds the multicall that replaces the given live order with the
+    /// given deployment. The inputs and outputs whose vault id is not set by
+    /// the deployment reuse the vault of the live order for the same token,
+    /// and the balances of the vaults the new order no longer uses are
+    /// withdrawn and deposited into the vault it uses for their token, at
+    /// their current balance read from the orderbook
+    pub async fn prepare_replacement(
+        &self,
+        deployment_name: &str,
+        args: ReplaceArgs,
+    ) -> Result<OrderReplacement, ReplaceError> {
+        let deployment = self.config.deployments.get(deployment_name).ok_or(
+            ReplaceError::DeploymentNotFound(deployment_name.to_string()),
+        )?;
+        let orderbook = deployment
+            .order
+            .orderbook
+            .clone()
+            .ok_or(ReplaceError::OrderbookNotFound)?;
+
+        if !args.order.active {
+            return Err(ReplaceError::OrderNotActive(
+                args.order.order_hash.0.clone(),
+            ));
+        }
+        let order_orderbook = args.order.orderbook.id.0.parse::<Address>()?;
+        if order_orderbook != orderbook.address {
+            return Err(ReplaceError::OrderbookMismatch(order_orderbook));
+        }
+        let old_order: OrderV3 = args.order.clone().try_into()?;
+        let remove_order: removeOrder2Call =
+            RemoveOrderArgs::from(args.order.clone()).try_into()?;
+
+        // every read of the replacement is made on the same rpc, and made
+        // again on the next one if it fails midway
+        RpcProvider::for_network(&orderbook.network)
+            .with_failover(|rpc_url| {
+                self.prepare_replacement_on(
+                    deployment_name,
+                    deployment,
+                    &orderbook,
+                    &args,
+                    &old_order,
+                    &remove_order,
+                    rpc_url.to_string(),
+                )
+            })
+            .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn prepare_replacement_on(
+        &self,
+        deployment_name: &str,
+        deployment: &Deployment,
+        orderbook: &Orderbook,
+        args: &ReplaceArgs,
+        old_order: &OrderV3,
+        remove_order: &removeOrder2Call,
+        rpc_url: String,
+    ) -> Result<OrderReplacement, ReplaceError> {
+        let mut add_order_args = match &args.salt {
+            Some(salt) => {
+                AddOrderArgs::new_from_deployment_derived(
+                    self.dotrain.clone(),
+                    deployment.clone(),
+                    OrderDerivation {
+                        owner: old_order.owner,
+                        deployment: deployment_name.to_string(),
+                        salt: salt.clone(),
+                    },
+                )
+                .await?
+            }
+            None => {
+                AddOrderArgs::new_from_deployment(self.dotrain.clone(), deployment.clone()).await?
+            }
+        };
+        let old_inputs_first = [
+            old_order.validInputs.clone(),
+            old_order.validOutputs.clone(),
+        ]
+        .concat();
+        let old_outputs_first = [
+            old_order.validOutputs.clone(),
+            old_order.validInputs.clone(),
+        ]
+        .concat();
+        reuse_vault_ids(
+            &mut add_order_args.inputs,
+            &deployment.order.inputs,
+            &old_inputs_first,
+        );
+        reuse_vault_ids(
+            &mut add_order_args.outputs,
+            &deployment.order.outputs,
+            &old_outputs_first,
+        );
+        let add_order = add_order_args.try_into_call(rpc_url.clone()).await?;
+
+        // the subgraph may lag behind, the moved amounts are read on chain
+        let mut old_vaults: Vec<IO> = vec![];
+        for io in old_outputs_first {
+            if !old_vaults
+                .iter()
+                .any(|other| other.token == io.token && other.vaultId == io.vaultId)
+            {
+                old_vaults.push(io);
+            }
+        }
+        let vault_balances = read_vault_balances(
+            old_order.owner,
+            orderbook.address,
+            &rpc_url,
+            &old_vaults
+                .iter()
+                .map(|io| (io.token, io.vaultId))
+                .collect::<Vec<_>>(),
+        )
+        .await?;
+        let balances: Vec<(IO, U256)> = old_vaults.into_iter().zip(vault_balances).collect();
+        let new_ios = [
+            add_order.config.validOutputs.clone(),
+            add_order.config.validInputs.clone(),
+        ]
+        .concat();
+        let moves = vault_moves(&balances, &new_ios);
+        let approvals = deposit_approvals(
+            old_order.owner,
+            orderbook.address,
+            &rpc_url,
+            &moves.iter().map(VaultMove::deposit).collect::<Vec<_>>(),
+        )
+        .await?;
+
+        let old_rainlang = args
+            .order
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.try_decode_rainlangsource().ok())
+            .unwrap_or_default();
+        let diff = OrderDiff {
+            bindings: args
+                .previous_bindings
+                .as_ref()
+                .map(|old| diff_bindings(old, &add_order_args.bindings))
+                .unwrap_or_default(),
+            rainlang: diff_lines(&old_rainlang, &add_order_args.compose_to_rainlang()?),
+            ..OrderDiff::from_ios(&old_order, &add_order)
+        };
+
+        Ok(OrderReplacement {
+            owner: old_order.owner,
+            orderbook: orderbook.address,
+            rpc_url,
+            approvals,
+            remove_order: remove_order.clone(),
+            add_order,
+            moves,
+            diff,
+        })
+    }
+}
+
+/// Points the ios whose vault id is not set by the deployment at the first
+/// of the old ios with the same token, so they keep using its vault
+fn reuse_vault_ids(ios: &mut [IO], order_ios: &[OrderIO], old_ios: &[IO]) {
+    for (io, order_io) in ios.iter_mut().zip(order_ios) {
+        if order_io.vault_id.is_some() {
+            continue;
+        }
+        if let Some(old) = old_ios.iter().find(|old| old.token == io.token) {
+            io.vaultId = old.vaultId;
+        }
+    }
+}
+
+/// Moves the non zero balances of the old vaults that none of the new ios
+/// uses into the vault of the first new io with the same token, balances
+/// of tokens the new order does not trade stay where they are
+fn vault_moves(balances: &[(IO, U256)], new_ios: &[IO]) -> Vec<VaultMove> {
+    balances
+        .iter()
+        .filter(|(old, amount)| {
+            !amount.is_zero()
+                && !new_ios
+                    .iter()
+                    .any(|io| io.token == old.token && io.vaultId == old.vaultId)
+        })
+        .filter_map(|(old, amount)| {
+            let new = new_ios.iter().find(|io| io.token == old.token)?;
+            Some(VaultMove {
+                token: old.token,
+                from_vault_id: old.vaultId,
+                to_vault_id: new.vaultId,
+                amount: *amount,
+            })
+        })
+        .collect()
+}
+
+/// Bindings that are added, removed or changed, sorted by name
+fn diff_bindings(
+    old: &HashMap<String, String>,
+    new: &HashMap<String, String>,
+) -> Vec<BindingChange> {
+    let mut names: Vec<&String> = old.keys().chain(new.keys()).collect();
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .filter(|name| old.get(*name) != new.get(*name))
+        .map(|name| BindingChange {
+            name: name.clone(),
+            old: old.get(name).cloned(),
+            new: new.get(name).cloned(),
+        })
+        .collect()
+}
+
+/// Line diff of two texts along their longest common subsequence of lines
+fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // common[i][j] is the length of the longest common subsequence of
+    // old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for (i, old_line) in old.iter().enumerate().rev() {
+        for (j, new_line) in new.iter().enumerate().rev() {
+            common[i][j] = if old_line == new_line {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(DiffLine::Unchanged(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if common[i + 1][j] >= common[i][j + 1] {
+            diff.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    diff.extend(
+        old[i..]
+            .iter()
+            .map(|line| DiffLine::Removed(line.to_string())),
+    );
+    diff.extend(
+        new[j..]
+            .iter()
+            .map(|line| DiffLine::Added(line.to_string())),
+    );
+    diff
+}
+
+impl VaultMove {
+    pub fn withdraw(&self) -> withdraw2Call {
+        withdraw2Call {
+            token: self.token,
+            vaultId: self.from_vault_id,
+            targetAmount: self.amount,
+            tasks: vec![],
+        }
+    }
+
+    pub fn deposit(&self) -> deposit2Call {
+        deposit2Call {
+            token: self.token,
+            vaultId: self.to_vault_id,
+            amount: self.amount,
+            tasks: vec![],
+        }
+    }
+}
+
+impl OrderDiff {
+    fn from_ios(old: &OrderV3, new: &addOrder2Call) -> Self {
+        let missing_from = |ios: &[IO], others: &[IO]| -> Vec<IO> {
+            ios.iter()
+                .filter(|io| !others.contains(io))
+                .cloned()
+                .collect()
+        };
+        OrderDiff {
+            removed_inputs: missing_from(&old.validInputs, &new.config.validInputs),
+            added_inputs: missing_from(&new.config.validInputs, &old.validInputs),
+            removed_outputs: missing_from(&old.validOutputs, &new.config.validOutputs),
+            added_outputs: missing_from(&new.config.validOutputs, &old.validOutputs),
+            ..Default::default()
+        }
+    }
+}
+
+impl fmt::Display for OrderDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (kind, removed, added) in [
+            ("Inputs", &self.removed_inputs, &self.added_inputs),
+            ("Outputs", &self.removed_outputs, &self.added_outputs),
+        ] {
+            if removed.is_empty() && added.is_empty() {
+                writeln!(f, "{}: unchanged", kind)?;
+                continue;
+            }
+            writeln!(f, "{}:", kind)?;
+            for io in removed.iter() {
+                writeln!(f, "- {} vault {}", io.token, io.vaultId)?;
+            }
+            for io in added.iter() {
+                writeln!(f, "+ {} vault {}", io.token, io.vaultId)?;
+            }
+        }
+        if !self.bindings.is_empty() {
+            writeln!(f, "Bindings:")?;
+            for binding in &self.bindings {
+                writeln!(
+                    f,
+                    "  {}: {} -> {}",
+                    binding.name,
+                    binding.old.as_deref().unwrap_or("(unset)"),
+                    binding.new.as_deref().unwrap_or("(unset)")
+                )?;
+            }
+        }
+        writeln!(f, "Rainlang:")?;
+        for line in &self.rainlang {
+            match line {
+                DiffLine::Unchanged(line) => writeln!(f, "  {}", line)?,
+                DiffLine::Removed(line) => writeln!(f, "- {}", line)?,
+                DiffLine::Added(line) => writeln!(f, "+ {}", line)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl OrderReplacement {
+    /// The orderbook multicall removing the old order and adding the new
+    /// one, followed by the withdrawal and deposit of each vault move
+    pub fn multicall(&self) -> multicallCall {
+        let mut data = vec![
+            Bytes::from(self.remove_order.abi_encode()),
+            Bytes::from(self.add_order.abi_encode()),
+        ];
+        for vault_move in &self.moves {
+            data.push(Bytes::from(vault_move.withdraw().abi_encode()));
+            data.push(Bytes::from(vault_move.deposit().abi_encode()));
+        }
+        multicallCall { data }
+    }
+
+    /// The new order as it is stored by the orderbook once added
+    pub fn order(&self) -> OrderV3 {
+        OrderV3 {
+            owner: self.owner,
+            evaluable: self.add_order.config.evaluable.clone(),
+            validInputs: self.add_order.config.validInputs.clone(),
+            validOutputs: self.add_order.config.validOutputs.clone(),
+            nonce: self.add_order.config.nonce,
+        }
+    }
+
+    pub fn order_hash(&self) -> B256 {
+        keccak256(self.order().abi_encode())
+    }
+
+    /// Previews the approvals and the multicall sent from the owner
+    pub async fn preview(&self) -> Result<TransactionPreview, ReplaceError> {
+        Ok(preview_multicall(
+            self.owner,
+            self.orderbook,
+            &self.rpc_url,
+            &self.approvals,
+            self.multicall(),
+        )
+        .await?)
+    }
+
+    /// Reads the balances of the vaults the moves withdraw from again and
+    /// fails if any changed since the replacement was prepared, ie as the old
+    /// order traded, since each move deposits the amount read at that time
+    /// whatever its withdrawal gets
+    pub async fn check_balances(&self) -> Result<(), ReplaceError> {
+        let balances = read_vault_balances(
+            self.owner,
+            self.orderbook,
+            &self.rpc_url,
+            &self
+                .moves
+                .iter()
+                .map(|vault_move| (vault_move.token, vault_move.from_vault_id))
+                .collect::<Vec<_>>(),
+        )
+        .await?;
+        for (vault_move, balance) in self.moves.iter().zip(balances) {
+            if balance != vault_move.amount {
+                return Err(ReplaceError::VaultBalanceChanged {
+                    token: vault_move.token,
+                    vault_id: vault_move.from_vault_id,
+                    expected: vault_move.amount,
+                    actual: balance,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// The approvals and the multicall as the calls of a Safe batch, for
+    /// orders owned by a Safe. Their balances can only be checked when the
+    /// batch is proposed, not when it is executed
+    pub fn safe_transactions(&self) -> Vec<SafeTransaction> {
+        multicall_safe_transactions(self.orderbook, &self.approvals, self.multicall())
+    }
+
+    /// Sends the approvals and then the multicall with the ledger wallet of
+    /// the transaction args, once the moved balances are checked unchanged
+    pub async fn execute<A, M>(
+        &self,
+        transaction_args: TransactionArgs,
+        approval_status_changed: A,
+        multicall_status_changed: M,
+    ) -> Result<(), ReplaceError>
+    where
+        A: Fn(WriteTransactionStatus<approveCall>),
+        M: Fn(WriteTransactionStatus<multicallCall>),
+    {
+        self.check_balances().await?;
+        Ok(execute_multicall(
+            TransactionArgs {
+                orderbook_address: self.orderbook,
+                rpc_url: self.rpc_url.clone(),
+                ..transaction_args
+            },
+            &self.approvals,
+            self.multicall(),
+            approval_status_changed,
+            multicall_status_changed,
+        )
+        .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{hex::encode_prefixed, utils::parse_ether};
+    use alloy_ethers_typecast::{multicall::IMulticall3::Result as MulticallResult, rpc::Response};
+    use httpmock::{Method::POST, MockServer};
+    use rain_orderbook_app_settings::{network::Network, token::Token};
+    use std::sync::Arc;
+    use url::Url;
+
+    fn io(token: u8, vault_id: u64) -> IO {
+        IO {
+            token: Address::with_last_byte(token),
+            decimals: 18,
+            vaultId: U256::from(vault_id),
+        }
+    }
+
+    fn order_io(token: u8, vault_id: Option<u64>) -> OrderIO {
+        OrderIO {
+            token: Arc::new(Token {
+                address: Address::with_last_byte(token),
+                network: Arc::new(Network {
+                    name: "test-network".to_string(),
+                    rpc: Url::parse("https://some-rpc.com").unwrap(),
+                    rpcs: vec![],
+                    chain_id: 137,
+                    label: None,
+                    network_id: None,
+                    currency: None,
+                }),
+                decimals: Some(18),
+                label: None,
+                symbol: Some("TKN".to_string()),
+            }),
+            vault_id: vault_id.map(U256::from),
+        }
+    }
+
+    #[test]
+    fn test_reuse_vault_ids() {
+        let mut ios = vec![io(1, 100), io(2, 7), io(3, 100)];
+        reuse_vault_ids(
+            &mut ios,
+            &[order_io(1, None), order_io(2, Some(7)), order_io(3, None)],
+            &[io(1, 5), io(2, 5), io(1, 6)],
+        );
+        // pinned vault ids and tokens new to the order are left alone
+        assert_eq!(ios, vec![io(1, 5), io(2, 7), io(3, 100)]);
+    }
+
+    #[test]
+    fn test_vault_moves() {
+        let balances = vec![
+            (io(1, 5), U256::from(10)),
+            (io(2, 5), U256::from(20)),
+            (io(3, 5), U256::from(30)),
+            (io(4, 5), U256::ZERO),
+        ];
+        let new_ios = vec![io(1, 5), io(2, 7), io(4, 7)];
+        assert_eq!(
+            vault_moves(&balances, &new_ios),
+            vec![VaultMove {
+                token: Address::with_last_byte(2),
+                from_vault_id: U256::from(5),
+                to_vault_id: U256::from(7),
+                amount: U256::from(20),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_diff_bindings() {
+        let old = HashMap::from([
+            ("a".to_string(), "1".to_string()),
+            ("b".to_string(), "2".to_string()),
+            ("c".to_string(), "3".to_string()),
+        ]);
+        let new = HashMap::from([
+            ("a".to_string(), "1".to_string()),
+            ("b".to_string(), "4".to_string()),
+            ("d".to_string(), "5".to_string()),
+        ]);
+        assert_eq!(
+            diff_bindings(&old, &new),
+            vec![
+                BindingChange {
+                    name: "b".to_string(),
+                    old: Some("2".to_string()),
+                    new: Some("4".to_string()),
+                },
+                BindingChange {
+                    name: "c".to_string(),
+                    old: Some("3".to_string()),
+                    new: None,
+                },
+                BindingChange {
+                    name: "d".to_string(),
+                    old: None,
+                    new: Some("5".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        assert_eq!(
+            diff_lines("a\nb\nc\nd", "a\nc\ne\nd"),
+            vec![
+                DiffLine::Unchanged("a".to_string()),
+                DiffLine::Removed("b".to_string()),
+                DiffLine::Unchanged("c".to_string()),
+                DiffLine::Added("e".to_string()),
+                DiffLine::Unchanged("d".to_string()),
+            ]
+        );
+        assert_eq!(diff_lines("", "a"), vec![DiffLine::Added("a".to_string())]);
+        assert_eq!(
+            diff_lines("a", ""),
+            vec![DiffLine::Removed("a".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_balances() {
+        let server = MockServer::start_async().await;
+        let mut balance_mock = server.mock(|when, then| {
+            when.method(POST).path("/rpc");
+            then.json_body_obj(&vault_balances_response(parse_ether("3").unwrap()));
+        });
+        let replacement = OrderReplacement {
+            owner: Address::random(),
+            orderbook: Address::random(),
+            rpc_url: server.url("/rpc"),
+            approvals: vec![],
+            remove_order: removeOrder2Call::default(),
+            add_order: addOrder2Call::default(),
+            moves: vec![VaultMove {
+                token: Address::with_last_byte(1),
+                from_vault_id: U256::from(1),
+                to_vault_id: U256::from(2),
+                amount: parse_ether("3").unwrap(),
+            }],
+            diff: OrderDiff::default(),
+        };
+        replacement.check_balances().await.unwrap();
+
+        // the old order traded out of the vault since
+        balance_mock.delete();
+        server.mock(|when, then| {
+            when.method(POST).path("/rpc");
+            then.json_body_obj(&vault_balances_response(parse_ether("1").unwrap()));
+        });
+        let err = replacement.check_balances().await.unwrap_err();
+        assert!(matches!(
+            err,
+            ReplaceError::VaultBalanceChanged { expected, actual, .. }
+                if expected == parse_ether("3").unwrap() && actual == parse_ether("1").unwrap()
+        ));
+    }
+
+    fn vault_balances_response(balance: U256) -> serde_json::Value {
+        let results = vec![MulticallResult {
+            success: true,
+            returnData: balance.abi_encode().into(),
+        }];
+        serde_json::from_str(
+            &Response::new_success(1, &encode_prefixed(results.abi_encode()))
+                .to_json_string()
+                .unwrap(),
+        )
+        .unwrap()
+    }
+}
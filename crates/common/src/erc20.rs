@@ -13,6 +13,7 @@ use alloy_ethers_typecast::{
     transaction::ReadContractParametersBuilderError,
 };
 use rain_error_decoding::{AbiDecodeFailedErrors, AbiDecodedErrorType};
+use rain_orderbook_app_settings::rpc::RpcProviderError;
 use rain_orderbook_bindings::IERC20::{decimalsCall, nameCall, symbolCall};
 #[cfg(target_family = "wasm")]
 use rain_orderbook_bindings::{impl_all_wasm_traits, wasm_traits::prelude::*};
@@ -130,4 +131,6 @@ pub enum Error {
     AbiDecodeError(#[from] AbiDecodeFailedErrors),
     #[error(transparent)]
     SolTypesError(#[from] alloy::sol_types::Error),
+    #[error(transparent)]
+    RpcProviderError(#[from] RpcProviderError),
 }
@@ -0,0 +1,423 @@
+use crate::add_order::ORDERBOOK_ADDORDER_POST_TASK_ENTRYPOINTS;
+use crate::meta::{TryDecodeRainlangSource, TryDecodeRainlangSourceError};
+use alloy::primitives::{hex::FromHexError, Address};
+use rain_orderbook_app_settings::config_source::{
+    ConfigSource, DeployerConfigSource, DeploymentConfigSource, IOString, NetworkConfigSource,
+    OrderConfigSource, OrderbookConfigSource, ScenarioConfigSource, TokenConfigSource,
+};
+use rain_orderbook_bindings::IOrderBookV4::{OrderV3, IO};
+use rain_orderbook_subgraph_client::types::{common::Order, order_detail_traits::OrderDetailError};
+use std::collections::HashMap;
+use thiserror::Error;
+use url::Url;
+
+#[derive(Error, Debug)]
+pub enum ExportOrderError {
+    #[error("Order has no meta to recover its rainlang from")]
+    MissingMeta,
+    #[error(transparent)]
+    TryDecodeRainlangSourceError(#[from] TryDecodeRainlangSourceError),
+    #[error(transparent)]
+    OrderDetailError(#[from] OrderDetailError),
+    #[error(transparent)]
+    FromHexError(#[from] FromHexError),
+    #[error(transparent)]
+    YamlError(#[from] serde_yaml::Error),
+}
+
+/// The network an exported order is deployed on, which the subgraph does
+/// not index, all of the generated frontmatter is keyed by its name
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportNetwork {
+    pub name: String,
+    pub rpc: Url,
+    pub chain_id: u64,
+    /// Deployer to parse the exported rainlang with
+    pub deployer: Address,
+    pub subgraph: Url,
+}
+
+/// Reconstructs a runnable .rain file from an order indexed by the subgraph
+#[derive(Debug, Clone)]
+pub struct ExportOrderArgs {
+    pub order: Order,
+    pub network: ExportNetwork,
+}
+
+impl ExportOrderArgs {
+    /// Generates the frontmatter of the order's network, orderbook, tokens,
+    /// vaults and a deployment of it, followed by the rainlang decoded from
+    /// its meta. The bindings of the order were composed into its rainlang
+    /// so the scenario binds nothing
+    pub fn to_dotrain(&self) -> Result<String, ExportOrderError> {
+        let rainlang = self
+            .order
+            .meta
+            .as_ref()
+            .ok_or(ExportOrderError::MissingMeta)?
+            .try_decode_rainlangsource()?;
+        let order: OrderV3 = self.order.clone().try_into()?;
+
+        let name = self.network.name.clone();
+        let mut config = ConfigSource::default();
+        config.networks.insert(
+            name.clone(),
+            NetworkConfigSource {
+                rpc: self.network.rpc.clone(),
+                rpcs: vec![],
+                chain_id: self.network.chain_id,
+                label: None,
+                network_id: None,
+                currency: None,
+            },
+        );
+        config
+            .subgraphs
+            .insert(name.clone(), self.network.subgraph.clone());
+        config.orderbooks.insert(
+            name.clone(),
+            OrderbookConfigSource {
+                address: self.order.orderbook.id.0.parse()?,
+                network: Some(name.clone()),
+                subgraph: Some(name.clone()),
+                label: None,
+            },
+        );
+        config.deployers.insert(
+            name.clone(),
+            DeployerConfigSource {
+                address: self.network.deployer,
+                network: Some(name.clone()),
+                label: None,
+            },
+        );
+
+        let mut inputs = vec![];
+        for io in &order.validInputs {
+            inputs.push(self.io_string(io, &mut config));
+        }
+        let mut outputs = vec![];
+        for io in &order.validOutputs {
+            outputs.push(self.io_string(io, &mut config));
+        }
+        config.orders.insert(
+            name.clone(),
+            OrderConfigSource {
+                inputs,
+                outputs,
+                deployer: Some(name.clone()),
+                orderbook: Some(name.clone()),
+            },
+        );
+        config.scenarios.insert(
+            name.clone(),
+            ScenarioConfigSource {
+                bindings: HashMap::new(),
+                runs: None,
+                blocks: None,
+                deployer: Some(name.clone()),
+                scenarios: None,
+                matrix: HashMap::new(),
+            },
+        );
+        config.deployments.insert(
+            name.clone(),
+            DeploymentConfigSource {
+                scenario: name.clone(),
+                order: name,
+            },
+        );
+
+        Ok(format!(
+            "{}---\n{}",
+            config.to_yaml_string()?,
+            rainlang_to_sources(&rainlang)
+        ))
+    }
+
+    /// Adds the token of an io to the config, keyed by its symbol, and
+    /// returns the io pointing at it
+    fn io_string(&self, io: &IO, config: &mut ConfigSource) -> IOString {
+        let symbol = self
+            .order
+            .inputs
+            .iter()
+            .chain(&self.order.outputs)
+            .map(|vault| &vault.token)
+            .find(|token| {
+                token
+                    .address
+                    .0
+                    .parse::<Address>()
+                    .is_ok_and(|address| address == io.token)
+            })
+            .and_then(|token| token.symbol.clone());
+
+        let key = match config
+            .tokens
+            .iter()
+            .find(|(_, token)| token.address == io.token)
+        {
+            Some((key, _)) => key.clone(),
+            None => {
+                let base = symbol
+                    .as_deref()
+                    .map(token_key)
+                    .filter(|key| !key.is_empty())
+                    .unwrap_or_else(|| "token".to_string());
+                let mut key = base.clone();
+                let mut suffix = 2;
+                while config.tokens.contains_key(&key) {
+                    key = format!("{}-{}", base, suffix);
+                    suffix += 1;
+                }
+                config.tokens.insert(
+                    key.clone(),
+                    TokenConfigSource {
+                        network: self.network.name.clone(),
+                        address: io.token,
+                        decimals: Some(io.decimals),
+                        label: symbol.clone(),
+                        symbol,
+                    },
+                );
+                key
+            }
+        };
+
+        IOString {
+            token: key,
+            vault_id: Some(io.vaultId),
+        }
+    }
+}
+
+/// Lowercases a token symbol into a frontmatter key
+fn token_key(symbol: &str) -> String {
+    symbol
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Name of the entrypoint a `/* N. name */` line of composed rainlang is the
+/// header of
+fn source_header(line: &str) -> Option<&str> {
+    let (index, name) = line
+        .trim()
+        .strip_prefix("/*")?
+        .strip_suffix("*/")?
+        .trim()
+        .split_once(". ")?;
+    (!index.is_empty() && index.chars().all(|c| c.is_ascii_digit())).then_some(name.trim())
+}
+
+/// Turns composed rainlang back into dotrain sources, each entrypoint header
+/// becomes its `#name` source. The post add order task is not part of the
+/// meta so an empty one is added
+fn rainlang_to_sources(rainlang: &str) -> String {
+    let mut sources = rainlang
+        .lines()
+        .map(|line| match source_header(line) {
+            Some(name) => format!("#{}", name),
+            None => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    for entrypoint in ORDERBOOK_ADDORDER_POST_TASK_ENTRYPOINTS.iter() {
+        if !sources.contains(&format!("#{}", entrypoint)) {
+            sources.push_str(&format!("\n\n#{}\n:;", entrypoint));
+        }
+    }
+    sources
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontmatter::parse_frontmatter;
+    use alloy::primitives::{hex::encode_prefixed, B256, U256};
+    use alloy::sol_types::SolValue;
+    use rain_metadata::{
+        ContentEncoding, ContentLanguage, ContentType, KnownMagic, RainMetaDocumentV1Item,
+    };
+    use rain_orderbook_app_settings::Config;
+    use rain_orderbook_bindings::IOrderBookV4::EvaluableV3;
+    use rain_orderbook_subgraph_client::types::common::{BigInt, Bytes, Erc20, Orderbook, Vault};
+    use serde_bytes::ByteBuf;
+
+    const RAINLANG: &str = "/* 0. calculate-io */ \n_ _: 16 52;\n\n/* 1. handle-io */ \n:;";
+
+    fn vault(token: Address, symbol: &str, vault_id: u64) -> Vault {
+        Vault {
+            id: Bytes("0x01".into()),
+            owner: Bytes("0x02".into()),
+            vault_id: BigInt(vault_id.to_string()),
+            balance: BigInt("0".into()),
+            token: Erc20 {
+                id: Bytes(token.to_string()),
+                address: Bytes(token.to_string()),
+                name: Some(symbol.to_string()),
+                symbol: Some(symbol.to_string()),
+                decimals: Some(BigInt("6".into())),
+            },
+            orderbook: Orderbook {
+                id: Bytes("0x03".into()),
+            },
+            orders_as_output: vec![],
+            orders_as_input: vec![],
+            balance_changes: vec![],
+        }
+    }
+
+    fn order(meta: Option<String>) -> Order {
+        let usdc = Address::with_last_byte(1);
+        let weth = Address::with_last_byte(2);
+        let order = OrderV3 {
+            owner: Address::with_last_byte(4),
+            evaluable: EvaluableV3 {
+                interpreter: Address::with_last_byte(5),
+                store: Address::with_last_byte(6),
+                bytecode: vec![].into(),
+            },
+            validInputs: vec![IO {
+                token: usdc,
+                decimals: 6,
+                vaultId: U256::from(7),
+            }],
+            validOutputs: vec![
+                IO {
+                    token: weth,
+                    decimals: 18,
+                    vaultId: U256::from(7),
+                },
+                IO {
+                    token: usdc,
+                    decimals: 6,
+                    vaultId: U256::from(8),
+                },
+            ],
+            nonce: B256::ZERO,
+        };
+        Order {
+            id: Bytes("0x01".into()),
+            order_bytes: Bytes(encode_prefixed(order.abi_encode())),
+            order_hash: Bytes("0x02".into()),
+            owner: Bytes(order.owner.to_string()),
+            outputs: vec![vault(weth, "WETH", 7), vault(usdc, "USDC.e", 8)],
+            inputs: vec![vault(usdc, "USDC.e", 7)],
+            orderbook: Orderbook {
+                id: Bytes(Address::with_last_byte(3).to_string()),
+            },
+            active: true,
+            timestamp_added: BigInt("0".into()),
+            meta: meta.map(Bytes),
+            add_events: vec![],
+            trades: vec![],
+        }
+    }
+
+    fn network() -> ExportNetwork {
+        ExportNetwork {
+            name: "base".to_string(),
+            rpc: Url::parse("https://rpc.example.com").unwrap(),
+            chain_id: 8453,
+            deployer: Address::with_last_byte(9),
+            subgraph: Url::parse("https://subgraph.example.com").unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_to_dotrain() {
+        let meta = RainMetaDocumentV1Item::cbor_encode_seq(
+            &vec![RainMetaDocumentV1Item {
+                payload: ByteBuf::from(RAINLANG.as_bytes()),
+                magic: KnownMagic::RainlangSourceV1,
+                content_type: ContentType::OctetStream,
+                content_encoding: ContentEncoding::None,
+                content_language: ContentLanguage::None,
+            }],
+            KnownMagic::RainMetaDocumentV1,
+        )
+        .unwrap();
+        let dotrain = ExportOrderArgs {
+            order: order(Some(encode_prefixed(meta))),
+            network: network(),
+        }
+        .to_dotrain()
+        .unwrap();
+
+        assert!(dotrain.ends_with(
+            "---\n#calculate-io\n_ _: 16 52;\n\n#handle-io\n:;\n\n#handle-add-order\n:;"
+        ));
+
+        let config: Config = parse_frontmatter(dotrain)
+            .await
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let deployment = config.deployments.get("base").unwrap();
+        assert_eq!(
+            deployment.scenario.deployer.address,
+            Address::with_last_byte(9)
+        );
+        assert_eq!(
+            deployment.order.orderbook.as_ref().unwrap().address,
+            Address::with_last_byte(3)
+        );
+        assert_eq!(
+            deployment
+                .order
+                .orderbook
+                .as_ref()
+                .unwrap()
+                .network
+                .chain_id,
+            8453
+        );
+
+        let inputs = &deployment.order.inputs;
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs[0].token.symbol, Some("USDC.e".to_string()));
+        assert_eq!(inputs[0].token.decimals, Some(6));
+        assert_eq!(inputs[0].vault_id, Some(U256::from(7)));
+
+        // the same token is only configured once
+        let outputs = &deployment.order.outputs;
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[0].token.symbol, Some("WETH".to_string()));
+        assert_eq!(outputs[0].token.decimals, Some(18));
+        assert_eq!(outputs[1].token, inputs[0].token);
+        assert_eq!(outputs[1].vault_id, Some(U256::from(8)));
+        assert_eq!(config.tokens.len(), 2);
+        assert!(config.tokens.contains_key("usdc-e"));
+        assert!(config.tokens.contains_key("weth"));
+    }
+
+    #[test]
+    fn test_to_dotrain_without_meta() {
+        let err = ExportOrderArgs {
+            order: order(None),
+            network: network(),
+        }
+        .to_dotrain()
+        .unwrap_err();
+        assert!(matches!(err, ExportOrderError::MissingMeta));
+    }
+
+    #[test]
+    fn test_rainlang_to_sources() {
+        assert_eq!(
+            rainlang_to_sources(
+                "/* 0. calculate-io */ \n/* a comment */\n_: 1;\n\n\
+                 /* 1. handle-io */ \n:;\n\n\
+                 /* 2. handle-add-order */ \n:;"
+            ),
+            "#calculate-io\n/* a comment */\n_: 1;\n\n#handle-io\n:;\n\n#handle-add-order\n:;"
+        );
+        assert_eq!(token_key("USDC.e"), "usdc-e");
+        assert_eq!(token_key("$"), "");
+    }
+}
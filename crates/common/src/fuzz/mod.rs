@@ -14,9 +14,10 @@ pub use rain_interpreter_eval::trace::{
 use rain_interpreter_eval::{
     error::ForkCallError, eval::ForkEvalArgs, fork::Forker, trace::RainEvalResult,
 };
-use rain_orderbook_app_settings::blocks::BlockError;
+use rain_orderbook_app_settings::blocks::{BlockError, BlockTimestamps};
 use rain_orderbook_app_settings::chart::Chart;
 use rain_orderbook_app_settings::config::*;
+use rain_orderbook_app_settings::rpc::{RpcProvider, RpcProviderError};
 use rain_orderbook_app_settings::scenario::Scenario;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -29,11 +30,16 @@ use typeshare::typeshare;
 pub struct ChartData {
     scenarios_data: HashMap<String, FuzzResultFlat>,
     charts: HashMap<String, Chart>,
+    /// Names of the scenarios generated from the matrix of each charted
+    /// scenario, their results are in `scenarios_data` with the swept values
+    /// as `parameters` to plot metrics against
+    sweeps: HashMap<String, Vec<String>>,
 }
 
 #[derive(Debug)]
 pub struct FuzzResult {
     pub scenario: String,
+    pub parameters: HashMap<String, String>,
     pub runs: RainEvalResults,
 }
 
@@ -41,6 +47,7 @@ pub struct FuzzResult {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FuzzResultFlat {
     pub scenario: String,
+    pub parameters: HashMap<String, String>,
     pub data: RainEvalResultsTable,
 }
 
@@ -50,6 +57,7 @@ impl FuzzResult {
 
         Ok(FuzzResultFlat {
             scenario: self.scenario.clone(),
+            parameters: self.parameters.clone(),
             data: result_table,
         })
     }
@@ -73,6 +81,8 @@ pub enum FuzzRunnerError {
     CorruptTraces,
     #[error("{0} is not a testable scenario")]
     ScenarioNotTestable(String),
+    #[error("Scenario {0} has a matrix, run the scenarios generated from it")]
+    MatrixScenario(String),
     #[error(transparent)]
     ForkCallError(#[from] ForkCallError),
     #[error("Empty Front Matter")]
@@ -89,6 +99,8 @@ pub enum FuzzRunnerError {
     BlockError(#[from] BlockError),
     #[error(transparent)]
     RainEvalResultError(#[from] RainEvalResultError),
+    #[error(transparent)]
+    RpcProviderError(#[from] RpcProviderError),
 }
 
 impl FuzzRunner {
@@ -120,28 +132,40 @@ impl FuzzRunner {
         &mut self,
         scenario: &Arc<Scenario>,
     ) -> Result<FuzzResult, FuzzRunnerError> {
+        if !self.settings.matrix_scenarios(&scenario.name).is_empty() {
+            return Err(FuzzRunnerError::MatrixScenario(scenario.name.clone()));
+        }
+
         // If the scenario doesn't have runs, default is 1
         let no_of_runs = scenario.runs.unwrap_or(1);
 
         let deployer = scenario.deployer.clone();
 
-        // Fetch the latest block number
-        let block_number = ReadableClientHttp::new_from_url(deployer.network.rpc.to_string())?
-            .get_block_number()
+        // Fetch the latest block number, forking from the rpc that returned it
+        let (block_number, rpc_url) = RpcProvider::for_network(&deployer.network)
+            .with_failover(|rpc_url| async move {
+                let block_number = ReadableClientHttp::new_from_url(rpc_url.to_string())?
+                    .get_block_number()
+                    .await?;
+                Ok::<_, FuzzRunnerError>((block_number, rpc_url))
+            })
             .await?;
 
-        let blocks = scenario
-            .blocks
-            .as_ref()
-            .map_or(Ok(vec![block_number]), |b| {
-                b.expand_to_block_numbers(block_number)
-            })?;
+        let blocks = match &scenario.blocks {
+            Some(blocks) => {
+                let timestamps = BlockTimestamps::for_network(&deployer.network);
+                blocks
+                    .resolve_block_numbers(&timestamps, block_number)
+                    .await?
+            }
+            None => vec![block_number],
+        };
 
         // Create a fork with the first block number
         self.forker
             .add_or_select(
                 NewForkedEvm {
-                    fork_url: deployer.network.rpc.clone().into(),
+                    fork_url: rpc_url.into(),
                     fork_block_number: Some(blocks[0]),
                 },
                 None,
@@ -241,25 +265,56 @@ impl FuzzRunner {
 
         Ok(FuzzResult {
             scenario: scenario.name.clone(),
+            parameters: scenario.parameters.clone(),
             runs: runs.into(),
         })
     }
 
     pub async fn make_chart_data(&self) -> Result<ChartData, FuzzRunnerError> {
         let charts = self.settings.charts.clone();
-        let mut scenarios_data: HashMap<String, FuzzResultFlat> = HashMap::new();
 
-        for (_, chart) in charts.clone() {
+        // Charted scenarios and the scenarios swept by their matrices
+        let mut scenarios: HashMap<String, Arc<Scenario>> = HashMap::new();
+        let mut sweeps: HashMap<String, Vec<String>> = HashMap::new();
+        for chart in charts.values() {
             let scenario_name = chart.scenario.name.clone();
-            let mut runner = self.clone();
-            scenarios_data.entry(scenario_name.clone()).or_insert(
-                runner
-                    .run_scenario_by_name(&scenario_name)
-                    .await?
-                    .flatten_traces()?,
-            );
+
+            // A matrix scenario is charted through its sweep, the parent itself
+            // only holds the shared bindings
+            let swept = self.settings.matrix_scenarios(&scenario_name);
+            if swept.is_empty() {
+                scenarios.insert(scenario_name, chart.scenario.clone());
+            } else {
+                sweeps.insert(
+                    scenario_name,
+                    swept.iter().map(|scenario| scenario.name.clone()).collect(),
+                );
+                scenarios.extend(
+                    swept
+                        .into_iter()
+                        .map(|scenario| (scenario.name.clone(), scenario)),
+                );
+            }
         }
 
+        // Run every scenario on its own task and runner so the forks are
+        // evaluated in parallel
+        let handles = scenarios
+            .into_values()
+            .map(|scenario| {
+                let mut runner = self.clone();
+                tokio::spawn(async move { runner.run_scenario(&scenario).await?.flatten_traces() })
+            })
+            .collect::<Vec<_>>();
+        let mut results = vec![];
+        for handle in handles {
+            results.push(handle.await??);
+        }
+        let scenarios_data: HashMap<String, FuzzResultFlat> = results
+            .into_iter()
+            .map(|result| (result.scenario.clone(), result))
+            .collect();
+
         let charts: HashMap<String, Chart> = charts
             .iter()
             .map(|(k, v)| (k.clone(), v.as_ref().clone()))
@@ -268,6 +323,7 @@ impl FuzzRunner {
         Ok(ChartData {
             scenarios_data,
             charts,
+            sweeps,
         })
     }
 }
@@ -329,6 +385,84 @@ b: fuzzed;
         assert!(res.runs.len() == 50);
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+    async fn test_make_chart_data_matrix() {
+        let local_evm = LocalEvm::new().await;
+        let dotrain = format!(
+            r#"
+deployers:
+    some-key:
+        address: {deployer}
+networks:
+    some-key:
+        rpc: {rpc_url}
+        chain-id: 123
+scenarios:
+    some-key:
+        runs: 5
+        matrix:
+            bound: [1, 2]
+    other-key:
+        deployer: some-key
+        runs: 5
+        bindings:
+            bound: 3
+charts:
+    swept:
+        scenario: some-key
+    single:
+        scenario: other-key
+---
+#bound !bind it
+#fuzzed !fuzz it
+#calculate-io
+a: bound,
+b: fuzzed;
+#handle-io
+:;
+    "#,
+            rpc_url = local_evm.url(),
+            deployer = local_evm.deployer.address()
+        );
+        let frontmatter = RainDocument::get_front_matter(&dotrain).unwrap();
+        let config = Config::try_from_string(frontmatter.to_string())
+            .await
+            .unwrap();
+
+        let runner = FuzzRunner::new(&dotrain, config, None).await;
+        let chart_data = runner.make_chart_data().await.unwrap();
+
+        // the matrix parent is only charted through its sweep
+        let mut scenarios = chart_data.scenarios_data.keys().collect::<Vec<_>>();
+        scenarios.sort();
+        assert_eq!(
+            scenarios,
+            vec!["other-key", "some-key.bound=1", "some-key.bound=2"]
+        );
+        assert_eq!(
+            chart_data.sweeps,
+            HashMap::from([(
+                "some-key".to_string(),
+                vec![
+                    "some-key.bound=1".to_string(),
+                    "some-key.bound=2".to_string()
+                ]
+            )])
+        );
+
+        let result = &chart_data.scenarios_data["some-key.bound=2"];
+        assert_eq!(
+            result.parameters,
+            HashMap::from([("bound".to_string(), "2".to_string())])
+        );
+        assert_eq!(result.data.rows.len(), 5);
+        assert!(result
+            .data
+            .rows
+            .iter()
+            .all(|row| row.contains(&parse_ether("2").unwrap())));
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
     async fn test_block_range() {
         let local_evm = LocalEvm::new().await;
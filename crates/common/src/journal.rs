@@ -0,0 +1,996 @@
+use crate::preview::{fetch_fees, FeeEstimate};
+use alloy::primitives::{Address, Bytes, B256, U256};
+use alloy::sol;
+use alloy::sol_types::SolCall;
+use alloy_ethers_typecast::{gas_fee_middleware::GasFeeSpeed, transaction::WriteTransactionStatus};
+use rain_orderbook_app_settings::rpc::{json_rpc, parse_quantity};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use url::Url;
+
+sol! {
+    /// Call a replacement transaction cancelling a stuck one makes to the
+    /// sender itself, its calldata is ignored by an externally owned account
+    interface IJournalCancel {
+        function cancel() external;
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum JournalError {
+    #[error("Journal has no step {1} in flow {0}")]
+    StepNotFound(String, usize),
+    #[error("Step {1} of flow {0} is not pending, it is {2}")]
+    StepNotPending(String, usize, String),
+    #[error("Step {1} of flow {0} was sent and is not confirmed yet, speed it up or cancel it")]
+    StepPending(String, usize),
+    #[error(
+        "Step {1} of flow {0} was sent with calldata {2}, not {3} as now, keep the journaled calldata to send it again"
+    )]
+    CalldataChanged(String, usize, Bytes, Bytes),
+    #[error("Failed to read the nonce of {0}: {1}")]
+    ReadNonce(Address, String),
+    #[error("Failed to read the current fees")]
+    ReadFees,
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+    #[error(transparent)]
+    UrlParse(#[from] url::ParseError),
+    #[error("Journal {0} is locked by another process")]
+    Locked(PathBuf),
+}
+
+/// Where a journaled transaction is at, follows the statuses of the write
+/// transaction
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", content = "payload")]
+pub enum JournalStatus {
+    /// Built with its nonce, not sent yet
+    Prepared,
+    /// Waiting for the signature of the wallet
+    Signing,
+    /// Signed and sent, waiting for its confirmations
+    Sent,
+    /// Confirmed with the given transaction hash
+    Confirmed(B256),
+    Failed(String),
+    /// A speed up or cancel transaction with the same nonce was sent in its
+    /// place
+    Replaced,
+}
+
+impl JournalStatus {
+    /// Whether the transaction may still be mined
+    pub fn is_pending(&self) -> bool {
+        matches!(self, JournalStatus::Signing | JournalStatus::Sent)
+    }
+}
+
+impl<T: SolCall> From<&WriteTransactionStatus<T>> for JournalStatus {
+    fn from(status: &WriteTransactionStatus<T>) -> Self {
+        match status {
+            WriteTransactionStatus::PendingPrepare(_) => JournalStatus::Prepared,
+            WriteTransactionStatus::PendingSign(_) => JournalStatus::Signing,
+            WriteTransactionStatus::PendingSend(_) => JournalStatus::Sent,
+            WriteTransactionStatus::Confirmed(receipt) => {
+                JournalStatus::Confirmed(B256::from(receipt.transaction_hash.0))
+            }
+        }
+    }
+}
+
+/// A transaction sent as a step of a flow, speed ups and cancels of a step
+/// are further attempts of it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub flow: String,
+    pub step: usize,
+    pub attempt: usize,
+    /// Name of the called function
+    pub label: String,
+    pub chain_id: u64,
+    pub from: Address,
+    pub to: Address,
+    pub calldata: Bytes,
+    pub nonce: U256,
+    pub max_fee_per_gas: Option<U256>,
+    pub max_priority_fee_per_gas: Option<U256>,
+    pub status: JournalStatus,
+    /// Unix timestamp in milliseconds of the last status change
+    pub updated_at: i64,
+}
+
+impl JournalEntry {
+    /// Whether this entry is an attempt of the given call, regardless of the
+    /// arguments of the call
+    fn is_attempt_of(&self, chain_id: u64, from: Address, to: Address, label: &str) -> bool {
+        self.chain_id == chain_id && self.from == from && self.to == to && self.label == label
+    }
+}
+
+/// How long to wait for another process to release the journal
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+/// Age after which a lock file is considered left behind by a process that
+/// did not exit cleanly
+const STALE_LOCK: Duration = Duration::from_secs(60);
+
+/// Exclusive lock on the journal file, held while it is read and written
+/// back so concurrent processes do not overwrite each other's entries
+struct JournalLock {
+    path: PathBuf,
+}
+
+impl JournalLock {
+    fn acquire(journal: &Path) -> Result<Self, JournalError> {
+        if let Some(parent) = journal.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut path = journal.as_os_str().to_owned();
+        path.push(".lock");
+        let path = PathBuf::from(path);
+        let started = Instant::now();
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(Self { path }),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let stale = fs::metadata(&path)
+                        .and_then(|metadata| metadata.modified())
+                        .is_ok_and(|modified| modified.elapsed().unwrap_or_default() > STALE_LOCK);
+                    if stale {
+                        let _ = fs::remove_file(&path);
+                    } else if started.elapsed() > LOCK_TIMEOUT {
+                        return Err(JournalError::Locked(path));
+                    } else {
+                        std::thread::sleep(Duration::from_millis(20));
+                    }
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+impl Drop for JournalLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Transactions prepared, sent and confirmed by the write commands, kept in
+/// a json file so interrupted flows can be inspected and resumed. The file
+/// is shared by every process using the journal, it is read again before
+/// each lookup and locked while it is updated
+#[derive(Debug)]
+pub struct TransactionJournal {
+    path: PathBuf,
+    entries: Mutex<Vec<JournalEntry>>,
+}
+
+impl TransactionJournal {
+    /// Opens the journal at the given path, an empty one if the file does not
+    /// exist yet
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, JournalError> {
+        let path = path.into();
+        let entries = read_entries(&path)?;
+        Ok(Self {
+            path,
+            entries: Mutex::new(entries),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Entries of the journal as currently saved, the last ones read if the
+    /// file cannot be read
+    pub fn entries(&self) -> Vec<JournalEntry> {
+        let mut entries = self.entries.lock().unwrap();
+        match read_entries(&self.path) {
+            Ok(saved) => *entries = saved,
+            Err(e) => tracing::warn!("Failed to read the journal: {}", e),
+        }
+        entries.clone()
+    }
+
+    /// Entries of the given flow ordered by step and attempt
+    pub fn flow_entries(&self, flow: &str) -> Vec<JournalEntry> {
+        let mut entries = self
+            .entries()
+            .into_iter()
+            .filter(|entry| entry.flow == flow)
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|entry| (entry.step, entry.attempt));
+        entries
+    }
+
+    /// Latest attempt of the given step of a flow
+    pub fn latest_attempt(&self, flow: &str, step: usize) -> Option<JournalEntry> {
+        self.flow_entries(flow)
+            .into_iter()
+            .rfind(|entry| entry.step == step)
+    }
+
+    /// Nonce to send the next transaction of the sender at, after the pending
+    /// nonce of the rpc and after every transaction of the journal that may
+    /// still be mined
+    pub fn next_nonce(&self, chain_id: u64, from: Address, pending_nonce: U256) -> U256 {
+        next_nonce(&self.entries(), chain_id, from, pending_nonce)
+    }
+
+    pub fn insert(&self, entry: JournalEntry) -> Result<(), JournalError> {
+        self.update(|entries| {
+            entries.push(entry);
+            Ok(())
+        })
+    }
+
+    /// Records an entry at the next nonce of its sender, picked while the
+    /// journal is locked so concurrent processes sending from the same
+    /// wallet do not pick the same nonce. A new step of its flow gets the
+    /// step after the last one of the flow.
+    pub fn insert_at_next_nonce(
+        &self,
+        mut entry: JournalEntry,
+        pending_nonce: U256,
+        new_step: bool,
+    ) -> Result<JournalEntry, JournalError> {
+        self.update(|entries| {
+            entry.nonce = next_nonce(entries, entry.chain_id, entry.from, pending_nonce);
+            if new_step {
+                entry.step = entries
+                    .iter()
+                    .filter(|v| v.flow == entry.flow)
+                    .map(|v| v.step + 1)
+                    .max()
+                    .unwrap_or(0);
+            }
+            entries.push(entry.clone());
+            Ok(entry)
+        })
+    }
+
+    /// Sets the status of an attempt, returning the updated entry
+    pub fn set_status(
+        &self,
+        flow: &str,
+        step: usize,
+        attempt: usize,
+        status: JournalStatus,
+    ) -> Result<JournalEntry, JournalError> {
+        self.update(|entries| {
+            let entry = entries
+                .iter_mut()
+                .find(|entry| entry.flow == flow && entry.step == step && entry.attempt == attempt)
+                .ok_or(JournalError::StepNotFound(flow.to_string(), step))?;
+            entry.status = status;
+            entry.updated_at = chrono::Utc::now().timestamp_millis();
+            Ok(entry.clone())
+        })
+    }
+
+    /// Latest attempt of a step that was sent but not confirmed, the one a
+    /// speed up or cancel replaces
+    pub fn pending_attempt(&self, flow: &str, step: usize) -> Result<JournalEntry, JournalError> {
+        let latest = self
+            .latest_attempt(flow, step)
+            .ok_or(JournalError::StepNotFound(flow.to_string(), step))?;
+        self.flow_entries(flow)
+            .into_iter()
+            .rfind(|entry| entry.step == step && entry.status.is_pending())
+            .ok_or(JournalError::StepNotPending(
+                flow.to_string(),
+                step,
+                format!("{:?}", latest.status),
+            ))
+    }
+
+    /// Applies a change to the entries saved in the journal file while
+    /// holding its lock, and saves them back
+    fn update<R>(
+        &self,
+        change: impl FnOnce(&mut Vec<JournalEntry>) -> Result<R, JournalError>,
+    ) -> Result<R, JournalError> {
+        let mut entries = self.entries.lock().unwrap();
+        let _lock = JournalLock::acquire(&self.path)?;
+        let mut saved = read_entries(&self.path)?;
+        let result = change(&mut saved)?;
+
+        // written to a temporary file first so readers never see a partial file
+        let mut tmp = self.path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(&saved)?)?;
+        fs::rename(&tmp, &self.path)?;
+        *entries = saved;
+        Ok(result)
+    }
+}
+
+fn read_entries(path: &Path) -> Result<Vec<JournalEntry>, JournalError> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(serde_json::from_str(&content)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn next_nonce(entries: &[JournalEntry], chain_id: u64, from: Address, pending_nonce: U256) -> U256 {
+    entries
+        .iter()
+        .filter(|entry| {
+            entry.chain_id == chain_id && entry.from == from && entry.status.is_pending()
+        })
+        .map(|entry| entry.nonce + U256::from(1))
+        .fold(pending_nonce, U256::max)
+}
+
+/// A multi step flow of transactions recorded in a journal. Resuming a flow
+/// skips the steps it already confirmed, so a flow that failed halfway can
+/// be run again from its last confirmed step.
+///
+/// The calls of a resumed run are matched to the steps of the journal in
+/// order, by the contract and function they call rather than by their
+/// calldata, as a command run again may pick different random values like
+/// vault ids. A step that was not confirmed is only sent again when its
+/// journaled calldata is the one of this run, or when the flow is told to
+/// keep the journaled calldata so the steps stay consistent with each other.
+#[derive(Debug, Clone)]
+pub struct JournalFlow {
+    journal: Arc<TransactionJournal>,
+    id: String,
+    /// Step of the journal after the last one this run matched
+    cursor: Arc<Mutex<usize>>,
+    last_entry: Arc<Mutex<Option<JournalEntry>>>,
+    /// Whether a step sent again may keep journaled calldata that differs
+    /// from the call of this run
+    keep_calldata: bool,
+}
+
+/// What to do with a step of a flow before sending it
+#[derive(Debug, Clone, PartialEq)]
+pub enum JournalStep {
+    /// Send the calldata of this new entry
+    Send(JournalEntry),
+    /// The call was already confirmed by this entry
+    Confirmed(JournalEntry),
+}
+
+impl JournalFlow {
+    /// Starts a new flow, identified by the time it starts at
+    pub fn new(journal: Arc<TransactionJournal>) -> Self {
+        let id = chrono::Utc::now().format("%Y%m%d-%H%M%S%3f").to_string();
+        Self::resume(journal, id)
+    }
+
+    /// Continues the flow with the given id
+    pub fn resume(journal: Arc<TransactionJournal>, id: String) -> Self {
+        Self {
+            journal,
+            id,
+            cursor: Arc::new(Mutex::new(0)),
+            last_entry: Arc::new(Mutex::new(None)),
+            keep_calldata: false,
+        }
+    }
+
+    /// Sends steps again with their journaled calldata even when this run
+    /// calls them with other arguments, otherwise such steps are refused
+    pub fn with_keep_calldata(mut self, keep_calldata: bool) -> Self {
+        self.keep_calldata = keep_calldata;
+        self
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn journal(&self) -> &TransactionJournal {
+        &self.journal
+    }
+
+    /// Entry of the last step started or skipped by this run
+    pub fn last_entry(&self) -> Option<JournalEntry> {
+        self.last_entry.lock().unwrap().clone()
+    }
+
+    /// Matches a call against the next steps of the flow, or else records it
+    /// as a new step at the next nonce of the sender
+    #[allow(clippy::too_many_arguments)]
+    pub async fn begin_step<T: SolCall>(
+        &self,
+        rpc_url: &str,
+        chain_id: u64,
+        from: Address,
+        to: Address,
+        call: &T,
+        max_fee_per_gas: Option<U256>,
+        max_priority_fee_per_gas: Option<U256>,
+    ) -> Result<JournalStep, JournalError> {
+        let label = call_name::<T>();
+        let entries = self.journal.flow_entries(&self.id);
+        let cursor = *self.cursor.lock().unwrap();
+
+        // the first step from the cursor calling the same function, steps
+        // this run no longer makes, like an approval no longer needed, are
+        // passed over
+        let step = entries
+            .iter()
+            .filter(|entry| entry.step >= cursor && entry.attempt == 0)
+            .find(|entry| entry.is_attempt_of(chain_id, from, to, &label))
+            .map(|entry| entry.step);
+        let attempts = entries
+            .iter()
+            .filter(|entry| Some(entry.step) == step)
+            .collect::<Vec<_>>();
+
+        if let Some(step) = step {
+            *self.cursor.lock().unwrap() = step + 1;
+            // a confirmed cancel does not count as the step being done
+            if let Some(entry) = attempts.iter().find(|entry| {
+                matches!(entry.status, JournalStatus::Confirmed(_))
+                    && entry.is_attempt_of(chain_id, from, to, &label)
+            }) {
+                *self.last_entry.lock().unwrap() = Some((*entry).clone());
+                return Ok(JournalStep::Confirmed((*entry).clone()));
+            }
+            if attempts.iter().any(|entry| entry.status.is_pending()) {
+                return Err(JournalError::StepPending(self.id.clone(), step));
+            }
+        }
+
+        // a step sent again keeps the calldata it was first sent with, which
+        // is only done blindly when it is what this run would send anyway
+        let calldata = Bytes::from(call.abi_encode());
+        let calldata = match attempts.first() {
+            Some(first) if first.calldata != calldata => {
+                if !self.keep_calldata {
+                    return Err(JournalError::CalldataChanged(
+                        self.id.clone(),
+                        first.step,
+                        first.calldata.clone(),
+                        calldata,
+                    ));
+                }
+                tracing::warn!(
+                    "Sending {} of step {} of flow {} again with its journaled calldata {} instead of {}",
+                    label,
+                    first.step,
+                    self.id,
+                    first.calldata,
+                    calldata
+                );
+                first.calldata.clone()
+            }
+            _ => calldata,
+        };
+
+        let pending_nonce = read_nonce(rpc_url, from, "pending").await?;
+        let entry = JournalEntry {
+            flow: self.id.clone(),
+            step: step.unwrap_or_default(),
+            attempt: attempts.len(),
+            label,
+            chain_id,
+            from,
+            to,
+            calldata,
+            nonce: U256::ZERO,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            status: JournalStatus::Prepared,
+            updated_at: chrono::Utc::now().timestamp_millis(),
+        };
+        let entry = self
+            .journal
+            .insert_at_next_nonce(entry, pending_nonce, step.is_none())?;
+        if step.is_none() {
+            *self.cursor.lock().unwrap() = entry.step + 1;
+        }
+        *self.last_entry.lock().unwrap() = Some(entry.clone());
+        Ok(JournalStep::Send(entry))
+    }
+
+    /// Records a status change of the transaction of an entry
+    pub fn record(&self, entry: &JournalEntry, status: JournalStatus) -> Result<(), JournalError> {
+        let entry = self
+            .journal
+            .set_status(&entry.flow, entry.step, entry.attempt, status)?;
+        *self.last_entry.lock().unwrap() = Some(entry);
+        Ok(())
+    }
+}
+
+/// How to replace a stuck transaction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Replacement {
+    /// Send the same call with higher fees
+    SpeedUp,
+    /// Send a call to the sender itself with higher fees
+    Cancel,
+}
+
+impl TransactionJournal {
+    /// Records a new attempt replacing the pending attempt of a step at the
+    /// same nonce, with fees high enough for the rpc to accept it. The
+    /// pending attempt is only marked replaced once the new one is sent
+    pub async fn prepare_replacement(
+        &self,
+        flow: &str,
+        step: usize,
+        replacement: Replacement,
+        rpc_url: &str,
+    ) -> Result<JournalEntry, JournalError> {
+        let pending = self.pending_attempt(flow, step)?;
+        let attempts = self
+            .latest_attempt(flow, step)
+            .map_or(0, |latest| latest.attempt + 1);
+        let current = fetch_fees(&Url::parse(rpc_url)?)
+            .await
+            .ok_or(JournalError::ReadFees)?;
+        let (max_fee_per_gas, max_priority_fee_per_gas) = replacement_fees(
+            pending.max_fee_per_gas,
+            pending.max_priority_fee_per_gas,
+            current,
+        );
+        let (label, to, calldata) = match replacement {
+            Replacement::SpeedUp => (pending.label.clone(), pending.to, pending.calldata.clone()),
+            Replacement::Cancel => (
+                call_name::<IJournalCancel::cancelCall>(),
+                pending.from,
+                Bytes::from(IJournalCancel::cancelCall {}.abi_encode()),
+            ),
+        };
+        let entry = JournalEntry {
+            attempt: attempts,
+            label,
+            to,
+            calldata,
+            max_fee_per_gas: Some(max_fee_per_gas),
+            max_priority_fee_per_gas: Some(max_priority_fee_per_gas),
+            status: JournalStatus::Prepared,
+            updated_at: chrono::Utc::now().timestamp_millis(),
+            ..pending
+        };
+        self.insert(entry.clone())?;
+        Ok(entry)
+    }
+}
+
+/// Fees of a replacement transaction, at least the current fees and at
+/// least 12.5% over the replaced fees as rpcs require a bump of 10%. Unknown
+/// replaced fees were picked from the fees of the time so double the
+/// current priority fee is used instead
+pub fn replacement_fees(
+    max_fee_per_gas: Option<U256>,
+    max_priority_fee_per_gas: Option<U256>,
+    current: FeeEstimate,
+) -> (U256, U256) {
+    let bump = |fee: U256| fee + (fee + U256::from(7)) / U256::from(8);
+    let current_priority_fee = U256::from(current.max_priority_fee_per_gas);
+    let priority_fee = match max_priority_fee_per_gas {
+        Some(fee) => bump(fee).max(current_priority_fee),
+        None => current_priority_fee * U256::from(2),
+    };
+    let current_max_fee = U256::from(current.base_fee_per_gas) * U256::from(2) + priority_fee;
+    let max_fee = match max_fee_per_gas {
+        Some(fee) => bump(fee).max(current_max_fee),
+        None => current_max_fee,
+    };
+    (max_fee, priority_fee)
+}
+
+/// Fees of a transaction sent at the given speed, a higher speed tips a
+/// larger share of the current priority fee
+pub fn speed_fees(speed: &GasFeeSpeed, current: FeeEstimate) -> (U256, U256) {
+    let percent = match speed {
+        GasFeeSpeed::Slow => 100,
+        GasFeeSpeed::Medium => 125,
+        GasFeeSpeed::Fast => 150,
+        GasFeeSpeed::Fastest => 200,
+    };
+    let priority_fee =
+        U256::from(current.max_priority_fee_per_gas) * U256::from(percent) / U256::from(100);
+    let max_fee = U256::from(current.base_fee_per_gas) * U256::from(2) + priority_fee;
+    (max_fee, priority_fee)
+}
+
+/// Reads the transaction count of an address at the given block tag
+pub async fn read_nonce(
+    rpc_url: &str,
+    address: Address,
+    block: &str,
+) -> Result<U256, JournalError> {
+    let nonce = json_rpc(
+        &Url::parse(rpc_url)?,
+        "eth_getTransactionCount",
+        serde_json::json!([address, block]),
+    )
+    .await
+    .map_err(|e| JournalError::ReadNonce(address, e))?;
+    parse_quantity(&nonce)
+        .map(U256::from)
+        .ok_or(JournalError::ReadNonce(
+            address,
+            format!("invalid nonce {}", nonce),
+        ))
+}
+
+fn call_name<T: SolCall>() -> String {
+    T::SIGNATURE
+        .split_once('(')
+        .map_or(T::SIGNATURE, |(name, _)| name)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::{Method::POST, MockServer};
+    use rain_orderbook_bindings::{IOrderBookV4::withdraw2Call, IERC20::approveCall};
+
+    fn journal_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("journal-{}-{}.json", name, std::process::id()))
+    }
+
+    fn entry(flow: &str, step: usize, nonce: u64, status: JournalStatus) -> JournalEntry {
+        JournalEntry {
+            flow: flow.to_string(),
+            step,
+            attempt: 0,
+            label: "approve".to_string(),
+            chain_id: 137,
+            from: Address::repeat_byte(1),
+            to: Address::repeat_byte(2),
+            calldata: Bytes::from(vec![1, 2, 3]),
+            nonce: U256::from(nonce),
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            status,
+            updated_at: 0,
+        }
+    }
+
+    fn mock_rpc(server: &MockServer, nonce: &str) {
+        server.mock(|when, then| {
+            when.method(POST).body_contains("eth_getTransactionCount");
+            then.json_body(serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": nonce}));
+        });
+    }
+
+    #[test]
+    fn test_open_and_save() {
+        let path = journal_path("save");
+        let journal = TransactionJournal::open(&path).unwrap();
+        assert!(journal.entries().is_empty());
+
+        journal
+            .insert(entry("flow", 0, 1, JournalStatus::Prepared))
+            .unwrap();
+        let hash = B256::repeat_byte(3);
+        journal
+            .set_status("flow", 0, 0, JournalStatus::Confirmed(hash))
+            .unwrap();
+        assert!(matches!(
+            journal.set_status("flow", 1, 0, JournalStatus::Sent),
+            Err(JournalError::StepNotFound(_, 1))
+        ));
+
+        let reopened = TransactionJournal::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let entries = reopened.entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].status, JournalStatus::Confirmed(hash));
+        assert_eq!(entries[0].nonce, U256::from(1));
+    }
+
+    #[test]
+    fn test_next_nonce() {
+        let journal = TransactionJournal::open(journal_path("nonce")).unwrap();
+        journal
+            .insert(entry("a", 0, 5, JournalStatus::Sent))
+            .unwrap();
+        journal
+            .insert(entry("a", 1, 9, JournalStatus::Failed("reverted".into())))
+            .unwrap();
+        journal
+            .insert(entry("b", 0, 6, JournalStatus::Signing))
+            .unwrap();
+
+        let from = Address::repeat_byte(1);
+        assert_eq!(journal.next_nonce(137, from, U256::from(4)), U256::from(7));
+        assert_eq!(
+            journal.next_nonce(137, from, U256::from(10)),
+            U256::from(10)
+        );
+        assert_eq!(journal.next_nonce(1, from, U256::from(4)), U256::from(4));
+
+        // the nonce is picked while the journal is locked
+        let inserted = journal
+            .insert_at_next_nonce(
+                entry("c", 0, 0, JournalStatus::Prepared),
+                U256::from(4),
+                true,
+            )
+            .unwrap();
+        assert_eq!(inserted.nonce, U256::from(7));
+        std::fs::remove_file(journal.path()).unwrap();
+    }
+
+    #[test]
+    fn test_shared_journal_file() {
+        let path = journal_path("shared");
+        // two processes with the journal open
+        let first = TransactionJournal::open(&path).unwrap();
+        let second = TransactionJournal::open(&path).unwrap();
+        first.insert(entry("a", 0, 1, JournalStatus::Sent)).unwrap();
+        second
+            .insert(entry("b", 0, 2, JournalStatus::Sent))
+            .unwrap();
+        first
+            .set_status("a", 0, 0, JournalStatus::Confirmed(B256::ZERO))
+            .unwrap();
+
+        let entries = TransactionJournal::open(&path).unwrap().entries();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].status, JournalStatus::Confirmed(B256::ZERO));
+        assert_eq!(entries[1].flow, "b");
+    }
+
+    #[tokio::test]
+    async fn test_flow_resume() {
+        let server = MockServer::start();
+        mock_rpc(&server, "0x7");
+        let journal = Arc::new(TransactionJournal::open(journal_path("resume")).unwrap());
+        let (from, orderbook, token) = (
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+        );
+        let approve = approveCall {
+            spender: orderbook,
+            amount: U256::from(10),
+        };
+        let withdraw = withdraw2Call {
+            token,
+            vaultId: U256::from(1),
+            targetAmount: U256::from(10),
+            tasks: vec![],
+        };
+
+        let flow = JournalFlow::new(journal.clone());
+        let JournalStep::Send(approve_entry) = flow
+            .begin_step(&server.url("/"), 137, from, token, &approve, None, None)
+            .await
+            .unwrap()
+        else {
+            panic!("expected a new step");
+        };
+        assert_eq!(approve_entry.step, 0);
+        assert_eq!(approve_entry.nonce, U256::from(7));
+        assert_eq!(approve_entry.label, "approve");
+        flow.record(
+            &approve_entry,
+            JournalStatus::Confirmed(B256::repeat_byte(9)),
+        )
+        .unwrap();
+
+        let JournalStep::Send(withdraw_entry) = flow
+            .begin_step(
+                &server.url("/"),
+                137,
+                from,
+                orderbook,
+                &withdraw,
+                None,
+                None,
+            )
+            .await
+            .unwrap()
+        else {
+            panic!("expected a new step");
+        };
+        assert_eq!(withdraw_entry.step, 1);
+        flow.record(&withdraw_entry, JournalStatus::Failed("reverted".into()))
+            .unwrap();
+        assert_eq!(
+            flow.last_entry().unwrap().status,
+            JournalStatus::Failed("reverted".into())
+        );
+
+        // running the flow again skips the confirmed approval, and refuses
+        // the failed withdraw as this run picked another vault id
+        let refused = JournalFlow::resume(journal.clone(), flow.id().to_string());
+        let other_vault = withdraw2Call {
+            vaultId: U256::from(2),
+            ..withdraw.clone()
+        };
+        assert!(matches!(
+            refused
+                .begin_step(&server.url("/"), 137, from, token, &approve, None, None)
+                .await,
+            Ok(JournalStep::Confirmed(_))
+        ));
+        assert!(matches!(
+            refused
+                .begin_step(
+                    &server.url("/"),
+                    137,
+                    from,
+                    orderbook,
+                    &other_vault,
+                    None,
+                    None
+                )
+                .await,
+            Err(JournalError::CalldataChanged(_, 1, journaled, new))
+                if journaled == Bytes::from(withdraw.abi_encode())
+                    && new == Bytes::from(other_vault.abi_encode())
+        ));
+
+        // keeping the journaled calldata sends the withdraw again as it was
+        let resumed =
+            JournalFlow::resume(journal.clone(), flow.id().to_string()).with_keep_calldata(true);
+        let step = resumed
+            .begin_step(&server.url("/"), 137, from, token, &approve, None, None)
+            .await
+            .unwrap();
+        assert!(matches!(step, JournalStep::Confirmed(entry) if entry.step == 0));
+        let JournalStep::Send(retry) = resumed
+            .begin_step(
+                &server.url("/"),
+                137,
+                from,
+                orderbook,
+                &other_vault,
+                None,
+                None,
+            )
+            .await
+            .unwrap()
+        else {
+            panic!("expected a new attempt");
+        };
+        assert_eq!(retry.step, 1);
+        assert_eq!(retry.attempt, 1);
+        assert_eq!(retry.calldata, Bytes::from(withdraw.abi_encode()));
+
+        // a sent step blocks sending it again
+        resumed.record(&retry, JournalStatus::Sent).unwrap();
+        let again = JournalFlow::resume(journal.clone(), flow.id().to_string());
+        assert!(matches!(
+            again
+                .begin_step(&server.url("/"), 137, from, token, &approve, None, None)
+                .await,
+            Ok(JournalStep::Confirmed(_))
+        ));
+        assert!(matches!(
+            again
+                .begin_step(
+                    &server.url("/"),
+                    137,
+                    from,
+                    orderbook,
+                    &withdraw,
+                    None,
+                    None
+                )
+                .await,
+            Err(JournalError::StepPending(_, 1))
+        ));
+
+        // a step this run no longer makes is passed over
+        resumed
+            .record(&retry, JournalStatus::Confirmed(B256::repeat_byte(8)))
+            .unwrap();
+        let without_approve = JournalFlow::resume(journal.clone(), flow.id().to_string());
+        assert!(matches!(
+            without_approve
+                .begin_step(
+                    &server.url("/"),
+                    137,
+                    from,
+                    orderbook,
+                    &withdraw,
+                    None,
+                    None
+                )
+                .await,
+            Ok(JournalStep::Confirmed(entry)) if entry.step == 1 && entry.attempt == 1
+        ));
+        std::fs::remove_file(journal.path()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_prepare_replacement() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).body_contains("eth_getBlockByNumber");
+            then.json_body(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {"baseFeePerGas": "0x64"}
+            }));
+        });
+        server.mock(|when, then| {
+            when.method(POST).body_contains("eth_maxPriorityFeePerGas");
+            then.json_body(serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "0xa"}));
+        });
+
+        let journal = TransactionJournal::open(journal_path("replace")).unwrap();
+        journal
+            .insert(entry("flow", 0, 4, JournalStatus::Sent))
+            .unwrap();
+        let cancel = journal
+            .prepare_replacement("flow", 0, Replacement::Cancel, &server.url("/"))
+            .await
+            .unwrap();
+        assert_eq!(cancel.attempt, 1);
+        assert_eq!(cancel.nonce, U256::from(4));
+        assert_eq!(cancel.to, cancel.from);
+        assert_eq!(cancel.label, "cancel");
+        assert_eq!(cancel.max_priority_fee_per_gas, Some(U256::from(20)));
+        assert_eq!(cancel.max_fee_per_gas, Some(U256::from(220)));
+        // the replaced attempt stays pending until the new one is sent
+        journal
+            .set_status("flow", 0, 1, JournalStatus::Failed("rejected".into()))
+            .unwrap();
+        assert_eq!(journal.pending_attempt("flow", 0).unwrap().attempt, 0);
+
+        journal
+            .set_status("flow", 0, 0, JournalStatus::Replaced)
+            .unwrap();
+        assert!(matches!(
+            journal
+                .prepare_replacement("flow", 0, Replacement::SpeedUp, &server.url("/"))
+                .await,
+            Err(JournalError::StepNotPending(_, 0, _))
+        ));
+        std::fs::remove_file(journal.path()).unwrap();
+    }
+
+    #[test]
+    fn test_speed_fees() {
+        let current = FeeEstimate {
+            base_fee_per_gas: 100,
+            max_priority_fee_per_gas: 10,
+        };
+        assert_eq!(
+            speed_fees(&GasFeeSpeed::Slow, current),
+            (U256::from(210), U256::from(10))
+        );
+        assert_eq!(
+            speed_fees(&GasFeeSpeed::Fastest, current),
+            (U256::from(220), U256::from(20))
+        );
+    }
+
+    #[test]
+    fn test_replacement_fees() {
+        let current = FeeEstimate {
+            base_fee_per_gas: 100,
+            max_priority_fee_per_gas: 10,
+        };
+        // bumped over the replaced fees
+        assert_eq!(
+            replacement_fees(Some(U256::from(1000)), Some(U256::from(80)), current),
+            (U256::from(1125), U256::from(90))
+        );
+        // never under the current fees
+        assert_eq!(
+            replacement_fees(Some(U256::from(100)), Some(U256::from(1)), current),
+            (U256::from(210), U256::from(10))
+        );
+        assert_eq!(
+            replacement_fees(None, None, current),
+            (U256::from(220), U256::from(20))
+        );
+    }
+}
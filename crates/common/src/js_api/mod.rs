@@ -1,7 +1,7 @@
 use crate::{
     add_order::{AddOrderArgs, AddOrderArgsError},
     frontmatter::parse_frontmatter,
-    transaction::TransactionArgs,
+    transaction::{TransactionArgs, TransactionArgsError},
 };
 use js_sys::Uint8Array;
 use rain_orderbook_app_settings::{Config, ParseConfigSourceError};
@@ -18,6 +18,8 @@ pub enum Error {
     ParseConfigSourceError(#[from] ParseConfigSourceError),
     #[error(transparent)]
     AddOrderArgsError(#[from] AddOrderArgsError),
+    #[error(transparent)]
+    TransactionArgsError(#[from] TransactionArgsError),
 }
 
 impl From<Error> for JsValue {
@@ -38,10 +40,7 @@ pub async fn get_add_order_calldata(dotrain: &str, deployment: &str) -> Result<U
         AddOrderArgs::new_from_deployment(dotrain.to_string(), deployment_ref.deref().clone())
             .await?;
 
-    let tx_args = TransactionArgs {
-        rpc_url: deployment_ref.scenario.deployer.network.rpc.to_string(),
-        ..Default::default()
-    };
+    let tx_args = TransactionArgs::for_network(&deployment_ref.scenario.deployer.network).await?;
     Ok(add_order_args
         .get_add_order_calldata(tx_args)
         .await?
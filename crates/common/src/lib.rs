@@ -1,18 +1,32 @@
 pub mod add_order;
+#[cfg(not(target_family = "wasm"))]
+pub mod annotated_trace;
+#[cfg(not(target_family = "wasm"))]
+pub mod bulk_vault;
 pub mod csv;
 pub mod deposit;
+pub mod derivation;
 pub mod dotrain_add_order_lsp;
 pub mod dotrain_order;
 pub mod erc20;
+pub mod export_order;
 pub mod frontmatter;
 #[cfg(not(target_family = "wasm"))]
 pub mod fuzz;
+#[cfg(not(target_family = "wasm"))]
+pub mod journal;
 pub mod meta;
+pub mod order_health;
+#[cfg(not(target_family = "wasm"))]
+pub mod preview;
 pub mod rainlang;
 pub mod remove_order;
 #[cfg(not(target_family = "wasm"))]
 pub mod replays;
+pub mod safe;
+pub mod strategy;
 pub mod subgraph;
+pub mod token_metadata;
 pub mod transaction;
 pub mod types;
 #[cfg(not(target_family = "wasm"))]
@@ -1,7 +1,15 @@
-use alloy::primitives::hex::{decode, FromHexError};
-use rain_metadata::{Error as RainMetadataError, KnownMagic, RainMetaDocumentV1Item};
+use alloy::primitives::{
+    hex::{decode, FromHexError},
+    Address,
+};
+use rain_metadata::{
+    ContentEncoding, ContentLanguage, ContentType, Error as RainMetadataError, KnownMagic,
+    RainMetaDocumentV1Item,
+};
 use rain_orderbook_subgraph_client::types::common::RainMetaV1;
-use std::string::FromUtf8Error;
+use serde::{Deserialize, Serialize};
+use serde_bytes::ByteBuf;
+use std::{collections::BTreeMap, string::FromUtf8Error};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -22,20 +30,25 @@ pub trait TryDecodeRainlangSource {
     fn try_decode_rainlangsource(&self) -> Result<String, TryDecodeRainlangSourceError>;
 }
 
+/// Decodes the items of a rain meta document
+fn decode_meta_items(
+    meta: &RainMetaV1,
+) -> Result<Vec<RainMetaDocumentV1Item>, TryDecodeRainlangSourceError> {
+    // Ensure meta has expected magic prefix
+    let meta_bytes = decode(meta.clone().0)?;
+    if !meta_bytes
+        .clone()
+        .starts_with(&KnownMagic::RainMetaDocumentV1.to_prefix_bytes())
+    {
+        return Err(TryDecodeRainlangSourceError::MissingRainlangSourceV1);
+    }
+    Ok(RainMetaDocumentV1Item::cbor_decode(meta_bytes.as_slice())?)
+}
+
 impl TryDecodeRainlangSource for RainMetaV1 {
     fn try_decode_rainlangsource(&self) -> Result<String, TryDecodeRainlangSourceError> {
-        // Ensure meta has expected magic prefix
-        let meta_bytes = decode(self.clone().0)?;
-        if !meta_bytes
-            .clone()
-            .starts_with(&KnownMagic::RainMetaDocumentV1.to_prefix_bytes())
-        {
-            return Err(TryDecodeRainlangSourceError::MissingRainlangSourceV1);
-        }
-
         // Decode meta to string
-        let meta_bytes_slice = meta_bytes.as_slice();
-        let rain_meta_document_item = RainMetaDocumentV1Item::cbor_decode(meta_bytes_slice)?;
+        let rain_meta_document_item = decode_meta_items(self)?;
         let rainlangsource_item = rain_meta_document_item
             .first()
             .ok_or(TryDecodeRainlangSourceError::MissingRainlangSourceV1)?;
@@ -44,3 +57,176 @@ impl TryDecodeRainlangSource for RainMetaV1 {
         Ok(rainlangsource)
     }
 }
+
+/// State of the gui an order was added from, enough to reopen the gui with
+/// the same choices
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GuiStateMeta {
+    pub deployment_name: String,
+    /// The gui state as serialized by the gui itself
+    pub state: String,
+    /// Addresses chosen for the select tokens of the deployment, which the
+    /// serialized state does not cover
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub select_tokens: BTreeMap<String, Address>,
+}
+
+/// Source an order was composed from, embedded in its meta next to the
+/// composed rainlang so the order can be audited against it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddedSource {
+    pub dotrain: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gui_state: Option<GuiStateMeta>,
+}
+
+#[derive(Error, Debug)]
+pub enum TryDecodeEmbeddedSourceError {
+    #[error(transparent)]
+    TryDecodeRainlangSourceError(#[from] TryDecodeRainlangSourceError),
+    #[error(transparent)]
+    FromUtf8Error(#[from] FromUtf8Error),
+    #[error(transparent)]
+    SerdeJsonError(#[from] serde_json::Error),
+}
+
+impl EmbeddedSource {
+    /// Source of a dotrain without its networks, subgraphs and metaboards,
+    /// as the urls in them often carry api keys that must not be published
+    /// onchain. Whoever reopens the source provides them again
+    pub fn new(dotrain: &str, gui_state: Option<GuiStateMeta>) -> Self {
+        EmbeddedSource {
+            dotrain: strip_endpoints(dotrain),
+            gui_state,
+        }
+    }
+
+    /// The meta items of the source, a `DotrainV1` item holding the dotrain
+    /// and, if set, a json `DotrainV1` item holding the gui state.
+    ///
+    /// The gui state is told apart from the dotrain by its content type, as
+    /// the rain.metadata this builds against has no magic of its own for it.
+    /// Once it has one the gui state should move to it, still decoding the
+    /// json `DotrainV1` items of the orders already added
+    pub fn to_meta_items(&self) -> Result<Vec<RainMetaDocumentV1Item>, serde_json::Error> {
+        let mut items = vec![RainMetaDocumentV1Item {
+            payload: ByteBuf::from(self.dotrain.as_bytes()),
+            magic: KnownMagic::DotrainV1,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::None,
+        }];
+        if let Some(gui_state) = &self.gui_state {
+            items.push(RainMetaDocumentV1Item {
+                payload: ByteBuf::from(serde_json::to_vec(gui_state)?),
+                magic: KnownMagic::DotrainV1,
+                content_type: ContentType::Json,
+                content_encoding: ContentEncoding::None,
+                content_language: ContentLanguage::None,
+            });
+        }
+        Ok(items)
+    }
+}
+
+/// Top level frontmatter sections holding urls, left out of the embedded
+/// source
+const ENDPOINT_SECTIONS: [&str; 3] = ["networks:", "subgraphs:", "metaboards:"];
+
+/// Removes the top level sections holding urls from the frontmatter of a
+/// dotrain, leaving everything else as written
+fn strip_endpoints(dotrain: &str) -> String {
+    let mut stripped = String::new();
+    let mut in_frontmatter = true;
+    let mut in_endpoints = false;
+    for line in dotrain.split_inclusive('\n') {
+        if in_frontmatter {
+            if line.trim_end() == "---" {
+                in_frontmatter = false;
+                in_endpoints = false;
+            } else if in_endpoints && (line.trim().is_empty() || line.starts_with([' ', '\t'])) {
+                continue;
+            } else {
+                in_endpoints = ENDPOINT_SECTIONS
+                    .iter()
+                    .any(|section| line.starts_with(section));
+                if in_endpoints {
+                    continue;
+                }
+            }
+        }
+        stripped.push_str(line);
+    }
+    stripped
+}
+
+pub trait TryDecodeEmbeddedSource {
+    /// The embedded source of an order, None if its meta has none
+    fn try_decode_embedded_source(
+        &self,
+    ) -> Result<Option<EmbeddedSource>, TryDecodeEmbeddedSourceError>;
+}
+
+impl TryDecodeEmbeddedSource for RainMetaV1 {
+    fn try_decode_embedded_source(
+        &self,
+    ) -> Result<Option<EmbeddedSource>, TryDecodeEmbeddedSourceError> {
+        let items = decode_meta_items(self)?;
+        let mut dotrain = None;
+        let mut gui_state = None;
+        for item in items {
+            match (item.magic, item.content_type) {
+                (KnownMagic::DotrainV1, ContentType::Json) => {
+                    gui_state = Some(serde_json::from_slice(&item.payload)?);
+                }
+                (KnownMagic::DotrainV1, _) => {
+                    dotrain = Some(String::from_utf8(item.payload.to_vec())?);
+                }
+                _ => {}
+            }
+        }
+        Ok(dotrain.map(|dotrain| EmbeddedSource { dotrain, gui_state }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_endpoints() {
+        let dotrain = r#"networks:
+    mainnet:
+        rpc: https://mainnet.node/some-api-key
+
+        chain-id: 1
+subgraphs:
+    mainnet: https://subgraph.node/some-api-key
+metaboards:
+    mainnet: https://metaboard.node/some-api-key
+# the deployers
+deployers:
+    mainnet:
+        address: 0x1
+networks-note: kept
+---
+#calculate-io
+networks: 1;
+"#;
+        assert_eq!(
+            strip_endpoints(dotrain),
+            r#"# the deployers
+deployers:
+    mainnet:
+        address: 0x1
+networks-note: kept
+---
+#calculate-io
+networks: 1;
+"#
+        );
+        assert_eq!(strip_endpoints("a: 1\n---\n_: 1;"), "a: 1\n---\n_: 1;");
+    }
+}
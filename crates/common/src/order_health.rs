@@ -0,0 +1,766 @@
+use alloy::primitives::{
+    hex::{encode_prefixed, FromHex, FromHexError},
+    utils::format_units,
+    Address, B256, U256,
+};
+use alloy::sol_types::SolCall;
+use alloy_ethers_typecast::{
+    multicall::{
+        IMulticall3::{aggregate3Call, Call3},
+        MULTICALL3_ADDRESS,
+    },
+    transaction::{ReadContractParameters, ReadableClient, ReadableClientError},
+};
+use rain_orderbook_app_settings::rpc::RpcProvider;
+use rain_orderbook_bindings::{IOrderBookV4::OrderV3, IERC20::allowanceCall};
+use rain_orderbook_quote::{
+    error::Error as QuoteError, get_order_quotes, rpc::batch_token_metadata,
+    BatchOrderQuotesResponse, QuoteToken,
+};
+use rain_orderbook_subgraph_client::{
+    types::{
+        common::{Bytes, Order, OrdersListFilterArgs, Vault},
+        order_detail_traits::OrderDetailError,
+        Id,
+    },
+    utils::make_order_id,
+    OrderbookSubgraphClient, OrderbookSubgraphClientError, PaginationArgs,
+};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use thiserror::Error;
+use typeshare::typeshare;
+use url::Url;
+
+const OWNER_ORDERS_PAGE_SIZE: u16 = 200;
+
+#[derive(Error, Debug)]
+pub enum OrderHealthError {
+    #[error(transparent)]
+    SubgraphClientError(#[from] OrderbookSubgraphClientError),
+    #[error(transparent)]
+    OrderDetailError(#[from] OrderDetailError),
+    #[error(transparent)]
+    QuoteError(#[from] QuoteError),
+    #[error(transparent)]
+    FromHexError(#[from] FromHexError),
+    #[error(transparent)]
+    ReadableClientError(#[from] ReadableClientError),
+}
+
+/// Severity of an order problem, ordered from the most severe
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum OrderProblemSeverity {
+    /// The order cannot trade
+    Critical,
+    /// The order can trade but likely not as intended
+    Warning,
+    /// Does not affect trading now but may need attention
+    Info,
+}
+
+/// Kind of an order problem, variants are ordered by their priority within
+/// the same severity
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(tag = "type", content = "data")]
+pub enum OrderProblemKind {
+    /// The order has been removed from the orderbook
+    Inactive,
+    /// Quote of an IO pair failed, holds the pair name
+    QuoteFailed(String),
+    /// An output vault of the order has no balance, holds the token address
+    EmptyOutputVault(String),
+    /// Quote of an IO pair has zero max output while its output vault is not empty
+    ZeroMaxOutput(String),
+    /// Decimals of an IO differs from the token decimals, holds the token address
+    DecimalsMismatch(String),
+    /// The owner has not approved the orderbook to spend an output token,
+    /// holds the token address
+    NoAllowance(String),
+}
+
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct OrderProblem {
+    pub severity: OrderProblemSeverity,
+    pub kind: OrderProblemKind,
+    pub message: String,
+}
+
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct VaultHealth {
+    pub vault_id: String,
+    pub token: String,
+    pub symbol: Option<String>,
+    pub balance: String,
+    pub formatted_balance: Option<String>,
+    pub is_output: bool,
+}
+
+/// Health report of an order, problems are sorted from the most severe
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OrderHealthReport {
+    pub order_hash: String,
+    pub orderbook: String,
+    pub owner: String,
+    pub active: bool,
+    pub vaults: Vec<VaultHealth>,
+    pub quotes: Vec<BatchOrderQuotesResponse>,
+    pub problems: Vec<OrderProblem>,
+}
+
+impl OrderHealthReport {
+    /// Whether the report has no critical or warning problems
+    pub fn is_healthy(&self) -> bool {
+        self.problems
+            .iter()
+            .all(|v| v.severity == OrderProblemSeverity::Info)
+    }
+}
+
+/// Reads the order with the given hash from the subgraph and reports its health
+pub async fn get_order_health(
+    client: &OrderbookSubgraphClient,
+    rpc_url: &str,
+    orderbook: Address,
+    order_hash: B256,
+) -> Result<OrderHealthReport, OrderHealthError> {
+    let order = client
+        .order_detail(Id::new(encode_prefixed(make_order_id(
+            orderbook,
+            order_hash.into(),
+        ))))
+        .await?;
+    build_order_health_report(&order, rpc_url).await
+}
+
+/// Reads all the orders of the given owner from the subgraph and reports
+/// their health, active orders first
+pub async fn get_owner_orders_health(
+    client: &OrderbookSubgraphClient,
+    rpc_url: &str,
+    owner: Address,
+) -> Result<Vec<OrderHealthReport>, OrderHealthError> {
+    let mut orders = vec![];
+    let mut page = 1;
+    loop {
+        let page_data = client
+            .orders_list(
+                OrdersListFilterArgs {
+                    owners: vec![Bytes(encode_prefixed(owner))],
+                    active: None,
+                    order_hash: None,
+                },
+                PaginationArgs {
+                    page,
+                    page_size: OWNER_ORDERS_PAGE_SIZE,
+                },
+            )
+            .await?;
+        if page_data.is_empty() {
+            break;
+        }
+        orders.extend(page_data);
+        page += 1;
+    }
+
+    let mut reports = vec![];
+    for order in &orders {
+        reports.push(build_order_health_report(order, rpc_url).await?);
+    }
+    reports.sort_by_key(|v| !v.active);
+    Ok(reports)
+}
+
+/// Builds the health report of the given order by checking its subgraph
+/// state along with its live quotes, token decimals and owner allowances
+pub async fn build_order_health_report(
+    order: &Order,
+    rpc_url: &str,
+) -> Result<OrderHealthReport, OrderHealthError> {
+    let order_v3: OrderV3 = order.clone().try_into()?;
+
+    let mut tokens = vec![];
+    for io in order_v3.validInputs.iter().chain(&order_v3.validOutputs) {
+        if !tokens.contains(&io.token) {
+            tokens.push(io.token);
+        }
+    }
+    let tokens_metadata = batch_token_metadata(&tokens, rpc_url, None, None).await?;
+
+    let vaults = order
+        .inputs
+        .iter()
+        .map(|v| vault_health(v, false))
+        .chain(order.outputs.iter().map(|v| vault_health(v, true)))
+        .collect::<Vec<_>>();
+
+    // a removed order no longer takes deposits into account
+    let allowances = if order.active {
+        let mut output_tokens = vec![];
+        for io in &order_v3.validOutputs {
+            if !output_tokens.contains(&io.token) {
+                output_tokens.push(io.token);
+            }
+        }
+        read_allowances(
+            rpc_url,
+            order_v3.owner,
+            Address::from_str(&order.orderbook.id.0)?,
+            &output_tokens,
+        )
+        .await?
+    } else {
+        vec![]
+    };
+
+    // live quotes, a removed order is not quotable
+    let quotes = if order.active {
+        let rpc = RpcProvider::for_url(Url::parse(rpc_url).map_err(QuoteError::from)?);
+        get_order_quotes(vec![order.clone()], None, &rpc, None).await?
+    } else {
+        vec![]
+    };
+
+    let problems = order_problems(
+        order.active,
+        &order_v3,
+        &tokens_metadata,
+        &vaults,
+        &allowances,
+        &quotes,
+    );
+
+    Ok(OrderHealthReport {
+        order_hash: order.order_hash.0.clone(),
+        orderbook: order.orderbook.id.0.clone(),
+        owner: order.owner.0.clone(),
+        active: order.active,
+        vaults,
+        quotes,
+        problems,
+    })
+}
+
+/// Finds the problems of an order from its state, sorted from the most severe
+fn order_problems(
+    active: bool,
+    order_v3: &OrderV3,
+    tokens_metadata: &[QuoteToken],
+    vaults: &[VaultHealth],
+    allowances: &[(Address, U256)],
+    quotes: &[BatchOrderQuotesResponse],
+) -> Vec<OrderProblem> {
+    let mut problems = vec![];
+    let symbol_of = |token: &Address| {
+        tokens_metadata
+            .iter()
+            .find(|v| v.address == *token)
+            .map(QuoteToken::display_name)
+            .unwrap_or(token.to_string())
+    };
+
+    if !active {
+        problems.push(OrderProblem {
+            severity: OrderProblemSeverity::Critical,
+            kind: OrderProblemKind::Inactive,
+            message: "Order has been removed from the orderbook".to_string(),
+        });
+    }
+
+    // token decimals
+    for io in order_v3.validInputs.iter().chain(&order_v3.validOutputs) {
+        if let Some(decimals) = tokens_metadata
+            .iter()
+            .find(|v| v.address == io.token)
+            .and_then(|v| v.decimals)
+        {
+            let kind = OrderProblemKind::DecimalsMismatch(io.token.to_string());
+            if decimals != io.decimals && !problems.iter().any(|v: &OrderProblem| v.kind == kind) {
+                problems.push(OrderProblem {
+                    severity: OrderProblemSeverity::Warning,
+                    kind,
+                    message: format!(
+                        "{} has {} decimals but the order uses {}",
+                        symbol_of(&io.token),
+                        decimals,
+                        io.decimals
+                    ),
+                });
+            }
+        }
+    }
+
+    // vault balances
+    let mut empty_output_tokens = vec![];
+    for vault in vaults.iter().filter(|v| v.is_output) {
+        if U256::from_str(&vault.balance).unwrap_or_default().is_zero() {
+            let Ok(token) = Address::from_str(&vault.token) else {
+                continue;
+            };
+            empty_output_tokens.push(token);
+            // a removed order does not trade from its vaults anymore
+            problems.push(OrderProblem {
+                severity: if active {
+                    OrderProblemSeverity::Warning
+                } else {
+                    OrderProblemSeverity::Info
+                },
+                kind: OrderProblemKind::EmptyOutputVault(token.to_string()),
+                message: format!(
+                    "Output vault {} of {} is empty",
+                    vault.vault_id,
+                    symbol_of(&token)
+                ),
+            });
+        }
+    }
+
+    // owner allowances, needed to top up the output vaults
+    for (token, allowance) in allowances {
+        if allowance.is_zero() {
+            // an empty output vault can only be refilled after an approval
+            let severity = if empty_output_tokens.contains(token) {
+                OrderProblemSeverity::Warning
+            } else {
+                OrderProblemSeverity::Info
+            };
+            problems.push(OrderProblem {
+                severity,
+                kind: OrderProblemKind::NoAllowance(token.to_string()),
+                message: format!(
+                    "Owner has not approved the orderbook to spend {}, depositing into its output vault needs an approval first",
+                    symbol_of(token)
+                ),
+            });
+        }
+    }
+
+    for quote in quotes {
+        let pair_name = quote.pair.pair_name.clone();
+        if let Some(error) = &quote.error {
+            problems.push(OrderProblem {
+                severity: OrderProblemSeverity::Critical,
+                kind: OrderProblemKind::QuoteFailed(pair_name.clone()),
+                message: format!("Quote of {} failed: {}", pair_name, error),
+            });
+        } else if let Some(data) = &quote.data {
+            let output_token = order_v3
+                .validOutputs
+                .get(quote.pair.output_index as usize)
+                .map(|v| v.token);
+            let is_output_empty = output_token
+                .map(|v| empty_output_tokens.contains(&v))
+                .unwrap_or(false);
+            if data.max_output.is_zero() && !is_output_empty {
+                problems.push(OrderProblem {
+                    severity: OrderProblemSeverity::Warning,
+                    kind: OrderProblemKind::ZeroMaxOutput(pair_name.clone()),
+                    message: format!("Quote of {} has zero max output", pair_name),
+                });
+            }
+        }
+    }
+
+    problems.sort_by(|a, b| (a.severity, &a.kind).cmp(&(b.severity, &b.kind)));
+    problems
+}
+
+/// Reads the allowances the owner gave the orderbook for the given tokens in
+/// a single multicall, those that fail to read are left out
+async fn read_allowances(
+    rpc_url: &str,
+    owner: Address,
+    orderbook: Address,
+    tokens: &[Address],
+) -> Result<Vec<(Address, U256)>, OrderHealthError> {
+    if tokens.is_empty() {
+        return Ok(vec![]);
+    }
+    let client = ReadableClient::new_from_url(rpc_url.to_string())?;
+    let parameters = ReadContractParameters {
+        gas: None,
+        address: Address::from_hex(MULTICALL3_ADDRESS)?,
+        block_number: None,
+        call: aggregate3Call {
+            calls: tokens
+                .iter()
+                .map(|token| Call3 {
+                    allowFailure: true,
+                    target: *token,
+                    callData: allowanceCall {
+                        owner,
+                        spender: orderbook,
+                    }
+                    .abi_encode()
+                    .into(),
+                })
+                .collect(),
+        },
+    };
+    let result = client.read(parameters).await?;
+    Ok(tokens
+        .iter()
+        .zip(result.returnData)
+        .filter(|(_, res)| res.success)
+        .filter_map(|(token, res)| {
+            allowanceCall::abi_decode_returns(&res.returnData, true)
+                .ok()
+                .map(|v| (*token, v._0))
+        })
+        .collect())
+}
+
+fn vault_health(vault: &Vault, is_output: bool) -> VaultHealth {
+    VaultHealth {
+        vault_id: vault.vault_id.0.clone(),
+        token: vault.token.address.0.clone(),
+        symbol: vault.token.symbol.clone(),
+        balance: vault.balance.0.clone(),
+        formatted_balance: vault
+            .token
+            .decimals
+            .as_ref()
+            .and_then(|v| v.0.parse::<u8>().ok())
+            .and_then(|decimals| {
+                format_units(U256::from_str(&vault.balance.0).ok()?, decimals).ok()
+            }),
+        is_output,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rain_orderbook_bindings::IOrderBookV4::{EvaluableV3, IO};
+    use rain_orderbook_quote::{OrderQuoteValue, Pair};
+    use rain_orderbook_subgraph_client::types::common::{BigInt, Erc20, Orderbook};
+
+    fn get_vault(balance: &str, decimals: Option<&str>) -> Vault {
+        Vault {
+            id: Bytes("0x01".to_string()),
+            owner: Bytes(Address::ZERO.to_string()),
+            vault_id: BigInt("1".to_string()),
+            balance: BigInt(balance.to_string()),
+            token: Erc20 {
+                id: Bytes(Address::ZERO.to_string()),
+                address: Bytes(Address::ZERO.to_string()),
+                name: Some("Token".to_string()),
+                symbol: Some("T".to_string()),
+                decimals: decimals.map(|v| BigInt(v.to_string())),
+            },
+            orderbook: Orderbook {
+                id: Bytes(Address::ZERO.to_string()),
+            },
+            orders_as_output: vec![],
+            orders_as_input: vec![],
+            balance_changes: vec![],
+        }
+    }
+
+    #[test]
+    fn test_vault_health() {
+        let result = vault_health(&get_vault("1500000", Some("6")), true);
+        let expected = VaultHealth {
+            vault_id: "1".to_string(),
+            token: Address::ZERO.to_string(),
+            symbol: Some("T".to_string()),
+            balance: "1500000".to_string(),
+            formatted_balance: Some("1.500000".to_string()),
+            is_output: true,
+        };
+        assert_eq!(result, expected);
+
+        let result = vault_health(&get_vault("1500000", None), false);
+        assert_eq!(result.formatted_balance, None);
+        assert!(!result.is_output);
+    }
+
+    #[test]
+    fn test_problem_severity_order() {
+        let mut severities = vec![
+            OrderProblemSeverity::Info,
+            OrderProblemSeverity::Critical,
+            OrderProblemSeverity::Warning,
+        ];
+        severities.sort();
+        assert_eq!(
+            severities,
+            vec![
+                OrderProblemSeverity::Critical,
+                OrderProblemSeverity::Warning,
+                OrderProblemSeverity::Info,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_healthy() {
+        let mut report = OrderHealthReport {
+            order_hash: "0x01".to_string(),
+            orderbook: Address::ZERO.to_string(),
+            owner: Address::ZERO.to_string(),
+            active: true,
+            vaults: vec![],
+            quotes: vec![],
+            problems: vec![OrderProblem {
+                severity: OrderProblemSeverity::Info,
+                kind: OrderProblemKind::DecimalsMismatch(Address::ZERO.to_string()),
+                message: String::new(),
+            }],
+        };
+        assert!(report.is_healthy());
+
+        report.problems.push(OrderProblem {
+            severity: OrderProblemSeverity::Critical,
+            kind: OrderProblemKind::Inactive,
+            message: String::new(),
+        });
+        assert!(!report.is_healthy());
+    }
+
+    fn get_order_v3(input: Address, output: Address) -> OrderV3 {
+        OrderV3 {
+            owner: Address::with_last_byte(9),
+            evaluable: EvaluableV3 {
+                interpreter: Address::ZERO,
+                store: Address::ZERO,
+                bytecode: vec![].into(),
+            },
+            validInputs: vec![IO {
+                token: input,
+                decimals: 6,
+                vaultId: U256::from(1),
+            }],
+            validOutputs: vec![IO {
+                token: output,
+                decimals: 18,
+                vaultId: U256::from(1),
+            }],
+            nonce: B256::ZERO,
+        }
+    }
+
+    fn get_vault_health(token: Address, balance: &str, is_output: bool) -> VaultHealth {
+        VaultHealth {
+            vault_id: "1".to_string(),
+            token: token.to_string(),
+            symbol: None,
+            balance: balance.to_string(),
+            formatted_balance: None,
+            is_output,
+        }
+    }
+
+    fn get_quote(max_output: U256, error: Option<&str>) -> BatchOrderQuotesResponse {
+        BatchOrderQuotesResponse {
+            pair: Pair {
+                pair_name: "A/B".to_string(),
+                input_index: 0,
+                output_index: 0,
+            },
+            block_number: U256::from(1),
+            data: error.is_none().then_some(OrderQuoteValue {
+                max_output,
+                ratio: U256::from(1),
+            }),
+            formatted: None,
+            success: error.is_none(),
+            error: error.map(str::to_string),
+        }
+    }
+
+    fn kinds(problems: &[OrderProblem]) -> Vec<(OrderProblemSeverity, OrderProblemKind)> {
+        problems
+            .iter()
+            .map(|v| (v.severity, v.kind.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_order_problems_healthy() {
+        let input = Address::with_last_byte(1);
+        let output = Address::with_last_byte(2);
+        let order = get_order_v3(input, output);
+        let metadata = vec![
+            QuoteToken {
+                address: input,
+                symbol: Some("A".to_string()),
+                decimals: Some(6),
+            },
+            QuoteToken {
+                address: output,
+                symbol: Some("B".to_string()),
+                decimals: Some(18),
+            },
+        ];
+        let vaults = vec![
+            get_vault_health(input, "0", false),
+            get_vault_health(output, "10", true),
+        ];
+        let problems = order_problems(
+            true,
+            &order,
+            &metadata,
+            &vaults,
+            &[(output, U256::from(1))],
+            &[get_quote(U256::from(10), None)],
+        );
+        assert!(problems.is_empty());
+    }
+
+    #[test]
+    fn test_order_problems_allowance() {
+        let input = Address::with_last_byte(1);
+        let output = Address::with_last_byte(2);
+        let order = get_order_v3(input, output);
+        let quotes = vec![get_quote(U256::from(10), None)];
+
+        // funded output vault, missing approval is only informative
+        let problems = order_problems(
+            true,
+            &order,
+            &[],
+            &[get_vault_health(output, "10", true)],
+            &[(output, U256::ZERO)],
+            &quotes,
+        );
+        assert_eq!(
+            kinds(&problems),
+            vec![(
+                OrderProblemSeverity::Info,
+                OrderProblemKind::NoAllowance(output.to_string())
+            )]
+        );
+
+        // empty output vault cannot be refilled without an approval
+        let problems = order_problems(
+            true,
+            &order,
+            &[],
+            &[get_vault_health(output, "0", true)],
+            &[(output, U256::ZERO)],
+            &quotes,
+        );
+        assert_eq!(
+            kinds(&problems),
+            vec![
+                (
+                    OrderProblemSeverity::Warning,
+                    OrderProblemKind::EmptyOutputVault(output.to_string())
+                ),
+                (
+                    OrderProblemSeverity::Warning,
+                    OrderProblemKind::NoAllowance(output.to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_order_problems_sorted() {
+        let input = Address::with_last_byte(1);
+        let output = Address::with_last_byte(2);
+        let order = get_order_v3(input, output);
+        let metadata = vec![QuoteToken {
+            address: input,
+            symbol: Some("A".to_string()),
+            decimals: Some(18),
+        }];
+        let problems = order_problems(
+            true,
+            &order,
+            &metadata,
+            &[get_vault_health(output, "10", true)],
+            &[],
+            &[get_quote(U256::ZERO, Some("reverted"))],
+        );
+        assert_eq!(
+            kinds(&problems),
+            vec![
+                (
+                    OrderProblemSeverity::Critical,
+                    OrderProblemKind::QuoteFailed("A/B".to_string())
+                ),
+                (
+                    OrderProblemSeverity::Warning,
+                    OrderProblemKind::DecimalsMismatch(input.to_string())
+                ),
+            ]
+        );
+        assert_eq!(
+            problems[1].message,
+            "A has 18 decimals but the order uses 6"
+        );
+    }
+
+    #[test]
+    fn test_order_problems_zero_max_output() {
+        let input = Address::with_last_byte(1);
+        let output = Address::with_last_byte(2);
+        let order = get_order_v3(input, output);
+        let quotes = vec![get_quote(U256::ZERO, None)];
+
+        let problems = order_problems(
+            true,
+            &order,
+            &[],
+            &[get_vault_health(output, "10", true)],
+            &[],
+            &quotes,
+        );
+        assert_eq!(
+            kinds(&problems),
+            vec![(
+                OrderProblemSeverity::Warning,
+                OrderProblemKind::ZeroMaxOutput("A/B".to_string())
+            )]
+        );
+
+        // an empty output vault already explains the zero max output
+        let problems = order_problems(
+            true,
+            &order,
+            &[],
+            &[get_vault_health(output, "0", true)],
+            &[],
+            &quotes,
+        );
+        assert_eq!(
+            kinds(&problems),
+            vec![(
+                OrderProblemSeverity::Warning,
+                OrderProblemKind::EmptyOutputVault(output.to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn test_order_problems_inactive() {
+        let input = Address::with_last_byte(1);
+        let output = Address::with_last_byte(2);
+        let order = get_order_v3(input, output);
+        let problems = order_problems(
+            false,
+            &order,
+            &[],
+            &[get_vault_health(output, "0", true)],
+            &[],
+            &[],
+        );
+        assert_eq!(
+            kinds(&problems),
+            vec![
+                (OrderProblemSeverity::Critical, OrderProblemKind::Inactive),
+                (
+                    OrderProblemSeverity::Info,
+                    OrderProblemKind::EmptyOutputVault(output.to_string())
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,692 @@
+use crate::erc20::ERC20;
+use alloy::primitives::{keccak256, utils::format_units, Address, Bytes, B256, U256};
+use alloy::sol_types::{SolCall, SolEventInterface, SolInterface, SolValue};
+use rain_interpreter_eval::{
+    error::ForkCallError,
+    fork::{Forker, NewForkedEvm},
+};
+use rain_orderbook_app_settings::rpc::{json_rpc, parse_quantity};
+use rain_orderbook_bindings::{
+    OrderBook::{OrderBookCalls, OrderBookEvents, IO},
+    IERC20::{IERC20Calls, IERC20Events},
+};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use thiserror::Error;
+use url::Url;
+
+#[derive(Error, Debug)]
+pub enum PreviewError {
+    #[error(transparent)]
+    ForkCallError(#[from] ForkCallError),
+    #[error(transparent)]
+    UrlParseError(#[from] url::ParseError),
+}
+
+/// Argument of a decoded call
+#[derive(Debug, Clone, PartialEq)]
+pub enum CallArg {
+    Value(String),
+    /// Token amount, formatted in the decimals of its token once known
+    Amount {
+        token: Address,
+        amount: U256,
+    },
+}
+
+impl CallArg {
+    pub fn format(&self, decimals: &HashMap<Address, u8>) -> String {
+        match self {
+            CallArg::Value(value) => value.clone(),
+            CallArg::Amount { token, amount } => {
+                format_amount(*amount, decimals.get(token).copied())
+            }
+        }
+    }
+}
+
+impl<T: ToString> From<T> for CallArg {
+    fn from(value: T) -> Self {
+        CallArg::Value(value.to_string())
+    }
+}
+
+/// A call decoded against the orderbook and erc20 abis
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedCall {
+    pub to: Address,
+    pub function: String,
+    pub args: Vec<(String, CallArg)>,
+    /// Calls of a multicall
+    pub calls: Vec<DecodedCall>,
+}
+
+/// An event emitted during the simulation
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedEvent {
+    pub address: Address,
+    pub name: String,
+    pub args: Vec<(String, String)>,
+}
+
+/// Net change of a balance, as an increase and a decrease so it needs no
+/// signed integers
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BalanceChange {
+    pub increase: U256,
+    pub decrease: U256,
+}
+
+impl BalanceChange {
+    fn format(&self, decimals: Option<u8>) -> String {
+        let (sign, amount) = if self.increase >= self.decrease {
+            ("+", self.increase - self.decrease)
+        } else {
+            ("-", self.decrease - self.increase)
+        };
+        format!("{}{}", sign, format_amount(amount, decimals))
+    }
+}
+
+/// Estimated fee of the previewed transactions at the current fees
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeeEstimate {
+    pub base_fee_per_gas: u64,
+    pub max_priority_fee_per_gas: u64,
+}
+
+impl FeeEstimate {
+    pub fn fee(&self, gas: u64) -> U256 {
+        U256::from(gas)
+            * (U256::from(self.base_fee_per_gas) + U256::from(self.max_priority_fee_per_gas))
+    }
+}
+
+/// What sending a set of transactions does, as simulated on a fork of the
+/// latest block
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionPreview {
+    pub from: Address,
+    pub calls: Vec<DecodedCall>,
+    pub events: Vec<DecodedEvent>,
+    /// Token balance changes of the sender, keyed by token
+    pub token_changes: BTreeMap<Address, BalanceChange>,
+    /// Vault balance changes of the sender, keyed by token and vault id
+    pub vault_changes: BTreeMap<(Address, U256), BalanceChange>,
+    pub gas_used: u64,
+    /// None if the fees could not be read from the rpc
+    pub fees: Option<FeeEstimate>,
+    pub decimals: HashMap<Address, u8>,
+}
+
+/// Simulates calls one after the other on a fork to preview them
+pub struct PreviewBuilder {
+    rpc_url: String,
+    forker: Forker,
+    preview: TransactionPreview,
+}
+
+impl PreviewBuilder {
+    pub async fn new(rpc_url: String, from: Address) -> Result<Self, PreviewError> {
+        let forker = Forker::new_with_fork(
+            NewForkedEvm {
+                fork_url: rpc_url.clone(),
+                fork_block_number: None,
+            },
+            None,
+            None,
+        )
+        .await?;
+        Ok(Self {
+            rpc_url,
+            forker,
+            preview: TransactionPreview {
+                from,
+                calls: vec![],
+                events: vec![],
+                token_changes: BTreeMap::new(),
+                vault_changes: BTreeMap::new(),
+                gas_used: 0,
+                fees: None,
+                decimals: HashMap::new(),
+            },
+        })
+    }
+
+    /// Sends the call on the fork, failing if it reverts, and records it
+    /// along with its events and gas
+    pub async fn call<T: SolCall>(&mut self, to: Address, call: T) -> Result<(), PreviewError> {
+        let data = Bytes::from(call.abi_encode());
+        let result = self
+            .forker
+            .alloy_call_committing(self.preview.from, to, call, U256::ZERO, true)
+            .await?;
+        self.preview.gas_used += result.raw.gas_used;
+
+        let decoded = decode_call(to, &data);
+        self.fetch_decimals(&decoded).await;
+        self.preview.calls.push(decoded);
+        for log in result.raw.logs.iter() {
+            if let Some(event) = self.record_event(log.address, log.data.topics(), &log.data.data) {
+                self.preview.events.push(event);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the current fees and the decimals of the tokens whose balances
+    /// change
+    pub async fn finish(mut self) -> Result<TransactionPreview, PreviewError> {
+        let tokens = self
+            .preview
+            .token_changes
+            .keys()
+            .chain(self.preview.vault_changes.keys().map(|(token, _)| token))
+            .copied()
+            .collect::<Vec<_>>();
+        for token in tokens {
+            self.fetch_token_decimals(token).await;
+        }
+        self.preview.fees = fetch_fees(&Url::parse(&self.rpc_url)?).await;
+        Ok(self.preview)
+    }
+
+    async fn fetch_token_decimals(&mut self, token: Address) {
+        if self.preview.decimals.contains_key(&token) {
+            return;
+        }
+        let Ok(url) = Url::parse(&self.rpc_url) else {
+            return;
+        };
+        if let Ok(decimals) = ERC20::new(url, token).decimals().await {
+            self.preview.decimals.insert(token, decimals);
+        }
+    }
+
+    async fn fetch_decimals(&mut self, call: &DecodedCall) {
+        let mut tokens = vec![];
+        collect_amount_tokens(call, &mut tokens);
+        for token in tokens {
+            self.fetch_token_decimals(token).await;
+        }
+    }
+
+    /// Decodes a log and applies the balance changes of the sender it
+    /// carries
+    fn record_event(
+        &mut self,
+        address: Address,
+        topics: &[B256],
+        data: &[u8],
+    ) -> Option<DecodedEvent> {
+        let from = self.preview.from;
+        if let Ok(event) = OrderBookEvents::decode_raw_log(topics, data, true) {
+            let (name, args) = match event {
+                OrderBookEvents::Deposit(e) => {
+                    if e.sender == from {
+                        self.preview
+                            .vault_changes
+                            .entry((e.token, e.vaultId))
+                            .or_default()
+                            .increase += e.amount;
+                    }
+                    (
+                        "Deposit",
+                        vec![
+                            ("token", e.token.to_string()),
+                            ("vault id", e.vaultId.to_string()),
+                            ("amount", e.amount.to_string()),
+                        ],
+                    )
+                }
+                OrderBookEvents::Withdraw(e) => {
+                    if e.sender == from {
+                        self.preview
+                            .vault_changes
+                            .entry((e.token, e.vaultId))
+                            .or_default()
+                            .decrease += e.amount;
+                    }
+                    (
+                        "Withdraw",
+                        vec![
+                            ("token", e.token.to_string()),
+                            ("vault id", e.vaultId.to_string()),
+                            ("amount", e.amount.to_string()),
+                        ],
+                    )
+                }
+                OrderBookEvents::AddOrderV2(e) => {
+                    ("AddOrderV2", vec![("order hash", e.orderHash.to_string())])
+                }
+                OrderBookEvents::RemoveOrderV2(e) => (
+                    "RemoveOrderV2",
+                    vec![("order hash", e.orderHash.to_string())],
+                ),
+                _ => ("OrderBook event", vec![]),
+            };
+            return Some(decoded_event(address, name, args));
+        }
+        if let Ok(event) = IERC20Events::decode_raw_log(topics, data, true) {
+            let (name, args) = match event {
+                IERC20Events::Transfer(e) => {
+                    if e.from == from {
+                        self.preview
+                            .token_changes
+                            .entry(address)
+                            .or_default()
+                            .decrease += e.value;
+                    }
+                    if e.to == from {
+                        self.preview
+                            .token_changes
+                            .entry(address)
+                            .or_default()
+                            .increase += e.value;
+                    }
+                    (
+                        "Transfer",
+                        vec![
+                            ("from", e.from.to_string()),
+                            ("to", e.to.to_string()),
+                            ("value", e.value.to_string()),
+                        ],
+                    )
+                }
+                IERC20Events::Approval(e) => (
+                    "Approval",
+                    vec![
+                        ("owner", e.owner.to_string()),
+                        ("spender", e.spender.to_string()),
+                        ("value", e.value.to_string()),
+                    ],
+                ),
+            };
+            return Some(decoded_event(address, name, args));
+        }
+        None
+    }
+}
+
+fn decoded_event(address: Address, name: &str, args: Vec<(&str, String)>) -> DecodedEvent {
+    DecodedEvent {
+        address,
+        name: name.to_string(),
+        args: args.into_iter().map(|(k, v)| (k.to_string(), v)).collect(),
+    }
+}
+
+fn collect_amount_tokens(call: &DecodedCall, tokens: &mut Vec<Address>) {
+    for (_, arg) in &call.args {
+        if let CallArg::Amount { token, .. } = arg {
+            tokens.push(*token);
+        }
+    }
+    for inner in &call.calls {
+        collect_amount_tokens(inner, tokens);
+    }
+}
+
+fn format_amount(amount: U256, decimals: Option<u8>) -> String {
+    match decimals.and_then(|decimals| format_units(amount, decimals).ok()) {
+        Some(formatted) => formatted,
+        None => amount.to_string(),
+    }
+}
+
+fn format_ios(kind: &str, ios: &[IO]) -> Vec<(String, CallArg)> {
+    ios.iter()
+        .enumerate()
+        .map(|(i, io)| {
+            (
+                format!("{} {}", kind, i),
+                format!(
+                    "{} ({} decimals) vault {}",
+                    io.token, io.decimals, io.vaultId
+                )
+                .into(),
+            )
+        })
+        .collect()
+}
+
+/// Decodes calldata against the orderbook and erc20 abis, leaving it as raw
+/// data if it matches neither
+pub fn decode_call(to: Address, data: &[u8]) -> DecodedCall {
+    let decoded =
+        |function: &str, args: Vec<(String, CallArg)>, calls: Vec<DecodedCall>| DecodedCall {
+            to,
+            function: function.to_string(),
+            args,
+            calls,
+        };
+    if let Ok(call) = OrderBookCalls::abi_decode(data, true) {
+        return match call {
+            OrderBookCalls::multicall(call) => decoded(
+                "multicall",
+                vec![],
+                call.data.iter().map(|data| decode_call(to, data)).collect(),
+            ),
+            OrderBookCalls::addOrder2(call) => {
+                let mut args = format_ios("input", &call.config.validInputs);
+                args.extend(format_ios("output", &call.config.validOutputs));
+                args.push(("nonce".to_string(), call.config.nonce.into()));
+                args.push((
+                    "meta".to_string(),
+                    format!("{} bytes", call.config.meta.len()).into(),
+                ));
+                decoded("addOrder2", args, vec![])
+            }
+            OrderBookCalls::removeOrder2(call) => decoded(
+                "removeOrder2",
+                vec![
+                    ("owner".to_string(), call.order.owner.into()),
+                    (
+                        "order hash".to_string(),
+                        keccak256(call.order.abi_encode()).into(),
+                    ),
+                ],
+                vec![],
+            ),
+            OrderBookCalls::deposit2(call) => decoded(
+                "deposit2",
+                vec![
+                    ("token".to_string(), call.token.into()),
+                    ("vault id".to_string(), call.vaultId.into()),
+                    (
+                        "amount".to_string(),
+                        CallArg::Amount {
+                            token: call.token,
+                            amount: call.amount,
+                        },
+                    ),
+                ],
+                vec![],
+            ),
+            OrderBookCalls::withdraw2(call) => decoded(
+                "withdraw2",
+                vec![
+                    ("token".to_string(), call.token.into()),
+                    ("vault id".to_string(), call.vaultId.into()),
+                    (
+                        "target amount".to_string(),
+                        CallArg::Amount {
+                            token: call.token,
+                            amount: call.targetAmount,
+                        },
+                    ),
+                ],
+                vec![],
+            ),
+            _ => decoded(
+                "orderbook call",
+                vec![("data".to_string(), Bytes::copy_from_slice(data).into())],
+                vec![],
+            ),
+        };
+    }
+    if let Ok(IERC20Calls::approve(call)) = IERC20Calls::abi_decode(data, true) {
+        return decoded(
+            "approve",
+            vec![
+                ("spender".to_string(), call.spender.into()),
+                (
+                    "amount".to_string(),
+                    CallArg::Amount {
+                        token: to,
+                        amount: call.amount,
+                    },
+                ),
+            ],
+            vec![],
+        );
+    }
+    decoded(
+        "unknown",
+        vec![("data".to_string(), Bytes::copy_from_slice(data).into())],
+        vec![],
+    )
+}
+
+/// Reads the base fee of the latest block and the suggested priority fee,
+/// falling back to the gas price on chains without a base fee
+pub(crate) async fn fetch_fees(url: &Url) -> Option<FeeEstimate> {
+    let block = json_rpc(
+        url,
+        "eth_getBlockByNumber",
+        serde_json::json!(["latest", false]),
+    )
+    .await
+    .ok()?;
+    let priority_fee = json_rpc(url, "eth_maxPriorityFeePerGas", serde_json::json!([]))
+        .await
+        .ok()
+        .and_then(|fee| parse_quantity(&fee));
+    match (parse_quantity(&block["baseFeePerGas"]), priority_fee) {
+        (Some(base_fee_per_gas), priority_fee) => Some(FeeEstimate {
+            base_fee_per_gas,
+            max_priority_fee_per_gas: priority_fee.unwrap_or_default(),
+        }),
+        (None, _) => {
+            let gas_price = json_rpc(url, "eth_gasPrice", serde_json::json!([]))
+                .await
+                .ok()?;
+            Some(FeeEstimate {
+                base_fee_per_gas: parse_quantity(&gas_price)?,
+                max_priority_fee_per_gas: 0,
+            })
+        }
+    }
+}
+
+fn write_call(
+    f: &mut fmt::Formatter<'_>,
+    call: &DecodedCall,
+    decimals: &HashMap<Address, u8>,
+    depth: usize,
+) -> fmt::Result {
+    let indent = "  ".repeat(depth);
+    writeln!(f, "{}{} -> {}", indent, call.function, call.to)?;
+    for (name, arg) in &call.args {
+        writeln!(f, "{}  {}: {}", indent, name, arg.format(decimals))?;
+    }
+    for inner in &call.calls {
+        write_call(f, inner, decimals, depth + 1)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for TransactionPreview {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "From: {}", self.from)?;
+        writeln!(f, "Calls:")?;
+        for call in &self.calls {
+            write_call(f, call, &self.decimals, 1)?;
+        }
+        if !self.token_changes.is_empty() {
+            writeln!(f, "Token balance changes:")?;
+            for (token, change) in &self.token_changes {
+                let decimals = self.decimals.get(token).copied();
+                writeln!(f, "  {}: {}", token, change.format(decimals))?;
+            }
+        }
+        if !self.vault_changes.is_empty() {
+            writeln!(f, "Vault balance changes:")?;
+            for ((token, vault_id), change) in &self.vault_changes {
+                let decimals = self.decimals.get(token).copied();
+                writeln!(
+                    f,
+                    "  {} vault {}: {}",
+                    token,
+                    vault_id,
+                    change.format(decimals)
+                )?;
+            }
+        }
+        writeln!(f, "Events:")?;
+        for event in &self.events {
+            let args = event
+                .args
+                .iter()
+                .map(|(name, value)| format!("{}: {}", name, value))
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(f, "  {} {}({})", event.address, event.name, args)?;
+        }
+        writeln!(f, "Gas used: {}", self.gas_used)?;
+        match &self.fees {
+            Some(fees) => write!(
+                f,
+                "Estimated fee: {} (base fee {} gwei, priority fee {} gwei)",
+                format_amount(fees.fee(self.gas_used), Some(18)),
+                format_amount(U256::from(fees.base_fee_per_gas), Some(9)),
+                format_amount(U256::from(fees.max_priority_fee_per_gas), Some(9)),
+            ),
+            None => write!(f, "Estimated fee: unknown"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::{Method::POST, MockServer};
+    use rain_orderbook_bindings::{
+        OrderBook::{
+            addOrder2Call, deposit2Call, multicallCall, withdraw2Call, EvaluableV3, OrderConfigV3,
+        },
+        IERC20::approveCall,
+    };
+
+    #[test]
+    fn test_decode_call() {
+        let token = Address::repeat_byte(0x11);
+        let orderbook = Address::repeat_byte(0x22);
+        let deposit = deposit2Call {
+            token,
+            vaultId: U256::from(7),
+            amount: U256::from(1500000),
+            tasks: vec![],
+        };
+        let add_order = addOrder2Call {
+            config: OrderConfigV3 {
+                evaluable: EvaluableV3 {
+                    interpreter: Address::ZERO,
+                    store: Address::ZERO,
+                    bytecode: Bytes::new(),
+                },
+                validInputs: vec![IO {
+                    token,
+                    decimals: 6,
+                    vaultId: U256::from(7),
+                }],
+                validOutputs: vec![],
+                nonce: B256::ZERO,
+                secret: B256::ZERO,
+                meta: Bytes::from(vec![1, 2, 3]),
+            },
+            tasks: vec![],
+        };
+        let multicall = multicallCall {
+            data: vec![
+                Bytes::from(add_order.abi_encode()),
+                Bytes::from(deposit.abi_encode()),
+            ],
+        };
+
+        let decoded = decode_call(orderbook, &multicall.abi_encode());
+        assert_eq!(decoded.function, "multicall");
+        assert_eq!(decoded.calls.len(), 2);
+        assert_eq!(decoded.calls[0].function, "addOrder2");
+        assert_eq!(
+            decoded.calls[0].args[0],
+            (
+                "input 0".to_string(),
+                CallArg::Value(format!("{} (6 decimals) vault 7", token))
+            )
+        );
+        assert_eq!(
+            decoded.calls[0].args[2].1,
+            CallArg::Value("3 bytes".to_string())
+        );
+        assert_eq!(decoded.calls[1].function, "deposit2");
+        assert_eq!(
+            decoded.calls[1].args[2]
+                .1
+                .format(&HashMap::from([(token, 6)])),
+            "1.500000"
+        );
+
+        let mut tokens = vec![];
+        collect_amount_tokens(&decoded, &mut tokens);
+        assert_eq!(tokens, vec![token]);
+
+        let withdraw = withdraw2Call {
+            token,
+            vaultId: U256::from(7),
+            targetAmount: U256::from(5),
+            tasks: vec![],
+        };
+        let decoded = decode_call(orderbook, &withdraw.abi_encode());
+        assert_eq!(decoded.function, "withdraw2");
+        assert_eq!(decoded.args[2].1.format(&HashMap::new()), "5");
+
+        let approve = approveCall {
+            spender: orderbook,
+            amount: U256::from(10).pow(U256::from(18)),
+        };
+        let decoded = decode_call(token, &approve.abi_encode());
+        assert_eq!(decoded.function, "approve");
+        assert_eq!(
+            decoded.args[1].1.format(&HashMap::from([(token, 18)])),
+            "1.000000000000000000"
+        );
+
+        let decoded = decode_call(token, &[1, 2, 3, 4]);
+        assert_eq!(decoded.function, "unknown");
+        assert_eq!(decoded.args[0].1, CallArg::Value("0x01020304".to_string()));
+    }
+
+    #[test]
+    fn test_balance_change_format() {
+        let change = BalanceChange {
+            increase: U256::from(1),
+            decrease: U256::from(3000000),
+        };
+        assert_eq!(change.format(Some(6)), "-2.999999");
+        assert_eq!(change.format(None), "-2999999");
+        assert_eq!(BalanceChange::default().format(None), "+0");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_fees() {
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST).body_contains("eth_getBlockByNumber");
+            then.json_body(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": { "baseFeePerGas": "0x3b9aca00" }
+            }));
+        });
+        server.mock(|when, then| {
+            when.method(POST).body_contains("eth_maxPriorityFeePerGas");
+            then.json_body(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": "0x77359400"
+            }));
+        });
+        let fees = fetch_fees(&Url::parse(&server.url("/")).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(
+            fees,
+            FeeEstimate {
+                base_fee_per_gas: 1_000_000_000,
+                max_priority_fee_per_gas: 2_000_000_000,
+            }
+        );
+        assert_eq!(fees.fee(21000), U256::from(63_000_000_000_000u64));
+    }
+}
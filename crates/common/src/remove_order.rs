@@ -5,7 +5,7 @@ use alloy::primitives::hex::FromHexError;
 use alloy::sol_types::SolCall;
 use alloy_ethers_typecast::transaction::WritableClientError;
 #[cfg(not(target_family = "wasm"))]
-use alloy_ethers_typecast::transaction::{WriteTransaction, WriteTransactionStatus};
+use alloy_ethers_typecast::transaction::WriteTransactionStatus;
 use rain_orderbook_bindings::IOrderBookV4::removeOrder2Call;
 use rain_orderbook_subgraph_client::types::{common::Order, order_detail_traits::OrderDetailError};
 use serde::{Deserialize, Serialize};
@@ -52,20 +52,15 @@ impl RemoveOrderArgs {
         transaction_args: TransactionArgs,
         transaction_status_changed: S,
     ) -> Result<(), RemoveOrderArgsError> {
-        let ledger_client = transaction_args.clone().try_into_ledger_client().await?;
-
         let remove_order_call: removeOrder2Call = self.try_into()?;
-        let params = transaction_args
-            .try_into_write_contract_parameters(
+        transaction_args
+            .send(
                 remove_order_call,
                 transaction_args.orderbook_address,
+                transaction_status_changed,
             )
             .await?;
 
-        WriteTransaction::new(ledger_client.client, params, 4, transaction_status_changed)
-            .execute()
-            .await?;
-
         Ok(())
     }
 
@@ -3,10 +3,13 @@ use rain_interpreter_eval::{
     fork::{Forker, NewForkedEvm},
     trace::RainEvalResult,
 };
-use url::Url;
+use rain_orderbook_app_settings::rpc::{RpcProvider, RpcProviderError};
+use std::sync::Arc;
 
 pub struct NewTradeReplayer {
-    pub fork_url: Url,
+    /// Rpcs to fork from, the fork is made on the first that serves it and
+    /// keeps reading its state from that rpc
+    pub rpc: Arc<RpcProvider>,
 }
 pub struct TradeReplayer {
     forker: Forker,
@@ -16,19 +19,27 @@ pub struct TradeReplayer {
 pub enum TradeReplayerError {
     #[error("Forker error: {0}")]
     ForkerError(#[from] rain_interpreter_eval::error::ForkCallError),
+    #[error(transparent)]
+    RpcProviderError(#[from] RpcProviderError),
 }
 
 impl TradeReplayer {
     pub async fn new(args: NewTradeReplayer) -> Result<Self, TradeReplayerError> {
-        let forker = Forker::new_with_fork(
-            NewForkedEvm {
-                fork_url: args.fork_url.to_string(),
-                fork_block_number: None,
-            },
-            None,
-            None,
-        )
-        .await?;
+        let forker = args
+            .rpc
+            .with_failover(|url| async move {
+                Forker::new_with_fork(
+                    NewForkedEvm {
+                        fork_url: url.to_string(),
+                        fork_block_number: None,
+                    },
+                    None,
+                    None,
+                )
+                .await
+                .map_err(TradeReplayerError::from)
+            })
+            .await?;
 
         Ok(Self { forker })
     }
@@ -201,7 +212,7 @@ amount price: 2 1;
             .unwrap();
 
         let mut replayer = TradeReplayer::new(NewTradeReplayer {
-            fork_url: local_evm.url().as_str().try_into().unwrap(),
+            rpc: RpcProvider::for_url(local_evm.url().parse().unwrap()),
         })
         .await
         .unwrap();
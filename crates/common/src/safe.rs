@@ -0,0 +1,361 @@
+use alloy::primitives::{address, Address, Bytes, B256, U256};
+use alloy::sol;
+use alloy::sol_types::{eip712_domain, SolCall, SolStruct};
+use alloy_ethers_typecast::transaction::{
+    ReadContractParametersBuilder, ReadContractParametersBuilderError, ReadableClient,
+    ReadableClientError,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+sol! {
+    /// Transaction the owners of a Safe sign, as hashed by the Safe's EIP-712
+    /// typed data
+    #[derive(Debug, PartialEq)]
+    struct SafeTx {
+        address to;
+        uint256 value;
+        bytes data;
+        uint8 operation;
+        uint256 safeTxGas;
+        uint256 baseGas;
+        uint256 gasPrice;
+        address gasToken;
+        address refundReceiver;
+        uint256 nonce;
+    }
+
+    interface ISafe {
+        function nonce() external view returns (uint256);
+    }
+
+    interface IMultiSend {
+        function multiSend(bytes memory transactions) external payable;
+    }
+}
+
+/// Canonical deployment of the Safe v1.3.0 MultiSendCallOnly contract, which
+/// the Safe delegatecalls to run a batch of transactions
+pub const MULTI_SEND_CALL_ONLY: Address = address!("40A2aCCbd92BCA938b02010E17A5b8929b49130D");
+
+/// Version of the Safe Transaction Builder the generated batches target
+pub const TX_BUILDER_VERSION: &str = "1.16.5";
+
+const OPERATION_CALL: u8 = 0;
+const OPERATION_DELEGATE_CALL: u8 = 1;
+
+#[derive(Error, Debug)]
+pub enum SafeError {
+    #[error("Safe batch has no transactions")]
+    EmptyBatch,
+    #[error(transparent)]
+    ReadableClientError(#[from] ReadableClientError),
+    #[error(transparent)]
+    ReadContractParametersBuilderError(#[from] ReadContractParametersBuilderError),
+    #[error(transparent)]
+    SerdeJsonError(#[from] serde_json::Error),
+}
+
+/// A single call made by a Safe
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SafeTransaction {
+    pub to: Address,
+    #[serde(with = "u256_string")]
+    pub value: U256,
+    pub data: Bytes,
+}
+
+impl SafeTransaction {
+    pub fn new<T: SolCall>(to: Address, call: &T) -> Self {
+        Self {
+            to,
+            value: U256::ZERO,
+            data: Bytes::from(call.abi_encode()),
+        }
+    }
+}
+
+/// Transactions to propose to a Safe as a single batch
+#[derive(Debug, Clone, PartialEq)]
+pub struct SafeBatch {
+    pub chain_id: u64,
+    pub safe: Address,
+    /// Nonce of the Safe the batch is proposed at
+    pub nonce: U256,
+    pub transactions: Vec<SafeTransaction>,
+}
+
+impl SafeBatch {
+    pub fn new(chain_id: u64, safe: Address, nonce: U256) -> Self {
+        Self {
+            chain_id,
+            safe,
+            nonce,
+            transactions: vec![],
+        }
+    }
+
+    /// Appends a call to the batch
+    pub fn push<T: SolCall>(&mut self, to: Address, call: &T) {
+        self.transactions.push(SafeTransaction::new(to, call));
+    }
+
+    /// Reads the current nonce of a Safe
+    pub async fn read_nonce(rpc_url: String, safe: Address) -> Result<U256, SafeError> {
+        let readable_client = ReadableClient::new_from_url(rpc_url)?;
+        let parameters = ReadContractParametersBuilder::<ISafe::nonceCall>::default()
+            .address(safe)
+            .call(ISafe::nonceCall {})
+            .build()?;
+        Ok(readable_client.read(parameters).await?._0)
+    }
+
+    /// The transactions packed as MultiSend expects them, each one as its
+    /// operation, target, value, data length and data
+    pub fn multi_send_data(&self) -> Bytes {
+        let mut packed = vec![];
+        for transaction in &self.transactions {
+            packed.push(OPERATION_CALL);
+            packed.extend_from_slice(transaction.to.as_slice());
+            packed.extend_from_slice(&transaction.value.to_be_bytes::<32>());
+            packed.extend_from_slice(&U256::from(transaction.data.len()).to_be_bytes::<32>());
+            packed.extend_from_slice(&transaction.data);
+        }
+        Bytes::from(packed)
+    }
+
+    /// The transaction the Safe owners sign, a plain call for a single
+    /// transaction or a delegatecall to MultiSendCallOnly for several
+    pub fn safe_tx(&self) -> Result<SafeTx, SafeError> {
+        let (to, value, data, operation) = match self.transactions.as_slice() {
+            [] => return Err(SafeError::EmptyBatch),
+            [transaction] => (
+                transaction.to,
+                transaction.value,
+                transaction.data.clone(),
+                OPERATION_CALL,
+            ),
+            _ => (
+                MULTI_SEND_CALL_ONLY,
+                U256::ZERO,
+                Bytes::from(
+                    IMultiSend::multiSendCall {
+                        transactions: self.multi_send_data(),
+                    }
+                    .abi_encode(),
+                ),
+                OPERATION_DELEGATE_CALL,
+            ),
+        };
+        Ok(SafeTx {
+            to,
+            value,
+            data,
+            operation,
+            safeTxGas: U256::ZERO,
+            baseGas: U256::ZERO,
+            gasPrice: U256::ZERO,
+            gasToken: Address::ZERO,
+            refundReceiver: Address::ZERO,
+            nonce: self.nonce,
+        })
+    }
+
+    /// EIP-712 hash of the Safe transaction, the one owners sign and that the
+    /// Safe UI shows for the proposal
+    pub fn safe_tx_hash(&self) -> Result<B256, SafeError> {
+        let domain = eip712_domain! {
+            chain_id: self.chain_id,
+            verifying_contract: self.safe,
+        };
+        Ok(self.safe_tx()?.eip712_signing_hash(&domain))
+    }
+
+    /// The batch as a Safe Transaction Builder json file, ready to be
+    /// uploaded in the Safe UI
+    pub fn to_transaction_builder_json(
+        &self,
+        name: &str,
+        created_at: i64,
+    ) -> Result<String, SafeError> {
+        if self.transactions.is_empty() {
+            return Err(SafeError::EmptyBatch);
+        }
+        let batch = TransactionBuilderBatch {
+            version: "1.0".to_string(),
+            chain_id: self.chain_id.to_string(),
+            created_at,
+            meta: TransactionBuilderMeta {
+                name: name.to_string(),
+                description: format!("Safe nonce {}", self.nonce),
+                tx_builder_version: TX_BUILDER_VERSION.to_string(),
+                created_from_safe_address: self.safe,
+                created_from_owner_address: String::new(),
+            },
+            transactions: self.transactions.clone(),
+        };
+        Ok(serde_json::to_string_pretty(&batch)?)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TransactionBuilderBatch {
+    version: String,
+    chain_id: String,
+    created_at: i64,
+    meta: TransactionBuilderMeta,
+    transactions: Vec<SafeTransaction>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TransactionBuilderMeta {
+    name: String,
+    description: String,
+    tx_builder_version: String,
+    created_from_safe_address: Address,
+    created_from_owner_address: String,
+}
+
+/// The Transaction Builder expects values as decimal strings
+mod u256_string {
+    use alloy::primitives::U256;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &U256, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<U256, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{hex::encode_prefixed, keccak256};
+    use rain_orderbook_bindings::{IOrderBookV4::deposit2Call, IERC20::approveCall};
+
+    fn batch() -> SafeBatch {
+        let token = Address::repeat_byte(0x11);
+        let orderbook = Address::repeat_byte(0x22);
+        let mut batch = SafeBatch::new(137, Address::repeat_byte(0x33), U256::from(7));
+        batch.push(
+            token,
+            &approveCall {
+                spender: orderbook,
+                amount: U256::from(100),
+            },
+        );
+        batch.push(
+            orderbook,
+            &deposit2Call {
+                token,
+                vaultId: U256::from(1),
+                amount: U256::from(100),
+                tasks: vec![],
+            },
+        );
+        batch
+    }
+
+    #[test]
+    fn test_safe_tx_typehash() {
+        // SAFE_TX_TYPEHASH of the Safe contracts
+        assert_eq!(
+            SafeTx::eip712_type_hash(&batch().safe_tx().unwrap()),
+            keccak256(
+                "SafeTx(address to,uint256 value,bytes data,uint8 operation,uint256 safeTxGas,\
+                uint256 baseGas,uint256 gasPrice,address gasToken,address refundReceiver,\
+                uint256 nonce)"
+            )
+        );
+    }
+
+    #[test]
+    fn test_single_transaction_is_a_call() {
+        let mut batch = batch();
+        batch.transactions.truncate(1);
+        let safe_tx = batch.safe_tx().unwrap();
+        assert_eq!(safe_tx.to, Address::repeat_byte(0x11));
+        assert_eq!(safe_tx.operation, OPERATION_CALL);
+        assert_eq!(safe_tx.data, batch.transactions[0].data);
+        assert_eq!(safe_tx.nonce, U256::from(7));
+    }
+
+    #[test]
+    fn test_several_transactions_are_a_multi_send() {
+        let batch = batch();
+        let safe_tx = batch.safe_tx().unwrap();
+        assert_eq!(safe_tx.to, MULTI_SEND_CALL_ONLY);
+        assert_eq!(safe_tx.operation, OPERATION_DELEGATE_CALL);
+
+        let multi_send = IMultiSend::multiSendCall::abi_decode(&safe_tx.data, true).unwrap();
+        let packed = multi_send.transactions;
+        let first_len = 1 + 20 + 32 + 32 + batch.transactions[0].data.len();
+        assert_eq!(packed[0], OPERATION_CALL);
+        assert_eq!(&packed[1..21], Address::repeat_byte(0x11).as_slice());
+        assert_eq!(&packed[85..first_len], &batch.transactions[0].data[..]);
+        assert_eq!(
+            &packed[first_len + 1..first_len + 21],
+            Address::repeat_byte(0x22).as_slice()
+        );
+        assert_eq!(
+            packed.len(),
+            first_len + 1 + 20 + 32 + 32 + batch.transactions[1].data.len()
+        );
+    }
+
+    #[test]
+    fn test_safe_tx_hash() {
+        let batch = batch();
+        let hash = batch.safe_tx_hash().unwrap();
+
+        let mut other_nonce = batch.clone();
+        other_nonce.nonce = U256::from(8);
+        assert_ne!(hash, other_nonce.safe_tx_hash().unwrap());
+
+        let mut other_chain = batch.clone();
+        other_chain.chain_id = 1;
+        assert_ne!(hash, other_chain.safe_tx_hash().unwrap());
+
+        assert!(matches!(
+            SafeBatch::new(137, Address::ZERO, U256::ZERO).safe_tx_hash(),
+            Err(SafeError::EmptyBatch)
+        ));
+    }
+
+    #[test]
+    fn test_to_transaction_builder_json() {
+        let batch = batch();
+        let json = batch
+            .to_transaction_builder_json("Deposit", 1700000000000)
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["version"], "1.0");
+        assert_eq!(value["chainId"], "137");
+        assert_eq!(value["createdAt"], 1700000000000i64);
+        assert_eq!(value["meta"]["name"], "Deposit");
+        assert_eq!(
+            value["meta"]["createdFromSafeAddress"]
+                .as_str()
+                .unwrap()
+                .parse::<Address>()
+                .unwrap(),
+            Address::repeat_byte(0x33)
+        );
+        assert_eq!(value["transactions"].as_array().unwrap().len(), 2);
+        assert_eq!(value["transactions"][0]["value"], "0");
+        assert_eq!(
+            value["transactions"][0]["data"],
+            encode_prefixed(&batch.transactions[0].data)
+        );
+
+        let transactions: Vec<SafeTransaction> =
+            serde_json::from_value(value["transactions"].clone()).unwrap();
+        assert_eq!(transactions, batch.transactions);
+    }
+}
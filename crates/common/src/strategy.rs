@@ -0,0 +1,714 @@
+use crate::dotrain_order::{DotrainOrder, DotrainOrderError};
+use rain_orderbook_app_settings::gui::GuiPresetSource;
+use rain_orderbook_app_settings::interpolation::InterpolationError;
+#[cfg(target_family = "wasm")]
+use rain_orderbook_bindings::{impl_all_wasm_traits, wasm_traits::prelude::*};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+#[cfg(not(target_family = "wasm"))]
+use std::path::Path;
+use thiserror::Error;
+
+/// File describing a strategy package in its directory
+pub const MANIFEST_FILE: &str = "manifest.yaml";
+
+/// Extension of the single file archives of strategy packages
+pub const ARCHIVE_EXTENSION: &str = "rainstrategy";
+
+/// Start of the references to the parameters of a strategy, the other
+/// `${...}` references are left for the interpolation of the config
+const PARAMETER_REFERENCE: &str = "${param.";
+
+#[derive(Error, Debug)]
+pub enum StrategyError {
+    #[error("Invalid version {0}, expected MAJOR.MINOR.PATCH")]
+    InvalidVersion(String),
+    #[error("Parameter {0} is declared more than once")]
+    DuplicateParameter(String),
+    #[error("Parameter {0} is used but not declared in the manifest")]
+    UndeclaredParameter(String),
+    #[error("Unknown parameter {0}")]
+    UnknownParameter(String),
+    #[error("Parameter {0} has no value and no default")]
+    MissingParameter(String),
+    #[error("Strategy {0} is in the registry more than once at version {1}")]
+    DuplicateStrategy(String, String),
+    #[error("Strategy {0} not found")]
+    StrategyNotFound(String),
+    #[error("Test {0} failed: {1}")]
+    TestFailed(String, String),
+    #[error(transparent)]
+    InterpolationError(#[from] InterpolationError),
+    #[error(transparent)]
+    DotrainOrderError(#[from] DotrainOrderError),
+    #[error(transparent)]
+    SerdeYamlError(#[from] serde_yaml::Error),
+    #[error(transparent)]
+    SerdeJsonError(#[from] serde_json::Error),
+    #[cfg(not(target_family = "wasm"))]
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+/// Manifest of a strategy package, the `manifest.yaml` of its directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(target_family = "wasm", derive(Tsify))]
+#[serde(rename_all = "kebab-case")]
+pub struct StrategyManifest {
+    pub name: String,
+    /// MAJOR.MINOR.PATCH version of the package
+    pub version: String,
+    pub description: String,
+    /// Path of the .rain file of the strategy, relative to the package
+    pub dotrain: String,
+    /// Path of the settings merged with the frontmatter, relative to the
+    /// package
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<String>,
+    #[serde(default)]
+    pub parameters: Vec<StrategyParameter>,
+    #[serde(default)]
+    pub tests: Vec<StrategyTest>,
+}
+#[cfg(target_family = "wasm")]
+impl_all_wasm_traits!(StrategyManifest);
+
+/// A value of the strategy, referenced as `${param.name}` anywhere in its
+/// dotrain and settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(target_family = "wasm", derive(Tsify))]
+#[serde(rename_all = "kebab-case")]
+pub struct StrategyParameter {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Value used when none is given, the parameter is required without one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presets: Option<Vec<GuiPresetSource>>,
+}
+
+/// Parameter values the strategy must instantiate and compose with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(target_family = "wasm", derive(Tsify))]
+#[serde(rename_all = "kebab-case")]
+pub struct StrategyTest {
+    pub name: String,
+    #[serde(default)]
+    #[cfg_attr(target_family = "wasm", tsify(type = "Record<string, string>"))]
+    pub parameters: HashMap<String, String>,
+    /// Deployments to compose, all of them if empty
+    #[serde(default)]
+    pub deployments: Vec<String>,
+}
+
+/// The dotrain and settings of a strategy with its parameters substituted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RenderedStrategy {
+    pub dotrain: String,
+    pub settings: Option<String>,
+}
+
+/// A reusable strategy, loaded from a package directory or archive
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrategyPackage {
+    pub manifest: StrategyManifest,
+    pub dotrain: String,
+    pub settings: Option<String>,
+}
+
+/// Replaces the `${param.name}` references of a text with the substitute
+/// of their name, everything else including `$${` escapes is kept as is
+fn substitute_parameters(
+    text: &str,
+    mut substitute: impl FnMut(&str) -> String,
+) -> Result<String, StrategyError> {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('$') {
+        result.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        if let Some(escaped) = tail.strip_prefix("$${") {
+            result.push_str("$${");
+            rest = escaped;
+        } else if let Some(reference) = tail.strip_prefix(PARAMETER_REFERENCE) {
+            let end = reference
+                .find('}')
+                .ok_or(InterpolationError::Unterminated(text.to_string()))?;
+            let name = &reference[..end];
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                return Err(InterpolationError::InvalidName(format!("param.{}", name)).into());
+            }
+            result.push_str(&substitute(name));
+            rest = &reference[end + 1..];
+        } else {
+            result.push('$');
+            rest = &tail[1..];
+        }
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// Words yaml reads as booleans or null rather than strings, in either of
+/// its versions
+const YAML_RESERVED_WORDS: [&str; 9] =
+    ["true", "false", "yes", "no", "y", "n", "on", "off", "null"];
+
+/// A value as a yaml scalar, double quoted unless yaml reads it plain as
+/// the very same string or as a decimal integer. Anything starting with a
+/// digit other than an integer, like `1e3` or `0x01`, is quoted as yaml
+/// may read it as another number
+fn yaml_scalar(value: &str) -> String {
+    let integer = value == "0"
+        || (value.starts_with(|c: char| ('1'..='9').contains(&c))
+            && value.chars().all(|c| c.is_ascii_digit()));
+    let plain_string = value.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '/')
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "._-+/:@".contains(c))
+        && !value.ends_with(':')
+        && !YAML_RESERVED_WORDS.contains(&value.to_lowercase().as_str());
+    if integer || plain_string {
+        value.to_string()
+    } else {
+        // a json string is a valid yaml double quoted scalar
+        serde_json::Value::String(value.to_string()).to_string()
+    }
+}
+
+/// Splits a dotrain into its frontmatter and the rest from its `---` line
+fn split_frontmatter(dotrain: &str) -> (&str, &str) {
+    let mut offset = 0;
+    for line in dotrain.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return dotrain.split_at(offset);
+        }
+        offset += line.len();
+    }
+    ("", dotrain)
+}
+
+impl StrategyPackage {
+    /// Reads a package directory, its manifest and the files it points to
+    #[cfg(not(target_family = "wasm"))]
+    pub fn load_dir(path: impl AsRef<Path>) -> Result<Self, StrategyError> {
+        let path = path.as_ref();
+        let manifest: StrategyManifest =
+            serde_yaml::from_str(&std::fs::read_to_string(path.join(MANIFEST_FILE))?)?;
+        let dotrain = std::fs::read_to_string(path.join(&manifest.dotrain))?;
+        let settings = manifest
+            .settings
+            .as_ref()
+            .map(|settings| std::fs::read_to_string(path.join(settings)))
+            .transpose()?;
+        Ok(Self {
+            manifest,
+            dotrain,
+            settings,
+        })
+    }
+
+    /// Reads a package from a directory or from an archive file
+    #[cfg(not(target_family = "wasm"))]
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, StrategyError> {
+        let path = path.as_ref();
+        if path.is_dir() {
+            Self::load_dir(path)
+        } else {
+            Self::from_archive(&std::fs::read_to_string(path)?)
+        }
+    }
+
+    pub fn from_archive(archive: &str) -> Result<Self, StrategyError> {
+        Ok(serde_json::from_str(archive)?)
+    }
+
+    /// Packs the manifest and files of the package in a single json document
+    pub fn to_archive(&self) -> Result<String, StrategyError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Names of the parameters referenced by the dotrain and settings
+    pub fn references(&self) -> Result<BTreeSet<String>, StrategyError> {
+        let mut references = BTreeSet::new();
+        for text in std::iter::once(&self.dotrain).chain(self.settings.as_ref()) {
+            substitute_parameters(text, |name| {
+                references.insert(name.to_string());
+                String::new()
+            })?;
+        }
+        Ok(references)
+    }
+
+    /// Checks the manifest against the files of the package, see
+    /// [StrategyPackage::run_tests] for checking it composes
+    pub fn validate(&self) -> Result<(), StrategyError> {
+        parse_version(&self.manifest.version)?;
+
+        let mut declared = BTreeSet::new();
+        for parameter in &self.manifest.parameters {
+            if !declared.insert(parameter.name.clone()) {
+                return Err(StrategyError::DuplicateParameter(parameter.name.clone()));
+            }
+        }
+        if let Some(name) = self.references()?.difference(&declared).next() {
+            return Err(StrategyError::UndeclaredParameter(name.clone()));
+        }
+        Ok(())
+    }
+
+    /// The given values with the defaults of the parameters they leave out
+    pub fn parameter_values(
+        &self,
+        values: &HashMap<String, String>,
+    ) -> Result<HashMap<String, String>, StrategyError> {
+        if let Some(name) = values.keys().find(|name| {
+            !self
+                .manifest
+                .parameters
+                .iter()
+                .any(|parameter| parameter.name == **name)
+        }) {
+            return Err(StrategyError::UnknownParameter(name.clone()));
+        }
+        self.manifest
+            .parameters
+            .iter()
+            .map(|parameter| {
+                let value = values
+                    .get(&parameter.name)
+                    .or(parameter.default.as_ref())
+                    .ok_or(StrategyError::MissingParameter(parameter.name.clone()))?;
+                Ok((parameter.name.clone(), value.clone()))
+            })
+            .collect()
+    }
+
+    /// Substitutes the parameter values in the dotrain and settings, as
+    /// yaml scalars in the frontmatter and settings and as they are in the
+    /// rainlang. Environment references are left for loading the order
+    pub fn render(
+        &self,
+        values: &HashMap<String, String>,
+    ) -> Result<RenderedStrategy, StrategyError> {
+        let values = self.parameter_values(values)?;
+        let value = |name: &str| values.get(name).cloned().unwrap_or_default();
+        let yaml_value = |name: &str| yaml_scalar(&value(name));
+
+        let (frontmatter, body) = split_frontmatter(&self.dotrain);
+        let dotrain =
+            substitute_parameters(frontmatter, yaml_value)? + &substitute_parameters(body, value)?;
+        let settings = self
+            .settings
+            .as_ref()
+            .map(|settings| substitute_parameters(settings, yaml_value))
+            .transpose()?;
+        Ok(RenderedStrategy { dotrain, settings })
+    }
+
+    /// Renders the strategy into an order for the given parameter values
+    pub async fn instantiate(
+        &self,
+        values: &HashMap<String, String>,
+    ) -> Result<DotrainOrder, StrategyError> {
+        let rendered = self.render(values)?;
+        Ok(DotrainOrder::new(rendered.dotrain, rendered.settings).await?)
+    }
+
+    /// Instantiates the strategy with the values of the test and composes
+    /// its deployments
+    pub async fn run_test(&self, test: &StrategyTest) -> Result<(), StrategyError> {
+        let failed = |e: StrategyError| StrategyError::TestFailed(test.name.clone(), e.to_string());
+        let dotrain_order = self.instantiate(&test.parameters).await.map_err(failed)?;
+        let deployments = if test.deployments.is_empty() {
+            let mut deployments: Vec<String> =
+                dotrain_order.config().deployments.keys().cloned().collect();
+            deployments.sort();
+            deployments
+        } else {
+            test.deployments.clone()
+        };
+        for deployment in deployments {
+            dotrain_order
+                .compose_deployment_to_rainlang(deployment)
+                .await
+                .map_err(|e| failed(e.into()))?;
+        }
+        Ok(())
+    }
+
+    /// Runs every test of the manifest, returning the result of each
+    pub async fn run_tests(&self) -> Vec<(String, Result<(), StrategyError>)> {
+        let mut results = vec![];
+        for test in &self.manifest.tests {
+            results.push((test.name.clone(), self.run_test(test).await));
+        }
+        results
+    }
+}
+
+/// Parses a MAJOR.MINOR.PATCH version so versions can be ordered
+pub fn parse_version(version: &str) -> Result<(u64, u64, u64), StrategyError> {
+    let mut parts = version.split('.').map(|part| part.parse::<u64>());
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) => Ok((major, minor, patch)),
+        _ => Err(StrategyError::InvalidVersion(version.to_string())),
+    }
+}
+
+/// Strategy packages loaded from a directory, each package being either a
+/// directory with a manifest or an archive
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StrategyRegistry {
+    packages: Vec<StrategyPackage>,
+}
+
+impl StrategyRegistry {
+    pub fn new(packages: Vec<StrategyPackage>) -> Result<Self, StrategyError> {
+        let mut seen = BTreeSet::new();
+        for package in &packages {
+            let manifest = &package.manifest;
+            if !seen.insert((manifest.name.clone(), manifest.version.clone())) {
+                return Err(StrategyError::DuplicateStrategy(
+                    manifest.name.clone(),
+                    manifest.version.clone(),
+                ));
+            }
+        }
+        Ok(Self { packages })
+    }
+
+    /// Loads the package directories and archives found in a directory
+    #[cfg(not(target_family = "wasm"))]
+    pub fn load_dir(path: impl AsRef<Path>) -> Result<Self, StrategyError> {
+        let mut paths = std::fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        paths.sort();
+
+        let mut packages = vec![];
+        for path in paths {
+            if path.join(MANIFEST_FILE).is_file() {
+                packages.push(StrategyPackage::load_dir(&path)?);
+            } else if path.extension().is_some_and(|ext| ext == ARCHIVE_EXTENSION) {
+                packages.push(StrategyPackage::load(&path)?);
+            }
+        }
+        Self::new(packages)
+    }
+
+    /// The packages, by name and then version
+    pub fn packages(&self) -> Vec<&StrategyPackage> {
+        let mut packages: Vec<&StrategyPackage> = self.packages.iter().collect();
+        packages.sort_by_key(|package| {
+            (
+                package.manifest.name.clone(),
+                parse_version(&package.manifest.version).unwrap_or_default(),
+            )
+        });
+        packages
+    }
+
+    /// Manifests of the packages, by name and then version
+    pub fn list(&self) -> Vec<&StrategyManifest> {
+        self.packages()
+            .into_iter()
+            .map(|package| &package.manifest)
+            .collect()
+    }
+
+    /// The package with the given name, at the given version or else at its
+    /// latest version
+    pub fn get(
+        &self,
+        name: &str,
+        version: Option<&str>,
+    ) -> Result<&StrategyPackage, StrategyError> {
+        self.packages
+            .iter()
+            .filter(|package| package.manifest.name == name)
+            .filter(|package| match version {
+                Some(version) => package.manifest.version == version,
+                None => true,
+            })
+            .max_by_key(|package| parse_version(&package.manifest.version).unwrap_or_default())
+            .ok_or(StrategyError::StrategyNotFound(match version {
+                Some(version) => format!("{}@{}", name, version),
+                None => name.to_string(),
+            }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::Address;
+
+    const DOTRAIN: &str = r#"
+networks:
+    polygon:
+        rpc: https://rpc.example.com/${RPC_KEY:-demo}
+        chain-id: 137
+deployers:
+    polygon:
+        address: 0x1234567890123456789012345678901234567890
+scenarios:
+    polygon:
+tokens:
+    t1:
+        network: polygon
+        address: 0x1111111111111111111111111111111111111111
+        decimals: 18
+    t2:
+        network: polygon
+        address: ${param.sell_token}
+        decimals: 18
+orders:
+    polygon:
+        inputs:
+            - token: t1
+        outputs:
+            - token: t2
+deployments:
+    polygon:
+        scenario: polygon
+        order: polygon
+---
+#calculate-io
+_ _: ${param.max_amount} ${param.ratio};
+#handle-io
+:;"#;
+
+    fn package() -> StrategyPackage {
+        StrategyPackage {
+            manifest: serde_yaml::from_str(
+                r#"
+name: limit
+version: 1.2.0
+description: Sells at a fixed ratio
+dotrain: limit.rain
+parameters:
+    - name: sell_token
+      description: Address of the token to sell
+      default: "0x2222222222222222222222222222222222222222"
+    - name: max_amount
+      description: The most to sell per trade
+    - name: ratio
+      default: "2"
+tests:
+    - name: default ratio
+      parameters:
+          max_amount: "10"
+"#,
+            )
+            .unwrap(),
+            dotrain: DOTRAIN.to_string(),
+            settings: None,
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let package = package();
+        assert_eq!(
+            package.references().unwrap(),
+            BTreeSet::from([
+                "max_amount".to_string(),
+                "ratio".to_string(),
+                "sell_token".to_string()
+            ])
+        );
+        package.validate().unwrap();
+
+        let mut undeclared = package.clone();
+        undeclared.manifest.parameters.pop();
+        assert!(matches!(
+            undeclared.validate(),
+            Err(StrategyError::UndeclaredParameter(name)) if name == "ratio"
+        ));
+
+        let mut duplicate = package.clone();
+        duplicate
+            .manifest
+            .parameters
+            .push(duplicate.manifest.parameters[0].clone());
+        assert!(matches!(
+            duplicate.validate(),
+            Err(StrategyError::DuplicateParameter(name)) if name == "sell_token"
+        ));
+
+        let mut version = package;
+        version.manifest.version = "1.2".to_string();
+        assert!(matches!(
+            version.validate(),
+            Err(StrategyError::InvalidVersion(_))
+        ));
+    }
+
+    #[test]
+    fn test_render() {
+        let package = package();
+        let rendered = package
+            .render(&HashMap::from([(
+                "max_amount".to_string(),
+                "10".to_string(),
+            )]))
+            .unwrap();
+        assert!(rendered
+            .dotrain
+            .contains(r#"address: "0x2222222222222222222222222222222222222222""#));
+        assert!(rendered.dotrain.contains("_ _: 10 2;"));
+        assert!(rendered
+            .dotrain
+            .contains("rpc: https://rpc.example.com/${RPC_KEY:-demo}"));
+        assert_eq!(rendered.settings, None);
+
+        // values that would change the yaml are quoted
+        let rendered = package
+            .render(&HashMap::from([
+                ("max_amount".to_string(), "10".to_string()),
+                ("sell_token".to_string(), "0x1\nnetworks: {}".to_string()),
+            ]))
+            .unwrap();
+        assert!(rendered.dotrain.contains(r#"address: "0x1\nnetworks: {}""#));
+
+        assert!(matches!(
+            package.render(&HashMap::new()),
+            Err(StrategyError::MissingParameter(name)) if name == "max_amount"
+        ));
+        assert!(matches!(
+            package.render(&HashMap::from([
+                ("max_amount".to_string(), "10".to_string()),
+                ("min_amount".to_string(), "1".to_string()),
+            ])),
+            Err(StrategyError::UnknownParameter(name)) if name == "min_amount"
+        ));
+    }
+
+    #[test]
+    fn test_yaml_scalar() {
+        for plain in [
+            "0",
+            "18",
+            "137",
+            "polygon",
+            "usdc-polygon",
+            "_private",
+            "Token_1.v2",
+            "https://rpc.example.com/key",
+            "/path/to/file",
+            "a@b",
+            "yesterday",
+        ] {
+            assert_eq!(yaml_scalar(plain), plain);
+        }
+        for quoted in [
+            "", "true", "False", "yes", "NO", "y", "on", "Off", "null", "Null", "~", "1e3", "0.01",
+            ".5", ".inf", "-1", "+1", "007", "1_000", "1:20", "0x01", "0o17", "@token", "`cmd`",
+            "!tag", "&anchor", "*alias", "%dir", "#comment", "-", "?", "key:", "a: b", "a #b",
+            " a", "a\nb", "[a]", "{a: b}",
+        ] {
+            assert_eq!(
+                yaml_scalar(quoted),
+                serde_json::Value::String(quoted.to_string()).to_string()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_instantiate() {
+        let package = package();
+        let sell_token = Address::random();
+        let dotrain_order = package
+            .instantiate(&HashMap::from([
+                ("sell_token".to_string(), sell_token.to_string()),
+                ("max_amount".to_string(), "10".to_string()),
+                ("ratio".to_string(), "3".to_string()),
+            ]))
+            .await
+            .unwrap();
+        assert_eq!(dotrain_order.config().tokens["t2"].address, sell_token);
+        assert_eq!(
+            dotrain_order
+                .compose_deployment_to_rainlang("polygon".to_string())
+                .await
+                .unwrap(),
+            r#"/* 0. calculate-io */ 
+_ _: 10 3;
+
+/* 1. handle-io */ 
+:;"#
+        );
+
+        let results = package.run_tests().await;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].1.is_ok());
+
+        let test = StrategyTest {
+            name: "missing deployment".to_string(),
+            parameters: HashMap::from([("max_amount".to_string(), "10".to_string())]),
+            deployments: vec!["mainnet".to_string()],
+        };
+        assert!(matches!(
+            package.run_test(&test).await,
+            Err(StrategyError::TestFailed(name, _)) if name == "missing deployment"
+        ));
+    }
+
+    #[test]
+    fn test_archive() {
+        let package = package();
+        let archive = package.to_archive().unwrap();
+        assert_eq!(StrategyPackage::from_archive(&archive).unwrap(), package);
+    }
+
+    #[test]
+    fn test_load_registry() {
+        let dir = std::env::temp_dir().join(format!("strategy-registry-{}", std::process::id()));
+        let package_dir = dir.join("limit");
+        std::fs::create_dir_all(&package_dir).unwrap();
+        let package = package();
+        std::fs::write(
+            package_dir.join(MANIFEST_FILE),
+            serde_yaml::to_string(&package.manifest).unwrap(),
+        )
+        .unwrap();
+        std::fs::write(package_dir.join("limit.rain"), DOTRAIN).unwrap();
+
+        let mut newer = package.clone();
+        newer.manifest.version = "1.10.0".to_string();
+        std::fs::write(
+            dir.join(format!("limit-1.10.0.{}", ARCHIVE_EXTENSION)),
+            newer.to_archive().unwrap(),
+        )
+        .unwrap();
+        std::fs::write(dir.join("README.md"), "not a package").unwrap();
+
+        let registry = StrategyRegistry::load_dir(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let versions: Vec<&str> = registry
+            .list()
+            .iter()
+            .map(|manifest| manifest.version.as_str())
+            .collect();
+        assert_eq!(versions, vec!["1.2.0", "1.10.0"]);
+        assert_eq!(registry.get("limit", None).unwrap(), &newer);
+        assert_eq!(registry.get("limit", Some("1.2.0")).unwrap(), &package);
+        assert!(matches!(
+            registry.get("limit", Some("2.0.0")),
+            Err(StrategyError::StrategyNotFound(name)) if name == "limit@2.0.0"
+        ));
+
+        assert!(matches!(
+            StrategyRegistry::new(vec![package.clone(), package]),
+            Err(StrategyError::DuplicateStrategy(_, _))
+        ));
+    }
+}
@@ -0,0 +1,468 @@
+use crate::erc20::{Error, TokenInfo, ERC20};
+use alloy::hex::FromHex;
+use alloy::primitives::Address;
+use alloy::sol_types::SolCall;
+use alloy_ethers_typecast::multicall::{
+    IMulticall3::{aggregate3Call, Call3},
+    MULTICALL3_ADDRESS,
+};
+use alloy_ethers_typecast::transaction::{ReadContractParameters, ReadableClientHttp};
+use futures::future::join_all;
+use once_cell::sync::Lazy;
+use rain_orderbook_app_settings::config_source::{serialize_redacted, ConfigSource};
+use rain_orderbook_app_settings::network::Network;
+use rain_orderbook_app_settings::rpc::RpcProvider;
+use rain_orderbook_bindings::IERC20::{decimalsCall, nameCall, symbolCall};
+#[cfg(target_family = "wasm")]
+use rain_orderbook_bindings::{impl_all_wasm_traits, wasm_traits::prelude::*};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use typeshare::typeshare;
+use url::Url;
+
+static GLOBAL_TOKEN_INFO_CACHE: Lazy<TokenInfoCache> = Lazy::new(TokenInfoCache::default);
+
+/// Token infos read from chain, keyed by chain id and token address
+#[derive(Debug, Clone, Default)]
+pub struct TokenInfoCache(Arc<RwLock<HashMap<(u64, Address), TokenInfo>>>);
+
+impl TokenInfoCache {
+    /// The cache shared by the whole process
+    pub fn global() -> &'static TokenInfoCache {
+        &GLOBAL_TOKEN_INFO_CACHE
+    }
+
+    pub fn get(&self, chain_id: u64, address: Address) -> Option<TokenInfo> {
+        self.0
+            .read()
+            .ok()
+            .and_then(|v| v.get(&(chain_id, address)).cloned())
+    }
+
+    pub fn insert(&self, chain_id: u64, address: Address, info: TokenInfo) {
+        if let Ok(mut v) = self.0.write() {
+            v.insert((chain_id, address), info);
+        }
+    }
+
+    /// The cached token info, or the one read from the network's rpcs which
+    /// is then cached
+    pub async fn get_or_fetch(
+        &self,
+        network: &Network,
+        address: Address,
+        multicall_address: Option<String>,
+    ) -> Result<TokenInfo, Error> {
+        if let Some(info) = self.get(network.chain_id, address) {
+            return Ok(info);
+        }
+        let info = RpcProvider::for_network(network)
+            .with_failover(|rpc_url| {
+                let multicall_address = multicall_address.clone();
+                async move {
+                    ERC20::new(rpc_url, address)
+                        .token_info(multicall_address)
+                        .await
+                }
+            })
+            .await?;
+        self.insert(network.chain_id, address, info.clone());
+        Ok(info)
+    }
+}
+
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(target_family = "wasm", derive(Tsify))]
+#[serde(tag = "type", content = "data")]
+pub enum TokenMetadataWarning {
+    /// The configured decimals of a token differ from the ones read from chain
+    DecimalsMismatch {
+        token: String,
+        configured: u8,
+        onchain: u8,
+    },
+    /// The token infos of a token could not be read from chain
+    Unavailable { token: String },
+    /// Reading the token infos of a network failed
+    FetchFailed { network: String, error: String },
+}
+#[cfg(target_family = "wasm")]
+impl_all_wasm_traits!(TokenMetadataWarning);
+
+impl fmt::Display for TokenMetadataWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenMetadataWarning::DecimalsMismatch {
+                token,
+                configured,
+                onchain,
+            } => write!(
+                f,
+                "Token '{}' is configured with {} decimals but has {} decimals onchain",
+                token, configured, onchain
+            ),
+            TokenMetadataWarning::Unavailable { token } => {
+                write!(f, "Failed to read the token info of token '{}'", token)
+            }
+            TokenMetadataWarning::FetchFailed { network, error } => write!(
+                f,
+                "Failed to read token infos on network '{}': {}",
+                network, error
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[cfg_attr(target_family = "wasm", derive(Tsify))]
+pub struct TokenMetadataWarnings(pub Vec<TokenMetadataWarning>);
+#[cfg(target_family = "wasm")]
+impl_all_wasm_traits!(TokenMetadataWarnings);
+
+/// A config source enriched with the token infos read from chain
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnrichedConfigSource {
+    #[serde(serialize_with = "serialize_redacted")]
+    pub config: ConfigSource,
+    pub warnings: Vec<TokenMetadataWarning>,
+}
+
+/// Reads the decimals, name and symbol of the given tokens with a single
+/// multicall, a token any of whose calls fail gets None
+pub async fn fetch_token_infos(
+    rpc_url: Url,
+    addresses: &[Address],
+    multicall_address: Option<Address>,
+) -> Result<Vec<Option<TokenInfo>>, Error> {
+    let client = ReadableClientHttp::new_from_url(rpc_url.to_string())?;
+    let calls = addresses
+        .iter()
+        .flat_map(|address| {
+            [
+                decimalsCall {}.abi_encode(),
+                nameCall {}.abi_encode(),
+                symbolCall {}.abi_encode(),
+            ]
+            .into_iter()
+            .map(|call_data| Call3 {
+                target: *address,
+                allowFailure: true,
+                callData: call_data.into(),
+            })
+        })
+        .collect();
+    let results = client
+        .read(ReadContractParameters {
+            gas: None,
+            address: multicall_address
+                .unwrap_or_else(|| Address::from_hex(MULTICALL3_ADDRESS).unwrap()),
+            call: aggregate3Call { calls },
+            block_number: None,
+        })
+        .await?;
+
+    Ok(results
+        .returnData
+        .chunks(3)
+        .map(|results| {
+            if results.len() != 3 || results.iter().any(|v| !v.success) {
+                return None;
+            }
+            Some(TokenInfo {
+                decimals: decimalsCall::abi_decode_returns(&results[0].returnData, true)
+                    .ok()?
+                    ._0,
+                name: nameCall::abi_decode_returns(&results[1].returnData, true)
+                    .ok()?
+                    ._0,
+                symbol: symbolCall::abi_decode_returns(&results[2].returnData, true)
+                    .ok()?
+                    ._0,
+            })
+        })
+        .collect())
+}
+
+/// Fills in the missing decimals, symbol and label of the config's tokens
+/// with their token infos read from chain, using one multicall per network
+/// for the tokens that are not cached yet, failing over between its rpcs.
+/// Only the tokens of the given networks are enriched if any are given.
+/// Tokens used by orders are read even if complete so their configured
+/// decimals can be checked, configured values are always kept. Failures are
+/// returned as warnings rather than errors so a config can still be used
+/// offline.
+pub async fn enrich_token_metadata(
+    config: &mut ConfigSource,
+    networks: Option<&[String]>,
+    cache: &TokenInfoCache,
+    multicall_address: Option<Address>,
+) -> Vec<TokenMetadataWarning> {
+    let used_tokens = config
+        .orders
+        .values()
+        .flat_map(|order| order.inputs.iter().chain(order.outputs.iter()))
+        .map(|io| io.token.clone())
+        .collect::<HashSet<_>>();
+
+    // token keys to enrich by network
+    let mut network_tokens: HashMap<String, Vec<String>> = HashMap::new();
+    for (key, token) in &config.tokens {
+        if networks.is_some_and(|networks| !networks.contains(&token.network)) {
+            continue;
+        }
+        let is_complete =
+            token.decimals.is_some() && token.symbol.is_some() && token.label.is_some();
+        if !is_complete || used_tokens.contains(key) {
+            network_tokens
+                .entry(token.network.clone())
+                .or_default()
+                .push(key.clone());
+        }
+    }
+
+    let fetches = network_tokens
+        .into_iter()
+        .filter_map(|(network_name, mut keys)| {
+            let network = config
+                .networks
+                .get(&network_name)?
+                .clone()
+                .try_into_network(network_name.clone())
+                .ok()?;
+            let chain_id = network.chain_id;
+            let provider = RpcProvider::for_network(&network);
+            keys.sort();
+            let mut addresses = keys
+                .iter()
+                .map(|key| config.tokens[key].address)
+                .filter(|address| cache.get(chain_id, *address).is_none())
+                .collect::<Vec<_>>();
+            addresses.sort();
+            addresses.dedup();
+            Some(async move {
+                let result = if addresses.is_empty() {
+                    Ok(vec![])
+                } else {
+                    provider
+                        .with_failover(|rpc_url| {
+                            fetch_token_infos(rpc_url, &addresses, multicall_address)
+                        })
+                        .await
+                };
+                (network_name, chain_id, keys, addresses, result)
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut warnings = vec![];
+    for (network_name, chain_id, keys, addresses, result) in join_all(fetches).await {
+        let fetched = match result {
+            Ok(infos) => {
+                for (address, info) in addresses.into_iter().zip(infos) {
+                    if let Some(info) = info {
+                        cache.insert(chain_id, address, info);
+                    }
+                }
+                true
+            }
+            Err(e) => {
+                warnings.push(TokenMetadataWarning::FetchFailed {
+                    network: network_name,
+                    // the rpc url may hold an interpolated secret
+                    error: config.secrets.redact(&e.to_string()),
+                });
+                false
+            }
+        };
+
+        for key in keys {
+            let Some(token) = config.tokens.get_mut(&key) else {
+                continue;
+            };
+            let Some(info) = cache.get(chain_id, token.address) else {
+                if fetched {
+                    warnings.push(TokenMetadataWarning::Unavailable { token: key });
+                }
+                continue;
+            };
+            match token.decimals {
+                Some(decimals) if decimals != info.decimals => {
+                    warnings.push(TokenMetadataWarning::DecimalsMismatch {
+                        token: key,
+                        configured: decimals,
+                        onchain: info.decimals,
+                    });
+                }
+                Some(_) => {}
+                None => token.decimals = Some(info.decimals),
+            }
+            token.symbol.get_or_insert(info.symbol);
+            token.label.get_or_insert(info.name);
+        }
+    }
+    warnings.sort_by_key(|v| v.to_string());
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::hex::encode_prefixed;
+    use alloy::sol_types::SolValue;
+    use alloy_ethers_typecast::multicall::IMulticall3::Result as MulticallResult;
+    use alloy_ethers_typecast::rpc::Response;
+    use httpmock::{Method::POST, MockServer};
+    use serde_json::{from_str, Value};
+
+    fn token_info_results(decimals: u8, name: &str, symbol: &str) -> Vec<MulticallResult> {
+        vec![
+            MulticallResult {
+                success: true,
+                returnData: decimalsCall::abi_encode_returns(&(decimals,)).into(),
+            },
+            MulticallResult {
+                success: true,
+                returnData: nameCall::abi_encode_returns(&(name.to_string(),)).into(),
+            },
+            MulticallResult {
+                success: true,
+                returnData: symbolCall::abi_encode_returns(&(symbol.to_string(),)).into(),
+            },
+        ]
+    }
+
+    fn mock_rpc(server: &MockServer, results: Vec<MulticallResult>) {
+        let response_data = results.abi_encode();
+        server.mock(|when, then| {
+            when.method(POST).path("/rpc");
+            then.json_body_obj(
+                &from_str::<Value>(
+                    &Response::new_success(1, encode_prefixed(response_data).as_str())
+                        .to_json_string()
+                        .unwrap(),
+                )
+                .unwrap(),
+            );
+        });
+    }
+
+    fn mock_config(rpc_url: String) -> ConfigSource {
+        serde_yaml::from_str(&format!(
+            r#"
+networks:
+    mainnet:
+        rpc: {rpc_url}
+        chain-id: 1
+tokens:
+    weth:
+        network: mainnet
+        address: 0x1234567890123456789012345678901234567891
+    usdc:
+        network: mainnet
+        address: 0x1234567890123456789012345678901234567892
+        decimals: 18
+        label: My USDC
+        symbol: USDC
+    dai:
+        network: mainnet
+        address: 0x1234567890123456789012345678901234567893
+        decimals: 18
+        label: Dai
+        symbol: DAI
+orders:
+    order:
+        inputs:
+            - token: weth
+        outputs:
+            - token: usdc
+"#
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_enrich_token_metadata() {
+        let server = MockServer::start_async().await;
+        // tokens are read sorted by address, dai is complete and unused
+        mock_rpc(
+            &server,
+            [
+                token_info_results(18, "Wrapped Ether", "WETH"),
+                token_info_results(6, "USD Coin", "USDC"),
+            ]
+            .concat(),
+        );
+
+        let mut config = mock_config(server.url("/rpc"));
+        let cache = TokenInfoCache::default();
+        let warnings = enrich_token_metadata(&mut config, None, &cache, None).await;
+
+        assert_eq!(
+            warnings,
+            vec![TokenMetadataWarning::DecimalsMismatch {
+                token: "usdc".to_string(),
+                configured: 18,
+                onchain: 6,
+            }]
+        );
+        let weth = &config.tokens["weth"];
+        assert_eq!(weth.decimals, Some(18));
+        assert_eq!(weth.label, Some("Wrapped Ether".to_string()));
+        assert_eq!(weth.symbol, Some("WETH".to_string()));
+        // configured values are kept
+        let usdc = &config.tokens["usdc"];
+        assert_eq!(usdc.decimals, Some(18));
+        assert_eq!(usdc.label, Some("My USDC".to_string()));
+        assert_eq!(
+            cache.get(1, weth.address).unwrap(),
+            TokenInfo {
+                decimals: 18,
+                name: "Wrapped Ether".to_string(),
+                symbol: "WETH".to_string(),
+            }
+        );
+        assert!(cache.get(1, config.tokens["dai"].address).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_enrich_token_metadata_failures() {
+        let server = MockServer::start_async().await;
+        let mut results = token_info_results(18, "Wrapped Ether", "WETH");
+        results[1].success = false;
+        results.extend(token_info_results(6, "USD Coin", "USDC"));
+        mock_rpc(&server, results);
+
+        let mut config = mock_config(server.url("/rpc"));
+        let warnings =
+            enrich_token_metadata(&mut config, None, &TokenInfoCache::default(), None).await;
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings.contains(&TokenMetadataWarning::Unavailable {
+            token: "weth".to_string()
+        }));
+        assert_eq!(config.tokens["weth"].decimals, None);
+
+        // an unreachable rpc does not fail the enrichment
+        let mut config = mock_config("http://localhost:1/rpc".to_string());
+        let warnings =
+            enrich_token_metadata(&mut config, None, &TokenInfoCache::default(), None).await;
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(
+            &warnings[0],
+            TokenMetadataWarning::FetchFailed { network, .. } if network == "mainnet"
+        ));
+
+        // nor is it read when enriching the tokens of another network only
+        let warnings = enrich_token_metadata(
+            &mut config,
+            Some(&["polygon".to_string()]),
+            &TokenInfoCache::default(),
+            None,
+        )
+        .await;
+        assert!(warnings.is_empty());
+    }
+}
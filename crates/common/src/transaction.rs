@@ -1,7 +1,19 @@
+#[cfg(not(target_family = "wasm"))]
+use crate::{
+    journal::{
+        speed_fees, IJournalCancel, JournalEntry, JournalError, JournalFlow, JournalStatus,
+        JournalStep, Replacement,
+    },
+    preview::fetch_fees,
+};
 use alloy::primitives::{ruint::FromUintError, Address, U256};
 use alloy::sol_types::SolCall;
 #[cfg(not(target_family = "wasm"))]
-use alloy_ethers_typecast::client::{LedgerClient, LedgerClientError};
+use alloy_ethers_typecast::{
+    client::{LedgerClient, LedgerClientError},
+    ethers_address_to_alloy,
+    transaction::{WriteTransaction, WriteTransactionStatus},
+};
 use alloy_ethers_typecast::{
     gas_fee_middleware::GasFeeSpeed,
     transaction::{
@@ -9,6 +21,16 @@ use alloy_ethers_typecast::{
         WriteContractParametersBuilder, WriteContractParametersBuilderError,
     },
 };
+use rain_orderbook_app_settings::{
+    network::Network,
+    rpc::{RpcProvider, RpcProviderError},
+};
+#[cfg(not(target_family = "wasm"))]
+use rain_orderbook_bindings::{
+    IOrderBookV4::{addOrder2Call, deposit2Call, removeOrder2Call, withdraw2Call},
+    OrderBook::multicallCall,
+    IERC20::approveCall,
+};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -38,6 +60,21 @@ pub enum TransactionArgsError {
     #[cfg(not(target_family = "wasm"))]
     #[error(transparent)]
     LedgerClient(#[from] LedgerClientError),
+    #[error(transparent)]
+    RpcProvider(#[from] RpcProviderError),
+    #[error(transparent)]
+    WritableClient(#[from] WritableClientError),
+    #[cfg(not(target_family = "wasm"))]
+    #[error(transparent)]
+    Journal(#[from] JournalError),
+    #[error(transparent)]
+    AbiDecode(#[from] alloy::sol_types::Error),
+    #[error("No transaction journal flow is set")]
+    JournalNone,
+    #[error("Journal transaction was sent from {0}, not from the Ledger wallet")]
+    JournalSenderMismatch(Address),
+    #[error("Cannot replace a transaction calling {0}")]
+    UnsupportedReplacement(String),
 }
 
 #[derive(Clone, Serialize, Deserialize, Default)]
@@ -49,9 +86,26 @@ pub struct TransactionArgs {
     pub max_priority_fee_per_gas: Option<U256>,
     pub max_fee_per_gas: Option<U256>,
     pub gas_fee_speed: Option<GasFeeSpeed>,
+    /// Flow of the transaction journal the sent transactions are recorded in
+    #[cfg(not(target_family = "wasm"))]
+    #[serde(skip)]
+    pub journal: Option<JournalFlow>,
 }
 
 impl TransactionArgs {
+    /// Args using the first healthy rpc of the network. The transactions are
+    /// then sent on that rpc only, a send is never retried on another one as
+    /// it may already have gone through
+    pub async fn for_network(network: &Network) -> Result<Self, TransactionArgsError> {
+        Ok(TransactionArgs {
+            rpc_url: RpcProvider::for_network(network)
+                .healthy_rpc()
+                .await?
+                .to_string(),
+            ..Default::default()
+        })
+    }
+
     pub async fn try_into_write_contract_parameters<T: SolCall + Clone>(
         &self,
         call: T,
@@ -67,6 +121,162 @@ impl TransactionArgs {
         Ok(params)
     }
 
+    /// Sends the call with the Ledger wallet. With a journal flow set the
+    /// call is recorded as its next step at the next nonce of the wallet,
+    /// and skipped if a previous run of the flow already confirmed it
+    #[cfg(not(target_family = "wasm"))]
+    pub async fn send<T, S>(
+        &self,
+        call: T,
+        contract: Address,
+        transaction_status_changed: S,
+    ) -> Result<(), TransactionArgsError>
+    where
+        T: SolCall + Clone,
+        S: Fn(WriteTransactionStatus<T>),
+    {
+        let ledger_client = self.clone().try_into_ledger_client().await?;
+        let Some(flow) = &self.journal else {
+            let params = self
+                .try_into_write_contract_parameters(call, contract)
+                .await?;
+            WriteTransaction::new(ledger_client.client, params, 4, transaction_status_changed)
+                .execute()
+                .await?;
+            return Ok(());
+        };
+
+        let (max_fee_per_gas, max_priority_fee_per_gas) = self.journal_fees().await;
+        let step = flow
+            .begin_step(
+                &self.rpc_url,
+                self.chain_id.ok_or(TransactionArgsError::ChainIdNone)?,
+                ethers_address_to_alloy(ledger_client.client.address()),
+                contract,
+                &call,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            )
+            .await?;
+        match step {
+            JournalStep::Confirmed(entry) => {
+                tracing::info!(
+                    "Skipping {} to {}, confirmed in step {} of flow {}",
+                    entry.label,
+                    entry.to,
+                    entry.step,
+                    entry.flow
+                );
+                Ok(())
+            }
+            JournalStep::Send(entry) => {
+                // a step sent again keeps its calldata from the journal, which
+                // begin_step only allows when it matches or is kept on purpose
+                let call = T::abi_decode(&entry.calldata, true)?;
+                send_journaled(
+                    ledger_client,
+                    flow,
+                    &entry,
+                    call,
+                    transaction_status_changed,
+                )
+                .await
+            }
+        }
+    }
+
+    /// Fees to send a journaled transaction with. Without explicit fees the
+    /// ones of the gas fee speed are picked here, as the transaction is sent
+    /// with the fees of its journal entry and its replacements bump them.
+    #[cfg(not(target_family = "wasm"))]
+    async fn journal_fees(&self) -> (Option<U256>, Option<U256>) {
+        let (None, None, Some(speed)) = (
+            self.max_fee_per_gas,
+            self.max_priority_fee_per_gas,
+            &self.gas_fee_speed,
+        ) else {
+            return (self.max_fee_per_gas, self.max_priority_fee_per_gas);
+        };
+        let Ok(url) = url::Url::parse(&self.rpc_url) else {
+            return (None, None);
+        };
+        match fetch_fees(&url).await {
+            Some(current) => {
+                let (max_fee_per_gas, max_priority_fee_per_gas) = speed_fees(speed, current);
+                (Some(max_fee_per_gas), Some(max_priority_fee_per_gas))
+            }
+            None => (None, None),
+        }
+    }
+
+    /// Speeds up or cancels a stuck step of the journal flow by sending a
+    /// replacement at its nonce with higher fees
+    #[cfg(not(target_family = "wasm"))]
+    pub async fn replace_journaled(
+        &self,
+        step: usize,
+        replacement: Replacement,
+    ) -> Result<(), TransactionArgsError> {
+        let flow = self
+            .journal
+            .as_ref()
+            .ok_or(TransactionArgsError::JournalNone)?;
+        let pending = flow.journal().pending_attempt(flow.id(), step)?;
+        let ledger_client = TransactionArgs {
+            chain_id: Some(pending.chain_id),
+            ..self.clone()
+        }
+        .try_into_ledger_client()
+        .await?;
+        if ethers_address_to_alloy(ledger_client.client.address()) != pending.from {
+            return Err(TransactionArgsError::JournalSenderMismatch(pending.from));
+        }
+
+        let entry = flow
+            .journal()
+            .prepare_replacement(flow.id(), step, replacement, &self.rpc_url)
+            .await?;
+        let data = &entry.calldata;
+        let selector = data.get(..4).unwrap_or_default();
+        if selector == approveCall::SELECTOR {
+            let call = approveCall::abi_decode(data, true)?;
+            send_journaled(ledger_client, flow, &entry, call, |_| {}).await?;
+        } else if selector == deposit2Call::SELECTOR {
+            let call = deposit2Call::abi_decode(data, true)?;
+            send_journaled(ledger_client, flow, &entry, call, |_| {}).await?;
+        } else if selector == withdraw2Call::SELECTOR {
+            let call = withdraw2Call::abi_decode(data, true)?;
+            send_journaled(ledger_client, flow, &entry, call, |_| {}).await?;
+        } else if selector == addOrder2Call::SELECTOR {
+            let call = addOrder2Call::abi_decode(data, true)?;
+            send_journaled(ledger_client, flow, &entry, call, |_| {}).await?;
+        } else if selector == removeOrder2Call::SELECTOR {
+            let call = removeOrder2Call::abi_decode(data, true)?;
+            send_journaled(ledger_client, flow, &entry, call, |_| {}).await?;
+        } else if selector == multicallCall::SELECTOR {
+            let call = multicallCall::abi_decode(data, true)?;
+            send_journaled(ledger_client, flow, &entry, call, |_| {}).await?;
+        } else if selector == IJournalCancel::cancelCall::SELECTOR {
+            let call = IJournalCancel::cancelCall::abi_decode(data, true)?;
+            send_journaled(ledger_client, flow, &entry, call, |_| {}).await?;
+        } else {
+            flow.record(
+                &entry,
+                JournalStatus::Failed("unsupported call".to_string()),
+            )?;
+            return Err(TransactionArgsError::UnsupportedReplacement(
+                entry.label.clone(),
+            ));
+        }
+        flow.journal().set_status(
+            &pending.flow,
+            pending.step,
+            pending.attempt,
+            JournalStatus::Replaced,
+        )?;
+        Ok(())
+    }
+
     pub async fn try_fill_chain_id(&mut self) -> Result<(), TransactionArgsError> {
         if self.chain_id.is_none() {
             let chain_id = ReadableClientHttp::new_from_url(self.rpc_url.clone())?
@@ -99,3 +309,45 @@ impl TransactionArgs {
         }
     }
 }
+
+/// Sends the call of a journal entry at its nonce and fees, recording each
+/// status change of the transaction. A transaction that fails once sent is
+/// kept pending as it may still be mined
+#[cfg(not(target_family = "wasm"))]
+async fn send_journaled<T, S>(
+    ledger_client: LedgerClient,
+    flow: &JournalFlow,
+    entry: &JournalEntry,
+    call: T,
+    transaction_status_changed: S,
+) -> Result<(), TransactionArgsError>
+where
+    T: SolCall + Clone,
+    S: Fn(WriteTransactionStatus<T>),
+{
+    let params = WriteContractParametersBuilder::default()
+        .address(entry.to)
+        .call(call)
+        .nonce(Some(entry.nonce))
+        .max_priority_fee_per_gas(entry.max_priority_fee_per_gas)
+        .max_fee_per_gas(entry.max_fee_per_gas)
+        .build()?;
+    let result = WriteTransaction::new(ledger_client.client, params, 4, |status| {
+        if let Err(e) = flow.record(entry, JournalStatus::from(&status)) {
+            tracing::warn!("Failed to record the transaction in the journal: {}", e);
+        }
+        transaction_status_changed(status);
+    })
+    .execute()
+    .await;
+    if let Err(e) = &result {
+        let sent = flow
+            .last_entry()
+            .is_some_and(|last| last.status == JournalStatus::Sent);
+        if !sent {
+            flow.record(entry, JournalStatus::Failed(e.to_string()))?;
+        }
+    }
+    result?;
+    Ok(())
+}
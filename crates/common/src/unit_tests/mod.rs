@@ -14,7 +14,11 @@ use rain_interpreter_eval::{
     trace::{RainEvalResultError, RainEvalResults},
 };
 use rain_orderbook_app_settings::{
-    blocks::BlockError, config::*, deployer::Deployer, unit_test::TestConfig,
+    blocks::{BlockError, BlockTimestamps},
+    config::*,
+    deployer::Deployer,
+    rpc::{RpcProvider, RpcProviderError},
+    unit_test::TestConfig,
 };
 use std::sync::Arc;
 use thiserror::Error;
@@ -63,6 +67,8 @@ pub enum TestRunnerError {
     ComposeError(#[from] ComposeError),
     #[error(transparent)]
     RainEvalResultError(#[from] RainEvalResultError),
+    #[error(transparent)]
+    RpcProviderError(#[from] RpcProviderError),
 }
 
 impl TestRunner {
@@ -329,27 +335,31 @@ impl TestRunner {
             ))?
             .clone();
 
-        // Fetch the latest block number
-        let block_number =
-            ReadableClientHttp::new_from_url(self.test_setup.deployer.network.rpc.to_string())?
-                .get_block_number()
-                .await?;
-        let blocks = self
-            .settings
-            .test_config
-            .scenario
-            .blocks
-            .as_ref()
-            .map_or(Ok(vec![block_number]), |b| {
-                b.expand_to_block_numbers(block_number)
-            })?;
+        // Fetch the latest block number, forking from the rpc that returned it
+        let (block_number, rpc_url) = RpcProvider::for_network(&self.test_setup.deployer.network)
+            .with_failover(|rpc_url| async move {
+                let block_number = ReadableClientHttp::new_from_url(rpc_url.to_string())?
+                    .get_block_number()
+                    .await?;
+                Ok::<_, TestRunnerError>((block_number, rpc_url))
+            })
+            .await?;
+        let blocks = match &self.settings.test_config.scenario.blocks {
+            Some(blocks) => {
+                let timestamps = BlockTimestamps::for_network(&self.test_setup.deployer.network);
+                blocks
+                    .resolve_block_numbers(&timestamps, block_number)
+                    .await?
+            }
+            None => vec![block_number],
+        };
         self.test_setup.block_number = blocks[0];
 
         // Create a fork with the first block number
         self.forker
             .add_or_select(
                 NewForkedEvm {
-                    fork_url: self.test_setup.deployer.network.rpc.clone().into(),
+                    fork_url: rpc_url.into(),
                     fork_block_number: Some(block_number),
                 },
                 None,
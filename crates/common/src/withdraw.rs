@@ -4,7 +4,7 @@ use crate::transaction::WritableTransactionExecuteError;
 use alloy::primitives::{Address, U256};
 use alloy::sol_types::SolCall;
 #[cfg(not(target_family = "wasm"))]
-use alloy_ethers_typecast::transaction::{WriteTransaction, WriteTransactionStatus};
+use alloy_ethers_typecast::transaction::WriteTransactionStatus;
 use rain_orderbook_bindings::IOrderBookV4::withdraw2Call;
 use serde::{Deserialize, Serialize};
 
@@ -34,15 +34,13 @@ impl WithdrawArgs {
         transaction_args: TransactionArgs,
         transaction_status_changed: S,
     ) -> Result<(), WritableTransactionExecuteError> {
-        let ledger_client = transaction_args.clone().try_into_ledger_client().await?;
-
         let withdraw_call: withdraw2Call = self.clone().into();
-        let params = transaction_args
-            .try_into_write_contract_parameters(withdraw_call, transaction_args.orderbook_address)
-            .await?;
-
-        WriteTransaction::new(ledger_client.client, params, 4, transaction_status_changed)
-            .execute()
+        transaction_args
+            .send(
+                withdraw_call,
+                transaction_args.orderbook_address,
+                transaction_status_changed,
+            )
             .await?;
 
         Ok(())
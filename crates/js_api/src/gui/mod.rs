@@ -4,12 +4,20 @@ use base64::{engine::general_purpose::URL_SAFE, Engine};
 use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 use rain_orderbook_app_settings::{
     gui::{Gui, GuiDeployment, GuiFieldDefinition, GuiPreset, ParseGuiConfigSourceError},
+    interpolation::ConfigVariables,
     Config,
 };
 use rain_orderbook_bindings::{impl_all_wasm_traits, wasm_traits::prelude::*};
 use rain_orderbook_common::{
-    dotrain_order::{calldata::DotrainOrderCalldataError, DotrainOrder, DotrainOrderError},
-    erc20::{TokenInfo, ERC20},
+    derivation::OrderDerivation,
+    dotrain_order::{
+        calldata::DotrainOrderCalldataError, quote::DeploymentQuoteError, DotrainOrder,
+        DotrainOrderError,
+    },
+    erc20::TokenInfo,
+    meta::TryDecodeEmbeddedSourceError,
+    strategy::StrategyError,
+    token_metadata::{TokenInfoCache, TokenMetadataWarnings},
 };
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
@@ -21,6 +29,7 @@ mod field_values;
 mod order_operations;
 mod select_tokens;
 mod state_management;
+mod strategy;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Tsify)]
 pub struct AvailableDeployments(Vec<GuiDeployment>);
@@ -39,14 +48,18 @@ pub struct DotrainOrderGui {
     deposits: BTreeMap<String, field_values::PairValue>,
     select_tokens: Option<BTreeMap<String, Address>>,
     onchain_token_info: BTreeMap<Address, TokenInfo>,
+    token_metadata_warnings: TokenMetadataWarnings,
+    derivation: Option<OrderDerivation>,
 }
 #[wasm_bindgen]
 impl DotrainOrderGui {
     #[wasm_bindgen(js_name = "getAvailableDeployments")]
     pub async fn get_available_deployments(
         dotrain: String,
+        variables: Option<ConfigVariables>,
     ) -> Result<AvailableDeployments, GuiError> {
-        let dotrain_order = DotrainOrder::new(dotrain, None).await?;
+        let dotrain_order =
+            DotrainOrder::new_with_variables(dotrain, None, variables.unwrap_or_default()).await?;
         let config = dotrain_order.config();
         let gui_config = config.gui.clone().ok_or(GuiError::GuiConfigNotFound)?;
         Ok(AvailableDeployments(gui_config.deployments))
@@ -57,8 +70,32 @@ impl DotrainOrderGui {
         dotrain: String,
         deployment_name: String,
         multicall_address: Option<String>,
+        variables: Option<ConfigVariables>,
     ) -> Result<DotrainOrderGui, GuiError> {
-        let dotrain_order = DotrainOrder::new(dotrain, None).await?;
+        let dotrain_order =
+            DotrainOrder::new_with_variables(dotrain, None, variables.unwrap_or_default()).await?;
+        Self::new_with_dotrain_order(dotrain_order, deployment_name, multicall_address).await
+    }
+
+    /// Gui of a deployment of an already parsed order
+    async fn new_with_dotrain_order(
+        mut dotrain_order: DotrainOrder,
+        deployment_name: String,
+        multicall_address: Option<String>,
+    ) -> Result<DotrainOrderGui, GuiError> {
+        // only the tokens of the deployment's network are read
+        let network_name = dotrain_order
+            .config()
+            .deployments
+            .get(&deployment_name)
+            .ok_or(GuiError::DeploymentNotFound(deployment_name.clone()))?
+            .order
+            .network
+            .name
+            .clone();
+        let token_metadata_warnings = dotrain_order
+            .enrich_token_metadata(Some(vec![network_name]), multicall_address.clone())
+            .await?;
 
         let config = dotrain_order.config();
         let gui_config = config.gui.clone().ok_or(GuiError::GuiConfigNotFound)?;
@@ -76,14 +113,13 @@ impl DotrainOrderGui {
                 .collect::<BTreeMap<String, Address>>()
         });
 
-        let rpc_url = gui_deployment
+        let network = gui_deployment
             .deployment
             .order
             .orderbook
             .clone()
             .ok_or(GuiError::OrderbookNotFound)?
             .network
-            .rpc
             .clone();
         let mut onchain_token_info: BTreeMap<Address, TokenInfo> = BTreeMap::new();
         for token in gui_deployment.deposits.iter() {
@@ -97,8 +133,9 @@ impl DotrainOrderGui {
                 }
             }
 
-            let erc20 = ERC20::new(rpc_url.clone(), token.token.address);
-            let token_info = erc20.token_info(multicall_address.clone()).await?;
+            let token_info = TokenInfoCache::global()
+                .get_or_fetch(&network, token.token.address, multicall_address.clone())
+                .await?;
             onchain_token_info.insert(token.token.address, token_info);
         }
 
@@ -109,6 +146,8 @@ impl DotrainOrderGui {
             deposits: BTreeMap::new(),
             select_tokens,
             onchain_token_info,
+            token_metadata_warnings,
+            derivation: None,
         })
     }
 
@@ -148,6 +187,13 @@ impl DotrainOrderGui {
     pub fn get_token_infos(&self) -> Result<TokenInfos, GuiError> {
         Ok(TokenInfos(self.onchain_token_info.clone()))
     }
+
+    /// Get the warnings of reading the metadata of the deployment's tokens
+    /// from chain, ie configured decimals that differ from the onchain ones
+    #[wasm_bindgen(js_name = "getTokenMetadataWarnings")]
+    pub fn get_token_metadata_warnings(&self) -> TokenMetadataWarnings {
+        self.token_metadata_warnings.clone()
+    }
 }
 
 #[derive(Error, Debug)]
@@ -178,6 +224,8 @@ pub enum GuiError {
     TokenMustBeSelected(String),
     #[error("Binding has no presets: {0}")]
     BindingHasNoPresets(String),
+    #[error("Order meta has no embedded gui state")]
+    EmbeddedGuiStateNotFound,
     #[error(transparent)]
     DotrainOrderError(#[from] DotrainOrderError),
     #[error(transparent)]
@@ -207,6 +255,8 @@ pub enum GuiError {
         #[from] rain_orderbook_common::transaction::WritableTransactionExecuteError,
     ),
     #[error(transparent)]
+    TransactionArgsError(#[from] rain_orderbook_common::transaction::TransactionArgsError),
+    #[error(transparent)]
     AddOrderArgsError(#[from] rain_orderbook_common::add_order::AddOrderArgsError),
     #[error(transparent)]
     ERC20Error(#[from] rain_orderbook_common::erc20::Error),
@@ -216,6 +266,12 @@ pub enum GuiError {
     SerdeWasmBindgenError(#[from] serde_wasm_bindgen::Error),
     #[error(transparent)]
     DotrainOrderCalldataError(#[from] DotrainOrderCalldataError),
+    #[error(transparent)]
+    DeploymentQuoteError(#[from] DeploymentQuoteError),
+    #[error(transparent)]
+    TryDecodeEmbeddedSourceError(#[from] TryDecodeEmbeddedSourceError),
+    #[error(transparent)]
+    StrategyError(#[from] StrategyError),
 }
 impl From<GuiError> for JsValue {
     fn from(value: GuiError) -> Self {
@@ -6,7 +6,10 @@ use alloy::{
 };
 use rain_orderbook_app_settings::{order::OrderIO, orderbook::Orderbook};
 use rain_orderbook_bindings::OrderBook::multicallCall;
-use rain_orderbook_common::{deposit::DepositArgs, dotrain_order, transaction::TransactionArgs};
+use rain_orderbook_common::{
+    deposit::DepositArgs, derivation::OrderDerivation, dotrain_order, meta::GuiStateMeta,
+    transaction::TransactionArgs,
+};
 use std::{collections::HashMap, str::FromStr, sync::Arc};
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Tsify)]
@@ -39,6 +42,24 @@ impl_all_wasm_traits!(AddOrderCalldataResult);
 pub struct DepositAndAddOrderCalldataResult(Bytes);
 impl_all_wasm_traits!(DepositAndAddOrderCalldataResult);
 
+/// Quote of one IO pair of the deployment's order, the max output is always
+/// zero as nothing is deposited for the order when it is quoted
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct DeploymentPairQuote {
+    pair_name: String,
+    input_index: u32,
+    output_index: u32,
+    max_output: Option<String>,
+    ratio: Option<String>,
+    error: Option<String>,
+}
+impl_all_wasm_traits!(DeploymentPairQuote);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Tsify)]
+pub struct DeploymentQuotesResult(Vec<DeploymentPairQuote>);
+impl_all_wasm_traits!(DeploymentQuotesResult);
+
 #[wasm_bindgen]
 impl DotrainOrderGui {
     fn get_orderbook(&self) -> Result<Arc<Orderbook>, GuiError> {
@@ -87,6 +108,21 @@ impl DotrainOrderGui {
         Ok(results)
     }
 
+    /// The current gui state to embed in the order meta, if asked for
+    fn get_embedded_gui_state(
+        &self,
+        embed_source: Option<bool>,
+    ) -> Result<Option<GuiStateMeta>, GuiError> {
+        if !embed_source.unwrap_or(false) {
+            return Ok(None);
+        }
+        Ok(Some(GuiStateMeta {
+            deployment_name: self.deployment.deployment_name.clone(),
+            state: self.serialize()?,
+            select_tokens: self.select_tokens.clone().unwrap_or_default(),
+        }))
+    }
+
     async fn check_allowance(
         &self,
         orderbook: &Orderbook,
@@ -98,8 +134,7 @@ impl DotrainOrderGui {
                 Address::from_str(owner)?,
                 TransactionArgs {
                     orderbook_address: orderbook.address,
-                    rpc_url: orderbook.network.rpc.to_string(),
-                    ..Default::default()
+                    ..TransactionArgs::for_network(&orderbook.network).await?
                 },
             )
             .await?;
@@ -157,8 +192,11 @@ impl DotrainOrderGui {
     }
 
     fn populate_vault_ids(&mut self) -> Result<(), GuiError> {
-        self.dotrain_order
-            .populate_vault_ids(&self.deployment.deployment_name, None)?;
+        self.dotrain_order.populate_vault_ids(
+            &self.deployment.deployment_name,
+            None,
+            self.derivation.as_ref(),
+        )?;
         self.refresh_gui_deployment()?;
         Ok(())
     }
@@ -202,16 +240,25 @@ impl DotrainOrderGui {
     }
 
     /// Generate add order calldata
+    ///
+    /// If `embed_source` is true the dotrain and the gui state are embedded
+    /// in the order meta so the order can be reopened in the gui later
     #[wasm_bindgen(js_name = "generateAddOrderCalldata")]
     pub async fn generate_add_order_calldata(
         &mut self,
+        embed_source: Option<bool>,
     ) -> Result<AddOrderCalldataResult, GuiError> {
         self.check_token_addresses()?;
         self.populate_vault_ids()?;
         self.update_config_source_bindings()?;
+        let gui_state = self.get_embedded_gui_state(embed_source)?;
         let calldata = self
             .dotrain_order
-            .generate_add_order_calldata(&self.deployment.deployment_name)
+            .generate_add_order_calldata(
+                &self.deployment.deployment_name,
+                gui_state,
+                self.derivation.clone(),
+            )
             .await?;
         Ok(AddOrderCalldataResult(calldata))
     }
@@ -219,6 +266,7 @@ impl DotrainOrderGui {
     #[wasm_bindgen(js_name = "generateDepositAndAddOrderCalldatas")]
     pub async fn generate_deposit_and_add_order_calldatas(
         &mut self,
+        embed_source: Option<bool>,
     ) -> Result<DepositAndAddOrderCalldataResult, GuiError> {
         self.check_token_addresses()?;
         self.populate_vault_ids()?;
@@ -241,9 +289,14 @@ impl DotrainOrderGui {
             .dotrain_order
             .generate_deposit_calldatas(&self.deployment.deployment_name, &token_deposits)
             .await?;
+        let gui_state = self.get_embedded_gui_state(embed_source)?;
         let add_order_calldata = self
             .dotrain_order
-            .generate_add_order_calldata(&self.deployment.deployment_name)
+            .generate_add_order_calldata(
+                &self.deployment.deployment_name,
+                gui_state,
+                self.derivation.clone(),
+            )
             .await?;
 
         calls.push(Bytes::copy_from_slice(&add_order_calldata));
@@ -256,6 +309,89 @@ impl DotrainOrderGui {
         )))
     }
 
+    /// Quote every IO pair of the order with the current choices before it
+    /// is deployed, to preview the ratios it would trade at. The order is
+    /// quoted with the chosen deposits, or the first preset of the deposits
+    /// not chosen yet, so the max outputs are those of a funded order
+    #[wasm_bindgen(js_name = "quoteDeployment")]
+    pub async fn quote_deployment(&mut self) -> Result<DeploymentQuotesResult, GuiError> {
+        self.check_token_addresses()?;
+        self.populate_vault_ids()?;
+        self.update_config_source_bindings()?;
+        let mut deposits = self
+            .dotrain_order
+            .preset_deposits(&self.deployment.deployment_name);
+        for deposit in self.get_deposits()? {
+            deposits.insert(deposit.token, deposit.amount);
+        }
+        let result = self
+            .dotrain_order
+            .quote_deployment_with_rpc(&self.deployment.deployment_name, &deposits, None, None)
+            .await?;
+
+        let symbol = |address: Address| {
+            result
+                .tokens
+                .iter()
+                .find(|token| token.address == address)
+                .and_then(|token| token.symbol.clone())
+                .unwrap_or("UNKNOWN".to_string())
+        };
+        let quotes = result
+            .quotes
+            .iter()
+            .map(|(quote_target, quote_result)| {
+                let quote_config = &quote_target.quote_config;
+                let input_index = quote_config.inputIOIndex.to::<u32>();
+                let output_index = quote_config.outputIOIndex.to::<u32>();
+                let pair_name = format!(
+                    "{}/{}",
+                    symbol(quote_config.order.validInputs[input_index as usize].token),
+                    symbol(quote_config.order.validOutputs[output_index as usize].token)
+                );
+                let (max_output, ratio, error) = match quote_result
+                    .as_ref()
+                    .map_err(|e| e.to_string())
+                    .and_then(|v| v.formatted().map_err(|e| e.to_string()))
+                {
+                    Ok(v) => (Some(v.max_output), Some(v.ratio), None),
+                    Err(e) => (None, None, Some(e)),
+                };
+                DeploymentPairQuote {
+                    pair_name,
+                    input_index,
+                    output_index,
+                    max_output,
+                    ratio,
+                    error,
+                }
+            })
+            .collect();
+        Ok(DeploymentQuotesResult(quotes))
+    }
+
+    /// Derive the nonce, secret and unset vault ids of the order from the
+    /// owner, the deployment and the salt instead of picking random ones, so
+    /// the same choices always add the same order. Must be set before the
+    /// calldatas are generated, as they fill in the vault ids. Without a
+    /// salt random ones are picked again
+    #[wasm_bindgen(js_name = "setOrderDerivation")]
+    pub fn set_order_derivation(
+        &mut self,
+        owner: String,
+        salt: Option<String>,
+    ) -> Result<(), GuiError> {
+        self.derivation = match salt {
+            Some(salt) => Some(OrderDerivation {
+                owner: Address::from_str(&owner)?,
+                deployment: self.deployment.deployment_name.clone(),
+                salt,
+            }),
+            None => None,
+        };
+        Ok(())
+    }
+
     #[wasm_bindgen(js_name = "setVaultId")]
     pub fn set_vault_id(
         &mut self,
@@ -1,11 +1,16 @@
 use std::str::FromStr;
 
 use super::*;
+use rain_orderbook_app_settings::token::Token;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Tsify)]
 pub struct SelectTokens(#[tsify(type = "Map<string, string>")] BTreeMap<String, Address>);
 impl_all_wasm_traits!(SelectTokens);
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Tsify)]
+pub struct SelectableTokens(Vec<Token>);
+impl_all_wasm_traits!(SelectableTokens);
+
 #[wasm_bindgen]
 impl DotrainOrderGui {
     pub fn check_token_addresses(&self) -> Result<(), GuiError> {
@@ -31,6 +36,47 @@ impl DotrainOrderGui {
         Ok(SelectTokens(select_tokens))
     }
 
+    /// Get the tokens of the config on the deployment's network, including
+    /// the ones of `using-tokens-from` lists, to choose select tokens from
+    ///
+    /// Optionally filtered by a case insensitive search on their symbol,
+    /// label or address, sorted by symbol
+    #[wasm_bindgen(js_name = "getSelectableTokens")]
+    pub fn get_selectable_tokens(
+        &self,
+        search: Option<String>,
+    ) -> Result<SelectableTokens, GuiError> {
+        let chain_id = self
+            .deployment
+            .deployment
+            .order
+            .orderbook
+            .clone()
+            .ok_or(GuiError::OrderbookNotFound)?
+            .network
+            .chain_id;
+        let search = search.unwrap_or_default().to_lowercase();
+        let mut tokens = self
+            .dotrain_order
+            .config()
+            .tokens
+            .values()
+            .filter(|token| token.network.chain_id == chain_id)
+            .filter(|token| {
+                search.is_empty()
+                    || [&token.symbol, &token.label]
+                        .into_iter()
+                        .flatten()
+                        .any(|v| v.to_lowercase().contains(&search))
+                    || token.address.to_string().to_lowercase().contains(&search)
+            })
+            .map(|token| token.as_ref().clone())
+            .collect::<Vec<_>>();
+        tokens.sort_by(|a, b| a.symbol.cmp(&b.symbol).then(a.address.cmp(&b.address)));
+        tokens.dedup_by(|a, b| a.address == b.address);
+        Ok(SelectableTokens(tokens))
+    }
+
     #[wasm_bindgen(js_name = "saveSelectTokenAddress")]
     pub async fn save_select_token_address(
         &mut self,
@@ -49,7 +95,7 @@ impl DotrainOrderGui {
         select_tokens.insert(token_name.clone(), address);
         self.select_tokens = Some(select_tokens);
 
-        let rpc_url = self
+        let network = self
             .deployment
             .deployment
             .order
@@ -57,10 +103,10 @@ impl DotrainOrderGui {
             .clone()
             .ok_or(GuiError::OrderbookNotFound)?
             .network
-            .rpc
             .clone();
-        let erc20 = ERC20::new(rpc_url.clone(), address);
-        let token_info = erc20.token_info(None).await?;
+        let token_info = TokenInfoCache::global()
+            .get_or_fetch(&network, address, None)
+            .await?;
         self.onchain_token_info.insert(address, token_info);
 
         self.dotrain_order
@@ -1,4 +1,6 @@
 use super::*;
+use rain_orderbook_common::meta::TryDecodeEmbeddedSource;
+use rain_orderbook_subgraph_client::types::common::Bytes;
 use sha2::{Digest, Sha256};
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -144,6 +146,37 @@ impl DotrainOrderGui {
         Ok(())
     }
 
+    /// Reopens the gui an order was added from, with the same choices, from
+    /// the dotrain and gui state embedded in the order meta. The networks
+    /// are not embedded, the settings provide them
+    #[wasm_bindgen(js_name = "restoreFromOrderMeta")]
+    pub async fn restore_from_order_meta(
+        meta: String,
+        settings: Option<String>,
+        multicall_address: Option<String>,
+    ) -> Result<DotrainOrderGui, GuiError> {
+        let source = Bytes(meta)
+            .try_decode_embedded_source()?
+            .ok_or(GuiError::EmbeddedGuiStateNotFound)?;
+        let gui_state = source.gui_state.ok_or(GuiError::EmbeddedGuiStateNotFound)?;
+
+        let dotrain_order =
+            DotrainOrder::new_with_variables(source.dotrain, settings, ConfigVariables::default())
+                .await?;
+        let mut gui = DotrainOrderGui::new_with_dotrain_order(
+            dotrain_order,
+            gui_state.deployment_name,
+            multicall_address,
+        )
+        .await?;
+        for (token_name, address) in gui_state.select_tokens {
+            gui.save_select_token_address(token_name, address.to_string())
+                .await?;
+        }
+        gui.deserialize_state(gui_state.state)?;
+        Ok(gui)
+    }
+
     #[wasm_bindgen(js_name = "clearState")]
     pub fn clear_state(&mut self) {
         self.field_values.clear();
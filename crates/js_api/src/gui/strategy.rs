@@ -0,0 +1,43 @@
+use super::*;
+use rain_orderbook_common::strategy::{StrategyManifest, StrategyPackage};
+
+#[wasm_bindgen]
+impl DotrainOrderGui {
+    /// Manifest of a strategy package archive, with the parameters to ask
+    /// values for before choosing a deployment
+    #[wasm_bindgen(js_name = "getStrategyManifest")]
+    pub fn get_strategy_manifest(package: String) -> Result<StrategyManifest, GuiError> {
+        Ok(StrategyPackage::from_archive(&package)?.manifest)
+    }
+
+    #[wasm_bindgen(js_name = "getStrategyDeployments")]
+    pub async fn get_strategy_deployments(
+        package: String,
+        parameters: ConfigVariables,
+    ) -> Result<AvailableDeployments, GuiError> {
+        let dotrain_order = StrategyPackage::from_archive(&package)?
+            .instantiate(&parameters.0)
+            .await?;
+        let gui_config = dotrain_order
+            .config()
+            .gui
+            .clone()
+            .ok_or(GuiError::GuiConfigNotFound)?;
+        Ok(AvailableDeployments(gui_config.deployments))
+    }
+
+    /// Same as `chooseDeployment` for the order a strategy package archive
+    /// renders into with the given parameter values
+    #[wasm_bindgen(js_name = "chooseStrategyDeployment")]
+    pub async fn choose_strategy_deployment(
+        package: String,
+        parameters: ConfigVariables,
+        deployment_name: String,
+        multicall_address: Option<String>,
+    ) -> Result<DotrainOrderGui, GuiError> {
+        let dotrain_order = StrategyPackage::from_archive(&package)?
+            .instantiate(&parameters.0)
+            .await?;
+        Self::new_with_dotrain_order(dotrain_order, deployment_name, multicall_address).await
+    }
+}
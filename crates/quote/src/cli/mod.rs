@@ -1,12 +1,19 @@
-use crate::{OrderQuoteValue, QuoteResult};
+use crate::{
+    batch_quote_opt_targets, rpc::batch_token_metadata, OrderQuoteValue, QuoteResult, QuoteTarget,
+};
 use alloy::primitives::Address;
 use clap::{command, ArgAction, Parser};
+use rain_orderbook_app_settings::rpc::RpcProvider;
 use serde::{Deserialize, Serialize};
 use std::{fs::write, io::Write, path::PathBuf};
 use url::Url;
 
 mod input;
+mod output;
+mod series;
 pub use input::*;
+pub use output::*;
+pub use series::*;
 
 /// Rain orderbook Quoter CLI app entrypoint sruct
 #[derive(Parser, Debug, Clone, PartialEq)]
@@ -51,6 +58,16 @@ pub struct Quoter {
     /// Pretty format the result
     #[arg(short, long, action = ArgAction::SetTrue)]
     pub pretty: bool,
+
+    /// Output format of the results
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Json)]
+    pub format: OutputFormat,
+
+    /// Resolve the IO tokens symbols and output human readable quote values
+    /// along with the inverse of the ratio as price, this is always the case
+    /// for table and csv formats
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub resolve_tokens: bool,
 }
 
 /// A serializable/deserializable struct that bridges [QuoteResult] for cli
@@ -88,40 +105,22 @@ impl From<Vec<QuoteResult>> for QuoterResult {
 impl Quoter {
     /// Executes the CLI call based on the given options of self
     pub async fn run(&self) -> anyhow::Result<QuoterResult> {
-        let result: QuoterResult = match self.input.read_content()? {
-            InputContentType::Target(v) => v
-                .do_quote(
-                    self.rpc.as_str(),
-                    self.block_number,
-                    None,
-                    self.multicall_address,
-                )
-                .await?
-                .into(),
-            InputContentType::Spec(v) => {
-                if let Some(sg) = &self.subgraph {
-                    v.do_quote(
-                        sg.as_str(),
-                        self.rpc.as_str(),
-                        self.block_number,
-                        None,
-                        self.multicall_address,
-                    )
-                    .await?
-                    .into()
-                } else {
-                    return Err(anyhow::anyhow!(
-                        "requires '--subgraph' url to read orders details from"
-                    ));
-                }
-            }
+        let (quote_targets, quote_results) = self.quote().await?;
+        let rows = if self.resolve_tokens || self.format != OutputFormat::Json {
+            Some(self.build_rows(&quote_targets, &quote_results).await?)
+        } else {
+            None
         };
+        let result: QuoterResult = quote_results.into();
 
         if !self.no_stdout || self.output.is_some() {
-            let stringified_result = if self.pretty {
-                serde_json::to_string_pretty::<QuoterResult>(&result)?
-            } else {
-                serde_json::to_string::<QuoterResult>(&result)?
+            let stringified_result = match &rows {
+                None => self.to_json_string(&result)?,
+                Some(rows) => match self.format {
+                    OutputFormat::Json => self.to_json_string(rows)?,
+                    OutputFormat::Table => build_table(rows).to_string(),
+                    OutputFormat::Csv => build_csv(rows)?,
+                },
             };
             if !self.no_stdout {
                 let mut stdout = std::io::stdout().lock();
@@ -134,6 +133,90 @@ impl Quoter {
 
         Ok(result)
     }
+
+    /// Quotes the input and returns the quote results along with their
+    /// respective quote targets, those that could not be resolved are None
+    async fn quote(&self) -> anyhow::Result<(Vec<Option<QuoteTarget>>, Vec<QuoteResult>)> {
+        match self.input.read_content()? {
+            InputContentType::Target(v) => {
+                let quote_results = v
+                    .do_quote(
+                        &RpcProvider::for_url(self.rpc.clone()),
+                        self.block_number,
+                        None,
+                        self.multicall_address,
+                    )
+                    .await?;
+                Ok((v.0.into_iter().map(Some).collect(), quote_results))
+            }
+            InputContentType::Spec(v) => {
+                if let Some(sg) = &self.subgraph {
+                    let quote_targets = v.get_batch_quote_target_from_subgraph(sg.as_str()).await?;
+                    let quote_results = batch_quote_opt_targets(
+                        &quote_targets,
+                        &RpcProvider::for_url(self.rpc.clone()),
+                        self.block_number,
+                        None,
+                        self.multicall_address,
+                    )
+                    .await?;
+                    Ok((quote_targets, quote_results))
+                } else {
+                    Err(anyhow::anyhow!(
+                        "requires '--subgraph' url to read orders details from"
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Resolves the IO tokens of the given quote targets and builds human
+    /// readable rows out of the quote results
+    async fn build_rows(
+        &self,
+        quote_targets: &[Option<QuoteTarget>],
+        quote_results: &[QuoteResult],
+    ) -> anyhow::Result<Vec<QuoterResultRow>> {
+        let mut tokens = vec![];
+        for (input, output) in quote_targets
+            .iter()
+            .flatten()
+            .filter_map(|v| v.get_io_tokens().ok())
+        {
+            for token in [input, output] {
+                if !tokens.contains(&token) {
+                    tokens.push(token);
+                }
+            }
+        }
+        let tokens_metadata = if tokens.is_empty() {
+            vec![]
+        } else {
+            batch_token_metadata(
+                &tokens,
+                self.rpc.as_str(),
+                self.block_number,
+                self.multicall_address,
+            )
+            .await?
+        };
+
+        Ok(quote_targets
+            .iter()
+            .zip(quote_results)
+            .map(|(quote_target, quote_result)| {
+                QuoterResultRow::new(quote_target.as_ref(), quote_result, &tokens_metadata)
+            })
+            .collect())
+    }
+
+    fn to_json_string<T: Serialize>(&self, value: &T) -> anyhow::Result<String> {
+        Ok(if self.pretty {
+            serde_json::to_string_pretty(value)?
+        } else {
+            serde_json::to_string(value)?
+        })
+    }
 }
 
 /// The main entrypoint for this crate's cli
@@ -191,9 +274,15 @@ mod tests {
                 &hex_bytes,
                 "--sg",
                 sg.as_str(),
+                "--format",
+                "csv",
             ])
             .unwrap();
         assert_eq!(result.get_one::<PathBuf>("output"), Some(&output));
+        assert_eq!(
+            result.get_one::<OutputFormat>("format"),
+            Some(&OutputFormat::Csv)
+        );
         assert_eq!(result.get_one::<Url>("subgraph"), Some(&sg));
         assert_eq!(result.get_one::<Url>("rpc"), Some(&rpc));
         assert_eq!(
@@ -266,6 +355,8 @@ mod tests {
             multicall_address: None,
             no_stdout: true,
             pretty: true,
+            format: OutputFormat::Json,
+            resolve_tokens: false,
             input: Input {
                 target: None,
                 spec: None,
@@ -406,6 +497,8 @@ mod tests {
             multicall_address: None,
             no_stdout: true,
             pretty: false,
+            format: OutputFormat::Json,
+            resolve_tokens: false,
             input: Input {
                 target: None,
                 spec: None,
@@ -440,6 +533,8 @@ mod tests {
             multicall_address: None,
             no_stdout: true,
             pretty: false,
+            format: OutputFormat::Json,
+            resolve_tokens: false,
             input: Input {
                 target: None,
                 input: None,
@@ -480,6 +575,8 @@ mod tests {
             multicall_address: None,
             no_stdout: false,
             pretty: false,
+            format: OutputFormat::Json,
+            resolve_tokens: false,
             input: Input {
                 input: None,
                 spec: None,
@@ -0,0 +1,422 @@
+use crate::{QuoteResult, QuoteSeries, QuoteTarget, QuoteToken};
+use alloy::primitives::{Address, B256};
+use clap::ValueEnum;
+use comfy_table::Table;
+use csv::Writer;
+use serde::{Deserialize, Serialize};
+
+/// Supported output formats of the quote results
+#[derive(Debug, Clone, Copy, PartialEq, Default, ValueEnum)]
+pub enum OutputFormat {
+    /// JSON array of the quote results
+    #[default]
+    Json,
+    /// Human readable table, always resolves the IO tokens
+    Table,
+    /// CSV rows, always resolves the IO tokens
+    Csv,
+}
+
+/// A human readable quote result, with 18 decimals fixed point quote values
+/// formatted as decimal strings and the IO tokens resolved to their symbols
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoterResultRow {
+    pub orderbook: Option<Address>,
+    pub order_hash: Option<B256>,
+    pub input_token: Option<String>,
+    pub output_token: Option<String>,
+    pub max_output: Option<String>,
+    pub ratio: Option<String>,
+    pub inverse_ratio: Option<String>,
+    pub error: Option<String>,
+}
+
+impl QuoterResultRow {
+    /// Builds a row from a quote result and its quote target, resolving the
+    /// IO tokens from the given tokens metadata
+    pub fn new(
+        quote_target: Option<&QuoteTarget>,
+        quote_result: &QuoteResult,
+        tokens: &[QuoteToken],
+    ) -> Self {
+        let token_name = |address: Address| {
+            tokens
+                .iter()
+                .find(|v| v.address == address)
+                .map(QuoteToken::display_name)
+                .unwrap_or_else(|| address.to_string())
+        };
+        let io_tokens = quote_target.and_then(|v| v.get_io_tokens().ok());
+
+        let mut row = QuoterResultRow {
+            orderbook: quote_target.map(|v| v.orderbook),
+            order_hash: quote_target.map(QuoteTarget::get_order_hash),
+            input_token: io_tokens.map(|(input, _)| token_name(input)),
+            output_token: io_tokens.map(|(_, output)| token_name(output)),
+            max_output: None,
+            ratio: None,
+            inverse_ratio: None,
+            error: None,
+        };
+        match quote_result
+            .as_ref()
+            .map_err(|e| e.to_string())
+            .and_then(|v| v.formatted().map_err(|e| e.to_string()))
+        {
+            Ok(formatted) => {
+                row.max_output = Some(formatted.max_output);
+                row.ratio = Some(formatted.ratio);
+                row.inverse_ratio = formatted.inverse_ratio;
+            }
+            Err(e) => row.error = Some(e),
+        }
+        row
+    }
+}
+
+/// Builds a human readable table out of the given rows
+pub fn build_table(rows: &[QuoterResultRow]) -> Table {
+    let mut table = Table::new();
+    table
+        .load_preset(comfy_table::presets::UTF8_FULL)
+        .set_content_arrangement(comfy_table::ContentArrangement::Dynamic)
+        .set_header(vec![
+            "Order Hash",
+            "Max Output",
+            "IO Ratio",
+            "Inverse IO Ratio",
+            "Error",
+        ]);
+
+    for row in rows {
+        let input = row.input_token.clone().unwrap_or_default();
+        let output = row.output_token.clone().unwrap_or_default();
+        table.add_row(vec![
+            row.order_hash.map(|v| v.to_string()).unwrap_or_default(),
+            row.max_output
+                .as_ref()
+                .map(|v| format!("{} {}", v, output))
+                .unwrap_or_default(),
+            row.ratio
+                .as_ref()
+                .map(|v| format!("{} {}/{}", v, input, output))
+                .unwrap_or_default(),
+            row.inverse_ratio
+                .as_ref()
+                .map(|v| format!("{} {}/{}", v, output, input))
+                .unwrap_or_default(),
+            row.error.clone().unwrap_or_default(),
+        ]);
+    }
+
+    table
+}
+
+/// A human readable quote of an order at a specific block
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteSeriesRow {
+    pub order_hash: B256,
+    pub block_number: u64,
+    pub timestamp: u64,
+    pub max_output: Option<String>,
+    pub ratio: Option<String>,
+    pub inverse_ratio: Option<String>,
+    pub error: Option<String>,
+}
+
+impl QuoteSeriesRow {
+    /// Builds a row for each point of the given quote series
+    pub fn from_series(quote_series: &QuoteSeries) -> Vec<Self> {
+        let order_hash = quote_series.quote_target.get_order_hash();
+        quote_series
+            .points
+            .iter()
+            .map(|point| QuoteSeriesRow {
+                order_hash,
+                block_number: point.block_number,
+                timestamp: point.timestamp,
+                max_output: point.formatted.as_ref().map(|v| v.max_output.clone()),
+                ratio: point.formatted.as_ref().map(|v| v.ratio.clone()),
+                inverse_ratio: point
+                    .formatted
+                    .as_ref()
+                    .and_then(|v| v.inverse_ratio.clone()),
+                error: point.error.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Builds a human readable table out of the given quote series rows
+pub fn build_series_table(rows: &[QuoteSeriesRow]) -> Table {
+    let mut table = Table::new();
+    table
+        .load_preset(comfy_table::presets::UTF8_FULL)
+        .set_content_arrangement(comfy_table::ContentArrangement::Dynamic)
+        .set_header(vec![
+            "Order Hash",
+            "Block",
+            "Timestamp",
+            "Max Output",
+            "IO Ratio",
+            "Inverse IO Ratio",
+            "Error",
+        ]);
+
+    for row in rows {
+        table.add_row(vec![
+            row.order_hash.to_string(),
+            row.block_number.to_string(),
+            row.timestamp.to_string(),
+            row.max_output.clone().unwrap_or_default(),
+            row.ratio.clone().unwrap_or_default(),
+            row.inverse_ratio.clone().unwrap_or_default(),
+            row.error.clone().unwrap_or_default(),
+        ]);
+    }
+
+    table
+}
+
+/// Serializes the given rows into csv text
+pub fn build_csv<T: Serialize>(rows: &[T]) -> anyhow::Result<String> {
+    let mut csv_writer = Writer::from_writer(vec![]);
+    for row in rows {
+        csv_writer.serialize(row)?;
+    }
+    Ok(String::from_utf8(csv_writer.into_inner()?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::FailedQuote, OrderQuoteValue, QuoteSeriesPoint};
+    use alloy::primitives::{hex::encode_prefixed, utils::parse_ether, U256};
+    use rain_orderbook_bindings::IOrderBookV4::{OrderV3, Quote, IO};
+
+    fn get_test_data() -> (QuoteTarget, Vec<QuoteToken>) {
+        let input_token = Address::random();
+        let output_token = Address::random();
+        let quote_target = QuoteTarget {
+            orderbook: Address::random(),
+            quote_config: Quote {
+                order: OrderV3 {
+                    validInputs: vec![IO {
+                        token: input_token,
+                        ..Default::default()
+                    }],
+                    validOutputs: vec![IO {
+                        token: output_token,
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        };
+        let tokens = vec![
+            QuoteToken {
+                address: input_token,
+                symbol: Some("IN".to_string()),
+                decimals: Some(6),
+            },
+            QuoteToken {
+                address: output_token,
+                symbol: Some("OUT".to_string()),
+                decimals: Some(18),
+            },
+        ];
+        (quote_target, tokens)
+    }
+
+    #[test]
+    fn test_quoter_result_row_ok() {
+        let (quote_target, tokens) = get_test_data();
+        let quote_result: QuoteResult = Ok(OrderQuoteValue {
+            max_output: parse_ether("3").unwrap(),
+            ratio: parse_ether("2").unwrap(),
+        });
+
+        let result = QuoterResultRow::new(Some(&quote_target), &quote_result, &tokens);
+        let expected = QuoterResultRow {
+            orderbook: Some(quote_target.orderbook),
+            order_hash: Some(quote_target.get_order_hash()),
+            input_token: Some("IN".to_string()),
+            output_token: Some("OUT".to_string()),
+            max_output: Some("3.000000000000000000".to_string()),
+            ratio: Some("2.000000000000000000".to_string()),
+            inverse_ratio: Some("0.500000000000000000".to_string()),
+            error: None,
+        };
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_quoter_result_row_err() {
+        let (quote_target, _) = get_test_data();
+        let quote_result: QuoteResult = Err(FailedQuote::NonExistent);
+
+        // unresolved tokens fallback to their address
+        let result = QuoterResultRow::new(Some(&quote_target), &quote_result, &[]);
+        let expected = QuoterResultRow {
+            orderbook: Some(quote_target.orderbook),
+            order_hash: Some(quote_target.get_order_hash()),
+            input_token: Some(
+                quote_target.quote_config.order.validInputs[0]
+                    .token
+                    .to_string(),
+            ),
+            output_token: Some(
+                quote_target.quote_config.order.validOutputs[0]
+                    .token
+                    .to_string(),
+            ),
+            max_output: None,
+            ratio: None,
+            inverse_ratio: None,
+            error: Some(FailedQuote::NonExistent.to_string()),
+        };
+        assert_eq!(result, expected);
+
+        let result = QuoterResultRow::new(None, &quote_result, &[]);
+        assert_eq!(result.order_hash, None);
+        assert_eq!(result.input_token, None);
+        assert_eq!(result.error, Some(FailedQuote::NonExistent.to_string()));
+    }
+
+    #[test]
+    fn test_build_table_and_csv() {
+        let (quote_target, tokens) = get_test_data();
+        let rows = vec![
+            QuoterResultRow::new(
+                Some(&quote_target),
+                &Ok(OrderQuoteValue {
+                    max_output: parse_ether("3").unwrap(),
+                    ratio: U256::ZERO,
+                }),
+                &tokens,
+            ),
+            QuoterResultRow::new(None, &Err(FailedQuote::NonExistent), &tokens),
+        ];
+
+        let table = build_table(&rows);
+        let table_rows = table
+            .row_iter()
+            .map(|row| row.cell_iter().map(|v| v.content()).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            table_rows,
+            vec![
+                vec![
+                    quote_target.get_order_hash().to_string(),
+                    "3.000000000000000000 OUT".to_string(),
+                    "0.000000000000000000 IN/OUT".to_string(),
+                    "".to_string(),
+                    "".to_string(),
+                ],
+                vec![
+                    "".to_string(),
+                    "".to_string(),
+                    "".to_string(),
+                    "".to_string(),
+                    FailedQuote::NonExistent.to_string(),
+                ],
+            ]
+        );
+
+        let csv = build_csv(&rows).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "orderbook,orderHash,inputToken,outputToken,maxOutput,ratio,inverseRatio,error"
+        );
+        assert_eq!(
+            lines.next().unwrap(),
+            format!(
+                "{},{},IN,OUT,3.000000000000000000,0.000000000000000000,,",
+                encode_prefixed(quote_target.orderbook),
+                encode_prefixed(quote_target.get_order_hash())
+            )
+        );
+        assert_eq!(
+            lines.next().unwrap(),
+            format!(",,,,,,,{}", FailedQuote::NonExistent)
+        );
+        assert!(lines.next().is_none());
+    }
+
+    #[test]
+    fn test_quote_series_rows() {
+        let (quote_target, _) = get_test_data();
+        let quote_series = QuoteSeries {
+            quote_target: quote_target.clone(),
+            points: vec![
+                QuoteSeriesPoint::new(
+                    10,
+                    1000,
+                    Ok(OrderQuoteValue {
+                        max_output: parse_ether("3").unwrap(),
+                        ratio: parse_ether("2").unwrap(),
+                    }),
+                ),
+                QuoteSeriesPoint::new(11, 1012, Err(FailedQuote::NonExistent)),
+            ],
+        };
+
+        let rows = QuoteSeriesRow::from_series(&quote_series);
+        let order_hash = quote_target.get_order_hash();
+        let expected = vec![
+            QuoteSeriesRow {
+                order_hash,
+                block_number: 10,
+                timestamp: 1000,
+                max_output: Some("3.000000000000000000".to_string()),
+                ratio: Some("2.000000000000000000".to_string()),
+                inverse_ratio: Some("0.500000000000000000".to_string()),
+                error: None,
+            },
+            QuoteSeriesRow {
+                order_hash,
+                block_number: 11,
+                timestamp: 1012,
+                max_output: None,
+                ratio: None,
+                inverse_ratio: None,
+                error: Some(FailedQuote::NonExistent.to_string()),
+            },
+        ];
+        assert_eq!(rows, expected);
+
+        let table_rows = build_series_table(&rows)
+            .row_iter()
+            .map(|row| row.cell_iter().map(|v| v.content()).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(table_rows[1][1], "11");
+        assert_eq!(table_rows[1][6], FailedQuote::NonExistent.to_string());
+
+        let csv = build_csv(&rows).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "orderHash,blockNumber,timestamp,maxOutput,ratio,inverseRatio,error"
+        );
+        assert_eq!(
+            lines.next().unwrap(),
+            format!(
+                "{},10,1000,3.000000000000000000,2.000000000000000000,0.500000000000000000,",
+                encode_prefixed(order_hash)
+            )
+        );
+        assert_eq!(
+            lines.next().unwrap(),
+            format!(
+                "{},11,1012,,,,{}",
+                encode_prefixed(order_hash),
+                FailedQuote::NonExistent
+            )
+        );
+        assert!(lines.next().is_none());
+    }
+}
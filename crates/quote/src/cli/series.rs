@@ -0,0 +1,220 @@
+use super::{build_csv, build_series_table, Input, InputContentType, OutputFormat, QuoteSeriesRow};
+use crate::{batch_quote_series, QuoteSeries, QuoteTarget};
+use alloy::primitives::Address;
+use clap::{ArgAction, Args};
+use rain_orderbook_app_settings::{
+    blocks::{BlockRange, Blocks, Interval},
+    rpc::RpcProvider,
+};
+use std::{fs::write, io::Write, path::PathBuf, str::FromStr};
+use url::Url;
+
+/// Quotes orders at every Nth block of a block range
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct QuoterSeries {
+    // input group, only one of which can be specified at a time
+    #[command(flatten)]
+    pub input: Input,
+
+    /// RPC URL of the evm chain to quote, needs to be an archive node for
+    /// historical blocks
+    #[arg(short, long, env, value_name = "URL", hide_env_values = true)]
+    pub rpc: Url,
+
+    /// Subgraph URL to read orders details from, presence of this
+    /// arg determines what type input's undelying content should be in
+    #[arg(
+        short,
+        long,
+        env,
+        value_name = "URL",
+        visible_alias = "sg",
+        hide_env_values = true
+    )]
+    pub subgraph: Option<Url>,
+
+    /// Block range to quote over, in the form of `a..b`, `a..` or `..b`,
+    /// where a and b are block numbers, ISO dates or times such as
+    /// `2024-01-01`, or offsets from the latest block such as `latest-1000`
+    /// or `latest-7d`
+    #[arg(long, value_name = "RANGE", value_parser = BlockRange::from_str)]
+    pub block_range: BlockRange,
+
+    /// Quote at every Nth block of the block range, or at every given
+    /// duration such as `1h`, defaults to every block
+    #[arg(long, value_name = "INTERVAL", value_parser = Interval::from_str)]
+    pub block_interval: Option<Interval>,
+
+    /// Optional multicall3 address to use when quoting
+    #[arg(short, long, env, value_name = "ADDRESS")]
+    pub multicall_address: Option<Address>,
+
+    /// Optional file path to write the output results into
+    #[arg(short, long, env, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Do NOT send the results to stdout
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub no_stdout: bool,
+
+    /// Output format of the results
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Json)]
+    pub format: OutputFormat,
+}
+
+impl QuoterSeries {
+    /// Executes the CLI call based on the given options of self
+    pub async fn run(&self) -> anyhow::Result<Vec<QuoteSeries>> {
+        let quote_targets = self.read_quote_targets().await?;
+        let series = batch_quote_series(
+            &quote_targets,
+            &self.blocks(),
+            &RpcProvider::for_url(self.rpc.clone()),
+            None,
+            self.multicall_address,
+        )
+        .await?;
+
+        if !self.no_stdout || self.output.is_some() {
+            let stringified_result = match self.format {
+                OutputFormat::Json => serde_json::to_string_pretty(&series)?,
+                OutputFormat::Table => build_series_table(&self.build_rows(&series)).to_string(),
+                OutputFormat::Csv => build_csv(&self.build_rows(&series))?,
+            };
+            if !self.no_stdout {
+                let mut stdout = std::io::stdout().lock();
+                stdout.write_all(stringified_result.as_bytes())?;
+            }
+            if let Some(v) = &self.output {
+                write(v, stringified_result)?;
+            }
+        }
+
+        Ok(series)
+    }
+
+    /// Blocks to quote at
+    fn blocks(&self) -> Blocks {
+        match &self.block_interval {
+            Some(interval) => Blocks::RangeWithInterval {
+                range: self.block_range.clone(),
+                interval: interval.clone(),
+            },
+            None => Blocks::SimpleRange(self.block_range.clone()),
+        }
+    }
+
+    /// Reads the input into quote targets, specs are resolved from the subgraph
+    async fn read_quote_targets(&self) -> anyhow::Result<Vec<QuoteTarget>> {
+        match self.input.read_content()? {
+            InputContentType::Target(v) => Ok(v.0),
+            InputContentType::Spec(v) => {
+                let sg = self.subgraph.as_ref().ok_or(anyhow::anyhow!(
+                    "requires '--subgraph' url to read orders details from"
+                ))?;
+                v.get_batch_quote_target_from_subgraph(sg.as_str())
+                    .await?
+                    .into_iter()
+                    .zip(&v.0)
+                    .map(|(quote_target, spec)| {
+                        quote_target.ok_or(anyhow::anyhow!(
+                            "order {:#x} not found in subgraph",
+                            spec.order_hash
+                        ))
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    fn build_rows(&self, series: &[QuoteSeries]) -> Vec<QuoteSeriesRow> {
+        series
+            .iter()
+            .flat_map(QuoteSeriesRow::from_series)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BatchQuoteTarget;
+    use alloy::primitives::{hex::encode_prefixed, U256};
+    use alloy::sol_types::SolValue;
+    use clap::{CommandFactory, Parser};
+    use rain_orderbook_bindings::IOrderBookV4::{OrderV3, Quote};
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        series: QuoterSeries,
+    }
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_cli_args() {
+        let orderbook = Address::random();
+        let order = OrderV3::default();
+        let cli = Cli::try_parse_from([
+            "cmd",
+            "--rpc",
+            "https://rpc.com",
+            "--target",
+            &encode_prefixed(orderbook),
+            "0",
+            "0",
+            &encode_prefixed(order.abi_encode()),
+            "--block-range",
+            "100..200",
+            "--block-interval",
+            "1h",
+            "-f",
+            "csv",
+        ])
+        .unwrap();
+        let series = cli.series;
+        assert_eq!(series.rpc, Url::parse("https://rpc.com").unwrap());
+        assert_eq!(
+            series.block_range,
+            BlockRange::from_str("100..200").unwrap()
+        );
+        assert_eq!(series.block_interval, Some(Interval::Seconds(3600)));
+        assert_eq!(series.format, OutputFormat::Csv);
+        assert_eq!(
+            series.blocks(),
+            Blocks::RangeWithInterval {
+                range: BlockRange::from_str("100..200").unwrap(),
+                interval: Interval::Seconds(3600),
+            }
+        );
+        assert_eq!(
+            series.input.read_content().unwrap(),
+            InputContentType::Target(BatchQuoteTarget(vec![QuoteTarget {
+                orderbook,
+                quote_config: Quote {
+                    order,
+                    inputIOIndex: U256::ZERO,
+                    outputIOIndex: U256::ZERO,
+                    signedContext: vec![],
+                },
+            }]))
+        );
+
+        // block range is required
+        assert!(Cli::try_parse_from([
+            "cmd",
+            "--rpc",
+            "https://rpc.com",
+            "--target",
+            &encode_prefixed(orderbook),
+            "0",
+            "0",
+            &encode_prefixed(OrderV3::default().abi_encode()),
+        ])
+        .is_err());
+    }
+}
@@ -1,6 +1,9 @@
-use alloy::primitives::{hex::FromHexError, U256};
+use alloy::primitives::{hex::FromHexError, utils::UnitsError, U256};
 use alloy_ethers_typecast::transaction::ReadableClientError;
 use rain_error_decoding::{AbiDecodeFailedErrors, AbiDecodedErrorType};
+use rain_orderbook_app_settings::blocks::BlockError;
+use rain_orderbook_app_settings::rpc::RpcProviderError;
+use rain_orderbook_math::MathError;
 use rain_orderbook_subgraph_client::{
     types::order_detail_traits::OrderDetailError, OrderbookSubgraphClientError,
 };
@@ -44,6 +47,14 @@ pub enum Error {
     SerdeWasmBindgenError(#[from] serde_wasm_bindgen::Error),
     #[error("Invalid quote target: index {0} is out of bounds for this Order")]
     InvalidQuoteTarget(U256),
+    #[error(transparent)]
+    MathError(#[from] MathError),
+    #[error(transparent)]
+    UnitsError(#[from] UnitsError),
+    #[error(transparent)]
+    BlockError(#[from] BlockError),
+    #[error(transparent)]
+    RpcProviderError(#[from] RpcProviderError),
 }
 
 #[cfg(target_family = "wasm")]
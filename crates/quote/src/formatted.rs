@@ -0,0 +1,127 @@
+use crate::{error::Error, OrderQuoteValue};
+use alloy::primitives::{utils::format_units, Address, U256};
+use rain_orderbook_math::{BigUintMath, FIXED_POINT_DECIMALS, ONE18};
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+/// Metadata of an order IO token used to render quote values
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteToken {
+    #[typeshare(typescript(type = "string"))]
+    pub address: Address,
+    pub symbol: Option<String>,
+    pub decimals: Option<u8>,
+}
+
+impl QuoteToken {
+    /// Returns the token symbol if known, otherwise the token address
+    pub fn display_name(&self) -> String {
+        self.symbol
+            .clone()
+            .unwrap_or_else(|| self.address.to_string())
+    }
+}
+
+/// Holds human readable quoted order max output, ratio and its inverse.
+/// Quote values are always 18 decimals fixed point, regardless of the
+/// decimals of the order's IO tokens.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FormattedOrderQuoteValue {
+    /// Max amount of output token the order offers
+    pub max_output: String,
+    /// Amount of input token the order asks for per 1 unit of output token
+    pub ratio: String,
+    /// Amount of output token the order offers per 1 unit of input token,
+    /// None if the ratio is zero
+    pub inverse_ratio: Option<String>,
+}
+
+impl OrderQuoteValue {
+    /// Calculates the inverse of the io ratio as 18 decimals fixed point,
+    /// None if the ratio is zero
+    pub fn inverse_ratio(&self) -> Result<Option<U256>, Error> {
+        if self.ratio.is_zero() {
+            return Ok(None);
+        }
+        Ok(Some(ONE18.div_18(self.ratio)?))
+    }
+
+    /// Renders the quote values as human readable decimal strings
+    pub fn formatted(&self) -> Result<FormattedOrderQuoteValue, Error> {
+        Ok(FormattedOrderQuoteValue {
+            max_output: format_units(self.max_output, FIXED_POINT_DECIMALS)?,
+            ratio: format_units(self.ratio, FIXED_POINT_DECIMALS)?,
+            inverse_ratio: self
+                .inverse_ratio()?
+                .map(|v| format_units(v, FIXED_POINT_DECIMALS))
+                .transpose()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::utils::parse_ether;
+
+    #[test]
+    fn test_inverse_ratio() {
+        let quote_value = OrderQuoteValue {
+            max_output: parse_ether("10").unwrap(),
+            ratio: parse_ether("2").unwrap(),
+        };
+        assert_eq!(
+            quote_value.inverse_ratio().unwrap(),
+            Some(parse_ether("0.5").unwrap())
+        );
+
+        let quote_value = OrderQuoteValue {
+            max_output: parse_ether("10").unwrap(),
+            ratio: U256::ZERO,
+        };
+        assert_eq!(quote_value.inverse_ratio().unwrap(), None);
+    }
+
+    #[test]
+    fn test_formatted() {
+        let quote_value = OrderQuoteValue {
+            max_output: parse_ether("1.5").unwrap(),
+            ratio: parse_ether("4").unwrap(),
+        };
+        let expected = FormattedOrderQuoteValue {
+            max_output: "1.500000000000000000".to_string(),
+            ratio: "4.000000000000000000".to_string(),
+            inverse_ratio: Some("0.250000000000000000".to_string()),
+        };
+        assert_eq!(quote_value.formatted().unwrap(), expected);
+
+        let quote_value = OrderQuoteValue::default();
+        let expected = FormattedOrderQuoteValue {
+            max_output: "0.000000000000000000".to_string(),
+            ratio: "0.000000000000000000".to_string(),
+            inverse_ratio: None,
+        };
+        assert_eq!(quote_value.formatted().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_quote_token_display_name() {
+        let token = QuoteToken {
+            address: Address::ZERO,
+            symbol: Some("T1".to_string()),
+            decimals: Some(18),
+        };
+        assert_eq!(token.display_name(), "T1");
+
+        let token = QuoteToken {
+            address: Address::ZERO,
+            symbol: None,
+            decimals: None,
+        };
+        assert_eq!(token.display_name(), Address::ZERO.to_string());
+    }
+}
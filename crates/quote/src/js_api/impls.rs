@@ -1,4 +1,5 @@
 use super::*;
+use crate::FormattedOrderQuoteValue as MainFormattedOrderQuoteValue;
 use crate::QuoteTarget as MainQuoteTarget;
 use crate::{BatchOrderQuotesResponse as MainBatchOrderQuotesResponse, Pair as MainPair};
 use crate::{OrderQuoteValue as MainOrderQuoteValue, QuoteSpec as MainQuoteSpec};
@@ -101,6 +102,25 @@ impl From<crate::QuoteResult> for super::QuoteResult {
     }
 }
 
+impl From<MainFormattedOrderQuoteValue> for FormattedOrderQuoteValue {
+    fn from(value: MainFormattedOrderQuoteValue) -> Self {
+        FormattedOrderQuoteValue {
+            max_output: value.max_output,
+            ratio: value.ratio,
+            inverse_ratio: value.inverse_ratio,
+        }
+    }
+}
+impl From<FormattedOrderQuoteValue> for MainFormattedOrderQuoteValue {
+    fn from(value: FormattedOrderQuoteValue) -> Self {
+        MainFormattedOrderQuoteValue {
+            max_output: value.max_output,
+            ratio: value.ratio,
+            inverse_ratio: value.inverse_ratio,
+        }
+    }
+}
+
 impl From<MainPair> for Pair {
     fn from(value: MainPair) -> Self {
         Pair {
@@ -129,6 +149,7 @@ impl From<MainBatchOrderQuotesResponse> for BatchOrderQuotesResponse {
                 .inspect_err(|e| block_number_error.push_str(&e.to_string()))
                 .expect_throw(&block_number_error),
             data: value.data.map(OrderQuoteValue::from),
+            formatted: value.formatted.map(FormattedOrderQuoteValue::from),
             success: value.success,
             error: value.error,
         }
@@ -149,6 +170,7 @@ impl From<BatchOrderQuotesResponse> for MainBatchOrderQuotesResponse {
                     .inspect_err(|e| ratio_error.push_str(&e.to_string()))
                     .expect_throw(&ratio_error),
             }),
+            formatted: value.formatted.map(MainFormattedOrderQuoteValue::from),
             success: value.success,
             error: value.error,
         }
@@ -163,6 +185,7 @@ impl_all_wasm_traits!(BatchQuoteSpec);
 impl_all_wasm_traits!(OrderQuoteValue);
 impl_all_wasm_traits!(BatchQuoteTarget);
 impl_all_wasm_traits!(BatchOrderQuotesResponse);
+impl_all_wasm_traits!(FormattedOrderQuoteValue);
 
 #[cfg(test)]
 mod tests {
@@ -6,6 +6,7 @@ use alloy::primitives::{
     hex::{encode_prefixed, FromHex},
     Address, U256,
 };
+use rain_orderbook_app_settings::rpc::RpcProvider;
 use rain_orderbook_bindings::js_api::{Quote, SignedContextV1};
 use rain_orderbook_bindings::{
     impl_all_wasm_traits,
@@ -14,6 +15,7 @@ use rain_orderbook_bindings::{
 use rain_orderbook_subgraph_client::{types::common::Order, utils::make_order_id};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
+use url::Url;
 
 mod impls;
 
@@ -107,7 +109,12 @@ pub async fn do_quote_targets(
         .collect();
     let batch_quote_target = MainBatchQuoteTarget(quote_targets);
     match batch_quote_target
-        .do_quote(rpc_url, block_number, gas_value, multicall_address)
+        .do_quote(
+            &RpcProvider::for_url(Url::parse(rpc_url)?),
+            block_number,
+            gas_value,
+            multicall_address,
+        )
         .await
     {
         Err(e) => Err(e),
@@ -150,7 +157,7 @@ pub async fn do_quote_specs(
     match batch_quote_spec
         .do_quote(
             subgraph_url,
-            rpc_url,
+            &RpcProvider::for_url(Url::parse(rpc_url)?),
             block_number,
             gas_value,
             multicall_address,
@@ -200,12 +207,22 @@ pub struct Pair {
     pub output_index: u32,
 }
 
+/// Holds human readable quoted order max output, ratio and its inverse
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct FormattedOrderQuoteValue {
+    pub max_output: String,
+    pub ratio: String,
+    pub inverse_ratio: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default, Tsify)]
 #[serde(rename_all = "camelCase")]
 pub struct BatchOrderQuotesResponse {
     pub pair: Pair,
     pub block_number: u64,
     pub data: Option<OrderQuoteValue>,
+    pub formatted: Option<FormattedOrderQuoteValue>,
     pub success: bool,
     pub error: Option<String>,
 }
@@ -226,12 +243,17 @@ pub async fn get_order_quote(
             .expect_throw(&gas_error)
     });
     Ok(to_value(
-        &get_order_quotes(order, block_number, rpc_url.to_string(), gas_value)
-            .await
-            .map(|v| {
-                v.into_iter()
-                    .map(BatchOrderQuotesResponse::from)
-                    .collect::<Vec<_>>()
-            })?,
+        &get_order_quotes(
+            order,
+            block_number,
+            &RpcProvider::for_url(Url::parse(rpc_url)?),
+            gas_value,
+        )
+        .await
+        .map(|v| {
+            v.into_iter()
+                .map(BatchOrderQuotesResponse::from)
+                .collect::<Vec<_>>()
+        })?,
     )?)
 }
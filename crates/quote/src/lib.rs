@@ -1,10 +1,12 @@
 #[cfg(not(target_family = "wasm"))]
 pub mod cli;
 pub mod error;
+mod formatted;
 mod quote;
 #[cfg(not(target_family = "wasm"))]
 mod quote_debug;
 pub mod rpc;
+mod series;
 
 #[cfg(target_family = "wasm")]
 pub mod js_api;
@@ -12,7 +14,9 @@ pub mod js_api;
 mod order_quotes;
 pub use order_quotes::*;
 
+pub use formatted::*;
 pub use quote::*;
+pub use series::*;
 
 #[cfg(not(target_family = "wasm"))]
 pub use quote_debug::*;
@@ -1,10 +1,11 @@
 use crate::{
     error::Error,
     quote::{BatchQuoteTarget, QuoteTarget},
-    OrderQuoteValue,
+    FormattedOrderQuoteValue, OrderQuoteValue,
 };
 use alloy::primitives::{Address, U256};
 use alloy_ethers_typecast::transaction::ReadableClient;
+use rain_orderbook_app_settings::rpc::RpcProvider;
 use rain_orderbook_bindings::IOrderBookV4::{OrderV3, Quote};
 use rain_orderbook_subgraph_client::types::common::Order;
 use serde::{Deserialize, Serialize};
@@ -18,6 +19,7 @@ pub struct BatchOrderQuotesResponse {
     #[typeshare(typescript(type = "string"))]
     pub block_number: U256,
     pub data: Option<OrderQuoteValue>,
+    pub formatted: Option<FormattedOrderQuoteValue>,
     pub success: bool,
     pub error: Option<String>,
 }
@@ -33,7 +35,7 @@ pub struct Pair {
 pub async fn get_order_quotes(
     orders: Vec<Order>,
     block_number: Option<u64>,
-    rpc_url: String,
+    rpc: &RpcProvider,
     gas: Option<U256>,
 ) -> Result<Vec<BatchOrderQuotesResponse>, Error> {
     let mut results: Vec<BatchOrderQuotesResponse> = Vec::new();
@@ -93,14 +95,22 @@ pub async fn get_order_quotes(
             }
         }
 
-        let req_block_number = block_number.unwrap_or(
-            ReadableClient::new_from_url(rpc_url.clone())?
-                .get_block_number()
-                .await?,
-        );
+        let req_block_number = match block_number {
+            Some(block_number) => block_number,
+            None => {
+                rpc.with_failover(|url| async move {
+                    Ok::<_, Error>(
+                        ReadableClient::new_from_url(url.to_string())?
+                            .get_block_number()
+                            .await?,
+                    )
+                })
+                .await?
+            }
+        };
 
         let quote_values = BatchQuoteTarget(quote_targets)
-            .do_quote(&rpc_url, Some(req_block_number), gas, None)
+            .do_quote(rpc, Some(req_block_number), gas, None)
             .await;
 
         if let Ok(quote_values) = quote_values {
@@ -111,6 +121,7 @@ pub async fn get_order_quotes(
                             pair,
                             block_number: U256::from(req_block_number),
                             success: true,
+                            formatted: quote_value.formatted().ok(),
                             data: Some(quote_value),
                             error: None,
                         });
@@ -121,6 +132,7 @@ pub async fn get_order_quotes(
                             block_number: U256::from(req_block_number),
                             success: false,
                             data: None,
+                            formatted: None,
                             error: Some(e.to_string()),
                         });
                     }
@@ -133,6 +145,7 @@ pub async fn get_order_quotes(
                     block_number: U256::from(req_block_number),
                     success: false,
                     data: None,
+                    formatted: None,
                     error: Some(e.to_string()),
                 });
             }
@@ -145,13 +158,7 @@ pub async fn get_order_quotes(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use alloy::{
-        hex::encode_prefixed,
-        primitives::B256,
-        providers::Provider,
-        sol_types::{SolCall, SolValue},
-    };
-    use rain_orderbook_common::{add_order::AddOrderArgs, dotrain_order::DotrainOrder};
+    use alloy::{hex::encode_prefixed, primitives::B256, providers::Provider, sol_types::SolValue};
     use rain_orderbook_subgraph_client::types::common::{BigInt, Bytes, Erc20, Orderbook, Vault};
     use rain_orderbook_test_fixtures::LocalEvm;
 
@@ -168,74 +175,20 @@ mod tests {
             .await;
         let orderbook = &local_evm.orderbook;
 
-        let dotrain = format!(
-            r#"
-networks:
-    some-key:
-        rpc: {rpc_url}
-        chain-id: 123
-        network-id: 123
-        currency: ETH
-deployers:
-    some-key:
-        address: {deployer}
-tokens:
-    t2:
-        network: some-key
-        address: {token2}
-        decimals: 18
-        label: Token2
-        symbol: Token2
-    t1:
-        network: some-key
-        address: {token1}
-        decimals: 18
-        label: Token1
-        symbol: token1
-orderbook:
-    some-key:
-        address: {orderbook}
-orders:
-    some-key:
-        inputs:
-            - token: t1
-            - token: t2
-        outputs:
-            - token: t1
-              vault-id: 0x01
-            - token: t2
-              vault-id: 0x01
-scenarios:
-    some-key:
-deployments:
-    some-key:
-        scenario: some-key
-        order: some-key
----
-#calculate-io
-/* use io addresses in context as calculate-io maxoutput and ratio */
-amount price: context<3 0>() context<4 0>();
-#handle-add-order
-:;
-#handle-io
-:;
-"#,
-            rpc_url = local_evm.url(),
-            orderbook = orderbook.address(),
-            deployer = local_evm.deployer.address(),
-            token1 = token1.address(),
-            token2 = token2.address(),
-        );
-
-        let order = DotrainOrder::new(dotrain.clone(), None).await.unwrap();
-        let deployment = order.config().deployments["some-key"].as_ref().clone();
-        let calldata = AddOrderArgs::new_from_deployment(dotrain, deployment)
-            .await
-            .unwrap()
-            .try_into_call(local_evm.url())
-            .await
-            .unwrap()
-            .abi_encode();
+        // use io addresses in context as calculate-io maxoutput and ratio
+        let calldata = local_evm
+            .add_order_calldata(
+                "amount price: context<3 0>() context<4 0>();:;",
+                &[
+                    (*token1.address(), 18, U256::from(2)),
+                    (*token2.address(), 18, U256::from(2)),
+                ],
+                &[
+                    (*token1.address(), 18, U256::from(1)),
+                    (*token2.address(), 18, U256::from(1)),
+                ],
+            )
+            .await;
 
         // add order
         let order = encode_prefixed(
@@ -315,9 +268,14 @@ amount price: context<3 0>() context<4 0>();
             trades: vec![],
         };
 
-        let result = get_order_quotes(vec![order], None, local_evm.url(), None)
-            .await
-            .unwrap();
+        let result = get_order_quotes(
+            vec![order],
+            None,
+            &RpcProvider::for_url(local_evm.url().parse().unwrap()),
+            None,
+        )
+        .await
+        .unwrap();
 
         let token1_as_u256 = U256::from_str(&token1.address().to_string()).unwrap();
         let token2_as_u256 = U256::from_str(&token2.address().to_string()).unwrap();
@@ -334,6 +292,14 @@ amount price: context<3 0>() context<4 0>();
                     max_output: token1_as_u256,
                     ratio: token2_as_u256,
                 }),
+                formatted: Some(
+                    OrderQuoteValue {
+                        max_output: token1_as_u256,
+                        ratio: token2_as_u256,
+                    }
+                    .formatted()
+                    .unwrap(),
+                ),
                 success: true,
                 error: None,
             },
@@ -348,6 +314,14 @@ amount price: context<3 0>() context<4 0>();
                     max_output: token2_as_u256,
                     ratio: token1_as_u256,
                 }),
+                formatted: Some(
+                    OrderQuoteValue {
+                        max_output: token2_as_u256,
+                        ratio: token1_as_u256,
+                    }
+                    .formatted()
+                    .unwrap(),
+                ),
                 success: true,
                 error: None,
             },
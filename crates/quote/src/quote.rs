@@ -7,6 +7,7 @@ use alloy::primitives::{
     keccak256, Address, B256, U256,
 };
 use alloy::sol_types::SolValue;
+use rain_orderbook_app_settings::rpc::RpcProvider;
 use rain_orderbook_bindings::IOrderBookV4::{quoteReturn, OrderV3, Quote, SignedContextV1};
 use rain_orderbook_subgraph_client::{
     types::{common::Bytes, Id},
@@ -60,25 +61,21 @@ impl QuoteTarget {
         make_order_id(self.orderbook, self.get_order_hash().into())
     }
 
-    /// Quotes the target on the given rpc url
+    /// Quotes the target on the rpcs of the given provider
     pub async fn do_quote(
         &self,
-        rpc_url: &str,
+        rpc: &RpcProvider,
         block_number: Option<u64>,
         gas: Option<U256>,
         multicall_address: Option<Address>,
     ) -> Result<QuoteResult, Error> {
-        Ok(batch_quote(
-            &[self.clone()],
-            rpc_url,
-            block_number,
-            gas,
-            multicall_address,
+        Ok(
+            batch_quote(&[self.clone()], rpc, block_number, gas, multicall_address)
+                .await?
+                .into_iter()
+                .next()
+                .unwrap(),
         )
-        .await?
-        .into_iter()
-        .next()
-        .unwrap())
     }
 
     /// Validate the quote target
@@ -93,6 +90,15 @@ impl QuoteTarget {
         }
         Ok(())
     }
+    /// Get the input and output token addresses of the quoted IO pair of self
+    pub fn get_io_tokens(&self) -> Result<(Address, Address), Error> {
+        self.validate()?;
+        Ok((
+            self.quote_config.order.validInputs[self.quote_config.inputIOIndex.to::<usize>()].token,
+            self.quote_config.order.validOutputs[self.quote_config.outputIOIndex.to::<usize>()]
+                .token,
+        ))
+    }
 }
 
 /// Specifies a batch of [QuoteTarget]s
@@ -102,15 +108,15 @@ impl QuoteTarget {
 pub struct BatchQuoteTarget(pub Vec<QuoteTarget>);
 
 impl BatchQuoteTarget {
-    /// Quotes the targets in batch on the given rpc url
+    /// Quotes the targets in batch on the rpcs of the given provider
     pub async fn do_quote(
         &self,
-        rpc_url: &str,
+        rpc: &RpcProvider,
         block_number: Option<u64>,
         gas: Option<U256>,
         multicall_address: Option<Address>,
     ) -> Result<Vec<QuoteResult>, Error> {
-        batch_quote(&self.0, rpc_url, block_number, gas, multicall_address).await
+        batch_quote(&self.0, rpc, block_number, gas, multicall_address).await
     }
 }
 
@@ -160,24 +166,18 @@ impl QuoteSpec {
     }
 
     /// Given a subgraph url, will fetch the order details from the subgraph and
-    /// then quotes it using the given rpc url.
+    /// then quotes it on the rpcs of the given provider.
     pub async fn do_quote(
         &self,
         subgraph_url: &str,
-        rpc_url: &str,
+        rpc: &RpcProvider,
         block_number: Option<u64>,
         gas: Option<U256>,
         multicall_address: Option<Address>,
     ) -> Result<QuoteResult, Error> {
         let quote_target = self.get_quote_target_from_subgraph(subgraph_url).await?;
-        let quote_result = batch_quote(
-            &[quote_target],
-            rpc_url,
-            block_number,
-            gas,
-            multicall_address,
-        )
-        .await?;
+        let quote_result =
+            batch_quote(&[quote_target], rpc, block_number, gas, multicall_address).await?;
 
         Ok(quote_result.into_iter().next().unwrap())
     }
@@ -236,13 +236,13 @@ impl BatchQuoteSpec {
     }
 
     /// Given a subgraph url, will fetch the order details from the subgraph and
-    /// then quotes them using the given rpc url.
+    /// then quotes them on the rpcs of the given provider.
     /// Those orders that are not found from subgraph are excluded from quoting,
     /// and final result also leaves their place in the array as None
     pub async fn do_quote(
         &self,
         subgraph_url: &str,
-        rpc_url: &str,
+        rpc: &RpcProvider,
         block_number: Option<u64>,
         gas: Option<U256>,
         multicall_address: Option<Address>,
@@ -251,39 +251,52 @@ impl BatchQuoteSpec {
             .get_batch_quote_target_from_subgraph(subgraph_url)
             .await?;
 
-        // quote the valid quote targets
-        let quote_targets: Vec<QuoteTarget> = opts_quote_targets
-            .iter()
-            .filter_map(|v| v.clone())
-            .collect();
-        let mut quote_results = VecDeque::from(
-            batch_quote(
-                &quote_targets,
-                rpc_url,
-                block_number,
-                gas,
-                multicall_address,
-            )
-            .await?,
-        );
+        batch_quote_opt_targets(
+            &opts_quote_targets,
+            rpc,
+            block_number,
+            gas,
+            multicall_address,
+        )
+        .await
+    }
+}
 
-        // fill the array with quote results and invalid quote targets following
-        // their original order
-        let mut result = vec![];
-        opts_quote_targets.iter().for_each(|v| {
-            if v.is_some() {
-                result.push(
-                    quote_results
-                        .pop_front()
-                        .unwrap_or(Err(FailedQuote::NonExistent)),
-                );
-            } else {
-                result.push(Err(FailedQuote::NonExistent))
-            }
-        });
+/// Quotes the given optional quote targets on the rpcs of the given provider.
+/// Those that are None are excluded from quoting, and final result also
+/// leaves their place in the array as [FailedQuote::NonExistent]
+pub async fn batch_quote_opt_targets(
+    opts_quote_targets: &[Option<QuoteTarget>],
+    rpc: &RpcProvider,
+    block_number: Option<u64>,
+    gas: Option<U256>,
+    multicall_address: Option<Address>,
+) -> Result<Vec<QuoteResult>, Error> {
+    // quote the valid quote targets
+    let quote_targets: Vec<QuoteTarget> = opts_quote_targets
+        .iter()
+        .filter_map(|v| v.clone())
+        .collect();
+    let mut quote_results = VecDeque::from(
+        batch_quote(&quote_targets, rpc, block_number, gas, multicall_address).await?,
+    );
+
+    // fill the array with quote results and invalid quote targets following
+    // their original order
+    let mut result = vec![];
+    opts_quote_targets.iter().for_each(|v| {
+        if v.is_some() {
+            result.push(
+                quote_results
+                    .pop_front()
+                    .unwrap_or(Err(FailedQuote::NonExistent)),
+            );
+        } else {
+            result.push(Err(FailedQuote::NonExistent))
+        }
+    });
 
-        Ok(result)
-    }
+    Ok(result)
 }
 
 #[cfg(not(target_family = "wasm"))]
@@ -566,7 +579,7 @@ mod tests {
         let result = quote_target_specifier
             .do_quote(
                 rpc_server.url("/sg").as_str(),
-                rpc_server.url("/rpc").as_str(),
+                &RpcProvider::for_url(rpc_server.url("/rpc").parse().unwrap()),
                 None,
                 None,
                 None,
@@ -631,7 +644,7 @@ mod tests {
         let result = batch_quote_targets_specifiers
             .do_quote(
                 rpc_server.url("/sg").as_str(),
-                rpc_server.url("/rpc").as_str(),
+                &RpcProvider::for_url(rpc_server.url("/rpc").parse().unwrap()),
                 None,
                 None,
                 None,
@@ -690,7 +703,12 @@ mod tests {
         });
 
         let result = quote_target
-            .do_quote(rpc_server.url("/rpc").as_str(), None, None, None)
+            .do_quote(
+                &RpcProvider::for_url(rpc_server.url("/rpc").parse().unwrap()),
+                None,
+                None,
+                None,
+            )
             .await
             .unwrap();
 
@@ -737,7 +755,12 @@ mod tests {
         });
 
         let result = quote_targets
-            .do_quote(rpc_server.url("/rpc").as_str(), None, None, None)
+            .do_quote(
+                &RpcProvider::for_url(rpc_server.url("/rpc").parse().unwrap()),
+                None,
+                None,
+                None,
+            )
             .await
             .unwrap();
         let mut iter_result = result.into_iter();
@@ -751,4 +774,42 @@ mod tests {
         );
         assert!(iter_result.next().is_none());
     }
+
+    #[test]
+    fn test_quote_target_get_io_tokens() {
+        let input_token = Address::random();
+        let output_token = Address::random();
+        let mut quote_target = QuoteTarget {
+            quote_config: Quote {
+                order: OrderV3 {
+                    validInputs: vec![
+                        IO::default(),
+                        IO {
+                            token: input_token,
+                            ..Default::default()
+                        },
+                    ],
+                    validOutputs: vec![IO {
+                        token: output_token,
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                inputIOIndex: U256::from(1),
+                outputIOIndex: U256::from(0),
+                ..Default::default()
+            },
+            orderbook: Address::random(),
+        };
+        assert_eq!(
+            quote_target.get_io_tokens().unwrap(),
+            (input_token, output_token)
+        );
+
+        quote_target.quote_config.outputIOIndex = U256::from(1);
+        assert!(matches!(
+            quote_target.get_io_tokens().unwrap_err(),
+            Error::InvalidQuoteTarget(_)
+        ));
+    }
 }
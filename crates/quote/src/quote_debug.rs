@@ -7,11 +7,14 @@ use rain_interpreter_eval::{
     fork::{Forker, NewForkedEvm},
     trace::RainEvalResult,
 };
+use rain_orderbook_app_settings::rpc::{RpcProvider, RpcProviderError};
 use rain_orderbook_bindings::IOrderBookV4::quoteCall;
-use url::Url;
+use std::sync::Arc;
 
 pub struct NewQuoteDebugger {
-    pub fork_url: Url,
+    /// Rpcs to fork from, the fork is made on the first that serves it and
+    /// keeps reading its state from that rpc
+    pub rpc: Arc<RpcProvider>,
     pub fork_block_number: Option<u64>,
 }
 pub struct QuoteDebugger {
@@ -24,19 +27,28 @@ pub enum QuoteDebuggerError {
     ForkerError(#[from] ForkCallError),
     #[error("Quote error: {0}")]
     QuoteError(#[from] crate::error::Error),
+    #[error(transparent)]
+    RpcProviderError(#[from] RpcProviderError),
 }
 
 impl QuoteDebugger {
     pub async fn new(args: NewQuoteDebugger) -> Result<Self, QuoteDebuggerError> {
-        let forker = Forker::new_with_fork(
-            NewForkedEvm {
-                fork_url: args.fork_url.to_string(),
-                fork_block_number: args.fork_block_number,
-            },
-            None,
-            None,
-        )
-        .await?;
+        let fork_block_number = args.fork_block_number;
+        let forker = args
+            .rpc
+            .with_failover(|url| async move {
+                Forker::new_with_fork(
+                    NewForkedEvm {
+                        fork_url: url.to_string(),
+                        fork_block_number,
+                    },
+                    None,
+                    None,
+                )
+                .await
+                .map_err(QuoteDebuggerError::from)
+            })
+            .await?;
 
         Ok(Self { forker })
     }
@@ -78,12 +90,9 @@ mod tests {
     use super::*;
     use alloy::primitives::utils::parse_ether;
     use alloy::primitives::U256;
-    use alloy::sol_types::{SolCall, SolValue};
+    use alloy::sol_types::SolValue;
     use rain_orderbook_bindings::IOrderBookV4::{OrderV3, Quote};
-    use rain_orderbook_common::add_order::AddOrderArgs;
-    use rain_orderbook_common::dotrain_order::DotrainOrder;
     use rain_orderbook_test_fixtures::LocalEvm;
-    use std::str::FromStr;
 
     #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
     async fn test_quote_debugger() {
@@ -94,70 +103,13 @@ mod tests {
         let token1 = local_evm.tokens[0].clone();
         let token2 = local_evm.tokens[1].clone();
 
-        let dotrain = format!(
-            r#"
-networks:
-    some-key:
-        rpc: {rpc_url}
-        chain-id: 123
-        network-id: 123
-        currency: ETH
-deployers:
-    some-key:
-        address: {deployer}
-tokens:
-    t1:
-        network: some-key
-        address: {token2}
-        decimals: 18
-        label: Token2
-        symbol: Token2
-    t2:
-        network: some-key
-        address: {token1}
-        decimals: 18
-        label: Token1
-        symbol: token1
-orderbook:
-    some-key:
-        address: {orderbook}
-orders:
-    some-key:
-        inputs:
-            - token: t1
-        outputs:
-            - token: t2
-              vault-id: 0x01
-scenarios:
-    some-key:
-deployments:
-    some-key:
-        scenario: some-key
-        order: some-key
----
-#calculate-io
-amount price: 16 52;
-#handle-add-order
-:;
-#handle-io
-:;
-"#,
-            rpc_url = local_evm.url(),
-            orderbook = orderbook.address(),
-            deployer = local_evm.deployer.address(),
-            token1 = token1.address(),
-            token2 = token2.address(),
-        );
-
-        let order = DotrainOrder::new(dotrain.clone(), None).await.unwrap();
-        let deployment = order.config().deployments["some-key"].as_ref().clone();
-        let calldata = AddOrderArgs::new_from_deployment(dotrain, deployment)
-            .await
-            .unwrap()
-            .try_into_call(local_evm.url())
-            .await
-            .unwrap()
-            .abi_encode();
+        let calldata = local_evm
+            .add_order_calldata(
+                "amount price: 16 52;:;",
+                &[(*token2.address(), 18, U256::from(2))],
+                &[(*token1.address(), 18, U256::from(1))],
+            )
+            .await;
 
         let order = local_evm
             .add_order_and_deposit(
@@ -172,7 +124,7 @@ amount price: 16 52;
             .order;
 
         let mut debugger = QuoteDebugger::new(NewQuoteDebugger {
-            fork_url: Url::from_str(&local_evm.url()).unwrap(),
+            rpc: RpcProvider::for_url(local_evm.url().parse().unwrap()),
             fork_block_number: None,
         })
         .await
@@ -208,75 +160,20 @@ amount price: 16 52;
         let token1 = local_evm.tokens[0].clone();
         let token2 = local_evm.tokens[1].clone();
 
-        let dotrain = format!(
-            r#"
-networks:
-    some-key:
-        rpc: {rpc_url}
-        chain-id: 123
-        network-id: 123
-        currency: ETH
-deployers:
-    some-key:
-        address: {deployer}
-tokens:
-    t1:
-        network: some-key
-        address: {token2}
-        decimals: 18
-        label: Token2
-        symbol: Token2
-    t2:
-        network: some-key
-        address: {token1}
-        decimals: 18
-        label: Token1
-        symbol: token1
-orderbook:
-    some-key:
-        address: {orderbook}
-orders:
-    some-key:
-        inputs:
-            - token: t1
-        outputs:
-            - token: t2
-              vault-id: 0x01
-scenarios:
-    some-key:
-deployments:
-    some-key:
-        scenario: some-key
-        order: some-key
----
-#calculate-io
+        let calldata = local_evm
+            .add_order_calldata(
+                r#"
 amount price: 16 52,
-current-time: call<'some-source>(),
+current-time: call<2>(),
 _: sub(16 52),
 _ _: amount price;
-#handle-add-order
-:;
-#handle-io
 :;
-#some-source
 _: 1;
 "#,
-            rpc_url = local_evm.url(),
-            orderbook = orderbook.address(),
-            deployer = local_evm.deployer.address(),
-            token1 = token1.address(),
-            token2 = token2.address(),
-        );
-
-        let order = DotrainOrder::new(dotrain.clone(), None).await.unwrap();
-        let deployment = order.config().deployments["some-key"].as_ref().clone();
-        let calldata = AddOrderArgs::new_from_deployment(dotrain, deployment)
-            .await
-            .unwrap()
-            .try_into_call(local_evm.url())
-            .await
-            .unwrap()
-            .abi_encode();
+                &[(*token2.address(), 18, U256::from(2))],
+                &[(*token1.address(), 18, U256::from(1))],
+            )
+            .await;
 
         let order = local_evm
             .add_order_and_deposit(
@@ -291,7 +188,7 @@ _: 1;
             .order;
 
         let mut debugger = QuoteDebugger::new(NewQuoteDebugger {
-            fork_url: Url::from_str(&local_evm.url()).unwrap(),
+            rpc: RpcProvider::for_url(local_evm.url().parse().unwrap()),
             fork_block_number: None,
         })
         .await
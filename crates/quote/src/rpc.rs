@@ -1,70 +1,198 @@
 use crate::{
     error::{Error, FailedQuote},
+    formatted::QuoteToken,
     quote::{QuoteResult, QuoteTarget},
 };
 use alloy::primitives::{hex::FromHex, Address, U256, U64};
 use alloy::sol_types::SolCall;
 use alloy_ethers_typecast::{
     multicall::{
-        IMulticall3::{aggregate3Call, Call3},
+        IMulticall3::{
+            aggregate3Call, aggregate3Return, getCurrentBlockTimestampCall, Call3,
+            Result as MulticallResult,
+        },
         MULTICALL3_ADDRESS,
     },
     transaction::{ReadContractParameters, ReadableClient},
 };
 use rain_error_decoding::AbiDecodedErrorType;
-use rain_orderbook_bindings::IOrderBookV4::quoteCall;
+use rain_orderbook_app_settings::rpc::RpcProvider;
+use rain_orderbook_bindings::{
+    IOrderBookV4::quoteCall,
+    IERC20::{decimalsCall, symbolCall},
+};
 
-/// Quotes array of given quote targets using the given rpc url
+/// Quotes array of given quote targets on the rpcs of the given provider
 pub async fn batch_quote(
     quote_targets: &[QuoteTarget],
-    rpc: &str,
+    rpc: &RpcProvider,
     block_number: Option<u64>,
     gas: Option<U256>,
     multicall_address: Option<Address>,
 ) -> Result<Vec<QuoteResult>, Error> {
+    let calls: Vec<Call3> = quote_targets.iter().map(quote_call3).collect();
+    let multicall_result = read_aggregate3(
+        rpc,
+        calls,
+        multicall_address.unwrap_or(Address::from_hex(MULTICALL3_ADDRESS).unwrap()),
+        block_number,
+        gas,
+    )
+    .await?;
+
+    let mut result: Vec<QuoteResult> = vec![];
+    for res in &multicall_result.returnData {
+        result.push(decode_quote_result(res).await);
+    }
+    Ok(result)
+}
+
+/// Quotes array of given quote targets at the given block on the rpcs of the
+/// given provider, along with the timestamp of that block read in the same
+/// multicall
+pub async fn batch_quote_with_timestamp(
+    quote_targets: &[QuoteTarget],
+    rpc: &RpcProvider,
+    block_number: u64,
+    gas: Option<U256>,
+    multicall_address: Option<Address>,
+) -> Result<(u64, Vec<QuoteResult>), Error> {
+    let multicall_address =
+        multicall_address.unwrap_or(Address::from_hex(MULTICALL3_ADDRESS).unwrap());
+    let mut calls: Vec<Call3> = quote_targets.iter().map(quote_call3).collect();
+    calls.push(Call3 {
+        allowFailure: false,
+        target: multicall_address,
+        callData: getCurrentBlockTimestampCall {}.abi_encode().into(),
+    });
+    let mut multicall_result =
+        read_aggregate3(rpc, calls, multicall_address, Some(block_number), gas).await?;
+
+    let timestamp = multicall_result
+        .returnData
+        .pop()
+        .map(|v| getCurrentBlockTimestampCall::abi_decode_returns(&v.returnData, true))
+        .transpose()?
+        .map(|v| v.timestamp.saturating_to::<u64>())
+        .unwrap_or_default();
+
+    let mut result: Vec<QuoteResult> = vec![];
+    for res in &multicall_result.returnData {
+        result.push(decode_quote_result(res).await);
+    }
+    Ok((timestamp, result))
+}
+
+fn quote_call3(quote_target: &QuoteTarget) -> Call3 {
+    Call3 {
+        allowFailure: true,
+        target: quote_target.orderbook,
+        callData: quoteCall {
+            quoteConfig: quote_target.quote_config.clone(),
+        }
+        .abi_encode()
+        .into(),
+    }
+}
+
+/// Reads the calls in a single multicall, failing over between the rpcs of
+/// the provider
+async fn read_aggregate3(
+    rpc: &RpcProvider,
+    calls: Vec<Call3>,
+    multicall_address: Address,
+    block_number: Option<u64>,
+    gas: Option<U256>,
+) -> Result<aggregate3Return, Error> {
+    rpc.with_failover(|url| {
+        let calls = calls.clone();
+        async move {
+            let client = ReadableClient::new_from_url(url.to_string())?;
+            let parameters = ReadContractParameters {
+                gas,
+                address: multicall_address,
+                block_number: block_number.map(U64::from),
+                call: aggregate3Call { calls },
+            };
+            Ok::<_, Error>(client.read(parameters).await?)
+        }
+    })
+    .await
+}
+
+/// Decodes a quote call result of a multicall
+pub async fn decode_quote_result(res: &MulticallResult) -> QuoteResult {
+    if res.success {
+        match quoteCall::abi_decode_returns(&res.returnData, true) {
+            Ok(v) => {
+                if v.exists {
+                    Ok(v.into())
+                } else {
+                    Err(FailedQuote::NonExistent)
+                }
+            }
+            Err(e) => Err(FailedQuote::CorruptReturnData(e.to_string())),
+        }
+    } else {
+        match AbiDecodedErrorType::selector_registry_abi_decode(&res.returnData).await {
+            Ok(e) => Err(FailedQuote::RevertError(e)),
+            Err(e) => Err(FailedQuote::RevertErrorDecodeFailed(e)),
+        }
+    }
+}
+
+/// Reads symbol and decimals of the given tokens in a single multicall using
+/// the given rpc url, those that fail to resolve are returned as None
+pub async fn batch_token_metadata(
+    tokens: &[Address],
+    rpc: &str,
+    block_number: Option<u64>,
+    multicall_address: Option<Address>,
+) -> Result<Vec<QuoteToken>, Error> {
     let client = ReadableClient::new_from_url(rpc.to_string())?;
     let parameters = ReadContractParameters {
-        gas,
+        gas: None,
         address: multicall_address.unwrap_or(Address::from_hex(MULTICALL3_ADDRESS).unwrap()),
         block_number: block_number.map(U64::from),
         call: aggregate3Call {
-            calls: quote_targets
+            calls: tokens
                 .iter()
-                .map(|quote_target| Call3 {
-                    allowFailure: true,
-                    target: quote_target.orderbook,
-                    callData: quoteCall {
-                        quoteConfig: quote_target.quote_config.clone(),
-                    }
-                    .abi_encode()
-                    .into(),
+                .flat_map(|token| {
+                    [
+                        Call3 {
+                            allowFailure: true,
+                            target: *token,
+                            callData: symbolCall {}.abi_encode().into(),
+                        },
+                        Call3 {
+                            allowFailure: true,
+                            target: *token,
+                            callData: decimalsCall {}.abi_encode().into(),
+                        },
+                    ]
                 })
                 .collect(),
         },
     };
     let multicall_result = client.read(parameters).await?;
 
-    let mut result: Vec<QuoteResult> = vec![];
-    for res in multicall_result.returnData {
-        if res.success {
-            match quoteCall::abi_decode_returns(&res.returnData, true) {
-                Ok(v) => {
-                    if v.exists {
-                        result.push(Ok(v.into()));
-                    } else {
-                        result.push(Err(FailedQuote::NonExistent));
-                    }
-                }
-                Err(e) => result.push(Err(FailedQuote::CorruptReturnData(e.to_string()))),
-            }
-        } else {
-            match AbiDecodedErrorType::selector_registry_abi_decode(&res.returnData).await {
-                Ok(e) => result.push(Err(FailedQuote::RevertError(e))),
-                Err(e) => result.push(Err(FailedQuote::RevertErrorDecodeFailed(e))),
-            }
-        }
-    }
-    Ok(result)
+    Ok(tokens
+        .iter()
+        .zip(multicall_result.returnData.chunks(2))
+        .map(|(token, res)| QuoteToken {
+            address: *token,
+            symbol: res
+                .first()
+                .filter(|v| v.success)
+                .and_then(|v| symbolCall::abi_decode_returns(&v.returnData, true).ok())
+                .map(|v| v._0),
+            decimals: res
+                .get(1)
+                .filter(|v| v.success)
+                .and_then(|v| decimalsCall::abi_decode_returns(&v.returnData, true).ok())
+                .map(|v| v._0),
+        })
+        .collect())
 }
 
 #[cfg(not(target_family = "wasm"))]
@@ -74,7 +202,6 @@ mod tests {
     use crate::quote::OrderQuoteValue;
     use alloy::primitives::hex::encode_prefixed;
     use alloy::sol_types::SolValue;
-    use alloy_ethers_typecast::multicall::IMulticall3::Result as MulticallResult;
     use alloy_ethers_typecast::{
         request_shim::{AlloyTransactionRequest, TransactionRequestShim},
         rpc::{eip2718::TypedTransaction, BlockNumber, Request, Response},
@@ -155,7 +282,7 @@ mod tests {
 
         let result = batch_quote(
             &quote_targets,
-            rpc_server.url("/").as_str(),
+            &RpcProvider::for_url(rpc_server.url("/").parse().unwrap()),
             None,
             None,
             None,
@@ -181,4 +308,62 @@ mod tests {
         );
         assert!(iter_result.next().is_none());
     }
+
+    #[tokio::test]
+    async fn test_batch_token_metadata() {
+        let rpc_server = MockServer::start_async().await;
+
+        let token1 = Address::random();
+        let token2 = Address::random();
+
+        // build response data, second token's symbol call fails
+        let response_data = vec![
+            MulticallResult {
+                success: true,
+                returnData: symbolCall::abi_encode_returns(&("T1".to_string(),)).into(),
+            },
+            MulticallResult {
+                success: true,
+                returnData: decimalsCall::abi_encode_returns(&(6u8,)).into(),
+            },
+            MulticallResult {
+                success: false,
+                returnData: vec![].into(),
+            },
+            MulticallResult {
+                success: true,
+                returnData: decimalsCall::abi_encode_returns(&(18u8,)).into(),
+            },
+        ]
+        .abi_encode();
+        rpc_server.mock(|when, then| {
+            when.method(POST).path("/");
+            then.json_body_obj(
+                &from_str::<Value>(
+                    &Response::new_success(1, encode_prefixed(response_data).as_str())
+                        .to_json_string()
+                        .unwrap(),
+                )
+                .unwrap(),
+            );
+        });
+
+        let result =
+            batch_token_metadata(&[token1, token2], rpc_server.url("/").as_str(), None, None)
+                .await
+                .unwrap();
+        let expected = vec![
+            QuoteToken {
+                address: token1,
+                symbol: Some("T1".to_string()),
+                decimals: Some(6),
+            },
+            QuoteToken {
+                address: token2,
+                symbol: None,
+                decimals: Some(18),
+            },
+        ];
+        assert_eq!(result, expected);
+    }
 }
@@ -0,0 +1,535 @@
+use crate::{
+    error::Error,
+    quote::{QuoteResult, QuoteTarget},
+    rpc::batch_quote_with_timestamp,
+    FormattedOrderQuoteValue, OrderQuoteValue,
+};
+use alloy::primitives::{utils::format_units, Address, I256, U256};
+use alloy_ethers_typecast::transaction::ReadableClient;
+use futures::{StreamExt, TryStreamExt};
+use rain_orderbook_app_settings::{
+    blocks::{BlockTimestamps, Blocks},
+    plot_source::{DotOptions, LineOptions, Mark, Plot},
+    rpc::RpcProvider,
+};
+use rain_orderbook_math::FIXED_POINT_DECIMALS;
+use rain_orderbook_subgraph_client::types::common::{Trade, TradeVaultBalanceChange};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, str::FromStr, sync::Arc};
+use typeshare::typeshare;
+
+/// Max number of blocks that are quoted concurrently
+const MAX_CONCURRENT_BLOCKS: usize = 10;
+
+/// Quote of an order at a specific block
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteSeriesPoint {
+    #[typeshare(typescript(type = "number"))]
+    pub block_number: u64,
+    #[typeshare(typescript(type = "number"))]
+    pub timestamp: u64,
+    pub data: Option<OrderQuoteValue>,
+    pub formatted: Option<FormattedOrderQuoteValue>,
+    pub error: Option<String>,
+}
+
+impl QuoteSeriesPoint {
+    pub fn new(block_number: u64, timestamp: u64, quote_result: QuoteResult) -> Self {
+        match quote_result {
+            Ok(v) => Self {
+                block_number,
+                timestamp,
+                formatted: v.formatted().ok(),
+                data: Some(v),
+                error: None,
+            },
+            Err(e) => Self {
+                block_number,
+                timestamp,
+                data: None,
+                formatted: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+}
+
+/// Quotes of a quote target over a range of blocks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteSeries {
+    pub quote_target: QuoteTarget,
+    pub points: Vec<QuoteSeriesPoint>,
+}
+
+impl QuoteSeries {
+    /// Flattens the successful points of the series into rows of numbers
+    /// keyed by `block-number`, `timestamp`, `max-output`, `ratio` and
+    /// `inverse-ratio`, which can be referenced by a [Plot] marks
+    pub fn plot_data(&self) -> Vec<BTreeMap<String, f64>> {
+        let to_f64 = |value: U256| {
+            format_units(value, FIXED_POINT_DECIMALS)
+                .ok()
+                .and_then(|v| v.parse::<f64>().ok())
+        };
+        self.points
+            .iter()
+            .filter_map(|point| {
+                let data = point.data.as_ref()?;
+                let mut row = BTreeMap::new();
+                row.insert("block-number".to_string(), point.block_number as f64);
+                row.insert("timestamp".to_string(), point.timestamp as f64);
+                row.insert("max-output".to_string(), to_f64(data.max_output)?);
+                row.insert("ratio".to_string(), to_f64(data.ratio)?);
+                if let Some(inverse_ratio) = data.inverse_ratio().ok().flatten() {
+                    row.insert("inverse-ratio".to_string(), to_f64(inverse_ratio)?);
+                }
+                Some(row)
+            })
+            .collect()
+    }
+
+    /// [QuoteSeries::plot_data] followed by a row for each of the given trades
+    /// of the order on the quoted IO pair, keyed by `timestamp`, `trade-output`
+    /// and `trade-ratio`. The trade rows come last so they don't break the
+    /// lines drawn through the series rows
+    pub fn plot_data_with_trades(&self, trades: &[Trade]) -> Vec<BTreeMap<String, f64>> {
+        let quote = &self.quote_target.quote_config;
+        let input_token = usize::try_from(quote.inputIOIndex)
+            .ok()
+            .and_then(|index| quote.order.validInputs.get(index))
+            .map(|io| io.token);
+        let output_token = usize::try_from(quote.outputIOIndex)
+            .ok()
+            .and_then(|index| quote.order.validOutputs.get(index))
+            .map(|io| io.token);
+        let mut rows = self.plot_data();
+        if let (Some(input_token), Some(output_token)) = (input_token, output_token) {
+            rows.extend(
+                trades
+                    .iter()
+                    .filter_map(|trade| trade_plot_row(trade, input_token, output_token)),
+            );
+        }
+        rows
+    }
+
+    /// A line plot of the ratio over time for the [QuoteSeries::plot_data]
+    pub fn default_plot() -> Plot {
+        Plot {
+            title: Some("IO Ratio".to_string()),
+            subtitle: None,
+            marks: vec![Mark::Line(LineOptions {
+                x: Some("timestamp".to_string()),
+                y: Some("ratio".to_string()),
+                r: None,
+                fill: None,
+                stroke: None,
+                transform: None,
+            })],
+            x: None,
+            y: None,
+            margin: None,
+            margin_left: None,
+            margin_right: None,
+            margin_top: None,
+            margin_bottom: None,
+            inset: None,
+        }
+    }
+
+    /// The [QuoteSeries::default_plot] with the ratios the order actually
+    /// traded at dotted over it, for the [QuoteSeries::plot_data_with_trades]
+    pub fn default_plot_with_trades() -> Plot {
+        let mut plot = Self::default_plot();
+        plot.marks.push(Mark::Dot(DotOptions {
+            x: Some("timestamp".to_string()),
+            y: Some("trade-ratio".to_string()),
+            r: None,
+            fill: None,
+            stroke: None,
+            transform: None,
+        }));
+        plot
+    }
+}
+
+/// The plot row of a trade if it traded the given input and output tokens,
+/// its ratio being the input received per output sent like a quote's ratio
+fn trade_plot_row(
+    trade: &Trade,
+    input_token: Address,
+    output_token: Address,
+) -> Option<BTreeMap<String, f64>> {
+    let token = |change: &TradeVaultBalanceChange| Address::from_str(&change.vault.token.address.0);
+    if token(&trade.input_vault_balance_change).ok()? != input_token
+        || token(&trade.output_vault_balance_change).ok()? != output_token
+    {
+        return None;
+    }
+    let amount = |change: &TradeVaultBalanceChange| {
+        let decimals = change.vault.token.decimals.as_ref()?.0.parse::<u8>().ok()?;
+        let amount = I256::from_dec_str(&change.amount.0).ok()?.abs();
+        format_units(amount, decimals).ok()?.parse::<f64>().ok()
+    };
+    let input = amount(&trade.input_vault_balance_change)?;
+    let output = amount(&trade.output_vault_balance_change)?;
+    if output == 0.0 {
+        return None;
+    }
+    let mut row = BTreeMap::new();
+    row.insert(
+        "timestamp".to_string(),
+        trade.timestamp.0.parse::<f64>().ok()?,
+    );
+    row.insert("trade-output".to_string(), output);
+    row.insert("trade-ratio".to_string(), input / output);
+    Some(row)
+}
+
+/// Quotes array of given quote targets at every block of the given blocks
+/// on the rpcs of the given provider, each block is quoted in a single
+/// multicall along with its timestamp. Dates and time intervals of the blocks
+/// are resolved from the block timestamps of the rpcs' chain
+pub async fn batch_quote_series(
+    quote_targets: &[QuoteTarget],
+    blocks: &Blocks,
+    rpc: &Arc<RpcProvider>,
+    gas: Option<U256>,
+    multicall_address: Option<Address>,
+) -> Result<Vec<QuoteSeries>, Error> {
+    for quote_target in quote_targets {
+        quote_target.validate()?;
+    }
+    let latest_block = rpc
+        .with_failover(|url| async move {
+            Ok::<_, Error>(
+                ReadableClient::new_from_url(url.to_string())?
+                    .get_block_number()
+                    .await?,
+            )
+        })
+        .await?;
+    let block_numbers = if blocks.uses_timestamps() {
+        let chain_id: u64 = rpc
+            .with_failover(|url| async move {
+                Ok::<_, Error>(
+                    ReadableClient::new_from_url(url.to_string())?
+                        .get_chainid()
+                        .await?
+                        .saturating_to(),
+                )
+            })
+            .await?;
+        blocks
+            .resolve_block_numbers(&BlockTimestamps::new(rpc.clone(), chain_id), latest_block)
+            .await?
+    } else {
+        blocks.expand_to_block_numbers(latest_block)?
+    };
+
+    let blocks_results =
+        futures::stream::iter(block_numbers.into_iter().map(|block_number| async move {
+            batch_quote_with_timestamp(quote_targets, rpc, block_number, gas, multicall_address)
+                .await
+                .map(|(timestamp, results)| (block_number, timestamp, results))
+        }))
+        .buffered(MAX_CONCURRENT_BLOCKS)
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    let mut series: Vec<QuoteSeries> = quote_targets
+        .iter()
+        .map(|quote_target| QuoteSeries {
+            quote_target: quote_target.clone(),
+            points: vec![],
+        })
+        .collect();
+    for (block_number, timestamp, results) in blocks_results {
+        for (quote_series, quote_result) in series.iter_mut().zip(results) {
+            quote_series
+                .points
+                .push(QuoteSeriesPoint::new(block_number, timestamp, quote_result));
+        }
+    }
+
+    Ok(series)
+}
+
+#[cfg(not(target_family = "wasm"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::FailedQuote;
+    use alloy::primitives::utils::parse_ether;
+    use alloy::providers::Provider;
+    use alloy::sol_types::SolValue;
+    use rain_orderbook_app_settings::blocks::BlockRange;
+    use rain_orderbook_bindings::IOrderBookV4::{OrderV3, Quote, IO};
+    use rain_orderbook_subgraph_client::types::common::{
+        BigInt, Bytes, Erc20, Orderbook, TradeEvent, TradeStructPartialOrder, Transaction,
+        VaultBalanceChangeVault,
+    };
+    use rain_orderbook_test_fixtures::LocalEvm;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_quote_series_point_new() {
+        let value = OrderQuoteValue {
+            max_output: parse_ether("3").unwrap(),
+            ratio: parse_ether("2").unwrap(),
+        };
+        let result = QuoteSeriesPoint::new(10, 1000, Ok(value));
+        let expected = QuoteSeriesPoint {
+            block_number: 10,
+            timestamp: 1000,
+            data: Some(value),
+            formatted: Some(value.formatted().unwrap()),
+            error: None,
+        };
+        assert_eq!(result, expected);
+
+        let result = QuoteSeriesPoint::new(10, 1000, Err(FailedQuote::NonExistent));
+        let expected = QuoteSeriesPoint {
+            block_number: 10,
+            timestamp: 1000,
+            data: None,
+            formatted: None,
+            error: Some(FailedQuote::NonExistent.to_string()),
+        };
+        assert_eq!(result, expected);
+    }
+
+    fn series() -> QuoteSeries {
+        QuoteSeries {
+            quote_target: QuoteTarget::default(),
+            points: vec![
+                QuoteSeriesPoint::new(
+                    10,
+                    1000,
+                    Ok(OrderQuoteValue {
+                        max_output: parse_ether("3").unwrap(),
+                        ratio: parse_ether("2").unwrap(),
+                    }),
+                ),
+                QuoteSeriesPoint::new(11, 1012, Err(FailedQuote::NonExistent)),
+                QuoteSeriesPoint::new(
+                    12,
+                    1024,
+                    Ok(OrderQuoteValue {
+                        max_output: parse_ether("1.5").unwrap(),
+                        ratio: U256::ZERO,
+                    }),
+                ),
+            ],
+        }
+    }
+
+    fn vault_balance_change(token: Address, decimals: u8, amount: &str) -> TradeVaultBalanceChange {
+        let transaction = Transaction {
+            id: Bytes("transaction_id".to_owned()),
+            from: Bytes("from_address".to_owned()),
+            block_number: BigInt("1".to_owned()),
+            timestamp: BigInt("1".to_owned()),
+        };
+        TradeVaultBalanceChange {
+            id: Bytes("change_id".to_owned()),
+            __typename: "TradeVaultBalanceChange".to_owned(),
+            amount: BigInt(amount.to_owned()),
+            new_vault_balance: BigInt("0".to_owned()),
+            old_vault_balance: BigInt("0".to_owned()),
+            vault: VaultBalanceChangeVault {
+                id: Bytes("vault_id".to_owned()),
+                vault_id: BigInt("1".to_owned()),
+                token: Erc20 {
+                    id: Bytes("token_id".to_owned()),
+                    address: Bytes(token.to_string()),
+                    name: None,
+                    symbol: None,
+                    decimals: Some(BigInt(decimals.to_string())),
+                },
+            },
+            timestamp: BigInt("1".to_owned()),
+            transaction,
+            orderbook: Orderbook {
+                id: Bytes("orderbook_id".to_owned()),
+            },
+        }
+    }
+
+    fn trade(
+        timestamp: u64,
+        input: TradeVaultBalanceChange,
+        output: TradeVaultBalanceChange,
+    ) -> Trade {
+        Trade {
+            id: Bytes("trade_id".to_owned()),
+            trade_event: TradeEvent {
+                transaction: input.transaction.clone(),
+                sender: Bytes("sender_address".to_owned()),
+            },
+            output_vault_balance_change: output,
+            order: TradeStructPartialOrder {
+                id: Bytes("order_id".to_owned()),
+                order_hash: Bytes("order_hash".to_owned()),
+            },
+            input_vault_balance_change: input,
+            timestamp: BigInt(timestamp.to_string()),
+            orderbook: Orderbook {
+                id: Bytes("orderbook_id".to_owned()),
+            },
+        }
+    }
+
+    #[test]
+    fn test_plot_data() {
+        let result = series().plot_data();
+        let expected = vec![
+            BTreeMap::from([
+                ("block-number".to_string(), 10.0),
+                ("timestamp".to_string(), 1000.0),
+                ("max-output".to_string(), 3.0),
+                ("ratio".to_string(), 2.0),
+                ("inverse-ratio".to_string(), 0.5),
+            ]),
+            BTreeMap::from([
+                ("block-number".to_string(), 12.0),
+                ("timestamp".to_string(), 1024.0),
+                ("max-output".to_string(), 1.5),
+                ("ratio".to_string(), 0.0),
+            ]),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_plot_data_with_trades() {
+        let input_token = Address::random();
+        let output_token = Address::random();
+        let mut series = series();
+        series.quote_target.quote_config.order.validInputs = vec![IO {
+            token: input_token,
+            decimals: 6,
+            vaultId: U256::from(1),
+        }];
+        series.quote_target.quote_config.order.validOutputs = vec![IO {
+            token: output_token,
+            decimals: 18,
+            vaultId: U256::from(1),
+        }];
+
+        let trades = vec![
+            // received 5 of the input for 2 of the output
+            trade(
+                1010,
+                vault_balance_change(input_token, 6, "5000000"),
+                vault_balance_change(output_token, 18, "-2000000000000000000"),
+            ),
+            // traded the other way around, on another IO pair
+            trade(
+                1020,
+                vault_balance_change(output_token, 18, "1000000000000000000"),
+                vault_balance_change(input_token, 6, "-1000000"),
+            ),
+        ];
+        let result = series.plot_data_with_trades(&trades);
+
+        let mut expected = series.plot_data();
+        expected.push(BTreeMap::from([
+            ("timestamp".to_string(), 1010.0),
+            ("trade-output".to_string(), 2.0),
+            ("trade-ratio".to_string(), 2.5),
+        ]));
+        assert_eq!(result, expected);
+
+        let plot = QuoteSeries::default_plot_with_trades();
+        assert_eq!(plot.marks[0], QuoteSeries::default_plot().marks[0]);
+        assert_eq!(
+            plot.marks[1],
+            Mark::Dot(DotOptions {
+                x: Some("timestamp".to_string()),
+                y: Some("trade-ratio".to_string()),
+                r: None,
+                fill: None,
+                stroke: None,
+                transform: None,
+            })
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+    async fn test_batch_quote_series() {
+        let local_evm = LocalEvm::new_with_tokens(2).await;
+
+        let orderbook = &local_evm.orderbook;
+        let token1_holder = local_evm.signer_wallets[0].default_signer().address();
+        let token1 = local_evm.tokens[0].clone();
+        let token2 = local_evm.tokens[1].clone();
+
+        let calldata = local_evm
+            .add_order_calldata(
+                "amount price: 16 52;:;",
+                &[(*token2.address(), 18, U256::from(2))],
+                &[(*token1.address(), 18, U256::from(1))],
+            )
+            .await;
+
+        let start_block = local_evm.provider.get_block_number().await.unwrap();
+        let order = local_evm
+            .add_order_and_deposit(
+                &calldata,
+                token1_holder,
+                *token1.address(),
+                parse_ether("1000").unwrap(),
+                U256::from(1),
+            )
+            .await
+            .0
+            .order;
+        let end_block = local_evm.provider.get_block_number().await.unwrap();
+
+        let quote_target = QuoteTarget {
+            orderbook: *orderbook.address(),
+            quote_config: Quote {
+                order: OrderV3::abi_decode(&order.abi_encode(), true).unwrap(),
+                inputIOIndex: U256::from(0),
+                outputIOIndex: U256::from(0),
+                signedContext: vec![],
+            },
+        };
+        let blocks = Blocks::SimpleRange(
+            BlockRange::from_str(&format!("{}..{}", start_block, end_block)).unwrap(),
+        );
+
+        let result = batch_quote_series(
+            &[quote_target.clone()],
+            &blocks,
+            &RpcProvider::for_url(local_evm.url().parse().unwrap()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].quote_target, quote_target);
+        let points = &result[0].points;
+        assert_eq!(
+            points.iter().map(|v| v.block_number).collect::<Vec<_>>(),
+            (start_block..=end_block).collect::<Vec<_>>()
+        );
+        assert!(points.windows(2).all(|v| v[0].timestamp <= v[1].timestamp));
+
+        // the order does not exist before it is added
+        assert_eq!(points[0].error, Some(FailedQuote::NonExistent.to_string()));
+        assert_eq!(
+            points.last().unwrap().data,
+            Some(OrderQuoteValue {
+                max_output: parse_ether("16").unwrap(),
+                ratio: parse_ether("52").unwrap(),
+            })
+        );
+    }
+}
@@ -1,11 +1,29 @@
+use crate::{
+    rpc::{json_rpc, parse_quantity, RpcProvider, RpcProviderError},
+    schema::pattern,
+    Network,
+};
 use alloy::primitives::BlockNumber;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat};
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{Schema, SchemaObject, SubschemaValidation},
+    JsonSchema,
+};
 use serde::{
     de::{self, Visitor},
     Deserialize, Deserializer, Serialize, Serializer,
 };
-use std::fmt;
+use serde_json::json;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    str::FromStr,
+    sync::{Arc, Mutex, OnceLock},
+};
 use thiserror::Error;
 use typeshare::typeshare;
+use url::Url;
 
 #[derive(Debug, PartialEq, Deserialize, Serialize, Clone)]
 #[typeshare]
@@ -14,14 +32,50 @@ pub enum Block {
     Number(BlockNumber),
     Genesis,
     Latest,
+    /// The last block at or before a unix timestamp
+    #[typeshare(skip)]
+    Timestamp(u64),
+    /// A number of blocks before the latest block
+    #[typeshare(skip)]
+    LatestMinusBlocks(u64),
+    /// The last block at or before a number of seconds before the latest block
+    #[typeshare(skip)]
+    LatestMinusSeconds(u64),
 }
 
 impl Block {
-    pub fn to_block_number(&self, latest_block: BlockNumber) -> BlockNumber {
+    pub fn to_block_number(&self, latest_block: BlockNumber) -> Result<BlockNumber, BlockError> {
+        match self {
+            Block::Number(n) => Ok(*n),
+            Block::Genesis => Ok(0),
+            Block::Latest => Ok(latest_block),
+            Block::LatestMinusBlocks(n) => Ok(latest_block.saturating_sub(*n)),
+            Block::Timestamp(_) | Block::LatestMinusSeconds(_) => {
+                Err(BlockError::TimestampsRequired)
+            }
+        }
+    }
+
+    pub fn uses_timestamps(&self) -> bool {
+        matches!(self, Block::Timestamp(_) | Block::LatestMinusSeconds(_))
+    }
+
+    /// Like [Block::to_block_number], reading block timestamps to resolve
+    /// the time based blocks
+    pub async fn resolve(
+        &self,
+        timestamps: &BlockTimestamps,
+        latest_block: BlockNumber,
+    ) -> Result<BlockNumber, BlockError> {
         match self {
-            Block::Number(n) => *n,
-            Block::Genesis => 0,
-            Block::Latest => latest_block,
+            Block::Timestamp(timestamp) => timestamps.block_at(*timestamp, latest_block).await,
+            Block::LatestMinusSeconds(seconds) => {
+                let latest_timestamp = timestamps.timestamp(latest_block).await?;
+                timestamps
+                    .block_at(latest_timestamp.saturating_sub(*seconds), latest_block)
+                    .await
+            }
+            _ => self.to_block_number(latest_block),
         }
     }
 }
@@ -35,13 +89,25 @@ pub struct BlockRange {
 
 impl BlockRange {
     pub fn validate(&self, latest_block: BlockNumber) -> Result<(), BlockError> {
-        let start = self.start.to_block_number(latest_block);
-        let end = self.end.to_block_number(latest_block);
+        let start = self.start.to_block_number(latest_block)?;
+        let end = self.end.to_block_number(latest_block)?;
         if start > end {
             return Err(BlockError::InvalidBlockRange);
         }
         Ok(())
     }
+
+    pub fn uses_timestamps(&self) -> bool {
+        self.start.uses_timestamps() || self.end.uses_timestamps()
+    }
+}
+
+impl FromStr for BlockRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_range(s)
+    }
 }
 
 // Serialize implementation for BlockRange
@@ -50,23 +116,11 @@ impl Serialize for BlockRange {
     where
         S: Serializer,
     {
-        let mut range_string = String::new();
-
-        match &self.start {
-            Block::Genesis => range_string.push_str(""),
-            Block::Latest => range_string.push_str(""),
-            Block::Number(n) => range_string.push_str(&n.to_string()),
-        }
-
-        range_string.push_str("..");
-
-        match &self.end {
-            Block::Genesis => range_string.push_str(""),
-            Block::Latest => range_string.push_str(""),
-            Block::Number(n) => range_string.push_str(&n.to_string()),
-        }
-
-        serializer.serialize_str(&range_string)
+        serializer.serialize_str(&format!(
+            "{}..{}",
+            format_block(&self.start),
+            format_block(&self.end)
+        ))
     }
 }
 
@@ -80,6 +134,34 @@ impl<'de> Deserialize<'de> for BlockRange {
     }
 }
 
+// a range string such as `100..200`, or the same split into a sequence
+impl JsonSchema for BlockRange {
+    fn schema_name() -> String {
+        "BlockRange".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let block =
+            "([0-9]*|[0-9]{4}-[0-9]{2}-[0-9]{2}(T[0-9:.]+(Z|[+-][0-9:]+)?)?|latest(-[0-9]+[smhdw]?)?)";
+        let mut schema = SchemaObject {
+            subschemas: Some(Box::new(SubschemaValidation {
+                one_of: Some(vec![
+                    pattern(&format!("{}\\.\\.{}", block, block)).into(),
+                    gen.subschema_for::<Vec<String>>(),
+                ]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        schema.metadata().description = Some(
+            "A block range of block numbers, ISO dates or times, or offsets from the latest \
+            block in blocks or time, such as [100..200], [2024-01-01..latest-7d], [100..] or [..200]"
+                .to_string(),
+        );
+        schema.into()
+    }
+}
+
 struct BlockRangeVisitor;
 
 impl<'de> Visitor<'de> for BlockRangeVisitor {
@@ -115,21 +197,196 @@ fn parse_range(s: &str) -> Result<BlockRange, String> {
     if parts.len() == 2 {
         let start = match parts[0] {
             "" => Block::Genesis,
-            s => Block::Number(s.parse().map_err(|_| "Invalid block number")?),
+            s => parse_block(s)?,
         };
         let end = match parts[1] {
             "" => Block::Latest,
-            s => Block::Number(s.parse().map_err(|_| "Invalid block number")?),
+            s => parse_block(s)?,
         };
         return Ok(BlockRange { start, end });
     }
     Err(format!("Invalid range syntax: {}", s))
 }
 
-#[derive(Debug, PartialEq, Deserialize, Serialize, Clone)]
+/// Parses a block number, an ISO 8601 date or time, `latest` or an offset
+/// from it such as `latest-1000` blocks or `latest-7d`
+fn parse_block(s: &str) -> Result<Block, String> {
+    if s == "latest" {
+        return Ok(Block::Latest);
+    }
+    if let Some(offset) = s.strip_prefix("latest-") {
+        return match offset.parse() {
+            Ok(n) => Ok(Block::LatestMinusBlocks(n)),
+            Err(_) => Ok(Block::LatestMinusSeconds(parse_duration(offset)?)),
+        };
+    }
+    if let Ok(n) = s.parse() {
+        return Ok(Block::Number(n));
+    }
+    parse_timestamp(s)
+        .map(Block::Timestamp)
+        .ok_or(format!("Invalid block: {}", s))
+}
+
+/// A block as written in a range, where genesis and latest are left empty
+fn format_block(block: &Block) -> String {
+    match block {
+        Block::Genesis | Block::Latest => String::new(),
+        Block::Number(n) => n.to_string(),
+        Block::Timestamp(timestamp) => format_timestamp(*timestamp),
+        Block::LatestMinusBlocks(n) => format!("latest-{}", n),
+        Block::LatestMinusSeconds(seconds) => format!("latest-{}", format_duration(*seconds)),
+    }
+}
+
+/// Parses an ISO 8601 date or time into a unix timestamp, times without an
+/// offset are taken as UTC
+fn parse_timestamp(s: &str) -> Option<u64> {
+    let timestamp = match DateTime::parse_from_rfc3339(s) {
+        Ok(time) => time.timestamp(),
+        Err(_) => match NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S") {
+            Ok(time) => time.and_utc().timestamp(),
+            Err(_) => NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()?
+                .and_hms_opt(0, 0, 0)?
+                .and_utc()
+                .timestamp(),
+        },
+    };
+    timestamp.try_into().ok()
+}
+
+fn format_timestamp(timestamp: u64) -> String {
+    DateTime::from_timestamp(timestamp as i64, 0)
+        .map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+const DURATION_UNITS: [(&str, u64); 5] = [
+    ("w", 604800),
+    ("d", 86400),
+    ("h", 3600),
+    ("m", 60),
+    ("s", 1),
+];
+
+/// Parses a duration such as `30m`, `1h` or `7d` into seconds
+fn parse_duration(s: &str) -> Result<u64, String> {
+    let (value, unit) = s.split_at(s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len()));
+    DURATION_UNITS
+        .iter()
+        .find(|(name, _)| *name == unit)
+        .and_then(|(_, seconds)| value.parse::<u64>().ok()?.checked_mul(*seconds))
+        .ok_or(format!("Invalid duration: {}", s))
+}
+
+fn format_duration(seconds: u64) -> String {
+    // zero is a multiple of every unit, so it is left in seconds
+    let (name, unit_seconds) = DURATION_UNITS
+        .iter()
+        .find(|(_, unit_seconds)| seconds > 0 && seconds % unit_seconds == 0)
+        .unwrap_or(&("s", 1));
+    format!("{}{}", seconds / unit_seconds, name)
+}
+
+/// Step between the blocks of a range, either a number of blocks or a
+/// duration such as `1h`
+#[derive(Debug, PartialEq, Clone)]
+pub enum Interval {
+    Blocks(u32),
+    Seconds(u64),
+}
+
+impl FromStr for Interval {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse() {
+            Ok(n) => Ok(Interval::Blocks(n)),
+            Err(_) => parse_duration(s).map(Interval::Seconds),
+        }
+    }
+}
+
+impl Serialize for Interval {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Interval::Blocks(n) => serializer.serialize_u32(*n),
+            Interval::Seconds(seconds) => serializer.serialize_str(&format_duration(*seconds)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Interval {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(IntervalVisitor)
+    }
+}
+
+impl JsonSchema for Interval {
+    fn schema_name() -> String {
+        "Interval".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let mut duration = pattern("[0-9]+[smhdw]");
+        duration.metadata().description = Some("Duration such as 30m, 1h or 7d".to_string());
+        SchemaObject {
+            subschemas: Some(Box::new(SubschemaValidation {
+                one_of: Some(vec![gen.subschema_for::<u32>(), duration.into()]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+struct IntervalVisitor;
+
+impl<'de> Visitor<'de> for IntervalVisitor {
+    type Value = Interval;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a number of blocks or a duration such as 1h")
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Interval::Blocks(v.try_into().map_err(de::Error::custom)?))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Interval::Blocks(v.try_into().map_err(de::Error::custom)?))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        v.parse().map_err(de::Error::custom)
+    }
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize, JsonSchema, Clone)]
 #[serde(untagged)]
+#[schemars(deny_unknown_fields)]
 pub enum Blocks {
-    RangeWithInterval { range: BlockRange, interval: u32 },
+    RangeWithInterval {
+        range: BlockRange,
+        interval: Interval,
+    },
     SimpleRange(BlockRange),
 }
 
@@ -137,6 +394,20 @@ pub enum Blocks {
 pub enum BlockError {
     #[error("Invalid block range")]
     InvalidBlockRange,
+    #[error("Interval must be greater than zero")]
+    ZeroInterval,
+    #[error("Block range uses dates, which need block timestamps of its network")]
+    TimestampsRequired,
+    #[error("{0} is before the genesis block")]
+    BeforeGenesis(String),
+    #[error("Failed to read block timestamp: {0}")]
+    BlockTimestamp(String),
+}
+
+impl From<RpcProviderError> for BlockError {
+    fn from(value: RpcProviderError) -> Self {
+        BlockError::BlockTimestamp(value.to_string())
+    }
 }
 
 impl Blocks {
@@ -144,12 +415,21 @@ impl Blocks {
         &self,
         latest_block: BlockNumber,
     ) -> Result<Vec<BlockNumber>, BlockError> {
+        if self.uses_timestamps() {
+            return Err(BlockError::TimestampsRequired);
+        }
         match self {
             Blocks::RangeWithInterval { range, interval } => {
                 range.validate(latest_block)?;
+                let Interval::Blocks(interval) = interval else {
+                    return Err(BlockError::TimestampsRequired);
+                };
+                if *interval == 0 {
+                    return Err(BlockError::ZeroInterval);
+                }
                 let mut blocks = vec![];
-                let mut current_block = range.start.to_block_number(latest_block);
-                let end_block = range.end.to_block_number(latest_block);
+                let mut current_block = range.start.to_block_number(latest_block)?;
+                let end_block = range.end.to_block_number(latest_block)?;
                 while current_block <= end_block {
                     blocks.push(current_block);
                     current_block += *interval as BlockNumber;
@@ -158,12 +438,65 @@ impl Blocks {
             }
             Blocks::SimpleRange(range) => {
                 range.validate(latest_block)?;
-                let start_block = range.start.to_block_number(latest_block);
-                let end_block = range.end.to_block_number(latest_block);
+                let start_block = range.start.to_block_number(latest_block)?;
+                let end_block = range.end.to_block_number(latest_block)?;
                 Ok((start_block..=end_block).collect())
             }
         }
     }
+
+    pub fn uses_timestamps(&self) -> bool {
+        match self {
+            Blocks::RangeWithInterval { range, interval } => {
+                range.uses_timestamps() || matches!(interval, Interval::Seconds(_))
+            }
+            Blocks::SimpleRange(range) => range.uses_timestamps(),
+        }
+    }
+
+    /// Like [Blocks::expand_to_block_numbers], reading block timestamps of
+    /// the network to resolve dates and time intervals. A time interval
+    /// steps from the timestamp of the range's first block and takes the
+    /// last block at or before every step.
+    pub async fn resolve_block_numbers(
+        &self,
+        timestamps: &BlockTimestamps,
+        latest_block: BlockNumber,
+    ) -> Result<Vec<BlockNumber>, BlockError> {
+        if !self.uses_timestamps() {
+            return self.expand_to_block_numbers(latest_block);
+        }
+        let (range, interval) = match self {
+            Blocks::RangeWithInterval { range, interval } => (range, Some(interval)),
+            Blocks::SimpleRange(range) => (range, None),
+        };
+        let start = range.start.resolve(timestamps, latest_block).await?;
+        let end = range.end.resolve(timestamps, latest_block).await?;
+        if start > end {
+            return Err(BlockError::InvalidBlockRange);
+        }
+
+        match interval {
+            None => Ok((start..=end).collect()),
+            Some(Interval::Blocks(0)) | Some(Interval::Seconds(0)) => Err(BlockError::ZeroInterval),
+            Some(Interval::Blocks(interval)) => {
+                Ok((start..=end).step_by(*interval as usize).collect())
+            }
+            Some(Interval::Seconds(interval)) => {
+                let end_timestamp = timestamps.timestamp(end).await?;
+                let mut timestamp = timestamps.timestamp(start).await?;
+                let mut blocks: Vec<BlockNumber> = vec![];
+                while timestamp <= end_timestamp {
+                    let block = timestamps.block_at(timestamp, end).await?;
+                    if blocks.last() != Some(&block) {
+                        blocks.push(block);
+                    }
+                    timestamp += interval;
+                }
+                Ok(blocks)
+            }
+        }
+    }
 }
 
 impl Default for Blocks {
@@ -173,14 +506,112 @@ impl Default for Blocks {
                 start: Block::Genesis,
                 end: Block::Latest,
             },
-            interval: 1,
+            interval: Interval::Blocks(1),
         }
     }
 }
 
+type TimestampCache = HashMap<u64, BTreeMap<BlockNumber, u64>>;
+
+static BLOCK_TIMESTAMPS: OnceLock<Mutex<TimestampCache>> = OnceLock::new();
+
+/// Reads block timestamps from the rpcs of a network, every timestamp read
+/// is cached per chain for the whole process
+#[derive(Debug, Clone)]
+pub struct BlockTimestamps {
+    provider: Arc<RpcProvider>,
+    chain_id: u64,
+}
+
+impl BlockTimestamps {
+    pub fn for_network(network: &Network) -> Self {
+        Self::new(RpcProvider::for_network(network), network.chain_id)
+    }
+
+    /// Reads the timestamps of the given chain from the rpcs of the provider
+    pub fn new(provider: Arc<RpcProvider>, chain_id: u64) -> Self {
+        BlockTimestamps { provider, chain_id }
+    }
+
+    fn with_cache<T>(&self, f: impl FnOnce(&mut BTreeMap<BlockNumber, u64>) -> T) -> T {
+        let mut caches = BLOCK_TIMESTAMPS
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap();
+        f(caches.entry(self.chain_id).or_default())
+    }
+
+    pub async fn timestamp(&self, block: BlockNumber) -> Result<u64, BlockError> {
+        if let Some(timestamp) = self.with_cache(|cache| cache.get(&block).copied()) {
+            return Ok(timestamp);
+        }
+        let timestamp = self
+            .provider
+            .with_failover(|url| fetch_block_timestamp(url, block))
+            .await?;
+        self.with_cache(|cache| cache.insert(block, timestamp));
+        Ok(timestamp)
+    }
+
+    /// The last block at or before the given timestamp, found by binary
+    /// search over the block timestamps between the closest cached ones
+    pub async fn block_at(
+        &self,
+        timestamp: u64,
+        latest_block: BlockNumber,
+    ) -> Result<BlockNumber, BlockError> {
+        if self.timestamp(latest_block).await? <= timestamp {
+            return Ok(latest_block);
+        }
+        if self.timestamp(0).await? > timestamp {
+            return Err(BlockError::BeforeGenesis(format_timestamp(timestamp)));
+        }
+
+        // the timestamp is at or after the one of low and before the one of high
+        let (mut low, mut high) = self.with_cache(|cache| {
+            let low = cache
+                .range(..=latest_block)
+                .filter(|(_, v)| **v <= timestamp)
+                .map(|(k, _)| *k)
+                .max()
+                .unwrap_or(0);
+            let high = cache
+                .range(low..=latest_block)
+                .find(|(_, v)| **v > timestamp)
+                .map(|(k, _)| *k)
+                .unwrap_or(latest_block);
+            (low, high)
+        });
+        while high - low > 1 {
+            let middle = low + (high - low) / 2;
+            if self.timestamp(middle).await? <= timestamp {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        Ok(low)
+    }
+}
+
+async fn fetch_block_timestamp(url: Url, block: BlockNumber) -> Result<u64, BlockError> {
+    let result = json_rpc(
+        &url,
+        "eth_getBlockByNumber",
+        json!([format!("{:#x}", block), false]),
+    )
+    .await
+    .map_err(BlockError::BlockTimestamp)?;
+    parse_quantity(&result["timestamp"]).ok_or(BlockError::BlockTimestamp(format!(
+        "Block {} not found",
+        block
+    )))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use httpmock::{Method::POST, MockServer};
 
     #[test]
     fn test_range_with_interval() {
@@ -193,7 +624,7 @@ interval: 5
                 start: Block::Number(0),
                 end: Block::Number(100),
             },
-            interval: 5,
+            interval: Interval::Blocks(5),
         };
 
         let result: Blocks = serde_yaml::from_str(yaml_data).unwrap();
@@ -302,7 +733,7 @@ interval: 5
                 start: Block::Genesis,
                 end: Block::Latest,
             },
-            interval: 1,
+            interval: Interval::Blocks(1),
         };
 
         assert_eq!(default_blocks, expected);
@@ -325,11 +756,36 @@ interval: 5
         assert_eq!(range.validate(100), Err(BlockError::InvalidBlockRange));
     }
 
+    #[test]
+    fn test_block_range_from_str() {
+        assert_eq!(
+            BlockRange::from_str("10..20").unwrap(),
+            BlockRange {
+                start: Block::Number(10),
+                end: Block::Number(20),
+            }
+        );
+        assert_eq!(
+            BlockRange::from_str("..").unwrap(),
+            BlockRange {
+                start: Block::Genesis,
+                end: Block::Latest,
+            }
+        );
+        assert!(BlockRange::from_str("10").is_err());
+        assert!(BlockRange::from_str("a..20").is_err());
+    }
+
     #[test]
     fn test_to_block_number() {
-        assert_eq!(Block::Genesis.to_block_number(100), 0);
-        assert_eq!(Block::Latest.to_block_number(100), 100);
-        assert_eq!(Block::Number(50).to_block_number(100), 50);
+        assert_eq!(Block::Genesis.to_block_number(100), Ok(0));
+        assert_eq!(Block::Latest.to_block_number(100), Ok(100));
+        assert_eq!(Block::Number(50).to_block_number(100), Ok(50));
+        assert_eq!(Block::LatestMinusBlocks(30).to_block_number(100), Ok(70));
+        assert_eq!(
+            Block::Timestamp(0).to_block_number(100),
+            Err(BlockError::TimestampsRequired)
+        );
     }
 
     #[test]
@@ -352,7 +808,7 @@ interval: 5
                 start: Block::Number(0),
                 end: Block::Number(20),
             },
-            interval: 5,
+            interval: Interval::Blocks(5),
         };
 
         let expected = vec![0, 5, 10, 15, 20];
@@ -369,4 +825,142 @@ interval: 5
         let expected = vec![0, 1, 2, 3, 4, 5];
         assert_eq!(blocks.expand_to_block_numbers(100).unwrap(), expected);
     }
+
+    #[test]
+    fn test_time_range() {
+        let yaml_data = r#"
+range: [2024-01-01..latest-7d]
+interval: 1h
+"#;
+        let expected = Blocks::RangeWithInterval {
+            range: BlockRange {
+                start: Block::Timestamp(1704067200),
+                end: Block::LatestMinusSeconds(7 * 86400),
+            },
+            interval: Interval::Seconds(3600),
+        };
+
+        let result: Blocks = serde_yaml::from_str(yaml_data).unwrap();
+        assert_eq!(result, expected);
+
+        let serialized = serde_yaml::to_string(&result).unwrap();
+        assert_eq!(
+            serialized,
+            "range: 2024-01-01T00:00:00Z..latest-1w\ninterval: 1h\n"
+        );
+        let deserialized: Blocks = serde_yaml::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, expected);
+
+        assert!(result.uses_timestamps());
+        assert_eq!(
+            result.expand_to_block_numbers(100),
+            Err(BlockError::TimestampsRequired)
+        );
+    }
+
+    #[test]
+    fn test_block_range_from_str_time() {
+        assert_eq!(
+            BlockRange::from_str("2024-01-01T12:00:00+02:00..latest-100").unwrap(),
+            BlockRange {
+                start: Block::Timestamp(1704103200),
+                end: Block::LatestMinusBlocks(100),
+            }
+        );
+        assert_eq!(
+            BlockRange::from_str("2024-01-01T00:00:30..latest").unwrap(),
+            BlockRange {
+                start: Block::Timestamp(1704067230),
+                end: Block::Latest,
+            }
+        );
+        assert!(BlockRange::from_str("latest-7x..").is_err());
+        assert!(BlockRange::from_str("2024-13-01..").is_err());
+
+        assert_eq!(Interval::from_str("10"), Ok(Interval::Blocks(10)));
+        assert_eq!(Interval::from_str("90m"), Ok(Interval::Seconds(5400)));
+        assert!(Interval::from_str("1y").is_err());
+        assert_eq!(format_duration(5400), "90m");
+        assert_eq!(format_duration(0), "0s");
+        assert_eq!(format_block(&Block::LatestMinusSeconds(0)), "latest-0s");
+    }
+
+    /// Mocks a chain with blocks 0 to 8 mined every 10 seconds from 1000
+    fn mock_chain(chain_id: u64) -> (MockServer, Network) {
+        let server = MockServer::start();
+        for block in 0..=8u64 {
+            server.mock(|when, then| {
+                when.method(POST)
+                    .body_contains("eth_getBlockByNumber")
+                    .body_contains(format!("\"{:#x}\"", block));
+                then.json_body(json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": { "timestamp": format!("{:#x}", 1000 + 10 * block) },
+                }));
+            });
+        }
+        let network = Network {
+            rpc: server.base_url().parse().unwrap(),
+            chain_id,
+            ..Network::dummy()
+        };
+        (server, network)
+    }
+
+    #[tokio::test]
+    async fn test_resolve_block_numbers() {
+        let (_server, network) = mock_chain(99901);
+        let timestamps = BlockTimestamps::for_network(&network);
+
+        let blocks = Blocks::SimpleRange(BlockRange {
+            start: Block::Timestamp(1035),
+            end: Block::LatestMinusSeconds(20),
+        });
+        assert_eq!(
+            blocks.resolve_block_numbers(&timestamps, 8).await.unwrap(),
+            vec![3, 4, 5, 6]
+        );
+
+        let blocks = Blocks::RangeWithInterval {
+            range: BlockRange {
+                start: Block::Number(1),
+                end: Block::Latest,
+            },
+            interval: Interval::Seconds(25),
+        };
+        assert_eq!(
+            blocks.resolve_block_numbers(&timestamps, 8).await.unwrap(),
+            vec![1, 3, 6]
+        );
+
+        let blocks = Blocks::SimpleRange(BlockRange {
+            start: Block::Timestamp(900),
+            end: Block::Latest,
+        });
+        assert_eq!(
+            blocks.resolve_block_numbers(&timestamps, 8).await,
+            Err(BlockError::BeforeGenesis(
+                "1970-01-01T00:15:00Z".to_string()
+            ))
+        );
+
+        // block numbers are expanded without reading timestamps
+        let blocks = Blocks::SimpleRange(BlockRange::from_str("2..latest-4").unwrap());
+        assert_eq!(
+            blocks.resolve_block_numbers(&timestamps, 8).await.unwrap(),
+            vec![2, 3, 4]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_block_at_uses_cache() {
+        let (server, network) = mock_chain(99902);
+        let timestamps = BlockTimestamps::for_network(&network);
+        assert_eq!(timestamps.block_at(1045, 8).await.unwrap(), 4);
+        drop(server);
+
+        // every timestamp needed is cached
+        assert_eq!(timestamps.block_at(1049, 8).await.unwrap(), 4);
+    }
 }
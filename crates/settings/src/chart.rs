@@ -1,4 +1,5 @@
 use crate::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, sync::Arc};
 use thiserror::Error;
@@ -15,8 +16,9 @@ pub struct Chart {
 }
 
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct Metric {
     pub label: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -94,6 +96,7 @@ mod tests {
             runs,
             blocks: None,
             deployer: mock_deployer(),
+            parameters: HashMap::new(),
         };
         (name.to_string(), Arc::new(scenario))
     }
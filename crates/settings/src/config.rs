@@ -217,6 +217,12 @@ impl Config {
         let config_source = ConfigSource::try_from_string(val, None).await?.0;
         std::convert::TryInto::<Config>::try_into(config_source)
     }
+
+    /// The scenarios generated from the matrix of the named scenario, sorted
+    /// by name
+    pub fn matrix_scenarios(&self, name: &str) -> Vec<Arc<Scenario>> {
+        matrix_scenarios(&self.scenarios, name)
+    }
 }
 
 #[cfg(test)]
@@ -233,6 +239,7 @@ mod tests {
             "mainnet".to_string(),
             NetworkConfigSource {
                 rpc: Url::parse("https://mainnet.node").unwrap(),
+                rpcs: vec![],
                 chain_id: 1,
                 label: Some("Ethereum Mainnet".to_string()),
                 network_id: Some(1),
@@ -308,8 +315,10 @@ mod tests {
         });
 
         let config_string = ConfigSource {
+            imports: vec![],
             raindex_version: Some("0x123".to_string()),
             using_networks_from,
+            using_tokens_from: HashMap::new(),
             networks,
             subgraphs,
             metaboards,
@@ -323,6 +332,7 @@ mod tests {
             sentry,
             accounts,
             gui,
+            secrets: Default::default(),
         };
 
         let config_result = Config::try_from(config_string);
@@ -1,7 +1,15 @@
 use crate::blocks::Blocks;
+use crate::imports::{ImportError, ImportLoaders};
+use crate::interpolation::{
+    interpolate_yaml_text, ConfigSecrets, EnvResolver, InterpolationError, Redacted,
+    VariableResolver,
+};
 use crate::remote::chains::{chainid::ChainIdError, RemoteNetworkError, RemoteNetworks};
+use crate::remote::tokens::RemoteTokensError;
+use crate::schema::{AddressSchema, HashSchema, U256Schema};
 use crate::{GuiConfigSource, Metric, Plot};
-use alloy::primitives::{Address, U256};
+use alloy::primitives::{Address, B256, U256};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use thiserror::Error;
@@ -9,14 +17,22 @@ use typeshare::typeshare;
 use url::Url;
 
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default, PartialEq)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct ConfigSource {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub imports: Vec<ImportConfigSource>,
+    /// Remote lists of networks to add to the config
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub using_networks_from: HashMap<String, RemoteNetworksConfigSource>,
+    /// Remote token lists to add to the config, local tokens take precedence
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub using_tokens_from: HashMap<String, RemoteTokensConfigSource>,
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub networks: HashMap<String, NetworkConfigSource>,
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    #[schemars(with = "HashMap<String, String>")]
     pub subgraphs: HashMap<String, Url>,
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub orderbooks: HashMap<String, OrderbookConfigSource>,
@@ -32,16 +48,24 @@ pub struct ConfigSource {
     pub charts: HashMap<String, ChartConfigSource>,
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub deployments: HashMap<String, DeploymentConfigSource>,
+    /// Metaboard subgraph urls by name
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    #[schemars(with = "HashMap<String, String>")]
     pub metaboards: HashMap<String, Url>,
+    /// Whether to report errors to Sentry
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sentry: Option<bool>,
+    /// The Raindex version this config is written for
     #[serde(skip_serializing_if = "Option::is_none")]
     pub raindex_version: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub accounts: Option<HashMap<String, String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub gui: Option<GuiConfigSource>,
+    /// Interpolated values of this config, used to redact them back out
+    #[serde(skip)]
+    #[typeshare(skip)]
+    pub secrets: ConfigSecrets,
 }
 
 #[typeshare]
@@ -72,10 +96,17 @@ pub type MetaboardRef = String;
 pub type DeploymentRef = String;
 
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct NetworkConfigSource {
+    /// RPC url of the network
+    #[schemars(with = "String")]
     pub rpc: Url,
+    /// Fallback rpcs, tried in order after `rpc`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rpcs: Vec<RpcConfigSource>,
+    /// Chain id of the network
     #[typeshare(typescript(type = "number"))]
     pub chain_id: u64,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -83,25 +114,80 @@ pub struct NetworkConfigSource {
     #[typeshare(typescript(type = "number"))]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub network_id: Option<u64>,
+    /// Symbol of the network's native currency
     #[serde(skip_serializing_if = "Option::is_none")]
     pub currency: Option<String>,
 }
 
+/// A fallback rpc of a network, tried in order after `rpc`
+#[typeshare]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
+pub struct RpcConfigSource {
+    #[schemars(with = "String")]
+    pub url: Url,
+    /// Share of requests sent to this rpc, `rpc` and rpcs without a weight
+    /// count as 1
+    #[typeshare(typescript(type = "number"))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight: Option<u32>,
+}
+
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct RemoteNetworksConfigSource {
+    /// Url of the list of networks
     pub url: String,
+    /// Format of the list of networks, only `chainid` is supported
     pub format: String,
 }
 
+/// A token list following the Uniswap token list schema
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
+pub struct RemoteTokensConfigSource {
+    #[schemars(with = "String")]
+    pub url: Url,
+}
+
+/// Another settings yaml to merge into this one, either a path relative to
+/// the importing file or a url, optionally pinned to the keccak256 of its content
+#[typeshare]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
+pub struct ImportConfigSource {
+    /// Path of the yaml to import, relative to the importing file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Url of the yaml to import
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>")]
+    pub url: Option<Url>,
+    /// keccak256 of the imported yaml's content
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<HashSchema>")]
+    #[typeshare(typescript(type = "string"))]
+    pub hash: Option<B256>,
+}
+
+#[typeshare]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct OrderbookConfigSource {
+    /// Address of the orderbook contract
+    #[schemars(with = "AddressSchema")]
     pub address: Address,
+    /// Name of the network, defaults to the orderbook's name
     #[serde(skip_serializing_if = "Option::is_none")]
     pub network: Option<NetworkRef>,
+    /// Name of the subgraph, defaults to the orderbook's name
     #[serde(skip_serializing_if = "Option::is_none")]
     pub subgraph: Option<SubgraphRef>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -109,10 +195,14 @@ pub struct OrderbookConfigSource {
 }
 
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct TokenConfigSource {
+    /// Name of the token's network
     pub network: NetworkRef,
+    /// Address of the token contract
+    #[schemars(with = "AddressSchema")]
     pub address: Address,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub decimals: Option<u8>,
@@ -123,10 +213,14 @@ pub struct TokenConfigSource {
 }
 
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct DeployerConfigSource {
+    /// Address of the deployer contract
+    #[schemars(with = "AddressSchema")]
     pub address: Address,
+    /// Name of the network, defaults to the deployer's name
     #[serde(skip_serializing_if = "Option::is_none")]
     pub network: Option<NetworkRef>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -134,57 +228,78 @@ pub struct DeployerConfigSource {
 }
 
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct DeploymentConfigSource {
+    /// Name of the scenario
     pub scenario: ScenarioRef,
+    /// Name of the order
     pub order: OrderRef,
 }
 
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct IOString {
+    /// Name of the token
     pub token: TokenRef,
+    /// Vault id, a random one is used if not set
     #[typeshare(typescript(type = "bigint"))]
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<U256Schema>")]
     pub vault_id: Option<U256>,
 }
 
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct OrderConfigSource {
     pub inputs: Vec<IOString>,
     pub outputs: Vec<IOString>,
+    /// Name of the deployer
     #[serde(skip_serializing_if = "Option::is_none")]
     pub deployer: Option<DeployerRef>,
+    /// Name of the orderbook
     #[serde(skip_serializing_if = "Option::is_none")]
     pub orderbook: Option<OrderbookRef>,
 }
 
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct ScenarioConfigSource {
+    /// Values of the rainlang bindings by name
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub bindings: HashMap<String, String>,
+    /// Number of fuzz runs
     #[typeshare(typescript(type = "number"))]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub runs: Option<u64>,
     #[typeshare(skip)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub blocks: Option<Blocks>,
+    /// Name of the deployer
     #[serde(skip_serializing_if = "Option::is_none")]
     pub deployer: Option<DeployerRef>,
+    /// Child scenarios by name
     #[serde(skip_serializing_if = "Option::is_none")]
     pub scenarios: Option<HashMap<String, ScenarioConfigSource>>,
+    /// Candidate values per binding, expanded into a child scenario for
+    /// every combination of them
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub matrix: HashMap<String, Vec<String>>,
 }
 
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct ChartConfigSource {
+    /// Name of the scenario, defaults to the chart's name
     #[serde(skip_serializing_if = "Option::is_none")]
     pub scenario: Option<ScenarioRef>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -203,44 +318,189 @@ pub enum ConfigSourceError {
     ConflictingNetworks(String),
     #[error(transparent)]
     ChainIdError(#[from] ChainIdError),
+    #[error(transparent)]
+    RemoteTokensError(#[from] RemoteTokensError),
+    #[error(transparent)]
+    InterpolationError(#[from] InterpolationError),
+    #[error("{0}")]
+    RedactedYamlDeserializerError(String),
+    #[error(transparent)]
+    ImportError(#[from] ImportError),
+    #[error("'{0}' can not be used in a config that only overrides another one")]
+    PartialConfigField(String),
 }
 
 impl ConfigSource {
+    /// Parses the given yml(s), resolving their `${VAR}` references from the
+    /// process environment
     pub async fn try_from_string(
         val: String,
         top_config: Option<String>,
     ) -> Result<(ConfigSource, ConfigSource), ConfigSourceError> {
-        if let Some(top_config) = top_config {
-            let merged = MergedConfigSource::new(val, top_config).await?;
-            Ok((merged.main, merged.top_config))
+        Self::try_from_string_with_resolver(val, top_config, &EnvResolver).await
+    }
+
+    pub async fn try_from_string_with_resolver(
+        val: String,
+        top_config: Option<String>,
+        resolver: &dyn VariableResolver,
+    ) -> Result<(ConfigSource, ConfigSource), ConfigSourceError> {
+        Self::try_from_string_with_loaders(val, top_config, resolver, &ImportLoaders::default())
+            .await
+    }
+
+    /// Parses the given yml(s), merges their `imports` into them and adds the
+    /// tokens of their `using-tokens-from` lists, an import already merged
+    /// into the top config is not merged into the main one again
+    pub async fn try_from_string_with_loaders(
+        val: String,
+        top_config: Option<String>,
+        resolver: &dyn VariableResolver,
+        loaders: &ImportLoaders,
+    ) -> Result<(ConfigSource, ConfigSource), ConfigSourceError> {
+        let (mut main, mut top_config) = if let Some(top_config) = top_config {
+            let merged = MergedConfigSource::new(val, top_config, resolver).await?;
+            (merged.main, merged.top_config)
         } else {
-            let mut conf: ConfigSource = serde_yaml::from_str(&val)?;
-            if !conf.using_networks_from.is_empty() {
-                for (_key, item) in conf.using_networks_from.iter() {
-                    let remote_networks =
-                        RemoteNetworks::try_from_remote_network_config_source(item.clone()).await?;
-                    match remote_networks {
-                        RemoteNetworks::ChainId(chains) => {
-                            for chain in &chains {
-                                if conf.networks.iter().all(|(k, _v)| *k != chain.short_name) {
-                                    if let Ok(v) = chain.clone().try_into() {
-                                        conf.networks.insert(chain.short_name.clone(), v);
-                                    }
-                                } else {
-                                    return Err(ConfigSourceError::ConflictingNetworks(
-                                        chain.name.clone(),
-                                    ));
+            (
+                Self::parse_yaml(val, resolver).await?,
+                ConfigSource::default(),
+            )
+        };
+        let mut imported = vec![];
+        top_config
+            .resolve_imports(&loaders.top_config, resolver, &mut imported)
+            .await?;
+        main.resolve_imports(&loaders.main, resolver, &mut imported)
+            .await?;
+
+        // listed tokens are resolved once all networks are known, as the
+        // configs are merged later their local tokens take precedence over
+        // the listed ones of either
+        let networks = top_config
+            .networks
+            .clone()
+            .into_iter()
+            .chain(main.networks.clone())
+            .collect();
+        let local_tokens = main.tokens.clone();
+        top_config
+            .resolve_remote_tokens(&networks, &local_tokens)
+            .await?;
+        let local_tokens = top_config.tokens.clone();
+        main.resolve_remote_tokens(&networks, &local_tokens).await?;
+        Ok((main, top_config))
+    }
+
+    /// Parses a single yml, without resolving its imports
+    pub(crate) async fn parse_yaml(
+        val: String,
+        resolver: &dyn VariableResolver,
+    ) -> Result<ConfigSource, ConfigSourceError> {
+        let (mut conf, secrets): (ConfigSource, _) = interpolate_config(&val, resolver)?;
+        conf.secrets = secrets;
+        if !conf.using_networks_from.is_empty() {
+            for (_key, item) in conf.using_networks_from.iter() {
+                let remote_networks =
+                    RemoteNetworks::try_from_remote_network_config_source(item.clone()).await?;
+                match remote_networks {
+                    RemoteNetworks::ChainId(chains) => {
+                        for chain in &chains {
+                            if conf.networks.iter().all(|(k, _v)| *k != chain.short_name) {
+                                if let Ok(v) = chain.clone().try_into() {
+                                    conf.networks.insert(chain.short_name.clone(), v);
                                 }
+                            } else {
+                                return Err(ConfigSourceError::ConflictingNetworks(
+                                    chain.name.clone(),
+                                ));
                             }
                         }
                     }
                 }
             }
-            Ok((conf, ConfigSource::default()))
         }
+        Ok(conf)
+    }
+
+    /// Parses a yml that only defines the fields it overrides of the config
+    /// it is merged over, resolving its `${VAR}` references. It is validated
+    /// once merged rather than on its own, so it can not load imports nor
+    /// remote networks and tokens
+    pub fn try_partial_from_string(
+        val: &str,
+        resolver: &dyn VariableResolver,
+    ) -> Result<(serde_yaml::Value, ConfigSecrets), ConfigSourceError> {
+        let (value, secrets): (serde_yaml::Value, _) = interpolate_config(val, resolver)?;
+        for field in ["imports", "using-networks-from", "using-tokens-from"] {
+            if value.get(field).is_some() {
+                return Err(ConfigSourceError::PartialConfigField(field.to_string()));
+            }
+        }
+        Ok((value, secrets))
+    }
+
+    /// Serializes this config to yaml with its interpolated values swapped
+    /// back to their `${VAR}` references
+    pub fn to_yaml_string(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.redacted())
+    }
+
+    /// This config for serializing with its interpolated values swapped back
+    /// to their `${VAR}` references, so resolved secrets never leave the process
+    pub fn redacted(&self) -> Redacted<&ConfigSource> {
+        self.secrets.redacted(self)
+    }
+
+    /// Rebuilds a config from its serialized form, resolving the `${VAR}`
+    /// references its interpolated values were redacted to. Unlike parsing,
+    /// its imports and remote networks and tokens are not loaded again.
+    pub fn try_from_value_with_resolver(
+        value: serde_yaml::Value,
+        resolver: &dyn VariableResolver,
+    ) -> Result<ConfigSource, ConfigSourceError> {
+        let text = serde_yaml::to_string(&value)?;
+        let (mut conf, secrets): (ConfigSource, _) = interpolate_config(&text, resolver)?;
+        conf.secrets = secrets;
+        Ok(conf)
     }
 }
 
+/// Serializes a config field redacted, see [ConfigSource::redacted]
+pub fn serialize_redacted<S: serde::Serializer>(
+    config: &ConfigSource,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    config.redacted().serialize(serializer)
+}
+
+/// Interpolates a yaml document and deserializes it, returning the resolved
+/// values alongside so they can be redacted later
+pub(crate) fn interpolate_config<T: serde::de::DeserializeOwned>(
+    text: &str,
+    resolver: &dyn VariableResolver,
+) -> Result<(T, ConfigSecrets), ConfigSourceError> {
+    let mut value: serde_yaml::Value = serde_yaml::from_str(text)?;
+    // an empty document parses as null
+    if value.is_null() {
+        let empty = serde_yaml::Value::Mapping(Default::default());
+        return Ok((serde_yaml::from_value(empty)?, ConfigSecrets::default()));
+    }
+    let mut secrets = ConfigSecrets::default();
+    let conf = match interpolate_yaml_text(text, &mut value, resolver, &mut secrets)? {
+        Some(text) => serde_yaml::from_str(&text),
+        None => serde_yaml::from_value(value),
+    };
+    let conf: T = conf.map_err(|e| {
+        if secrets.is_empty() {
+            ConfigSourceError::YamlDeserializerError(e)
+        } else {
+            ConfigSourceError::RedactedYamlDeserializerError(secrets.redact(&e.to_string()))
+        }
+    })?;
+    Ok((conf, secrets))
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 struct MergedConfigSource {
@@ -252,6 +512,7 @@ impl MergedConfigSource {
     async fn new(
         main_config: String,
         top_config: String,
+        resolver: &dyn VariableResolver,
     ) -> Result<MergedConfigSource, ConfigSourceError> {
         let mut main_indented = String::new();
         let mut top_config_indented = String::new();
@@ -282,7 +543,10 @@ main:
 ",
             top_config_indented, main_indented
         );
-        let mut merged_conf: MergedConfigSource = serde_yaml::from_str(&merged)?;
+        let (mut merged_conf, secrets): (MergedConfigSource, _) =
+            interpolate_config(&merged, resolver)?;
+        merged_conf.main.secrets = secrets.clone();
+        merged_conf.top_config.secrets = secrets;
 
         // handle remote networks for both ymls
         if !merged_conf.main.using_networks_from.is_empty() {
@@ -821,4 +1085,87 @@ orderbooks: *orderbooks
         // in this case both configs should be equal
         assert_eq!(config, top_config);
     }
+
+    #[tokio::test]
+    async fn test_interpolated_config() {
+        let variables = HashMap::from([
+            ("RPC_KEY".to_string(), "supersecret".to_string()),
+            (
+                "ORDERBOOK".to_string(),
+                "0xabc0000000000000000000000000000000000001".to_string(),
+            ),
+        ]);
+        let main = r#"
+networks:
+    mainnet:
+        rpc: https://mainnet.node/${RPC_KEY}
+        chain-id: 1
+        label: ${LABEL:-Mainnet}
+"#;
+        let top = r#"
+orderbooks:
+    mainnet:
+        address: ${ORDERBOOK}
+"#;
+        let (config, top_config) = ConfigSource::try_from_string_with_resolver(
+            main.to_string(),
+            Some(top.to_string()),
+            &variables,
+        )
+        .await
+        .unwrap();
+        let network = config.networks.get("mainnet").unwrap();
+        assert_eq!(
+            network.rpc,
+            Url::parse("https://mainnet.node/supersecret").unwrap()
+        );
+        assert_eq!(network.label, Some("Mainnet".to_string()));
+        assert_eq!(
+            top_config.orderbooks.get("mainnet").unwrap().address,
+            "0xabc0000000000000000000000000000000000001"
+                .parse::<Address>()
+                .unwrap()
+        );
+
+        // the resolved values are swapped back when serialized or printed
+        let yaml = config.to_yaml_string().unwrap();
+        assert!(yaml.contains("https://mainnet.node/${RPC_KEY}"));
+        assert!(!yaml.contains("supersecret"));
+        assert!(!format!("{:?}", config.secrets).contains("supersecret"));
+        let json = serde_json::to_string(&config.redacted()).unwrap();
+        assert!(json.contains("https://mainnet.node/${RPC_KEY}"));
+        assert!(!json.contains("supersecret"));
+        // while serializing it as is keeps the resolved values to work with
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains("https://mainnet.node/supersecret"));
+
+        // a redacted config can be resolved back into the original one
+        let value = serde_yaml::to_value(config.redacted()).unwrap();
+        let resolved = ConfigSource::try_from_value_with_resolver(value, &variables).unwrap();
+        assert_eq!(resolved.networks, config.networks);
+
+        // and in deserialization errors
+        let err = ConfigSource::try_from_string_with_resolver(
+            "networks:\n    mainnet:\n        rpc: https://mainnet.node\n        chain-id: ${RPC_KEY}\n".to_string(),
+            None,
+            &variables,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ConfigSourceError::RedactedYamlDeserializerError(_)
+        ));
+        assert!(!err.to_string().contains("supersecret"));
+        assert!(err.to_string().contains("${RPC_KEY}"));
+
+        let err =
+            ConfigSource::try_from_string_with_resolver(main.to_string(), None, &HashMap::new())
+                .await
+                .unwrap_err();
+        assert!(matches!(
+            err,
+            ConfigSourceError::InterpolationError(InterpolationError::MissingVariable(_))
+        ));
+    }
 }
@@ -28,6 +28,8 @@ pub enum ParseDeploymentConfigSourceError {
     OrderNotFoundError(String),
     #[error("Scenario and Order do not match")]
     NoMatch,
+    #[error("Scenario {0} has a matrix, deploy one of the scenarios generated from it")]
+    MatrixScenario(String),
 }
 
 impl DeploymentConfigSource {
@@ -42,6 +44,11 @@ impl DeploymentConfigSource {
                 self.scenario.clone(),
             ))
             .map(Arc::clone)?;
+        if !matrix_scenarios(scenarios, &self.scenario).is_empty() {
+            return Err(ParseDeploymentConfigSourceError::MatrixScenario(
+                self.scenario.clone(),
+            ));
+        }
 
         let order = orders
             .get(&self.order)
@@ -76,6 +83,7 @@ mod tests {
             deployer: mock_deployer(),
             runs: None,
             blocks: None,
+            parameters: HashMap::new(),
         };
         let order = Order {
             inputs: vec![],
@@ -105,6 +113,7 @@ mod tests {
             deployer: mock_deployer(),
             runs: None,
             blocks: None,
+            parameters: HashMap::new(),
         };
         let order = Order {
             inputs: vec![],
@@ -125,4 +134,55 @@ mod tests {
             Err(ParseDeploymentConfigSourceError::ScenarioNotFoundError(_))
         ));
     }
+
+    #[test]
+    fn test_try_into_deployment_matrix_scenario() {
+        let scenario = |name: &str, parameters: HashMap<String, String>| Scenario {
+            name: name.into(),
+            bindings: HashMap::new(),
+            deployer: mock_deployer(),
+            runs: None,
+            blocks: None,
+            parameters,
+        };
+        let order = Order {
+            inputs: vec![],
+            outputs: vec![],
+            network: mock_network(),
+            deployer: None,
+            orderbook: None,
+        };
+        let orders = HashMap::from([("order1".to_string(), Arc::new(order))]);
+        let scenarios = HashMap::from([
+            (
+                "scenario1".to_string(),
+                Arc::new(scenario("scenario1", HashMap::new())),
+            ),
+            (
+                "scenario1.size=1".to_string(),
+                Arc::new(scenario(
+                    "scenario1.size=1",
+                    HashMap::from([("size".to_string(), "1".to_string())]),
+                )),
+            ),
+        ]);
+
+        let result = DeploymentConfigSource {
+            scenario: "scenario1".to_string(),
+            order: "order1".to_string(),
+        }
+        .try_into_deployment(&scenarios, &orders);
+        assert_eq!(
+            result,
+            Err(ParseDeploymentConfigSourceError::MatrixScenario(
+                "scenario1".to_string()
+            ))
+        );
+        assert!(DeploymentConfigSource {
+            scenario: "scenario1.size=1".to_string(),
+            order: "order1".to_string(),
+        }
+        .try_into_deployment(&scenarios, &orders)
+        .is_ok());
+    }
 }
@@ -1,5 +1,6 @@
 use crate::{Deployment, DeploymentRef, Token, TokenRef};
 use alloy::primitives::{ruint::ParseError, utils::UnitsError};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, sync::Arc};
 use thiserror::Error;
@@ -10,9 +11,10 @@ use rain_orderbook_bindings::{impl_all_wasm_traits, wasm_traits::prelude::*};
 
 // Config source for Gui
 #[typeshare]
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema, Clone)]
 #[cfg_attr(target_family = "wasm", derive(Tsify))]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct GuiPresetSource {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
@@ -22,17 +24,22 @@ pub struct GuiPresetSource {
 impl_all_wasm_traits!(GuiPresetSource);
 
 #[typeshare]
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct GuiDepositSource {
+    /// Name of the token
     pub token: TokenRef,
+    /// Preset deposit amounts
     pub presets: Vec<String>,
 }
 
 #[typeshare]
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct GuiFieldDefinitionSource {
+    /// Name of the binding the field sets
     pub binding: String,
     pub name: String,
     pub description: Option<String>,
@@ -40,21 +47,25 @@ pub struct GuiFieldDefinitionSource {
 }
 
 #[typeshare]
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct GuiDeploymentSource {
+    /// Name of the deployment
     pub deployment: DeploymentRef,
     pub name: String,
     pub description: String,
     pub deposits: Vec<GuiDepositSource>,
     pub fields: Vec<GuiFieldDefinitionSource>,
+    /// Names of the tokens the user picks
     #[serde(skip_serializing_if = "Option::is_none")]
     pub select_tokens: Option<Vec<TokenRef>>,
 }
 
 #[typeshare]
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct GuiConfigSource {
     pub name: String,
     pub description: String,
@@ -306,6 +317,7 @@ mod tests {
             deployer: mock_deployer(),
             runs: None,
             blocks: None,
+            parameters: HashMap::new(),
         };
         let order = Order {
             inputs: vec![],
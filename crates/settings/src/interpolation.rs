@@ -0,0 +1,721 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_yaml::Value;
+use std::collections::HashMap;
+use thiserror::Error;
+use url::Url;
+
+#[cfg(target_family = "wasm")]
+use rain_orderbook_bindings::{impl_all_wasm_traits, wasm_traits::prelude::*};
+
+#[derive(Error, Debug, PartialEq)]
+pub enum InterpolationError {
+    #[error("Variable '{0}' is not set and has no default")]
+    MissingVariable(String),
+    #[error("Unterminated variable reference in '{0}'")]
+    Unterminated(String),
+    #[error("Invalid variable name '{0}'")]
+    InvalidName(String),
+    #[error("Invalid .env entry at line {0}: '{1}'")]
+    InvalidDotEnvLine(usize, String),
+    #[error("Failed to read .env file: {0}")]
+    DotEnvReadError(String),
+}
+
+/// Looks up the values of the `${VAR}` references found in a config
+pub trait VariableResolver: Send + Sync {
+    fn resolve(&self, name: &str) -> Option<String>;
+}
+
+/// Resolves variables from the process environment
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnvResolver;
+
+impl VariableResolver for EnvResolver {
+    fn resolve(&self, name: &str) -> Option<String> {
+        std::env::var(name).ok()
+    }
+}
+
+impl VariableResolver for HashMap<String, String> {
+    fn resolve(&self, name: &str) -> Option<String> {
+        self.get(name).cloned()
+    }
+}
+
+/// Caller supplied variables, for where there is no environment to read
+/// from such as the wasm/GUI path
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[cfg_attr(target_family = "wasm", derive(Tsify))]
+pub struct ConfigVariables(
+    #[cfg_attr(target_family = "wasm", tsify(type = "Record<string, string>"))]
+    pub  HashMap<String, String>,
+);
+#[cfg(target_family = "wasm")]
+impl_all_wasm_traits!(ConfigVariables);
+
+impl VariableResolver for ConfigVariables {
+    fn resolve(&self, name: &str) -> Option<String> {
+        self.0.get(name).cloned()
+    }
+}
+
+/// Resolves variables from the contents of a `.env` file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DotEnvResolver {
+    vars: HashMap<String, String>,
+}
+
+impl DotEnvResolver {
+    /// Parses `KEY=value` lines, ignoring blank lines and `#` comments.
+    /// Values may be wrapped in single or double quotes and keys may be
+    /// prefixed with `export`.
+    pub fn parse(content: &str) -> Result<Self, InterpolationError> {
+        let mut vars = HashMap::new();
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let line_without_export = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line_without_export.split_once('=').ok_or(
+                InterpolationError::InvalidDotEnvLine(i + 1, line.to_string()),
+            )?;
+            let key = key.trim();
+            if !is_valid_name(key) {
+                return Err(InterpolationError::InvalidDotEnvLine(
+                    i + 1,
+                    line.to_string(),
+                ));
+            }
+            let value = value.trim();
+            let value = match value.chars().next() {
+                Some(quote @ ('"' | '\'')) if value.len() > 1 && value.ends_with(quote) => {
+                    &value[1..value.len() - 1]
+                }
+                _ => value,
+            };
+            vars.insert(key.to_string(), value.to_string());
+        }
+        Ok(Self { vars })
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, InterpolationError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| InterpolationError::DotEnvReadError(e.to_string()))?;
+        Self::parse(&content)
+    }
+}
+
+impl VariableResolver for DotEnvResolver {
+    fn resolve(&self, name: &str) -> Option<String> {
+        self.vars.get(name).cloned()
+    }
+}
+
+/// Tries each of its resolvers in order and returns the first value found
+#[derive(Default)]
+pub struct ChainResolver {
+    resolvers: Vec<Box<dyn VariableResolver>>,
+}
+
+impl ChainResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, resolver: impl VariableResolver + 'static) -> Self {
+        self.resolvers.push(Box::new(resolver));
+        self
+    }
+}
+
+impl VariableResolver for ChainResolver {
+    fn resolve(&self, name: &str) -> Option<String> {
+        self.resolvers.iter().find_map(|r| r.resolve(name))
+    }
+}
+
+/// The strings of a config that got their value from a resolver, kept so
+/// they can be swapped back to their `${VAR}` form whenever the config is
+/// written out or shown in an error
+#[derive(Clone, Default, PartialEq)]
+pub struct ConfigSecrets {
+    /// pairs of (resolved string, original template)
+    values: Vec<(String, String)>,
+    /// the variables that were resolved, so a redacted config can be
+    /// parsed again with the same values
+    variables: HashMap<String, String>,
+}
+
+impl ConfigSecrets {
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn extend(&mut self, other: ConfigSecrets) {
+        for value in other.values {
+            if !self.values.contains(&value) {
+                self.values.push(value);
+            }
+        }
+        self.variables.extend(other.variables);
+    }
+
+    fn insert(&mut self, resolved: String, template: String) {
+        if !resolved.is_empty() && !self.values.iter().any(|(r, _)| *r == resolved) {
+            self.values.push((resolved, template));
+        }
+    }
+
+    /// Replaces the resolved strings found in the given text with their
+    /// templates. A resolved url is replaced wherever it appears whole and
+    /// the url components that came from a single variable are replaced by
+    /// that variable, any other resolved string only when it is the whole
+    /// text or quoted, so short values like `1` do not rewrite unrelated text
+    pub fn redact(&self, text: &str) -> String {
+        let mut needles = vec![];
+        for (resolved, template) in &self.values {
+            let Some(url) = parse_url(resolved) else {
+                needles.push((resolved.clone(), template.clone(), Needle::Scalar));
+                continue;
+            };
+            needles.push((resolved.clone(), template.clone(), Needle::Url));
+            if url.as_str() != resolved {
+                needles.push((url.to_string(), template.clone(), Needle::Url));
+            }
+            for component in url_components(&url) {
+                if let Some((name, _)) = self.variables.iter().find(|(_, v)| **v == component) {
+                    needles.push((component, format!("${{{}}}", name), Needle::UrlComponent));
+                }
+            }
+        }
+        // longest first so a value that contains another one is redacted whole
+        needles.sort_by_key(|v| std::cmp::Reverse(v.0.len()));
+        needles
+            .into_iter()
+            .fold(text.to_string(), |text, (needle, replacement, kind)| {
+                replace_bounded(&text, &needle, &replacement, kind)
+            })
+    }
+
+    /// Replaces the resolved string scalars of a yaml value with their templates
+    pub fn redact_value(&self, value: &mut Value) {
+        match value {
+            Value::String(s) => {
+                if let Some(template) = self.template_of(s) {
+                    *s = template.to_string();
+                }
+            }
+            Value::Sequence(seq) => seq.iter_mut().for_each(|v| self.redact_value(v)),
+            Value::Mapping(map) => map.values_mut().for_each(|v| self.redact_value(v)),
+            Value::Tagged(tagged) => self.redact_value(&mut tagged.value),
+            _ => {}
+        }
+    }
+
+    /// The template of a whole resolved string, a url also matches its
+    /// normalized form as it is serialized once parsed
+    fn template_of(&self, s: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(resolved, _)| {
+                resolved == s || parse_url(resolved).is_some_and(|url| url.as_str() == s)
+            })
+            .map(|(_, template)| template.as_str())
+    }
+
+    /// Wraps a value so it serializes with the resolved strings of this
+    /// config swapped back to their templates
+    pub fn redacted<T>(&self, value: T) -> Redacted<T> {
+        Redacted {
+            value,
+            secrets: self.clone(),
+        }
+    }
+
+    /// Deserializes an interpolated yaml value, redacting the error message if it fails
+    pub fn deserialize<T: DeserializeOwned>(&self, value: Value) -> Result<T, RedactedError> {
+        serde_yaml::from_value(value).map_err(|e| RedactedError(self.redact(&e.to_string())))
+    }
+}
+
+// never print the resolved values
+impl std::fmt::Debug for ConfigSecrets {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfigSecrets")
+            .field("variables", &self.variables.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl VariableResolver for ConfigSecrets {
+    fn resolve(&self, name: &str) -> Option<String> {
+        self.variables.get(name).cloned()
+    }
+}
+
+/// A value that serializes with the resolved strings of its config swapped
+/// back to their `${VAR}` templates, to hand a config out of the process
+/// without its secrets
+#[derive(Debug, Clone, PartialEq)]
+pub struct Redacted<T> {
+    pub value: T,
+    secrets: ConfigSecrets,
+}
+
+impl<T: Serialize> Serialize for Redacted<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut value = serde_yaml::to_value(&self.value).map_err(serde::ser::Error::custom)?;
+        self.secrets.redact_value(&mut value);
+        value.serialize(serializer)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Needle {
+    Scalar,
+    Url,
+    UrlComponent,
+}
+
+fn parse_url(value: &str) -> Option<Url> {
+    Url::parse(value)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https" | "ws" | "wss"))
+}
+
+/// The parts of a url a variable could have been interpolated into
+fn url_components(url: &Url) -> Vec<String> {
+    let mut components = vec![url.username().to_string()];
+    components.extend(url.password().map(str::to_string));
+    components.extend(url.host_str().map(str::to_string));
+    components.extend(
+        url.path_segments()
+            .into_iter()
+            .flatten()
+            .map(str::to_string),
+    );
+    components.extend(url.query_pairs().map(|(_, value)| value.into_owned()));
+    components.extend(url.fragment().map(str::to_string));
+    components.retain(|c| !c.is_empty());
+    components
+}
+
+/// Whether a char can be part of a url component, so a match next to it
+/// is only part of a longer string
+fn is_component_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '-' | '_' | '~' | '%' | '+' | '.')
+}
+
+/// Replaces the occurrences of a needle that are bounded as its kind requires
+fn replace_bounded(text: &str, needle: &str, replacement: &str, kind: Needle) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+    for (start, _) in text.match_indices(needle) {
+        let end = start + needle.len();
+        let before = text[..start].chars().next_back();
+        let after = text[end..].chars().next();
+        let bounded = match kind {
+            Needle::Scalar => {
+                (before.is_none() && after.is_none())
+                    || matches!(before, Some('"' | '\'' | '`')) && before == after
+            }
+            Needle::Url => {
+                !before.is_some_and(is_component_char) && !after.is_some_and(is_component_char)
+            }
+            Needle::UrlComponent => {
+                matches!(before, Some('/' | '?' | '&' | '=' | '#' | '@' | ':'))
+                    && !after.is_some_and(is_component_char)
+            }
+        };
+        if bounded {
+            result.push_str(&text[last..start]);
+            result.push_str(replacement);
+            last = end;
+        }
+    }
+    result.push_str(&text[last..]);
+    result
+}
+
+/// An error whose message has been stripped of resolved secrets
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("{0}")]
+pub struct RedactedError(pub String);
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Expands the `${VAR}` and `${VAR:-default}` references of a string, `$${`
+/// escapes a literal `${`. Returns the expanded string and the variables
+/// that were resolved by the resolver rather than taken from their default.
+pub fn interpolate_str(
+    template: &str,
+    resolver: &dyn VariableResolver,
+) -> Result<(String, HashMap<String, String>), InterpolationError> {
+    let mut result = String::with_capacity(template.len());
+    let mut resolved = HashMap::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('$') {
+        result.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        if let Some(escaped) = tail.strip_prefix("$${") {
+            result.push_str("${");
+            rest = escaped;
+        } else if let Some(reference) = tail.strip_prefix("${") {
+            let end = reference
+                .find('}')
+                .ok_or(InterpolationError::Unterminated(template.to_string()))?;
+            let (name, default) = match reference[..end].split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (&reference[..end], None),
+            };
+            if !is_valid_name(name) {
+                return Err(InterpolationError::InvalidName(name.to_string()));
+            }
+            match (resolver.resolve(name), default) {
+                (Some(value), _) => {
+                    result.push_str(&value);
+                    resolved.insert(name.to_string(), value);
+                }
+                (None, Some(default)) => result.push_str(default),
+                (None, None) => return Err(InterpolationError::MissingVariable(name.to_string())),
+            }
+            rest = &reference[end + 1..];
+        } else {
+            result.push('$');
+            rest = &tail[1..];
+        }
+    }
+    result.push_str(rest);
+    Ok((result, resolved))
+}
+
+/// Interpolates every string scalar of a yaml value in place, mapping keys
+/// are left untouched. Strings that got a value from the resolver are
+/// recorded in the given secrets.
+pub fn interpolate_yaml(
+    value: &mut Value,
+    resolver: &dyn VariableResolver,
+    secrets: &mut ConfigSecrets,
+) -> Result<(), InterpolationError> {
+    match value {
+        Value::String(s) if s.contains('$') => {
+            let (interpolated, resolved) = interpolate_str(s, resolver)?;
+            if !resolved.is_empty() {
+                secrets.insert(interpolated.clone(), s.clone());
+                secrets.variables.extend(resolved);
+            }
+            *s = interpolated;
+        }
+        Value::Sequence(seq) => {
+            for v in seq.iter_mut() {
+                interpolate_yaml(v, resolver, secrets)?;
+            }
+        }
+        Value::Mapping(map) => {
+            for v in map.values_mut() {
+                interpolate_yaml(v, resolver, secrets)?;
+            }
+        }
+        Value::Tagged(tagged) => interpolate_yaml(&mut tagged.value, resolver, secrets)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Interpolates a yaml document like [interpolate_yaml] and substitutes the
+/// interpolated strings in its text as well, so it can still be deserialized
+/// from the text, as deserializing a [Value] turns plain scalars like `0x01`
+/// or `123` into numbers. Returns None if the substituted text does not parse
+/// to the interpolated value, eg a resolved value changed the yaml structure,
+/// in which case the interpolated value is to be deserialized instead.
+pub fn interpolate_yaml_text(
+    text: &str,
+    value: &mut Value,
+    resolver: &dyn VariableResolver,
+    secrets: &mut ConfigSecrets,
+) -> Result<Option<String>, InterpolationError> {
+    let original = value.clone();
+    interpolate_yaml(value, resolver, secrets)?;
+
+    let mut replacements = vec![];
+    collect_replacements(&original, value, &mut replacements);
+    if replacements.is_empty() {
+        return Ok(Some(text.to_string()));
+    }
+    // longer templates first so a template is not partially replaced by
+    // another one it contains
+    replacements.sort_by_key(|(template, _)| std::cmp::Reverse(template.len()));
+    let mut interpolated = text.to_string();
+    for (template, replacement) in replacements {
+        interpolated = interpolated.replace(template, replacement);
+    }
+    match serde_yaml::from_str::<Value>(&interpolated) {
+        Ok(v) if v == *value => Ok(Some(interpolated)),
+        _ => Ok(None),
+    }
+}
+
+/// Pairs the strings of a yaml value that changed when interpolated
+fn collect_replacements<'a>(
+    original: &'a Value,
+    interpolated: &'a Value,
+    replacements: &mut Vec<(&'a str, &'a str)>,
+) {
+    match (original, interpolated) {
+        (Value::String(a), Value::String(b)) if a != b => replacements.push((a, b)),
+        (Value::Sequence(a), Value::Sequence(b)) => a
+            .iter()
+            .zip(b.iter())
+            .for_each(|(a, b)| collect_replacements(a, b, replacements)),
+        (Value::Mapping(a), Value::Mapping(b)) => a.iter().for_each(|(key, a)| {
+            if let Some(b) = b.get(key) {
+                collect_replacements(a, b, replacements)
+            }
+        }),
+        (Value::Tagged(a), Value::Tagged(b)) => {
+            collect_replacements(&a.value, &b.value, replacements)
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolver() -> HashMap<String, String> {
+        HashMap::from([
+            ("RPC_KEY".to_string(), "abc123".to_string()),
+            ("HOST".to_string(), "mainnet.node".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_interpolate_str() {
+        let resolver = resolver();
+        assert_eq!(
+            interpolate_str("https://${HOST}/${RPC_KEY}", &resolver).unwrap(),
+            ("https://mainnet.node/abc123".to_string(), resolver.clone())
+        );
+        assert_eq!(
+            interpolate_str("https://${OTHER:-fallback.node}", &resolver).unwrap(),
+            ("https://fallback.node".to_string(), HashMap::new())
+        );
+        assert_eq!(
+            interpolate_str("${HOST:-unused}", &resolver).unwrap(),
+            (
+                "mainnet.node".to_string(),
+                HashMap::from([("HOST".to_string(), "mainnet.node".to_string())])
+            )
+        );
+        assert_eq!(
+            interpolate_str("cost $5 and $${HOST}", &resolver).unwrap(),
+            ("cost $5 and ${HOST}".to_string(), HashMap::new())
+        );
+        assert_eq!(
+            interpolate_str("${MISSING}", &resolver),
+            Err(InterpolationError::MissingVariable("MISSING".to_string()))
+        );
+        assert_eq!(
+            interpolate_str("${HOST", &resolver),
+            Err(InterpolationError::Unterminated("${HOST".to_string()))
+        );
+        assert_eq!(
+            interpolate_str("${1HOST}", &resolver),
+            Err(InterpolationError::InvalidName("1HOST".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_interpolate_yaml_and_redact() {
+        let mut value: Value = serde_yaml::from_str(
+            r#"
+networks:
+    mainnet:
+        rpc: https://${HOST}/${RPC_KEY}
+        chain-id: 1
+        label: ${LABEL:-Mainnet}
+"#,
+        )
+        .unwrap();
+        let mut secrets = ConfigSecrets::default();
+        interpolate_yaml(&mut value, &resolver(), &mut secrets).unwrap();
+
+        assert_eq!(
+            value["networks"]["mainnet"]["rpc"].as_str(),
+            Some("https://mainnet.node/abc123")
+        );
+        assert_eq!(
+            value["networks"]["mainnet"]["label"].as_str(),
+            Some("Mainnet")
+        );
+
+        assert_eq!(
+            secrets.redact("invalid url https://mainnet.node/abc123"),
+            "invalid url https://${HOST}/${RPC_KEY}"
+        );
+
+        secrets.redact_value(&mut value);
+        assert_eq!(
+            value["networks"]["mainnet"]["rpc"].as_str(),
+            Some("https://${HOST}/${RPC_KEY}")
+        );
+        // only values that came from the resolver are redacted
+        assert_eq!(
+            value["networks"]["mainnet"]["label"].as_str(),
+            Some("Mainnet")
+        );
+
+        // the redacted value can be interpolated again from the secrets
+        let mut new_secrets = ConfigSecrets::default();
+        interpolate_yaml(&mut value, &secrets, &mut new_secrets).unwrap();
+        assert_eq!(
+            value["networks"]["mainnet"]["rpc"].as_str(),
+            Some("https://mainnet.node/abc123")
+        );
+        assert_eq!(new_secrets, secrets);
+    }
+
+    #[test]
+    fn test_redact_only_whole_values() {
+        let mut value: Value = serde_yaml::from_str(
+            r#"
+rpc: https://node.io/${CHAIN}/${KEY}?network=${NETWORK}
+chain-id: "${CHAIN}"
+network: ${NETWORK}
+"#,
+        )
+        .unwrap();
+        let resolver = HashMap::from([
+            ("CHAIN".to_string(), "1".to_string()),
+            ("KEY".to_string(), "secret".to_string()),
+            ("NETWORK".to_string(), "mainnet".to_string()),
+        ]);
+        let mut secrets = ConfigSecrets::default();
+        interpolate_yaml(&mut value, &resolver, &mut secrets).unwrap();
+
+        // short values are left alone in unrelated text
+        assert_eq!(
+            secrets.redact("mainnet: error at line 1 column 12"),
+            "mainnet: error at line 1 column 12"
+        );
+        // but redacted when quoted or whole
+        assert_eq!(
+            secrets.redact("invalid chain id \"1\" of mainnet"),
+            "invalid chain id \"${CHAIN}\" of mainnet"
+        );
+        assert_eq!(secrets.redact("mainnet"), "${NETWORK}");
+        // urls are redacted whole, normalized or not
+        assert_eq!(
+            secrets.redact("bad rpc https://node.io/1/secret?network=mainnet, retrying"),
+            "bad rpc https://node.io/${CHAIN}/${KEY}?network=${NETWORK}, retrying"
+        );
+        // and components of a url that came from a variable by themselves
+        assert_eq!(
+            secrets.redact("failed to reach https://node.io/1/secret/ at block 1"),
+            "failed to reach https://node.io/${CHAIN}/${KEY}/ at block 1"
+        );
+        // without touching the strings they are part of
+        assert_eq!(
+            secrets.redact("https://node.io/1/secretive"),
+            "https://node.io/${CHAIN}/secretive"
+        );
+
+        let mut url = Value::from("https://node.io/1/secret?network=mainnet");
+        secrets.redact_value(&mut url);
+        assert_eq!(
+            url,
+            Value::from("https://node.io/${CHAIN}/${KEY}?network=${NETWORK}")
+        );
+        let mut unrelated = Value::from("mainnet-fork");
+        secrets.redact_value(&mut unrelated);
+        assert_eq!(unrelated, Value::from("mainnet-fork"));
+    }
+
+    #[test]
+    fn test_interpolate_yaml_text() {
+        let text = r#"# ${NOT_SET} in a comment is left as is
+rpc: https://${HOST}/${RPC_KEY}
+address: 0x0000000000000000000000000000000000000001
+version: 123
+label: "${LABEL:-some label}"
+"#;
+        let mut value: Value = serde_yaml::from_str(text).unwrap();
+        let mut secrets = ConfigSecrets::default();
+        let interpolated = interpolate_yaml_text(text, &mut value, &resolver(), &mut secrets)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            interpolated,
+            r#"# ${NOT_SET} in a comment is left as is
+rpc: https://mainnet.node/abc123
+address: 0x0000000000000000000000000000000000000001
+version: 123
+label: "some label"
+"#
+        );
+        assert_eq!(value["rpc"], Value::from("https://mainnet.node/abc123"));
+        // defaults are not secrets
+        assert_eq!(
+            secrets.redact(&interpolated),
+            text.replace("${LABEL:-some label}", "some label")
+        );
+
+        // a value that changes the yaml structure can only be used through the value
+        let text = "label: ${LABEL}\n";
+        let resolver = HashMap::from([("LABEL".to_string(), "a: b".to_string())]);
+        let mut value: Value = serde_yaml::from_str(text).unwrap();
+        let mut secrets = ConfigSecrets::default();
+        assert_eq!(
+            interpolate_yaml_text(text, &mut value, &resolver, &mut secrets).unwrap(),
+            None
+        );
+        assert_eq!(value["label"], Value::from("a: b"));
+    }
+
+    #[test]
+    fn test_dotenv_resolver() {
+        let resolver = DotEnvResolver::parse(
+            r#"
+# comment
+RPC_KEY=abc123
+export HOST="mainnet.node"
+EMPTY=
+QUOTED='a b'
+"#,
+        )
+        .unwrap();
+        assert_eq!(resolver.resolve("RPC_KEY"), Some("abc123".to_string()));
+        assert_eq!(resolver.resolve("HOST"), Some("mainnet.node".to_string()));
+        assert_eq!(resolver.resolve("EMPTY"), Some("".to_string()));
+        assert_eq!(resolver.resolve("QUOTED"), Some("a b".to_string()));
+        assert_eq!(resolver.resolve("MISSING"), None);
+
+        assert_eq!(
+            DotEnvResolver::parse("NOT A VAR"),
+            Err(InterpolationError::InvalidDotEnvLine(
+                1,
+                "NOT A VAR".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_chain_resolver() {
+        let resolver = ChainResolver::new()
+            .with(HashMap::from([("A".to_string(), "first".to_string())]))
+            .with(HashMap::from([
+                ("A".to_string(), "second".to_string()),
+                ("B".to_string(), "second".to_string()),
+            ]));
+        assert_eq!(resolver.resolve("A"), Some("first".to_string()));
+        assert_eq!(resolver.resolve("B"), Some("second".to_string()));
+        assert_eq!(resolver.resolve("C"), None);
+    }
+}
@@ -5,13 +5,18 @@ pub mod config_source;
 pub mod deployer;
 pub mod deployment;
 pub mod gui;
+pub mod imports;
+pub mod interpolation;
+pub mod lint;
 pub mod merge;
 pub mod network;
 pub mod order;
 pub mod orderbook;
 pub mod plot_source;
 pub mod remote;
+pub mod rpc;
 pub mod scenario;
+pub mod schema;
 pub mod token;
 pub mod unit_test;
 
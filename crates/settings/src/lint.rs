@@ -0,0 +1,1316 @@
+use crate::config_source::{interpolate_config, ConfigSourceError};
+use crate::imports::ImportBase;
+use crate::interpolation::{EnvResolver, InterpolationError};
+use crate::merge::{ConfigLayers, ConfigProvenance};
+use crate::schema::{config_schema, find_unknown_fields};
+use crate::*;
+use alloy::primitives::{Address, U256};
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use std::{collections::HashMap, fmt, path::PathBuf, sync::Arc};
+use typeshare::typeshare;
+
+#[typeshare]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConfigLintSeverity {
+    Error,
+    Warning,
+}
+
+/// The yaml text a diagnostic belongs to
+#[typeshare]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConfigLintSource {
+    /// The linted text, ie a settings file or a dotrain frontmatter
+    Main,
+    /// The settings the main text is merged with
+    Settings,
+}
+
+/// Position of a diagnostic in its yaml text, line and column are 1-based
+/// and start and end are byte offsets of the spanned text
+#[typeshare]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct ConfigLintSpan {
+    #[typeshare(typescript(type = "number"))]
+    pub line: usize,
+    #[typeshare(typescript(type = "number"))]
+    pub column: usize,
+    #[typeshare(typescript(type = "number"))]
+    pub start: usize,
+    #[typeshare(typescript(type = "number"))]
+    pub end: usize,
+}
+
+#[typeshare]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ConfigLintDiagnostic {
+    pub severity: ConfigLintSeverity,
+    pub source: ConfigLintSource,
+    /// Dot separated path of the offending yaml key, eg `tokens.weth.network`
+    pub path: String,
+    pub message: String,
+    pub span: Option<ConfigLintSpan>,
+}
+
+impl fmt::Display for ConfigLintDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            ConfigLintSeverity::Error => "error",
+            ConfigLintSeverity::Warning => "warning",
+        };
+        write!(f, "{}: {}", severity, self.message)?;
+        if !self.path.is_empty() {
+            write!(f, " at {}", self.path)?;
+        }
+        if let Some(span) = &self.span {
+            write!(f, " (line {}, column {})", span.line, span.column)?;
+        }
+        if self.source == ConfigLintSource::Settings {
+            write!(f, " in settings")?;
+        }
+        Ok(())
+    }
+}
+
+/// Lints the given config yaml, optionally merged with the given settings
+/// yaml, and returns every parse, reference and semantic error found in
+/// them rather than stopping at the first one. Diagnostics are sorted by
+/// their position.
+///
+/// Remote networks of `using-networks-from` are not fetched, so when it is
+/// used, networks that are not defined locally are assumed to be remote ones.
+/// Likewise token lists of `using-tokens-from` are not fetched, tokens keyed
+/// as `<network>-...` that are not defined locally are assumed to be listed.
+pub fn lint_config(text: &str, settings: Option<&str>) -> Vec<ConfigLintDiagnostic> {
+    lint(text, settings, false)
+}
+
+/// Same as [lint_config] but also reports every key the config format does
+/// not know of, suggesting the closest known key for typos
+pub fn lint_config_strict(text: &str, settings: Option<&str>) -> Vec<ConfigLintDiagnostic> {
+    lint(text, settings, true)
+}
+
+fn lint(text: &str, settings: Option<&str>, strict: bool) -> Vec<ConfigLintDiagnostic> {
+    let mut linter = Linter {
+        main_spans: YamlSpans::new(text),
+        settings_spans: settings.map(YamlSpans::new),
+        diagnostics: vec![],
+    };
+
+    let settings_config = settings.map(|v| linter.parse(v, ConfigLintSource::Settings));
+    let main_config = linter.parse(text, ConfigLintSource::Main);
+    match (main_config, settings_config) {
+        (Some(main), None) => linter.check(main, None),
+        (Some(main), Some(Some(settings))) => linter.check(main, Some(settings)),
+        // references can not be checked without every config they are in
+        _ => {}
+    }
+    if strict {
+        if let Some(settings) = settings {
+            linter.check_unknown_fields(settings, ConfigLintSource::Settings);
+        }
+        linter.check_unknown_fields(text, ConfigLintSource::Main);
+    }
+
+    let mut diagnostics = linter.diagnostics;
+    diagnostics.sort_by_key(|v| (v.source, v.span.is_none(), v.span.map(|s| s.start)));
+    diagnostics
+}
+
+struct Linter {
+    main_spans: YamlSpans,
+    settings_spans: Option<YamlSpans>,
+    diagnostics: Vec<ConfigLintDiagnostic>,
+}
+
+impl Linter {
+    fn spans(&self, source: ConfigLintSource) -> Option<&YamlSpans> {
+        match source {
+            ConfigLintSource::Main => Some(&self.main_spans),
+            ConfigLintSource::Settings => self.settings_spans.as_ref(),
+        }
+    }
+
+    fn push(
+        &mut self,
+        severity: ConfigLintSeverity,
+        source: ConfigLintSource,
+        path: String,
+        message: String,
+    ) {
+        let span = self.spans(source).and_then(|v| v.locate(&path));
+        self.diagnostics.push(ConfigLintDiagnostic {
+            severity,
+            source,
+            path,
+            message,
+            span,
+        });
+    }
+
+    fn error(&mut self, source: ConfigLintSource, path: String, message: String) {
+        self.push(ConfigLintSeverity::Error, source, path, message);
+    }
+
+    fn warning(&mut self, source: ConfigLintSource, path: String, message: String) {
+        self.push(ConfigLintSeverity::Warning, source, path, message);
+    }
+
+    /// The text an entry of the merged config is defined in, the main text
+    /// when both define it as its values override those of the settings
+    fn source_of(&self, path: &str) -> ConfigLintSource {
+        let entry = path.split('.').take(2).collect::<Vec<_>>().join(".");
+        match &self.settings_spans {
+            Some(settings) if !self.main_spans.contains(&entry) && settings.contains(&entry) => {
+                ConfigLintSource::Settings
+            }
+            _ => ConfigLintSource::Main,
+        }
+    }
+
+    /// Parses a yaml text the same way loading a config does, resolving its
+    /// `${VAR}` references from the process environment
+    fn parse(&mut self, text: &str, source: ConfigLintSource) -> Option<ConfigSource> {
+        let (mut config, secrets) = match interpolate_config::<ConfigSource>(text, &EnvResolver) {
+            Ok(v) => v,
+            Err(e) => {
+                self.parse_error(text, source, e);
+                return None;
+            }
+        };
+        config.secrets = secrets;
+
+        for (i, import) in config.imports.iter().enumerate() {
+            if let Err(e) = import.location(&ImportBase::Dir(PathBuf::new())) {
+                self.error(source, format!("imports.{}", i), e.to_string());
+            }
+        }
+        if !config.imports.is_empty() {
+            self.warning(
+                source,
+                "imports".to_string(),
+                "Imports are not loaded while linting, entries they define are reported as missing"
+                    .to_string(),
+            );
+        }
+        Some(config)
+    }
+
+    fn parse_error(&mut self, text: &str, source: ConfigLintSource, error: ConfigSourceError) {
+        let (path, message, span) = match &error {
+            ConfigSourceError::YamlDeserializerError(e) => {
+                let span = e.location().map(|v| ConfigLintSpan {
+                    line: v.line(),
+                    column: v.column(),
+                    start: v.index(),
+                    end: v.index(),
+                });
+                let mut message = e.to_string();
+                if let Some(location) = e.location() {
+                    let suffix =
+                        format!(" at line {} column {}", location.line(), location.column());
+                    if let Some(v) = message.strip_suffix(&suffix) {
+                        message = v.to_string();
+                    }
+                }
+                let (path, message) = split_error_path(&message);
+                (path, message, span)
+            }
+            ConfigSourceError::RedactedYamlDeserializerError(e) => {
+                let (path, message) = split_error_path(e);
+                let span = self.spans(source).and_then(|v| v.locate(&path));
+                (path, message, span)
+            }
+            ConfigSourceError::InterpolationError(InterpolationError::MissingVariable(name)) => {
+                let span = locate_text(text, &format!("${{{}}}", name))
+                    .or_else(|| locate_text(text, &format!("${{{}", name)));
+                let path = span
+                    .and_then(|v| self.spans(source)?.path_at(v.line))
+                    .unwrap_or_default();
+                (path, error.to_string(), span)
+            }
+            _ => (String::new(), error.to_string(), None),
+        };
+        self.diagnostics.push(ConfigLintDiagnostic {
+            severity: ConfigLintSeverity::Error,
+            source,
+            path,
+            message,
+            span,
+        });
+    }
+
+    /// Merges the configs the same way loading a dotrain order with its
+    /// frontmatter taking precedence does and checks every entry of the
+    /// merged config, reporting the values the main text overrides, which a
+    /// strict merge rejects, and every reference and semantic error
+    fn check(&mut self, main: ConfigSource, settings: Option<ConfigSource>) {
+        let config = match settings {
+            Some(settings) => match ConfigLayers::dotrain(main, settings).merge() {
+                Ok((config, provenance)) => {
+                    self.check_overrides(&provenance);
+                    config
+                }
+                Err(e) => {
+                    self.error(ConfigLintSource::Main, String::new(), e.to_string());
+                    return;
+                }
+            },
+            None => main,
+        };
+        self.check_vault_ids(&config.orders);
+        self.collect(&config);
+    }
+
+    /// Resolves the references between the entries of the config the same
+    /// way converting into a [Config] does, but entry by entry so every
+    /// failure is reported rather than the first one
+    fn collect(&mut self, config: &ConfigSource) {
+        let mut networks: HashMap<String, Arc<Network>> = HashMap::new();
+        for (name, network) in sorted(&config.networks) {
+            match network.clone().try_into_network(name.clone()) {
+                Ok(v) => {
+                    networks.insert(name.clone(), Arc::new(v));
+                }
+                Err(e) => {
+                    let path = format!("networks.{}", name);
+                    self.error(self.source_of(&path), path, e.to_string());
+                }
+            }
+        }
+        if !config.using_networks_from.is_empty() {
+            let mut assumed = config
+                .orderbooks
+                .iter()
+                .map(|(name, v)| v.network.clone().unwrap_or_else(|| name.clone()))
+                .chain(config.tokens.values().map(|v| v.network.clone()))
+                .chain(
+                    config
+                        .deployers
+                        .iter()
+                        .map(|(name, v)| v.network.clone().unwrap_or_else(|| name.clone())),
+                )
+                .filter(|v| !networks.contains_key(v))
+                .collect::<Vec<_>>();
+            assumed.sort();
+            assumed.dedup();
+            if !assumed.is_empty() {
+                let path = "using-networks-from".to_string();
+                self.warning(
+                    self.source_of(&path),
+                    path,
+                    format!(
+                        "Remote networks are not fetched while linting, {} are assumed to be remote networks",
+                        quoted_list(&assumed)
+                    ),
+                );
+            }
+            for name in assumed {
+                networks.insert(
+                    name.clone(),
+                    Arc::new(Network {
+                        name,
+                        ..Network::dummy()
+                    }),
+                );
+            }
+        }
+        let subgraphs: HashMap<String, Arc<Subgraph>> = config
+            .subgraphs
+            .iter()
+            .map(|(name, v)| (name.clone(), Arc::new(v.clone())))
+            .collect();
+
+        let mut orderbooks: HashMap<String, Arc<Orderbook>> = HashMap::new();
+        for (name, orderbook) in sorted(&config.orderbooks) {
+            match orderbook
+                .clone()
+                .try_into_orderbook(name.clone(), &networks, &subgraphs)
+            {
+                Ok(v) => {
+                    orderbooks.insert(name.clone(), Arc::new(v));
+                }
+                Err(e) => {
+                    let field = match &e {
+                        ParseOrderbookConfigSourceError::NetworkNotFoundError(_) => ".network",
+                        ParseOrderbookConfigSourceError::SubgraphNotFoundError(_) => ".subgraph",
+                        _ => "",
+                    };
+                    let path = format!("orderbooks.{}{}", name, field);
+                    self.error(self.source_of(&path), path, e.to_string());
+                }
+            }
+        }
+
+        let mut tokens: HashMap<String, Arc<Token>> = HashMap::new();
+        for (name, token) in sorted(&config.tokens) {
+            match token.clone().try_into_token(&networks) {
+                Ok(v) => {
+                    tokens.insert(name.clone(), Arc::new(v));
+                }
+                Err(e) => {
+                    let path = format!("tokens.{}.network", name);
+                    self.error(self.source_of(&path), path, e.to_string());
+                }
+            }
+        }
+        if !config.using_tokens_from.is_empty() {
+            // listed tokens are keyed by their network's name
+            let mut assumed = config
+                .orders
+                .values()
+                .flat_map(|v| v.inputs.iter().chain(v.outputs.iter()))
+                .filter(|v| !tokens.contains_key(&v.token))
+                .filter_map(|v| {
+                    networks
+                        .values()
+                        .filter(|network| v.token.starts_with(&format!("{}-", network.name)))
+                        .max_by_key(|network| network.name.len())
+                        .map(|network| (v.token.clone(), network.clone()))
+                })
+                .collect::<Vec<_>>();
+            assumed.sort_by(|a, b| a.0.cmp(&b.0));
+            assumed.dedup_by(|a, b| a.0 == b.0);
+            if !assumed.is_empty() {
+                let path = "using-tokens-from".to_string();
+                self.warning(
+                    self.source_of(&path),
+                    path,
+                    format!(
+                        "Token lists are not fetched while linting, {} are assumed to be listed tokens",
+                        quoted_list(&assumed.iter().map(|v| v.0.clone()).collect::<Vec<_>>())
+                    ),
+                );
+            }
+            for (name, network) in assumed {
+                tokens.insert(
+                    name,
+                    Arc::new(Token {
+                        network,
+                        address: Address::ZERO,
+                        decimals: None,
+                        label: None,
+                        symbol: None,
+                    }),
+                );
+            }
+        }
+
+        let mut deployers: HashMap<String, Arc<Deployer>> = HashMap::new();
+        for (name, deployer) in sorted(&config.deployers) {
+            match deployer.clone().try_into_deployer(name.clone(), &networks) {
+                Ok(v) => {
+                    deployers.insert(name.clone(), Arc::new(v));
+                }
+                Err(e) => {
+                    let path = format!("deployers.{}.network", name);
+                    self.error(self.source_of(&path), path, e.to_string());
+                }
+            }
+        }
+
+        let mut orders: HashMap<String, Arc<Order>> = HashMap::new();
+        for (name, order) in sorted(&config.orders) {
+            if !self.check_order(name, order, &deployers, &orderbooks, &tokens) {
+                continue;
+            }
+            match order
+                .clone()
+                .try_into_order(&deployers, &orderbooks, &tokens)
+            {
+                Ok(v) => {
+                    orders.insert(name.clone(), Arc::new(v));
+                }
+                Err(e) => {
+                    let path = format!("orders.{}", name);
+                    self.error(self.source_of(&path), path, e.to_string());
+                }
+            }
+        }
+
+        let mut scenarios: HashMap<String, Arc<Scenario>> = HashMap::new();
+        for (name, scenario) in sorted(&config.scenarios) {
+            match scenario.try_into_scenarios(name.clone(), &ScenarioParent::default(), &deployers)
+            {
+                Ok(v) => scenarios.extend(v),
+                Err(e) => {
+                    let path = match &e {
+                        ParseScenarioConfigSourceError::DeployerNotFound(v) => {
+                            format!("scenarios.{}", v.replace('.', ".scenarios."))
+                        }
+                        _ => format!("scenarios.{}", name),
+                    };
+                    self.error(self.source_of(&path), path, e.to_string());
+                }
+            }
+        }
+
+        let mut deployments: HashMap<String, Arc<Deployment>> = HashMap::new();
+        for (name, deployment) in sorted(&config.deployments) {
+            if !self.check_deployment(name, deployment, &scenarios, &orders) {
+                continue;
+            }
+            match deployment.clone().try_into_deployment(&scenarios, &orders) {
+                Ok(v) => {
+                    deployments.insert(name.clone(), Arc::new(v));
+                }
+                Err(e) => {
+                    let path = format!("deployments.{}", name);
+                    self.error(self.source_of(&path), path, e.to_string());
+                }
+            }
+        }
+
+        for (name, chart) in sorted(&config.charts) {
+            if let Err(e) = chart.clone().try_into_chart(name.clone(), &scenarios) {
+                let path = format!("charts.{}.scenario", name);
+                self.error(self.source_of(&path), path, e.to_string());
+            }
+        }
+
+        if let Some(gui) = &config.gui {
+            let path = match gui
+                .deployments
+                .iter()
+                .position(|v| !deployments.contains_key(&v.deployment))
+            {
+                Some(index) => format!("gui.deployments.{}.deployment", index),
+                None => "gui".to_string(),
+            };
+            if let Err(e) = gui.clone().try_into_gui(&deployments, &tokens) {
+                self.error(self.source_of(&path), path, e.to_string());
+            }
+        }
+    }
+
+    /// Checks the references of an order and that all of them are on the
+    /// same network, returns false if any of them is invalid
+    fn check_order(
+        &mut self,
+        name: &str,
+        order: &OrderConfigSource,
+        deployers: &HashMap<String, Arc<Deployer>>,
+        orderbooks: &HashMap<String, Arc<Orderbook>>,
+        tokens: &HashMap<String, Arc<Token>>,
+    ) -> bool {
+        let errors_count = self.diagnostics.len();
+
+        // every network the order is bound to along with where it comes from
+        let mut bound_networks: Vec<(String, String, String)> = vec![];
+        if let Some(deployer) = &order.deployer {
+            let path = format!("orders.{}.deployer", name);
+            match deployers.get(deployer) {
+                Some(v) => bound_networks.push((
+                    path,
+                    format!("deployer '{}'", deployer),
+                    v.network.name.clone(),
+                )),
+                None => self.error(
+                    self.source_of(&path),
+                    path,
+                    format!("Deployer not found: {}", deployer),
+                ),
+            }
+        }
+        if let Some(orderbook) = &order.orderbook {
+            let path = format!("orders.{}.orderbook", name);
+            match orderbooks.get(orderbook) {
+                Some(v) => bound_networks.push((
+                    path,
+                    format!("orderbook '{}'", orderbook),
+                    v.network.name.clone(),
+                )),
+                None => self.error(
+                    self.source_of(&path),
+                    path,
+                    format!("Orderbook not found: {}", orderbook),
+                ),
+            }
+        }
+        for (io_name, ios) in [("inputs", &order.inputs), ("outputs", &order.outputs)] {
+            for (index, io) in ios.iter().enumerate() {
+                let path = format!("orders.{}.{}.{}.token", name, io_name, index);
+                match tokens.get(&io.token) {
+                    Some(v) => bound_networks.push((
+                        path,
+                        format!("token '{}'", io.token),
+                        v.network.name.clone(),
+                    )),
+                    None => self.error(
+                        self.source_of(&path),
+                        path,
+                        format!("Token not found: {}", io.token),
+                    ),
+                }
+            }
+        }
+
+        if let Some((_, first_what, first_network)) = bound_networks.first().cloned() {
+            for (path, what, network) in bound_networks.into_iter().skip(1) {
+                if network != first_network {
+                    self.error(
+                        self.source_of(&path),
+                        path,
+                        format!(
+                            "{} is on network '{}' but the order is on network '{}' of {}",
+                            what, network, first_network, first_what
+                        ),
+                    );
+                }
+            }
+        }
+
+        self.diagnostics.len() == errors_count
+    }
+
+    /// Checks the references of a deployment and that its order and scenario
+    /// are on the same network, returns false if any of them is invalid
+    fn check_deployment(
+        &mut self,
+        name: &str,
+        deployment: &DeploymentConfigSource,
+        scenarios: &HashMap<String, Arc<Scenario>>,
+        orders: &HashMap<String, Arc<Order>>,
+    ) -> bool {
+        let scenario = scenarios.get(&deployment.scenario);
+        if scenario.is_none() {
+            let path = format!("deployments.{}.scenario", name);
+            self.error(
+                self.source_of(&path),
+                path,
+                format!("Scenario not found: {}", deployment.scenario),
+            );
+        }
+        let order = orders.get(&deployment.order);
+        if order.is_none() {
+            let path = format!("deployments.{}.order", name);
+            self.error(
+                self.source_of(&path),
+                path,
+                format!("Order not found: {}", deployment.order),
+            );
+        }
+        let (Some(scenario), Some(order)) = (scenario, order) else {
+            return false;
+        };
+
+        let path = format!("deployments.{}", name);
+        if order.network.name != scenario.deployer.network.name {
+            self.error(
+                self.source_of(&path),
+                path,
+                format!(
+                    "Order '{}' is on network '{}' but the deployer of scenario '{}' is on network '{}'",
+                    deployment.order,
+                    order.network.name,
+                    deployment.scenario,
+                    scenario.deployer.network.name
+                ),
+            );
+            return false;
+        }
+        if order
+            .deployer
+            .as_ref()
+            .is_some_and(|v| v != &scenario.deployer)
+        {
+            self.error(
+                self.source_of(&path),
+                path,
+                format!(
+                    "Deployer of order '{}' does not match the deployer of scenario '{}'",
+                    deployment.order, deployment.scenario
+                ),
+            );
+            return false;
+        }
+        true
+    }
+
+    /// Warns about every entry of the settings the main text overrides
+    fn check_overrides(&mut self, provenance: &ConfigProvenance) {
+        let mut entries = provenance
+            .0
+            .iter()
+            .filter(|(_, v)| {
+                v.layer == "frontmatter" && v.overridden.iter().any(|v| v == "settings")
+            })
+            .map(|(path, _)| path.split('.').take(2).collect::<Vec<_>>().join("."))
+            .collect::<Vec<_>>();
+        entries.dedup();
+        for entry in entries {
+            self.warning(
+                ConfigLintSource::Main,
+                entry.clone(),
+                format!("Overrides values of '{}' defined in the settings", entry),
+            );
+        }
+    }
+
+    fn check_unknown_fields(&mut self, text: &str, source: ConfigLintSource) {
+        // syntax errors are already reported while parsing
+        let Ok(value) = serde_yaml::from_str::<Value>(text) else {
+            return;
+        };
+        for unknown in find_unknown_fields(&config_schema(), &value) {
+            let path = if unknown.path.is_empty() {
+                unknown.field.clone()
+            } else {
+                format!("{}.{}", unknown.path, unknown.field)
+            };
+            self.error(source, path, unknown.to_string());
+        }
+    }
+
+    /// Warns about vault ids repeated for the same token within an order's
+    /// inputs or outputs, and output vaults shared between orders
+    fn check_vault_ids(&mut self, orders: &HashMap<String, OrderConfigSource>) {
+        let mut names = orders.keys().collect::<Vec<_>>();
+        names.sort();
+
+        let mut output_vaults: HashMap<(String, U256), String> = HashMap::new();
+        for name in names {
+            let order = &orders[name];
+            for (io_name, ios) in [("inputs", &order.inputs), ("outputs", &order.outputs)] {
+                let mut seen = vec![];
+                for (index, io) in ios.iter().enumerate() {
+                    let Some(vault_id) = io.vault_id else {
+                        continue;
+                    };
+                    let path = format!("orders.{}.{}.{}", name, io_name, index);
+                    let source = self.source_of(&path);
+                    let vault = (io.token.clone(), vault_id);
+                    if seen.contains(&vault) {
+                        self.warning(
+                            source,
+                            path.clone(),
+                            format!(
+                                "Duplicate vault id {} for token '{}' in {}",
+                                vault_id, io.token, io_name
+                            ),
+                        );
+                    }
+                    if io_name == "outputs" {
+                        if let Some(other) = output_vaults.get(&vault).filter(|v| *v != name) {
+                            self.warning(
+                                source,
+                                path,
+                                format!(
+                                    "Output vault id {} for token '{}' is also used by order '{}'",
+                                    vault_id, io.token, other
+                                ),
+                            );
+                        } else {
+                            output_vaults.insert(vault.clone(), name.clone());
+                        }
+                    }
+                    seen.push(vault);
+                }
+            }
+        }
+    }
+}
+
+/// Splits the `path: message` of a yaml deserializer error, turning its
+/// `inputs[0]` sequence indices into `inputs.0`
+fn split_error_path(message: &str) -> (String, String) {
+    match message.split_once(": ") {
+        Some((path, rest))
+            if !path.is_empty()
+                && path
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || "-_.[]".contains(c)) =>
+        {
+            let path = path.replace('[', ".").replace(']', "");
+            (path, rest.to_string())
+        }
+        _ => (String::new(), message.to_string()),
+    }
+}
+
+/// Span of the first occurrence of the given text
+fn locate_text(text: &str, needle: &str) -> Option<ConfigLintSpan> {
+    let start = text.find(needle)?;
+    let line_start = text[..start].rfind('\n').map(|v| v + 1).unwrap_or(0);
+    Some(ConfigLintSpan {
+        line: text[..start].matches('\n').count() + 1,
+        column: start - line_start + 1,
+        start,
+        end: start + needle.len(),
+    })
+}
+
+/// Entries of a config section sorted by name, so diagnostics of entries
+/// without a span keep a stable order
+fn sorted<T>(entries: &HashMap<String, T>) -> Vec<(&String, &T)> {
+    let mut entries = entries.iter().collect::<Vec<_>>();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+}
+
+/// Names joined as `'a', 'b'`
+fn quoted_list(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|v| format!("'{}'", v))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Positions of the keys and sequence items of a yaml text keyed by their
+/// dot separated path, eg `orders.some-order.inputs.0.token`.
+///
+/// Only block style yaml, which config files are written in, is indexed,
+/// flow style mappings and sequences are treated as plain values.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct YamlSpans(HashMap<String, ConfigLintSpan>);
+
+impl YamlSpans {
+    pub fn new(text: &str) -> Self {
+        let mut spans = HashMap::new();
+        // indent, path segment and whether it is a sequence item
+        let mut stack: Vec<(usize, String, bool)> = vec![];
+        let mut sequence_lengths: HashMap<String, usize> = HashMap::new();
+        let mut block_scalar_indent: Option<usize> = None;
+
+        let path_of = |stack: &[(usize, String, bool)]| {
+            stack
+                .iter()
+                .map(|v| v.1.as_str())
+                .collect::<Vec<_>>()
+                .join(".")
+        };
+
+        let mut line_offset = 0;
+        for (line_index, line) in text.split_inclusive('\n').enumerate() {
+            let offset = line_offset;
+            line_offset += line.len();
+
+            let content = line.trim_end_matches(['\n', '\r']);
+            let mut rest = content.trim_start();
+            let mut indent = content.len() - rest.len();
+            if rest.is_empty() || rest.starts_with('#') {
+                continue;
+            }
+            if let Some(block_indent) = block_scalar_indent {
+                if indent > block_indent {
+                    continue;
+                }
+                block_scalar_indent = None;
+            }
+            let span = |indent: usize, len: usize| ConfigLintSpan {
+                line: line_index + 1,
+                column: indent + 1,
+                start: offset + indent,
+                end: offset + indent + len,
+            };
+
+            // sequence items, which can be nested on a single line
+            while rest == "-" || rest.starts_with("- ") {
+                while stack
+                    .last()
+                    .is_some_and(|v| v.0 > indent || (v.0 == indent && v.2))
+                {
+                    stack.pop();
+                }
+                let length = sequence_lengths.entry(path_of(&stack)).or_default();
+                stack.push((indent, length.to_string(), true));
+                *length += 1;
+                spans.insert(path_of(&stack), span(indent, rest.len()));
+
+                let item = &rest[1..];
+                let item_trimmed = item.trim_start();
+                indent += 1 + item.len() - item_trimmed.len();
+                rest = item_trimmed;
+            }
+
+            if let Some((key, value)) = split_key(rest) {
+                while stack.last().is_some_and(|v| v.0 >= indent) {
+                    stack.pop();
+                }
+                stack.push((indent, key.to_string(), false));
+                spans.insert(path_of(&stack), span(indent, key.len()));
+                if value.starts_with('|') || value.starts_with('>') {
+                    block_scalar_indent = Some(indent);
+                }
+            }
+        }
+
+        Self(spans)
+    }
+
+    /// Whether the given path is in the text
+    pub fn contains(&self, path: &str) -> bool {
+        self.0.contains_key(path)
+    }
+
+    /// The deepest path that starts at the given line
+    pub fn path_at(&self, line: usize) -> Option<String> {
+        self.0
+            .iter()
+            .filter(|(_, v)| v.line == line)
+            .max_by_key(|(k, _)| k.len())
+            .map(|(k, _)| k.clone())
+    }
+
+    /// Span of the given path, or of its closest ancestor that exists in
+    /// the text
+    pub fn locate(&self, path: &str) -> Option<ConfigLintSpan> {
+        let mut path = path;
+        loop {
+            if let Some(span) = self.0.get(path) {
+                return Some(*span);
+            }
+            path = &path[..path.rfind('.')?];
+        }
+    }
+}
+
+/// Splits a `key: value` line into its unquoted key and trimmed value
+fn split_key(text: &str) -> Option<(&str, &str)> {
+    if text.starts_with('{') || text.starts_with('[') {
+        return None;
+    }
+    if let Some(quote) = text.chars().next().filter(|v| *v == '"' || *v == '\'') {
+        let end = text[1..].find(quote)? + 1;
+        let value = text[end + 1..].strip_prefix(':')?;
+        if !value.is_empty() && !value.starts_with(' ') {
+            return None;
+        }
+        return Some((&text[1..end], value.trim()));
+    }
+    if let Some(key) = text.strip_suffix(':') {
+        if !key.contains(": ") {
+            return Some((key, ""));
+        }
+    }
+    let (key, value) = text.split_once(": ")?;
+    Some((key, value.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"networks:
+    mainnet:
+        rpc: https://mainnet.node
+        chain-id: 1
+    polygon:
+        rpc: https://polygon.node
+        chain-id: 137
+subgraphs:
+    mainnet: https://mainnet.subgraph
+orderbooks:
+    mainnet:
+        address: 0x1234567890123456789012345678901234567890
+    polygon:
+        address: 0x1234567890123456789012345678901234567890
+        subgraph: missing-subgraph
+tokens:
+    weth:
+        network: mainnet
+        address: 0x1234567890123456789012345678901234567891
+    usdc:
+        network: mainnet
+        address: 0x1234567890123456789012345678901234567892
+    wmatic:
+        network: polygon
+        address: 0x1234567890123456789012345678901234567893
+    broken:
+        network: missing-network
+        address: 0x1234567890123456789012345678901234567894
+deployers:
+    mainnet:
+        address: 0x1234567890123456789012345678901234567895
+    polygon:
+        address: 0x1234567890123456789012345678901234567896
+orders:
+    buy:
+        orderbook: mainnet
+        inputs:
+            - token: weth
+            - token: wmatic
+        outputs:
+            - token: usdc
+              vault-id: 1
+            - token: usdc
+              vault-id: 1
+    sell:
+        inputs:
+            - token: usdc
+        outputs:
+            - token: weth
+              vault-id: 2
+    wrong:
+        inputs:
+            - token: missing-token
+        outputs:
+            - token: weth
+scenarios:
+    mainnet:
+        bindings:
+            key: value
+    polygon:
+        deployer: polygon
+deployments:
+    sell:
+        scenario: polygon
+        order: sell
+    missing:
+        scenario: missing-scenario
+        order: sell
+"#;
+
+    #[test]
+    fn test_yaml_spans() {
+        let spans = YamlSpans::new(CONFIG);
+        assert_eq!(
+            spans.locate("networks"),
+            Some(ConfigLintSpan {
+                line: 1,
+                column: 1,
+                start: 0,
+                end: 8,
+            })
+        );
+        let span = spans.locate("networks.polygon.chain-id").unwrap();
+        assert_eq!((span.line, span.column), (7, 9));
+        assert_eq!(&CONFIG[span.start..span.end], "chain-id");
+
+        let span = spans.locate("orders.buy.inputs.1.token").unwrap();
+        assert_eq!((span.line, span.column), (39, 15));
+        let span = spans.locate("orders.buy.outputs.1").unwrap();
+        assert_eq!((span.line, span.column), (43, 13));
+        let span = spans.locate("orders.buy.outputs.1.vault-id").unwrap();
+        assert_eq!((span.line, span.column), (44, 15));
+
+        // falls back to the closest ancestor
+        assert_eq!(
+            spans.locate("orders.buy.deployer"),
+            spans.locate("orders.buy")
+        );
+        assert_eq!(spans.locate("missing"), None);
+    }
+
+    #[test]
+    fn test_yaml_spans_nested_sequences_and_block_scalars() {
+        let text = r#"a:
+- b: 1
+  c:
+  - - x: |
+        not: a key
+      y: 2
+d: "quoted: value"
+'e f': 3
+"#;
+        let spans = YamlSpans::new(text);
+        assert_eq!(spans.locate("a.0.b").unwrap().line, 2);
+        assert_eq!(spans.locate("a.0.c").unwrap().line, 3);
+        assert_eq!(spans.locate("a.0.c.0.0.x").unwrap().column, 7);
+        assert_eq!(spans.locate("a.0.c.0.0.y").unwrap().line, 6);
+        assert_eq!(spans.0.get("a.0.c.0.0.not"), None);
+        assert_eq!(spans.locate("d").unwrap().line, 7);
+        assert_eq!(spans.locate("e f").unwrap().line, 8);
+    }
+
+    #[test]
+    fn test_lint_config() {
+        let result = lint_config(CONFIG, None);
+        let messages = result
+            .iter()
+            .map(|v| (v.severity, v.path.as_str(), v.span.map(|s| s.line)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    ConfigLintSeverity::Error,
+                    "orderbooks.polygon.subgraph",
+                    Some(15)
+                ),
+                (ConfigLintSeverity::Error, "tokens.broken.network", Some(27)),
+                (
+                    ConfigLintSeverity::Error,
+                    "orders.buy.inputs.1.token",
+                    Some(39)
+                ),
+                (
+                    ConfigLintSeverity::Warning,
+                    "orders.buy.outputs.1",
+                    Some(43)
+                ),
+                (
+                    ConfigLintSeverity::Error,
+                    "orders.wrong.inputs.0.token",
+                    Some(53)
+                ),
+                (ConfigLintSeverity::Error, "deployments.sell", Some(63)),
+                (
+                    ConfigLintSeverity::Error,
+                    "deployments.missing.scenario",
+                    Some(67)
+                ),
+            ]
+        );
+        assert_eq!(
+            result[2].message,
+            "token 'wmatic' is on network 'polygon' but the order is on network 'mainnet' of orderbook 'mainnet'"
+        );
+        assert_eq!(
+            result[5].message,
+            "Order 'sell' is on network 'mainnet' but the deployer of scenario 'polygon' is on network 'polygon'"
+        );
+        assert_eq!(
+            result[1].to_string(),
+            "error: Network not found for Token: missing-network at tokens.broken.network (line 27, column 9)"
+        );
+    }
+
+    #[test]
+    fn test_lint_config_reference_errors() {
+        let text = r#"networks:
+    mainnet:
+        rpc: https://mainnet.node
+        chain-id: 1
+tokens:
+    weth:
+        network: mainnet
+        address: 0x1234567890123456789012345678901234567891
+    broken:
+        network: missing-network
+        address: 0x1234567890123456789012345678901234567894
+"#;
+        let result = lint_config(text, None);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].severity, ConfigLintSeverity::Error);
+        assert_eq!(result[0].path, "tokens.broken.network");
+        assert_eq!(result[0].span.unwrap().line, 10);
+        assert_eq!(
+            result[0].message,
+            "Network not found for Token: missing-network"
+        );
+
+        let text = r#"deployments:
+    some:
+        scenario: missing-scenario
+        order: some
+"#;
+        let result = lint_config(text, None);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].path, "deployments.some.scenario");
+        assert_eq!(result[0].span.unwrap().line, 3);
+        assert_eq!(result[1].path, "deployments.some.order");
+        assert_eq!(result[1].message, "Order not found: some");
+    }
+
+    #[test]
+    fn test_lint_config_invalid_entries() {
+        let text = r#"networks:
+    mainnet:
+        rpc: not a url
+        chain-id: 1
+"#;
+        let result = lint_config(text, None);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].severity, ConfigLintSeverity::Error);
+        assert_eq!(result[0].path, "networks.mainnet.rpc");
+        assert_eq!(result[0].span.unwrap().line, 3);
+    }
+
+    #[test]
+    fn test_lint_config_interpolation() {
+        let text = r#"networks:
+    mainnet:
+        rpc: https://${RAIN_LINT_TEST_UNSET_HOST:-mainnet.node}
+        chain-id: 1
+    other:
+        rpc: https://${RAIN_LINT_TEST_UNSET_HOST}
+        chain-id: 2
+"#;
+        let result = lint_config(text, None);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].path, "networks.other.rpc");
+        assert_eq!(
+            result[0].message,
+            "Variable 'RAIN_LINT_TEST_UNSET_HOST' is not set and has no default"
+        );
+        let span = result[0].span.unwrap();
+        assert_eq!((span.line, span.column), (6, 22));
+        assert_eq!(&text[span.start..span.end], "${RAIN_LINT_TEST_UNSET_HOST}");
+    }
+
+    #[test]
+    fn test_lint_config_imports() {
+        let text = r#"imports:
+    - path: ./tokens.yml
+    - hash: 0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef
+"#;
+        let result = lint_config(text, None);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].severity, ConfigLintSeverity::Warning);
+        assert_eq!(result[0].path, "imports");
+        assert_eq!(result[1].severity, ConfigLintSeverity::Error);
+        assert_eq!(result[1].path, "imports.1");
+        assert_eq!(result[1].span.unwrap().line, 3);
+    }
+
+    #[test]
+    fn test_lint_config_strict() {
+        let text = r#"networks:
+    mainnet:
+        rpc: https://mainnet.node
+        chain-id: 1
+        chain_id: 1
+"#;
+        assert!(lint_config(text, None).is_empty());
+
+        let result = lint_config_strict(text, None);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].severity, ConfigLintSeverity::Error);
+        assert_eq!(result[0].path, "networks.mainnet.chain_id");
+        assert_eq!(
+            result[0].message,
+            "Unknown field 'chain_id', did you mean 'chain-id'?"
+        );
+        assert_eq!(result[0].span.unwrap().line, 5);
+    }
+
+    #[test]
+    fn test_lint_config_syntax_error() {
+        let result = lint_config("networks:\n    a: b: c\n", None);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].severity, ConfigLintSeverity::Error);
+        assert_eq!(result[0].path, "");
+        assert_eq!(result[0].span.unwrap().line, 2);
+    }
+
+    #[test]
+    fn test_lint_config_remote_networks() {
+        let text = r#"using-networks-from:
+    chainid:
+        url: https://chainid.network/chains.json
+        format: chainid
+tokens:
+    weth:
+        network: eth
+        address: 0x1234567890123456789012345678901234567891
+    usdc:
+        network: matic
+        address: 0x1234567890123456789012345678901234567892
+orders:
+    some-order:
+        inputs:
+            - token: weth
+        outputs:
+            - token: usdc
+"#;
+        let result = lint_config(text, None);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].severity, ConfigLintSeverity::Warning);
+        assert_eq!(result[0].path, "using-networks-from");
+        assert_eq!(
+            result[0].message,
+            "Remote networks are not fetched while linting, 'eth', 'matic' are assumed to be remote networks"
+        );
+        assert_eq!(result[1].severity, ConfigLintSeverity::Error);
+        assert_eq!(result[1].path, "orders.some-order.outputs.0.token");
+    }
+
+    #[test]
+    fn test_lint_config_remote_tokens() {
+        let text = r#"using-tokens-from:
+    uniswap:
+        url: https://tokens.uniswap.org
+networks:
+    mainnet:
+        rpc: https://mainnet.node
+        chain-id: 1
+    mainnet-fork:
+        rpc: https://fork.node
+        chain-id: 1
+tokens:
+    weth:
+        network: mainnet-fork
+        address: 0x1234567890123456789012345678901234567891
+orders:
+    some-order:
+        inputs:
+            - token: weth
+            - token: mainnet-fork-usdc
+        outputs:
+            - token: mainnet-wbtc
+            - token: usdt
+"#;
+        let result = lint_config(text, None);
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0].severity, ConfigLintSeverity::Warning);
+        assert_eq!(result[0].path, "using-tokens-from");
+        assert_eq!(
+            result[0].message,
+            "Token lists are not fetched while linting, 'mainnet-fork-usdc', 'mainnet-wbtc' are assumed to be listed tokens"
+        );
+        assert_eq!(result[1].path, "orders.some-order.outputs.0.token");
+        assert_eq!(
+            result[1].message,
+            "token 'mainnet-wbtc' is on network 'mainnet' but the order is on network 'mainnet-fork' of token 'weth'"
+        );
+        assert_eq!(result[2].path, "orders.some-order.outputs.1.token");
+        assert_eq!(result[2].message, "Token not found: usdt");
+    }
+
+    #[test]
+    fn test_lint_config_with_settings() {
+        let settings = r#"networks:
+    mainnet:
+        rpc: https://mainnet.node
+        chain-id: 1
+deployers:
+    mainnet:
+        address: 0x1234567890123456789012345678901234567895
+        network: missing
+"#;
+        let text = r#"tokens:
+    weth:
+        network: mainnet
+        address: 0x1234567890123456789012345678901234567891
+"#;
+        let result = lint_config(text, Some(settings));
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].source, ConfigLintSource::Settings);
+        assert_eq!(result[0].path, "deployers.mainnet.network");
+        assert_eq!(result[0].span.unwrap().line, 8);
+
+        // without the settings the token's network is missing
+        let result = lint_config(text, None);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].source, ConfigLintSource::Main);
+        assert_eq!(result[0].path, "tokens.weth.network");
+    }
+
+    #[test]
+    fn test_lint_config_overrides_settings() {
+        let settings = r#"networks:
+    mainnet:
+        rpc: https://mainnet.node
+        chain-id: 1
+"#;
+        let text = r#"networks:
+    mainnet:
+        rpc: https://private.node
+        chain-id: 1
+"#;
+        let result = lint_config(text, Some(settings));
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].severity, ConfigLintSeverity::Warning);
+        assert_eq!(result[0].source, ConfigLintSource::Main);
+        assert_eq!(result[0].path, "networks.mainnet");
+        assert_eq!(
+            result[0].message,
+            "Overrides values of 'networks.mainnet' defined in the settings"
+        );
+        assert_eq!(result[0].span.unwrap().line, 2);
+    }
+}
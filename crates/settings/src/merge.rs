@@ -1,5 +1,8 @@
 #![allow(clippy::map_entry)]
-use crate::*;
+use crate::{interpolation::ConfigSecrets, *};
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+use std::collections::BTreeMap;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -39,6 +42,18 @@ pub enum MergeError {
 
     #[error("There is already a accounts called {0}")]
     AccountsCollision(String),
+
+    #[error("Failed to deep merge configs: {0}")]
+    DeepMergeError(String),
+}
+
+/// Which of two configs wins when both define the same field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergePrecedence {
+    /// The config being merged into wins
+    Ours,
+    /// The config being merged in wins
+    Theirs,
 }
 
 impl ConfigSource {
@@ -61,6 +76,15 @@ impl ConfigSource {
             remote_networks.insert(key, value);
         }
 
+        // Remote Tokens
+        let remote_tokens = &mut self.using_tokens_from;
+        for (key, value) in other.using_tokens_from {
+            if remote_tokens.contains_key(&key) {
+                return Err(MergeError::TokenCollision(key));
+            }
+            remote_tokens.insert(key, value);
+        }
+
         // Subgraphs
         let subgraphs = &mut self.subgraphs;
         for (key, value) in other.subgraphs {
@@ -166,6 +190,11 @@ impl ConfigSource {
             _ => {}
         }
 
+        // Imports
+        self.imports.extend(other.imports);
+
+        self.secrets.extend(other.secrets);
+
         Ok(())
     }
 }
@@ -290,6 +319,220 @@ impl Config {
     }
 }
 
+impl ConfigSource {
+    /// Deep merges another config into this one field by field, the
+    /// precedence decides which value is kept when both define the same field
+    pub fn merge_with_precedence(
+        &mut self,
+        other: ConfigSource,
+        precedence: MergePrecedence,
+    ) -> Result<(), MergeError> {
+        let ours = std::mem::take(self);
+        let layers = match precedence {
+            MergePrecedence::Ours => ConfigLayers::new()
+                .with_layer("theirs", other)
+                .with_layer("ours", ours),
+            MergePrecedence::Theirs => ConfigLayers::new()
+                .with_layer("ours", ours)
+                .with_layer("theirs", other),
+        };
+        *self = layers.merge()?.0;
+        Ok(())
+    }
+}
+
+/// Where a value of a deep merged config came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ValueProvenance {
+    /// The layer the final value came from
+    pub layer: String,
+    /// The layers that also defined this value and were overridden, in order
+    pub overridden: Vec<String>,
+}
+
+/// The provenance of every leaf value of a deep merged config, keyed by its
+/// dotted path such as `networks.mainnet.rpc`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ConfigProvenance(pub BTreeMap<String, ValueProvenance>);
+
+impl ConfigProvenance {
+    pub fn get(&self, path: &str) -> Option<&ValueProvenance> {
+        self.0.get(path)
+    }
+
+    /// The provenance of the value at the given path and of everything under it
+    pub fn explain(&self, path: &str) -> Vec<(&String, &ValueProvenance)> {
+        let prefix = format!("{}.", path);
+        self.0
+            .iter()
+            .filter(|(k, _)| path.is_empty() || *k == path || k.starts_with(&prefix))
+            .collect()
+    }
+
+    fn record(&mut self, path: &str, value: &Value, layer: &str, overridden: &[String]) {
+        match value {
+            Value::Mapping(map) if !map.is_empty() => {
+                for (key, value) in map {
+                    self.record(&join_path(path, key), value, layer, overridden);
+                }
+            }
+            Value::Sequence(seq) if !seq.is_empty() => {
+                for (i, value) in seq.iter().enumerate() {
+                    self.record(&join_path(path, &Value::from(i)), value, layer, overridden);
+                }
+            }
+            _ => {
+                self.0.insert(
+                    path.to_string(),
+                    ValueProvenance {
+                        layer: layer.to_string(),
+                        overridden: overridden.to_vec(),
+                    },
+                );
+            }
+        }
+    }
+
+    /// Records a value that replaced whatever was at its path, the layers of
+    /// the replaced values are kept as overridden by it
+    fn replace(&mut self, path: &str, value: &Value, layer: &str) {
+        let prefix = format!("{}.", path);
+        let mut overridden: Vec<String> = vec![];
+        self.0.retain(|k, v| {
+            if k != path && !k.starts_with(&prefix) {
+                return true;
+            }
+            for previous in v.overridden.iter().chain([&v.layer]) {
+                if previous != layer && !overridden.contains(previous) {
+                    overridden.push(previous.clone());
+                }
+            }
+            false
+        });
+        self.record(path, value, layer, &overridden);
+    }
+}
+
+fn join_path(path: &str, key: &Value) -> String {
+    let key = match key {
+        Value::String(v) => v.clone(),
+        other => serde_yaml::to_string(other)
+            .unwrap_or_default()
+            .trim()
+            .to_string(),
+    };
+    if path.is_empty() {
+        key
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+/// Named configs that are deep merged field by field, later layers override
+/// the values of the earlier ones. Mappings are merged key by key while any
+/// other value, including sequences, is replaced whole.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLayers {
+    layers: Vec<(String, ConfigLayer)>,
+}
+
+#[derive(Debug, Clone)]
+enum ConfigLayer {
+    Whole(ConfigSource),
+    /// Only the fields it overrides, validated once merged
+    Partial(Value, ConfigSecrets),
+}
+
+impl ConfigLayers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The layers of a dotrain order, its frontmatter overrides the values
+    /// the settings also define
+    pub fn dotrain(frontmatter: ConfigSource, settings: ConfigSource) -> Self {
+        Self::new()
+            .with_layer("settings", settings)
+            .with_layer("frontmatter", frontmatter)
+    }
+
+    pub fn with_layer(mut self, name: impl Into<String>, config: ConfigSource) -> Self {
+        self.layers.push((name.into(), ConfigLayer::Whole(config)));
+        self
+    }
+
+    /// Adds a layer that only defines the fields it overrides, ie the `rpc`
+    /// of a network without its `chain-id`, as parsed by
+    /// [ConfigSource::try_partial_from_string]
+    pub fn with_partial_layer(
+        mut self,
+        name: impl Into<String>,
+        value: Value,
+        secrets: ConfigSecrets,
+    ) -> Self {
+        self.layers
+            .push((name.into(), ConfigLayer::Partial(value, secrets)));
+        self
+    }
+
+    pub fn merge(self) -> Result<(ConfigSource, ConfigProvenance), MergeError> {
+        let mut merged = Value::Mapping(Mapping::new());
+        let mut provenance = ConfigProvenance::default();
+        let mut imports = vec![];
+        let mut secrets = ConfigSecrets::default();
+
+        for (name, layer) in self.layers {
+            let value = match layer {
+                ConfigLayer::Whole(mut config) => {
+                    imports.append(&mut config.imports);
+                    secrets.extend(std::mem::take(&mut config.secrets));
+                    serde_yaml::to_value(&config)
+                        .map_err(|e| MergeError::DeepMergeError(e.to_string()))?
+                }
+                ConfigLayer::Partial(value, layer_secrets) => {
+                    secrets.extend(layer_secrets);
+                    value
+                }
+            };
+            deep_merge(&mut merged, value, "", &name, &mut provenance);
+        }
+
+        let mut config: ConfigSource = serde_yaml::from_value(merged)
+            .map_err(|e| MergeError::DeepMergeError(e.to_string()))?;
+        config.imports = imports;
+        config.secrets = secrets;
+        Ok((config, provenance))
+    }
+}
+
+fn deep_merge(
+    base: &mut Value,
+    overlay: Value,
+    path: &str,
+    layer: &str,
+    provenance: &mut ConfigProvenance,
+) {
+    match (base, overlay) {
+        (Value::Mapping(base), Value::Mapping(overlay)) => {
+            for (key, value) in overlay {
+                let key_path = join_path(path, &key);
+                match base.get_mut(&key) {
+                    Some(existing) => deep_merge(existing, value, &key_path, layer, provenance),
+                    None => {
+                        provenance.record(&key_path, &value, layer, &[]);
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => {
+            provenance.replace(path, &overlay, layer);
+            *base = overlay;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use url::Url;
@@ -299,8 +542,10 @@ mod tests {
     #[test]
     fn test_successful_merge() {
         let mut config = ConfigSource {
+            imports: vec![],
             raindex_version: None,
             using_networks_from: HashMap::new(),
+            using_tokens_from: HashMap::new(),
             subgraphs: HashMap::new(),
             metaboards: HashMap::new(),
             orderbooks: HashMap::new(),
@@ -314,11 +559,14 @@ mod tests {
             sentry: None,
             accounts: None,
             gui: None,
+            secrets: Default::default(),
         };
 
         let other = ConfigSource {
+            imports: vec![],
             raindex_version: None,
             using_networks_from: HashMap::new(),
+            using_tokens_from: HashMap::new(),
             subgraphs: HashMap::new(),
             metaboards: HashMap::new(),
             orderbooks: HashMap::new(),
@@ -332,6 +580,7 @@ mod tests {
             sentry: None,
             accounts: None,
             gui: None,
+            secrets: Default::default(),
         };
 
         assert_eq!(config.merge(other), Ok(()));
@@ -340,8 +589,10 @@ mod tests {
     #[test]
     fn test_unsuccessful_merge() {
         let mut config = ConfigSource {
+            imports: vec![],
             raindex_version: None,
             using_networks_from: HashMap::new(),
+            using_tokens_from: HashMap::new(),
             subgraphs: HashMap::new(),
             metaboards: HashMap::new(),
             orderbooks: HashMap::new(),
@@ -355,11 +606,14 @@ mod tests {
             sentry: None,
             accounts: None,
             gui: None,
+            secrets: Default::default(),
         };
 
         let mut other = ConfigSource {
+            imports: vec![],
             raindex_version: None,
             using_networks_from: HashMap::new(),
+            using_tokens_from: HashMap::new(),
             subgraphs: HashMap::new(),
             metaboards: HashMap::new(),
             orderbooks: HashMap::new(),
@@ -373,6 +627,7 @@ mod tests {
             sentry: None,
             accounts: None,
             gui: None,
+            secrets: Default::default(),
         };
 
         // Add a collision to cause an unsuccessful merge
@@ -395,8 +650,10 @@ mod tests {
     #[test]
     fn test_successful_merge_metaboard() {
         let mut config = ConfigSource {
+            imports: vec![],
             raindex_version: None,
             using_networks_from: HashMap::new(),
+            using_tokens_from: HashMap::new(),
             subgraphs: HashMap::new(),
             metaboards: HashMap::new(),
             orderbooks: HashMap::new(),
@@ -410,11 +667,14 @@ mod tests {
             sentry: None,
             accounts: None,
             gui: None,
+            secrets: Default::default(),
         };
 
         let mut other = ConfigSource {
+            imports: vec![],
             raindex_version: None,
             using_networks_from: HashMap::new(),
+            using_tokens_from: HashMap::new(),
             subgraphs: HashMap::new(),
             metaboards: HashMap::new(),
             orderbooks: HashMap::new(),
@@ -428,6 +688,7 @@ mod tests {
             sentry: None,
             accounts: None,
             gui: None,
+            secrets: Default::default(),
         };
 
         other.metaboards.insert(
@@ -444,4 +705,183 @@ mod tests {
             Some(&Url::parse("https://myurl").unwrap())
         );
     }
+
+    fn layered_configs() -> (ConfigSource, ConfigSource) {
+        let settings: ConfigSource = serde_yaml::from_str(
+            r#"
+networks:
+    mainnet:
+        rpc: https://mainnet.node
+        chain-id: 1
+        label: Mainnet
+tokens:
+    weth:
+        network: mainnet
+        address: 0x1234567890123456789012345678901234567891
+        symbol: WETH
+"#,
+        )
+        .unwrap();
+        let frontmatter: ConfigSource = serde_yaml::from_str(
+            r#"
+networks:
+    mainnet:
+        rpc: https://private.node
+        chain-id: 1
+tokens:
+    usdc:
+        network: mainnet
+        address: 0x1234567890123456789012345678901234567892
+"#,
+        )
+        .unwrap();
+        (settings, frontmatter)
+    }
+
+    #[test]
+    fn test_config_layers_merge() {
+        let (settings, frontmatter) = layered_configs();
+        let (config, provenance) = ConfigLayers::new()
+            .with_layer("settings", settings)
+            .with_layer("frontmatter", frontmatter)
+            .merge()
+            .unwrap();
+
+        let mainnet = config.networks.get("mainnet").unwrap();
+        assert_eq!(mainnet.rpc, Url::parse("https://private.node").unwrap());
+        assert_eq!(mainnet.label, Some("Mainnet".to_string()));
+        assert_eq!(config.tokens.len(), 2);
+
+        assert_eq!(
+            provenance.get("networks.mainnet.rpc"),
+            Some(&ValueProvenance {
+                layer: "frontmatter".to_string(),
+                overridden: vec!["settings".to_string()],
+            })
+        );
+        assert_eq!(
+            provenance.get("networks.mainnet.label"),
+            Some(&ValueProvenance {
+                layer: "settings".to_string(),
+                overridden: vec![],
+            })
+        );
+        assert_eq!(
+            provenance.get("tokens.usdc.address").unwrap().layer,
+            "frontmatter"
+        );
+        assert_eq!(
+            provenance
+                .explain("networks.mainnet")
+                .into_iter()
+                .map(|(k, _)| k.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "networks.mainnet.chain-id",
+                "networks.mainnet.label",
+                "networks.mainnet.rpc"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dotrain_layers() {
+        let (settings, frontmatter) = layered_configs();
+
+        let (config, provenance) = ConfigLayers::dotrain(frontmatter.clone(), settings.clone())
+            .merge()
+            .unwrap();
+        assert_eq!(
+            config.networks.get("mainnet").unwrap().rpc,
+            Url::parse("https://private.node").unwrap()
+        );
+        assert_eq!(config.tokens.len(), 2);
+        assert_eq!(
+            provenance.get("networks.mainnet.rpc").unwrap().layer,
+            "frontmatter"
+        );
+
+        // strict merge still rejects the collision
+        let mut config = frontmatter;
+        assert_eq!(
+            config.merge(settings),
+            Err(MergeError::NetworkCollision("mainnet".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_merge_with_precedence() {
+        let (settings, frontmatter) = layered_configs();
+
+        let mut config = frontmatter.clone();
+        config
+            .merge_with_precedence(settings.clone(), MergePrecedence::Ours)
+            .unwrap();
+        assert_eq!(
+            config.networks.get("mainnet").unwrap().rpc,
+            Url::parse("https://private.node").unwrap()
+        );
+
+        let mut config = frontmatter;
+        config
+            .merge_with_precedence(settings, MergePrecedence::Theirs)
+            .unwrap();
+        assert_eq!(
+            config.networks.get("mainnet").unwrap().rpc,
+            Url::parse("https://mainnet.node").unwrap()
+        );
+        assert_eq!(config.tokens.len(), 2);
+    }
+
+    #[test]
+    fn test_partial_layer() {
+        let (settings, _) = layered_configs();
+        let (overrides, secrets) = ConfigSource::try_partial_from_string(
+            r#"
+networks:
+    mainnet:
+        rpc: https://private.node
+"#,
+            &crate::interpolation::EnvResolver,
+        )
+        .unwrap();
+
+        let (config, provenance) = ConfigLayers::new()
+            .with_layer("settings", settings.clone())
+            .with_partial_layer("frontmatter", overrides, secrets)
+            .merge()
+            .unwrap();
+        let mainnet = config.networks.get("mainnet").unwrap();
+        assert_eq!(mainnet.rpc, Url::parse("https://private.node").unwrap());
+        assert_eq!(mainnet.chain_id, 1);
+        assert_eq!(
+            provenance.get("networks.mainnet.chain-id").unwrap().layer,
+            "settings"
+        );
+
+        // it is still validated once merged
+        let (overrides, secrets) = ConfigSource::try_partial_from_string(
+            r#"
+networks:
+    polygon:
+        rpc: https://polygon.node
+"#,
+            &crate::interpolation::EnvResolver,
+        )
+        .unwrap();
+        assert!(ConfigLayers::new()
+            .with_layer("settings", settings)
+            .with_partial_layer("frontmatter", overrides, secrets)
+            .merge()
+            .is_err());
+
+        // and can not load anything of its own
+        assert!(matches!(
+            ConfigSource::try_partial_from_string(
+                "imports:\n    - ./other.yml",
+                &crate::interpolation::EnvResolver
+            ),
+            Err(ConfigSourceError::PartialConfigField(v)) if v == "imports"
+        ));
+    }
 }
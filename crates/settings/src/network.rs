@@ -17,6 +17,8 @@ pub struct Network {
     #[typeshare(typescript(type = "string"))]
     #[cfg_attr(target_family = "wasm", tsify(type = "string"))]
     pub rpc: Url,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rpcs: Vec<NetworkRpc>,
     #[typeshare(typescript(type = "number"))]
     pub chain_id: u64,
     pub label: Option<String>,
@@ -24,11 +26,24 @@ pub struct Network {
     pub network_id: Option<u64>,
     pub currency: Option<String>,
 }
+
+/// A fallback rpc of a network
+#[typeshare]
+#[derive(Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Clone)]
+#[cfg_attr(target_family = "wasm", derive(Tsify))]
+#[serde(rename_all = "kebab-case")]
+pub struct NetworkRpc {
+    #[typeshare(typescript(type = "string"))]
+    #[cfg_attr(target_family = "wasm", tsify(type = "string"))]
+    pub url: Url,
+    pub weight: Option<u32>,
+}
 impl Network {
     pub fn dummy() -> Self {
         Network {
             name: "".to_string(),
             rpc: Url::parse("http://rpc.com").unwrap(),
+            rpcs: vec![],
             chain_id: 1,
             label: None,
             network_id: None,
@@ -54,6 +69,14 @@ impl NetworkConfigSource {
         Ok(Network {
             name,
             rpc: self.rpc,
+            rpcs: self
+                .rpcs
+                .into_iter()
+                .map(|rpc| NetworkRpc {
+                    url: rpc.url,
+                    weight: rpc.weight,
+                })
+                .collect(),
             chain_id: self.chain_id,
             label: self.label,
             network_id: self.network_id,
@@ -71,6 +94,10 @@ mod tests {
     fn test_try_from_network_string_success() {
         let network_string = NetworkConfigSource {
             rpc: Url::parse("http://127.0.0.1:8545").unwrap(),
+            rpcs: vec![RpcConfigSource {
+                url: Url::parse("http://127.0.0.1:8546").unwrap(),
+                weight: Some(2),
+            }],
             chain_id: 1,
             network_id: Some(1),
             label: Some("Local Testnet".into()),
@@ -82,6 +109,13 @@ mod tests {
         let network = result.unwrap();
 
         assert_eq!(network.rpc, Url::parse("http://127.0.0.1:8545").unwrap());
+        assert_eq!(
+            network.rpcs,
+            vec![NetworkRpc {
+                url: Url::parse("http://127.0.0.1:8546").unwrap(),
+                weight: Some(2),
+            }]
+        );
         assert_eq!(network.chain_id, 1);
         assert_eq!(network.network_id, Some(1));
         assert_eq!(network.label, Some("Local Testnet".into()));
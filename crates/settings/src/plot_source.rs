@@ -1,9 +1,11 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use typeshare::typeshare;
 
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct Plot {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
@@ -28,17 +30,19 @@ pub struct Plot {
     pub inset: Option<u32>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
 #[typeshare]
 #[serde(tag = "type", content = "options")]
 #[serde(rename_all = "lowercase")]
+#[schemars(deny_unknown_fields)]
 pub enum Mark {
     Dot(DotOptions),
     Line(LineOptions),
     RectY(RectYOptions),
 }
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
+#[schemars(deny_unknown_fields)]
 pub struct DotOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub x: Option<String>,
@@ -55,7 +59,8 @@ pub struct DotOptions {
 }
 
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
+#[schemars(deny_unknown_fields)]
 pub struct LineOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub x: Option<String>,
@@ -73,7 +78,8 @@ pub struct LineOptions {
 
 // RectY mark
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
+#[schemars(deny_unknown_fields)]
 pub struct RectYOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub x0: Option<String>,
@@ -89,8 +95,9 @@ pub struct RectYOptions {
 
 // AxisX mark
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct AxisOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub label: Option<String>,
@@ -103,9 +110,10 @@ pub struct AxisOptions {
 }
 
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
 #[serde(tag = "type", content = "content")]
 #[serde(rename_all = "lowercase")]
+#[schemars(deny_unknown_fields)]
 pub enum Transform {
     HexBin(HexBinTransform),
     BinX(BinXTransform),
@@ -113,7 +121,8 @@ pub enum Transform {
 }
 
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
+#[schemars(deny_unknown_fields)]
 pub struct TransformOutputs {
     #[serde(skip_serializing_if = "Option::is_none")]
     x: Option<String>,
@@ -130,15 +139,17 @@ pub struct TransformOutputs {
 }
 
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
+#[schemars(deny_unknown_fields)]
 pub struct HexBinTransform {
     outputs: TransformOutputs,
     options: HexBinOptions,
 }
 
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct HexBinOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     x: Option<String>,
@@ -149,14 +160,16 @@ pub struct HexBinOptions {
 }
 
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
+#[schemars(deny_unknown_fields)]
 pub struct BinXTransform {
     outputs: TransformOutputs,
     options: BinXOptions,
 }
 
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
+#[schemars(deny_unknown_fields)]
 pub struct BinXOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     x: Option<String>,
@@ -68,16 +68,21 @@ impl TryFrom<ChainId> for NetworkConfigSource {
         if value.rpc.is_empty() {
             return Err(ChainIdError::NoRpc);
         }
-        for rpc in &value.rpc {
-            if !rpc.path().contains("API_KEY") && !rpc.scheme().starts_with("ws") {
-                return Ok(NetworkConfigSource {
-                    chain_id: value.chain_id,
-                    rpc: rpc.clone(),
-                    network_id: Some(value.network_id),
-                    currency: Some(value.native_currency.symbol),
-                    label: Some(value.name),
-                });
-            }
+        let mut rpcs = value
+            .rpc
+            .into_iter()
+            .filter(|rpc| !rpc.path().contains("API_KEY") && !rpc.scheme().starts_with("ws"));
+        if let Some(rpc) = rpcs.next() {
+            return Ok(NetworkConfigSource {
+                chain_id: value.chain_id,
+                rpc,
+                rpcs: rpcs
+                    .map(|url| RpcConfigSource { url, weight: None })
+                    .collect(),
+                network_id: Some(value.network_id),
+                currency: Some(value.native_currency.symbol),
+                label: Some(value.name),
+            });
         }
         Err(ChainIdError::UnsupportedRpcUrls)
     }
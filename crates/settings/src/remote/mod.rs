@@ -1 +1,2 @@
 pub mod chains;
+pub mod tokens;
@@ -0,0 +1,443 @@
+use crate::config_source::*;
+use alloy::primitives::Address;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard, OnceLock, PoisonError},
+};
+use thiserror::Error;
+use url::Url;
+
+/// How long a fetched token list is used before it is fetched again
+const TOKEN_LIST_TTL_MS: i64 = 10 * 60 * 1000;
+
+/// Fetched token lists by url, with the unix timestamp in milliseconds they
+/// were fetched at
+static TOKEN_LISTS: OnceLock<Mutex<HashMap<Url, (i64, TokenList)>>> = OnceLock::new();
+
+/// The cached token lists. The cache only ever holds whole lists, so it is
+/// still used after a panic while it was locked
+fn token_lists() -> MutexGuard<'static, HashMap<Url, (i64, TokenList)>> {
+    TOKEN_LISTS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+#[derive(Error, Debug)]
+pub enum RemoteTokensError {
+    #[error(transparent)]
+    ReqwestError(#[from] reqwest::Error),
+}
+
+/// A token list following the Uniswap token list schema,
+/// see https://github.com/Uniswap/token-lists
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenList {
+    pub name: String,
+    pub timestamp: Option<String>,
+    pub version: Option<TokenListVersion>,
+    #[serde(rename = "logoURI")]
+    pub logo_uri: Option<String>,
+    pub keywords: Option<Vec<String>>,
+    pub tokens: Vec<TokenListEntry>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenListVersion {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenListEntry {
+    pub chain_id: u64,
+    pub address: Address,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    #[serde(rename = "logoURI")]
+    pub logo_uri: Option<String>,
+    pub tags: Option<Vec<String>>,
+}
+
+impl TokenList {
+    /// Fetches the token list, every list fetched is cached by its url for
+    /// a few minutes so a long running process picks up list updates
+    pub async fn try_from_remote_tokens_config_source(
+        value: RemoteTokensConfigSource,
+    ) -> Result<TokenList, RemoteTokensError> {
+        if let Some((fetched_at, list)) = token_lists().get(&value.url) {
+            if chrono::Utc::now().timestamp_millis() - fetched_at < TOKEN_LIST_TTL_MS {
+                return Ok(list.clone());
+            }
+        }
+        let list = reqwest::get(value.url.clone())
+            .await?
+            .error_for_status()?
+            .json::<TokenList>()
+            .await?;
+        token_lists().insert(
+            value.url,
+            (chrono::Utc::now().timestamp_millis(), list.clone()),
+        );
+        Ok(list)
+    }
+
+    /// Maps the listed tokens to every given network with the same chain id.
+    ///
+    /// Tokens are keyed as `<network>-<symbol>` with the symbol lowercased,
+    /// or as `<network>-<address>` when the list has more than one token
+    /// with that symbol on the chain, so keys do not depend on list order.
+    pub fn into_token_config_sources(
+        self,
+        networks: &HashMap<String, NetworkConfigSource>,
+    ) -> HashMap<String, TokenConfigSource> {
+        let mut symbol_counts: HashMap<(u64, String), usize> = HashMap::new();
+        for entry in &self.tokens {
+            *symbol_counts
+                .entry((entry.chain_id, entry.symbol.to_lowercase()))
+                .or_default() += 1;
+        }
+
+        let mut tokens = HashMap::new();
+        for entry in self.tokens {
+            let symbol = entry.symbol.to_lowercase();
+            let suffix = if symbol_counts[&(entry.chain_id, symbol.clone())] > 1 {
+                entry.address.to_string().to_lowercase()
+            } else {
+                symbol
+            };
+            for (network_name, _) in networks
+                .iter()
+                .filter(|(_, network)| network.chain_id == entry.chain_id)
+            {
+                tokens.insert(
+                    format!("{}-{}", network_name, suffix),
+                    TokenConfigSource {
+                        network: network_name.clone(),
+                        address: entry.address,
+                        decimals: Some(entry.decimals),
+                        label: Some(entry.name.clone()),
+                        symbol: Some(entry.symbol.clone()),
+                    },
+                );
+            }
+        }
+        tokens
+    }
+}
+
+impl ConfigSource {
+    /// Fetches the token lists of `using-tokens-from` and adds their tokens
+    /// that are on the given networks. Local tokens take precedence, a listed
+    /// token is skipped if its key or its address on that network is already
+    /// taken by one of them.
+    pub async fn resolve_remote_tokens(
+        &mut self,
+        networks: &HashMap<String, NetworkConfigSource>,
+        local_tokens: &HashMap<String, TokenConfigSource>,
+    ) -> Result<(), RemoteTokensError> {
+        for item in self.using_tokens_from.values() {
+            let list = TokenList::try_from_remote_tokens_config_source(item.clone()).await?;
+            for (key, token) in list.into_token_config_sources(networks) {
+                let is_local = |tokens: &HashMap<String, TokenConfigSource>| {
+                    tokens.contains_key(&key)
+                        || tokens
+                            .values()
+                            .any(|v| v.network == token.network && v.address == token.address)
+                };
+                if is_local(local_tokens) || is_local(&self.tokens) {
+                    continue;
+                }
+                self.tokens.insert(key, token);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::{Method::GET, MockServer};
+    use serde_json::json;
+
+    fn token_list() -> serde_json::Value {
+        json!({
+            "name": "Test List",
+            "timestamp": "2024-01-01T00:00:00.000Z",
+            "version": { "major": 1, "minor": 0, "patch": 0 },
+            "tokens": [
+                {
+                    "chainId": 1,
+                    "address": "0x0000000000000000000000000000000000000001",
+                    "name": "Wrapped Ether",
+                    "symbol": "WETH",
+                    "decimals": 18,
+                    "logoURI": "https://logo.com/weth.png"
+                },
+                {
+                    "chainId": 1,
+                    "address": "0x0000000000000000000000000000000000000002",
+                    "name": "USD Coin",
+                    "symbol": "USDC",
+                    "decimals": 6
+                },
+                {
+                    "chainId": 1,
+                    "address": "0x00000000000000000000000000000000000000aB",
+                    "name": "USD Coin Bridged",
+                    "symbol": "usdc",
+                    "decimals": 6
+                },
+                {
+                    "chainId": 137,
+                    "address": "0x0000000000000000000000000000000000000003",
+                    "name": "USD Coin",
+                    "symbol": "USDC",
+                    "decimals": 6
+                },
+                {
+                    "chainId": 10,
+                    "address": "0x0000000000000000000000000000000000000004",
+                    "name": "Optimism",
+                    "symbol": "OP",
+                    "decimals": 18
+                }
+            ]
+        })
+    }
+
+    fn network(chain_id: u64) -> NetworkConfigSource {
+        NetworkConfigSource {
+            rpc: "https://rpc.com".parse().unwrap(),
+            rpcs: vec![],
+            chain_id,
+            label: None,
+            network_id: None,
+            currency: None,
+        }
+    }
+
+    #[test]
+    fn test_into_token_config_sources() {
+        let list: TokenList = serde_json::from_value(token_list()).unwrap();
+        let networks = HashMap::from([
+            ("mainnet".to_string(), network(1)),
+            ("polygon".to_string(), network(137)),
+        ]);
+        let tokens = list.into_token_config_sources(&networks);
+
+        assert_eq!(tokens.len(), 4);
+        assert_eq!(
+            tokens["mainnet-weth"],
+            TokenConfigSource {
+                network: "mainnet".to_string(),
+                address: Address::with_last_byte(1),
+                decimals: Some(18),
+                label: Some("Wrapped Ether".to_string()),
+                symbol: Some("WETH".to_string()),
+            }
+        );
+        // symbols listed more than once on a chain are keyed by address
+        assert!(!tokens.contains_key("mainnet-usdc"));
+        assert_eq!(
+            tokens["mainnet-0x0000000000000000000000000000000000000002"].label,
+            Some("USD Coin".to_string())
+        );
+        assert_eq!(
+            tokens["mainnet-0x00000000000000000000000000000000000000ab"].label,
+            Some("USD Coin Bridged".to_string())
+        );
+        assert_eq!(tokens["polygon-usdc"].network, "polygon");
+        // tokens of chains without a network are dropped
+        assert!(tokens.values().all(|v| v.symbol != Some("OP".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_token_list_is_cached() {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/tokens.json");
+            then.status(200).json_body(token_list());
+        });
+        let source = RemoteTokensConfigSource {
+            url: server.url("/tokens.json").parse().unwrap(),
+        };
+
+        let list = TokenList::try_from_remote_tokens_config_source(source.clone())
+            .await
+            .unwrap();
+        let cached = TokenList::try_from_remote_tokens_config_source(source)
+            .await
+            .unwrap();
+        assert_eq!(list, cached);
+        mock.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn test_token_list_cache_expires() {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/tokens.json");
+            then.status(200).json_body(token_list());
+        });
+        let source = RemoteTokensConfigSource {
+            url: server.url("/tokens.json").parse().unwrap(),
+        };
+        let stale = TokenList {
+            name: "Stale List".to_string(),
+            timestamp: None,
+            version: None,
+            logo_uri: None,
+            keywords: None,
+            tokens: vec![],
+        };
+        token_lists().insert(
+            source.url.clone(),
+            (
+                chrono::Utc::now().timestamp_millis() - TOKEN_LIST_TTL_MS,
+                stale,
+            ),
+        );
+
+        let list = TokenList::try_from_remote_tokens_config_source(source)
+            .await
+            .unwrap();
+        assert_eq!(list.name, "Test List");
+        mock.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn test_token_list_error_status() {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/tokens.json");
+            then.status(503).json_body(token_list());
+        });
+        let source = RemoteTokensConfigSource {
+            url: server.url("/tokens.json").parse().unwrap(),
+        };
+
+        // an error page is not taken for the list, nor cached
+        for _ in 0..2 {
+            assert!(matches!(
+                TokenList::try_from_remote_tokens_config_source(source.clone()).await,
+                Err(RemoteTokensError::ReqwestError(e)) if e.is_status()
+            ));
+        }
+        mock.assert_hits(2);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_remote_tokens() {
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(GET).path("/tokens.json");
+            then.status(200).json_body(token_list());
+        });
+
+        let mut config = ConfigSource {
+            using_tokens_from: HashMap::from([(
+                "list".to_string(),
+                RemoteTokensConfigSource {
+                    url: server.url("/tokens.json").parse().unwrap(),
+                },
+            )]),
+            tokens: HashMap::from([(
+                "weth".to_string(),
+                TokenConfigSource {
+                    network: "mainnet".to_string(),
+                    address: Address::with_last_byte(1),
+                    decimals: Some(18),
+                    label: None,
+                    symbol: None,
+                },
+            )]),
+            ..Default::default()
+        };
+        let local_tokens = HashMap::from([(
+            "polygon-usdc".to_string(),
+            TokenConfigSource {
+                network: "polygon".to_string(),
+                address: Address::with_last_byte(9),
+                decimals: Some(6),
+                label: Some("Local USDC".to_string()),
+                symbol: None,
+            },
+        )]);
+        let networks = HashMap::from([
+            ("mainnet".to_string(), network(1)),
+            ("polygon".to_string(), network(137)),
+        ]);
+        config
+            .resolve_remote_tokens(&networks, &local_tokens)
+            .await
+            .unwrap();
+
+        // weth is defined locally under another key and polygon-usdc is
+        // defined by the other config
+        assert_eq!(config.tokens.len(), 3);
+        assert!(config.tokens.contains_key("weth"));
+        assert!(!config.tokens.contains_key("mainnet-weth"));
+        assert!(!config.tokens.contains_key("polygon-usdc"));
+        assert!(config
+            .tokens
+            .contains_key("mainnet-0x0000000000000000000000000000000000000002"));
+    }
+
+    #[tokio::test]
+    async fn test_config_using_tokens_from() {
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(GET).path("/tokens.json");
+            then.status(200).json_body(token_list());
+        });
+
+        let settings = r#"
+networks:
+    polygon:
+        rpc: https://polygon.node
+        chain-id: 137
+tokens:
+    polygon-usdc:
+        network: polygon
+        address: 0x1234567890123456789012345678901234567890
+"#;
+        let frontmatter = format!(
+            r#"
+using-tokens-from:
+    list:
+        url: {}
+networks:
+    mainnet:
+        rpc: https://mainnet.node
+        chain-id: 1
+"#,
+            server.url("/tokens.json")
+        );
+        let (main, top_config) =
+            ConfigSource::try_from_string(frontmatter, Some(settings.to_string()))
+                .await
+                .unwrap();
+
+        assert_eq!(top_config.tokens.len(), 1);
+        assert_eq!(main.tokens.len(), 3);
+        assert_eq!(main.tokens["mainnet-weth"].network, "mainnet");
+        assert!(!main.tokens.contains_key("polygon-usdc"));
+
+        let mut merged = top_config;
+        merged.merge(main).unwrap();
+        assert_eq!(
+            merged.tokens["polygon-usdc"].address,
+            "0x1234567890123456789012345678901234567890"
+                .parse::<Address>()
+                .unwrap()
+        );
+    }
+}
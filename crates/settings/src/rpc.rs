@@ -0,0 +1,480 @@
+use crate::{Network, NetworkRpc};
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex, OnceLock},
+};
+use thiserror::Error;
+use url::Url;
+
+/// Longest backoff of a failing rpc, in requests
+const MAX_BACKOFF: u64 = 64;
+
+#[derive(Error, Debug)]
+pub enum RpcProviderError {
+    #[error("No healthy rpc for network {network}: {}", .reasons.join(", "))]
+    NoHealthyRpc {
+        network: String,
+        reasons: Vec<String>,
+    },
+}
+
+#[derive(Debug, Default, Clone)]
+struct RpcState {
+    /// Whether the rpc reported the chain id of the network
+    verified: bool,
+    failures: u32,
+    /// Until this request the rpc is only used as a last resort
+    backoff_until: u64,
+}
+
+#[derive(Debug)]
+struct ProviderState {
+    requests: u64,
+    rpcs: Vec<RpcState>,
+}
+
+type ProviderKey = (String, u64, Vec<NetworkRpc>);
+
+static PROVIDERS: OnceLock<Mutex<HashMap<ProviderKey, Arc<RpcProvider>>>> = OnceLock::new();
+
+/// Sends requests to the rpcs of a network, failing over to the next one
+/// when a rpc fails.
+///
+/// Rpcs are tried in order, `rpc` first. Before its first use, and again
+/// after any failure, a fallback is health checked by asking for its chain
+/// id, and skipped if it is unreachable or on another chain. A network with
+/// a single rpc is used as is. A failing rpc is moved to the back of the
+/// queue for a number of requests that doubles with every consecutive
+/// failure.
+///
+/// A request that errors on a rpc still reporting the chain id of the
+/// network is not failed over, as the error is the request's own, ie a
+/// contract call reverted, and would be the same on any rpc.
+///
+/// Once any fallback has a weight, requests are spread over the healthy
+/// rpcs in proportion to their weights, `rpc` and fallbacks without a weight
+/// counting as 1 and a weight of 0 keeping a rpc for failover only.
+#[derive(Debug)]
+pub struct RpcProvider {
+    network: String,
+    chain_id: u64,
+    rpcs: Vec<NetworkRpc>,
+    state: Mutex<ProviderState>,
+}
+
+impl RpcProvider {
+    pub fn new(network: &Network) -> Self {
+        let rpcs = std::iter::once(NetworkRpc {
+            url: network.rpc.clone(),
+            weight: None,
+        })
+        .chain(network.rpcs.iter().cloned())
+        .collect::<Vec<_>>();
+        RpcProvider {
+            network: network.name.clone(),
+            chain_id: network.chain_id,
+            state: Mutex::new(ProviderState {
+                requests: 0,
+                rpcs: vec![RpcState::default(); rpcs.len()],
+            }),
+            rpcs,
+        }
+    }
+
+    /// The provider of the network shared by every caller, so rpc health is
+    /// tracked once per process
+    pub fn for_network(network: &Network) -> Arc<Self> {
+        let provider = RpcProvider::new(network);
+        let key = (
+            provider.network.clone(),
+            provider.chain_id,
+            provider.rpcs.clone(),
+        );
+        PROVIDERS
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap()
+            .entry(key)
+            .or_insert_with(|| Arc::new(provider))
+            .clone()
+    }
+
+    /// A provider of a single rpc, for callers given a bare url rather than a
+    /// network, which is used as is
+    pub fn for_url(url: Url) -> Arc<Self> {
+        Arc::new(RpcProvider::new(&Network {
+            rpc: url,
+            ..Network::dummy()
+        }))
+    }
+
+    /// Runs the request against the rpcs until one serves it, returning the
+    /// error of the last rpc tried if none does
+    pub async fn with_failover<T, E, F, Fut>(&self, request: F) -> Result<T, E>
+    where
+        F: Fn(Url) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: From<RpcProviderError>,
+    {
+        let mut reasons = vec![];
+        let mut last_error = None;
+        for index in self.order() {
+            if let Err(reason) = self.check_health(index).await {
+                self.mark_failed(index);
+                reasons.push(reason);
+                continue;
+            }
+            match request(self.rpcs[index].url.clone()).await {
+                Ok(value) => {
+                    self.mark_ok(index);
+                    return Ok(value);
+                }
+                Err(e) => {
+                    if self.rpcs.len() == 1 || self.is_reachable(index).await {
+                        return Err(e);
+                    }
+                    self.mark_failed(index);
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            RpcProviderError::NoHealthyRpc {
+                network: self.network.clone(),
+                reasons,
+            }
+            .into()
+        }))
+    }
+
+    /// The first healthy rpc, for callers that hand the url on, ie to fork
+    /// or to send a transaction. Failover only applies to this pick, the url
+    /// is then used for the rest of the caller's flow, so flows that only
+    /// read should rather run as a whole inside [Self::with_failover]
+    pub async fn healthy_rpc(&self) -> Result<Url, RpcProviderError> {
+        self.with_failover(|url| async move { Ok(url) }).await
+    }
+
+    /// Indexes of the rpcs in the order they are tried for the next request
+    fn order(&self) -> Vec<usize> {
+        let mut state = self.state.lock().unwrap();
+        let request = state.requests;
+        state.requests += 1;
+
+        let (mut ready, mut backing_off): (Vec<usize>, Vec<usize>) =
+            (0..self.rpcs.len()).partition(|i| state.rpcs[*i].backoff_until <= request);
+
+        if self.rpcs.iter().any(|rpc| rpc.weight.is_some()) {
+            let weights = ready
+                .iter()
+                .map(|i| (*i, self.rpcs[*i].weight.unwrap_or(1) as u64))
+                .filter(|(_, weight)| *weight > 0)
+                .collect::<Vec<_>>();
+            let total = weights.iter().map(|(_, weight)| weight).sum::<u64>();
+            if total > 0 {
+                let mut pick = request % total;
+                let first = weights
+                    .iter()
+                    .find(|(_, weight)| {
+                        if pick < *weight {
+                            return true;
+                        }
+                        pick -= weight;
+                        false
+                    })
+                    .map(|(i, _)| *i)
+                    .unwrap();
+                ready.retain(|i| *i != first);
+                ready.insert(0, first);
+            }
+        }
+
+        ready.append(&mut backing_off);
+        ready
+    }
+
+    async fn check_health(&self, index: usize) -> Result<(), String> {
+        if self.rpcs.len() == 1 || self.state.lock().unwrap().rpcs[index].verified {
+            return Ok(());
+        }
+        match fetch_chain_id(&self.rpcs[index].url).await {
+            Some(chain_id) if chain_id == self.chain_id => {
+                self.state.lock().unwrap().rpcs[index].verified = true;
+                Ok(())
+            }
+            Some(chain_id) => Err(format!("{} is on chain {}", rpc_name(index), chain_id)),
+            None => Err(format!("{} did not report a chain id", rpc_name(index))),
+        }
+    }
+
+    /// Whether the rpc answers with the chain id of the network, telling the
+    /// errors of a request apart from the failures of the rpc
+    async fn is_reachable(&self, index: usize) -> bool {
+        fetch_chain_id(&self.rpcs[index].url).await == Some(self.chain_id)
+    }
+
+    fn mark_ok(&self, index: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.rpcs[index].failures = 0;
+        state.rpcs[index].backoff_until = 0;
+    }
+
+    fn mark_failed(&self, index: usize) {
+        let mut state = self.state.lock().unwrap();
+        let requests = state.requests;
+        let rpc = &mut state.rpcs[index];
+        rpc.verified = false;
+        rpc.failures = rpc.failures.saturating_add(1);
+        rpc.backoff_until = requests + 2u64.saturating_pow(rpc.failures).min(MAX_BACKOFF);
+    }
+}
+
+/// Name of the rpc in the network config, urls are not used as they may
+/// contain secrets
+fn rpc_name(index: usize) -> String {
+    match index {
+        0 => "rpc".to_string(),
+        _ => format!("rpcs[{}]", index - 1),
+    }
+}
+
+/// Sends a JSON-RPC request and returns its result, errors leave out the
+/// url as it may contain secrets
+pub async fn json_rpc(url: &Url, method: &str, params: Value) -> Result<Value, String> {
+    let response = reqwest::Client::new()
+        .post(url.clone())
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        }))
+        .send()
+        .await
+        .map_err(|e| e.without_url().to_string())?
+        .json::<Value>()
+        .await
+        .map_err(|e| e.without_url().to_string())?;
+    if let Some(error) = response.get("error") {
+        return Err(error["message"]
+            .as_str()
+            .map_or_else(|| error.to_string(), str::to_string));
+    }
+    Ok(response["result"].clone())
+}
+
+/// Parses a hex quantity of a JSON-RPC result
+pub fn parse_quantity(value: &Value) -> Option<u64> {
+    u64::from_str_radix(value.as_str()?.trim_start_matches("0x"), 16).ok()
+}
+
+async fn fetch_chain_id(url: &Url) -> Option<u64> {
+    parse_quantity(&json_rpc(url, "eth_chainId", json!([])).await.ok()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::{Method::POST, MockServer};
+
+    #[derive(Error, Debug)]
+    enum TestError {
+        #[error(transparent)]
+        RpcProviderError(#[from] RpcProviderError),
+        #[error("request failed")]
+        Failed,
+    }
+
+    fn rpc_server(chain_id: &str) -> MockServer {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).path("/").body_contains("eth_chainId");
+            then.json_body(json!({ "jsonrpc": "2.0", "id": 1, "result": chain_id }));
+        });
+        server
+    }
+
+    fn network(rpc: &str, rpcs: Vec<(&str, Option<u32>)>) -> Network {
+        Network {
+            name: "mainnet".to_string(),
+            rpc: rpc.parse().unwrap(),
+            rpcs: rpcs
+                .into_iter()
+                .map(|(url, weight)| NetworkRpc {
+                    url: url.parse().unwrap(),
+                    weight,
+                })
+                .collect(),
+            ..Network::dummy()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_single_rpc_is_not_checked() {
+        let provider = RpcProvider::new(&network("http://127.0.0.1:1", vec![]));
+        assert_eq!(
+            provider.healthy_rpc().await.unwrap().as_str(),
+            "http://127.0.0.1:1/"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failover_on_chain_id_mismatch() {
+        let optimism = rpc_server("0xa");
+        let mainnet = rpc_server("0x1");
+        let provider = RpcProvider::new(&network(
+            "http://127.0.0.1:1",
+            vec![(&optimism.base_url(), None), (&mainnet.base_url(), None)],
+        ));
+
+        let url = provider.healthy_rpc().await.unwrap();
+        assert_eq!(url.as_str(), mainnet.url("/"));
+
+        let unhealthy = RpcProvider::new(&network(
+            "http://127.0.0.1:1",
+            vec![(&optimism.base_url(), None)],
+        ));
+        let err = unhealthy.healthy_rpc().await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "No healthy rpc for network mainnet: rpc did not report a chain id, rpcs[0] is on chain 10"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failover_on_rpc_failure() {
+        let fallback = rpc_server("0x1");
+        let provider = RpcProvider::new(&network(
+            "http://127.0.0.1:1",
+            vec![(&fallback.base_url(), None)],
+        ));
+        // the primary went down after it was checked
+        provider.state.lock().unwrap().rpcs[0].verified = true;
+        let primary_url: Url = "http://127.0.0.1:1".parse().unwrap();
+
+        let request = |url: Url| {
+            let primary_url = primary_url.clone();
+            async move {
+                if url == primary_url {
+                    Err(TestError::Failed)
+                } else {
+                    Ok(url)
+                }
+            }
+        };
+        let url = provider.with_failover(request).await.unwrap();
+        assert_eq!(url.as_str(), fallback.url("/"));
+
+        // the failed rpc is tried last while backing off
+        assert_eq!(provider.order(), vec![1, 0]);
+        assert_eq!(provider.order(), vec![1, 0]);
+        assert_eq!(provider.order(), vec![0, 1]);
+
+        let err = provider
+            .with_failover(|_| async { Err::<(), _>(TestError::Failed) })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TestError::Failed));
+    }
+
+    #[tokio::test]
+    async fn test_no_failover_on_request_error() {
+        let primary = rpc_server("0x1");
+        let fallback = rpc_server("0x1");
+        let provider = RpcProvider::new(&network(
+            &primary.base_url(),
+            vec![(&fallback.base_url(), None)],
+        ));
+        let primary_url: Url = primary.url("/").parse().unwrap();
+
+        // ie a reverting call, the primary served it so it is not retried
+        let request = |url: Url| {
+            let primary_url = primary_url.clone();
+            async move {
+                if url == primary_url {
+                    Err(TestError::Failed)
+                } else {
+                    Ok(url)
+                }
+            }
+        };
+        let err = provider.with_failover(request).await.unwrap_err();
+        assert!(matches!(err, TestError::Failed));
+
+        // and it is not backing off
+        assert_eq!(provider.order(), vec![0, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_failover_mid_flow() {
+        let primary = MockServer::start();
+        let primary_mocks = Mutex::new(vec![
+            primary.mock(|when, then| {
+                when.method(POST).path("/").body_contains("eth_chainId");
+                then.json_body(json!({ "jsonrpc": "2.0", "id": 1, "result": "0x1" }));
+            }),
+            primary.mock(|when, then| {
+                when.method(POST).path("/").body_contains("eth_blockNumber");
+                then.json_body(json!({ "jsonrpc": "2.0", "id": 1, "result": "0x10" }));
+            }),
+        ]);
+        let fallback = rpc_server("0x1");
+        fallback.mock(|when, then| {
+            when.method(POST).path("/").body_contains("eth_blockNumber");
+            then.json_body(json!({ "jsonrpc": "2.0", "id": 1, "result": "0x20" }));
+        });
+        let provider = RpcProvider::new(&network(
+            &primary.base_url(),
+            vec![(&fallback.base_url(), None)],
+        ));
+        let primary_url: Url = primary.url("/").parse().unwrap();
+        let steps = Mutex::new(vec![]);
+
+        // a flow of two reads, the primary goes down after serving the first
+        let flow = |url: Url| {
+            let (primary_url, primary_mocks, steps) = (&primary_url, &primary_mocks, &steps);
+            async move {
+                let first = json_rpc(&url, "eth_blockNumber", json!([]))
+                    .await
+                    .map_err(|_| TestError::Failed)?;
+                steps.lock().unwrap().push(url.clone());
+                if url == *primary_url {
+                    for mut mock in primary_mocks.lock().unwrap().drain(..) {
+                        mock.delete();
+                    }
+                }
+                json_rpc(&url, "eth_blockNumber", json!([]))
+                    .await
+                    .map_err(|_| TestError::Failed)?;
+                Ok::<_, TestError>(first)
+            }
+        };
+        let result = provider.with_failover(flow).await.unwrap();
+
+        // the whole flow is made again on the fallback
+        assert_eq!(result, json!("0x20"));
+        assert_eq!(
+            *steps.lock().unwrap(),
+            vec![primary_url.clone(), fallback.url("/").parse().unwrap()]
+        );
+        assert_eq!(provider.order(), vec![1, 0]);
+    }
+
+    #[test]
+    fn test_weighted_order() {
+        let provider = RpcProvider::new(&network(
+            "http://rpc.com",
+            vec![("http://rpc2.com", Some(3)), ("http://rpc3.com", Some(0))],
+        ));
+        let firsts = (0..8).map(|_| provider.order()[0]).collect::<Vec<_>>();
+        assert_eq!(firsts, vec![0, 1, 1, 1, 0, 1, 1, 1]);
+        assert_eq!(provider.order(), vec![0, 1, 2]);
+
+        let unweighted =
+            RpcProvider::new(&network("http://rpc.com", vec![("http://rpc2.com", None)]));
+        assert_eq!(unweighted.order(), vec![0, 1]);
+        assert_eq!(unweighted.order(), vec![0, 1]);
+    }
+}
@@ -22,6 +22,10 @@ pub struct Scenario {
     pub blocks: Option<Blocks>,
     #[typeshare(typescript(type = "Deployer"))]
     pub deployer: Arc<Deployer>,
+    /// Values of the swept bindings, for a scenario generated from a matrix
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    #[cfg_attr(target_family = "wasm", tsify(type = "Record<string, string>"))]
+    pub parameters: HashMap<String, String>,
 }
 #[cfg(target_family = "wasm")]
 impl_all_wasm_traits!(Scenario);
@@ -38,6 +42,56 @@ pub enum ParseScenarioConfigSourceError {
     DeployerNotFound(String),
     #[error("Parent orderbook shadowed by child: {0}")]
     ParentOrderbookShadowedError(String),
+    #[error("Binding swept by matrix is already bound: {0}")]
+    MatrixBindingShadowedError(String),
+    #[error("Matrix has no values for binding: {0}")]
+    EmptyMatrixBinding(String),
+}
+
+/// Name of the scenario generated from a matrix for the given values of the
+/// swept bindings, ie `size=100,spread=0%2E01` with the bindings sorted. The
+/// dots of scenario paths and the separators of the name are escaped in the
+/// bindings and their values so the name is a single level of the path
+pub fn matrix_scenario_name(parameters: &HashMap<String, String>) -> String {
+    let mut parameters = parameters.iter().collect::<Vec<_>>();
+    parameters.sort();
+    parameters
+        .into_iter()
+        .map(|(k, v)| format!("{}={}", escape_matrix_value(k), escape_matrix_value(v)))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Percent encodes the characters a matrix scenario name can not hold as is
+fn escape_matrix_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '%' | '.' | ',' | '=' => escaped.push_str(&format!("%{:02X}", c as u8)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The scenarios generated from the matrix of the named scenario, sorted by
+/// name. A scenario with a matrix only holds the bindings its generated
+/// scenarios share, so it is not run or deployed itself
+pub fn matrix_scenarios(
+    scenarios: &HashMap<String, Arc<Scenario>>,
+    name: &str,
+) -> Vec<Arc<Scenario>> {
+    let mut swept = scenarios
+        .values()
+        .filter(|scenario| {
+            !scenario.parameters.is_empty()
+                && scenario.name
+                    == format!("{}.{}", name, matrix_scenario_name(&scenario.parameters))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    swept.sort_by(|a, b| a.name.cmp(&b.name));
+    swept
 }
 
 #[derive(Default)]
@@ -52,6 +106,12 @@ pub struct ScenarioParent {
 // Nested scenarios within the ScenarioConfigSource struct are flattened out into a
 // hashmap of scenarios, where the key is the path such as foo.bar.baz.
 // Every level of the scenario path inherits its parents bindings recursively.
+//
+// A matrix expands into a child scenario for every combination of its values,
+// named after them such as foo.size=100,spread=0%2E01. Swept bindings can not
+// also be bound by the scenario or its parents, and the scenario with the
+// matrix is kept only for its children to be looked up by, see
+// `matrix_scenarios`.
 impl ScenarioConfigSource {
     pub fn try_into_scenarios(
         &self,
@@ -104,11 +164,30 @@ impl ScenarioConfigSource {
             runs: self.runs,
             blocks: self.blocks.clone(),
             deployer: deployer_ref.clone(),
+            parameters: HashMap::new(),
         });
 
         let mut scenarios = HashMap::new();
         scenarios.insert(name.clone(), parent_scenario);
 
+        // Add a child scenario for every combination of the matrix values
+        for parameters in self.matrix_combinations(&bindings)? {
+            let mut child_bindings = bindings.clone();
+            child_bindings.extend(parameters.clone());
+            let child_name = format!("{}.{}", name, matrix_scenario_name(&parameters));
+            scenarios.insert(
+                child_name.clone(),
+                Arc::new(Scenario {
+                    name: child_name,
+                    bindings: child_bindings,
+                    runs: self.runs,
+                    blocks: self.blocks.clone(),
+                    deployer: deployer_ref.clone(),
+                    parameters,
+                }),
+            );
+        }
+
         // Recursively add child scenarios
         if let Some(scenarios_map) = &self.scenarios {
             for (child_name, child_scenario) in scenarios_map {
@@ -127,6 +206,44 @@ impl ScenarioConfigSource {
 
         Ok(scenarios)
     }
+
+    /// The cartesian product of the matrix values
+    fn matrix_combinations(
+        &self,
+        bindings: &HashMap<String, String>,
+    ) -> Result<Vec<HashMap<String, String>>, ParseScenarioConfigSourceError> {
+        if self.matrix.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut keys = self.matrix.keys().collect::<Vec<_>>();
+        keys.sort();
+
+        let mut combinations = vec![HashMap::new()];
+        for key in keys {
+            if bindings.contains_key(key) {
+                return Err(ParseScenarioConfigSourceError::MatrixBindingShadowedError(
+                    key.clone(),
+                ));
+            }
+            let values = &self.matrix[key];
+            if values.is_empty() {
+                return Err(ParseScenarioConfigSourceError::EmptyMatrixBinding(
+                    key.clone(),
+                ));
+            }
+            combinations = combinations
+                .into_iter()
+                .flat_map(|combination| {
+                    values.iter().map(move |value| {
+                        let mut combination = combination.clone();
+                        combination.insert(key.clone(), value.clone());
+                        combination
+                    })
+                })
+                .collect();
+        }
+        Ok(combinations)
+    }
 }
 
 #[cfg(test)]
@@ -147,6 +264,7 @@ mod tests {
             "mainnet".to_string(),
             NetworkConfigSource {
                 rpc: Url::parse("https://mainnet.node").unwrap(),
+                rpcs: vec![],
                 chain_id: 1,
                 label: Some("Ethereum Mainnet".to_string()),
                 network_id: Some(1),
@@ -177,6 +295,7 @@ mod tests {
                 blocks: None,
                 deployer: None,
                 scenarios: None, // No further nesting
+                matrix: HashMap::new(),
             },
         );
 
@@ -189,6 +308,7 @@ mod tests {
                 blocks: None,
                 deployer: None,
                 scenarios: Some(nested_scenario2), // Include nested_scenario2
+                matrix: HashMap::new(),
             },
         );
 
@@ -202,13 +322,16 @@ mod tests {
                 blocks: None,
                 deployer: Some("mainnet".to_string()),
                 scenarios: Some(nested_scenario1), // Include nested_scenario1
+                matrix: HashMap::new(),
             },
         );
 
         // Construct ConfigSource with the above scenarios
         let config_string = ConfigSource {
+            imports: vec![],
             raindex_version: None,
             using_networks_from: HashMap::new(),
+            using_tokens_from: HashMap::new(),
             networks,
             subgraphs: HashMap::new(), // Assuming no subgraphs for simplification
             metaboards: HashMap::new(), // Assuming no metaboards for simplification
@@ -222,6 +345,7 @@ mod tests {
             sentry: None,
             accounts: None, // Assuming no accounts for simplification
             gui: None,
+            secrets: Default::default(),
         };
 
         // Perform the conversion
@@ -276,6 +400,7 @@ mod tests {
             blocks: None,
             deployer: None,
             scenarios: None,
+            matrix: HashMap::new(),
         };
 
         let result = child_scenario.try_into_scenarios(
@@ -294,4 +419,114 @@ mod tests {
             _ => panic!("Expected ParentBindingShadowedError"),
         }
     }
+
+    #[tokio::test]
+    async fn test_scenario_matrix() {
+        let yaml = r#"
+networks:
+    mainnet:
+        rpc: https://mainnet.node
+        chain-id: 1
+deployers:
+    mainnet:
+        address: 0xabcdef0123456789ABCDEF0123456789ABCDEF01
+scenarios:
+    mainnet:
+        runs: 10
+        bindings:
+            fee: 1
+        matrix:
+            spread: [0.01, 0.02, 0.05]
+            size: [100, 200]
+        scenarios:
+            fixed:
+                bindings:
+                    spread: 0.03
+                    size: 300
+"#;
+        let config = Config::try_from_string(yaml.to_string()).await.unwrap();
+
+        // the matrix scenario, its 6 combinations and the fixed child
+        assert_eq!(config.scenarios.len(), 8);
+        let swept = config.matrix_scenarios("mainnet");
+        assert_eq!(
+            swept.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(),
+            vec![
+                "mainnet.size=100,spread=0%2E01",
+                "mainnet.size=100,spread=0%2E02",
+                "mainnet.size=100,spread=0%2E05",
+                "mainnet.size=200,spread=0%2E01",
+                "mainnet.size=200,spread=0%2E02",
+                "mainnet.size=200,spread=0%2E05",
+            ]
+        );
+        let scenario = &config.scenarios["mainnet.size=200,spread=0%2E02"];
+        assert_eq!(scenario.runs, Some(10));
+        assert_eq!(
+            scenario.bindings,
+            HashMap::from([
+                ("fee".to_string(), "1".to_string()),
+                ("size".to_string(), "200".to_string()),
+                ("spread".to_string(), "0.02".to_string()),
+            ])
+        );
+        assert_eq!(
+            scenario.parameters,
+            HashMap::from([
+                ("size".to_string(), "200".to_string()),
+                ("spread".to_string(), "0.02".to_string()),
+            ])
+        );
+        assert!(config.scenarios["mainnet"].parameters.is_empty());
+        assert!(config.scenarios["mainnet.fixed"].parameters.is_empty());
+        assert!(config.matrix_scenarios("mainnet.fixed").is_empty());
+    }
+
+    #[test]
+    fn test_matrix_scenario_name() {
+        assert_eq!(
+            matrix_scenario_name(&HashMap::from([
+                ("spread".to_string(), "0.01".to_string()),
+                ("a.b".to_string(), "1,2=3%".to_string()),
+            ])),
+            "a%2Eb=1%2C2%3D3%25,spread=0%2E01"
+        );
+        assert_eq!(matrix_scenario_name(&HashMap::new()), "");
+    }
+
+    #[test]
+    fn test_scenario_matrix_errors() {
+        let scenario = ScenarioConfigSource {
+            bindings: HashMap::from([("spread".to_string(), "0.01".to_string())]),
+            runs: None,
+            blocks: None,
+            deployer: None,
+            scenarios: None,
+            matrix: HashMap::from([("spread".to_string(), vec!["0.02".to_string()])]),
+        };
+        let parent = ScenarioParent {
+            bindings: None,
+            deployer: Some(mock_deployer()),
+        };
+        let err = scenario
+            .try_into_scenarios("child".to_string(), &parent, &HashMap::new())
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ParseScenarioConfigSourceError::MatrixBindingShadowedError("spread".to_string())
+        );
+
+        let scenario = ScenarioConfigSource {
+            bindings: HashMap::new(),
+            matrix: HashMap::from([("size".to_string(), vec![])]),
+            ..scenario
+        };
+        let err = scenario
+            .try_into_scenarios("child".to_string(), &parent, &HashMap::new())
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ParseScenarioConfigSourceError::EmptyMatrixBinding("size".to_string())
+        );
+    }
 }
@@ -0,0 +1,417 @@
+use crate::{unit_test::UnitTestConfigSource, ConfigSource};
+use schemars::{
+    gen::{SchemaGenerator, SchemaSettings},
+    schema::{InstanceType, Schema, SchemaObject, StringValidation},
+    JsonSchema,
+};
+use serde_json::{json, Value as JsonValue};
+use serde_yaml::Value;
+
+/// The JSON Schema of the settings and frontmatter format, kept in sync with
+/// [config_schema] by a test so editors can point at the file directly
+pub const CONFIG_SCHEMA_JSON: &str = include_str!("../schema/config.schema.json");
+
+/// The JSON Schema of the settings and frontmatter format, ie [crate::ConfigSource]
+pub fn config_schema() -> JsonValue {
+    root_schema::<ConfigSource>("Rain Orderbook settings")
+}
+
+/// The JSON Schema of a unit test's frontmatter, ie [crate::unit_test::UnitTestConfigSource]
+pub fn unit_test_schema() -> JsonValue {
+    root_schema::<UnitTestConfigSource>("Rain Orderbook unit test")
+}
+
+fn root_schema<T: JsonSchema>(title: &str) -> JsonValue {
+    // optional fields are left out rather than set to null in yaml, so there
+    // is no need to allow null for them
+    let mut root = SchemaSettings::draft07()
+        .with(|settings| settings.option_add_null_type = false)
+        .into_generator()
+        .into_root_schema_for::<T>();
+    root.schema.metadata().title = Some(title.to_string());
+    json!(root)
+}
+
+/// A string that must match the pattern unless it contains a `${VAR}` reference
+pub(crate) fn pattern(pattern: &str) -> SchemaObject {
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        string: Some(Box::new(StringValidation {
+            pattern: Some(format!("^({}|.*\\$\\{{.+\\}}.*)$", pattern)),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+}
+
+/// The schema of an address, for `#[schemars(with = "AddressSchema")]`
+pub(crate) struct AddressSchema;
+
+impl JsonSchema for AddressSchema {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        "Address".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        pattern("0x[0-9a-fA-F]{40}").into()
+    }
+}
+
+/// The schema of a 32 bytes hash, for `#[schemars(with = "HashSchema")]`
+pub(crate) struct HashSchema;
+
+impl JsonSchema for HashSchema {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        "Hash".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        pattern("0x[0-9a-fA-F]{64}").into()
+    }
+}
+
+/// The schema of a U256 that can be written as a number or as a decimal or
+/// hex string, for `#[schemars(with = "U256Schema")]`
+pub(crate) struct U256Schema;
+
+impl JsonSchema for U256Schema {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        "U256".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(vec![InstanceType::String, InstanceType::Integer].into()),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// A key the schema does not allow where it was found
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownField {
+    /// Dotted path of the mapping the key was found in
+    pub path: String,
+    pub field: String,
+    /// The closest allowed key, if any is close enough
+    pub suggestion: Option<String>,
+}
+
+impl std::fmt::Display for UnknownField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown field '{}'", self.field)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, ", did you mean '{}'?", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+/// Finds every key of a parsed yaml that the schema does not allow, the
+/// `deny_unknown_fields` counterpart of deserializing it
+pub fn find_unknown_fields(schema: &JsonValue, value: &Value) -> Vec<UnknownField> {
+    let mut unknown = vec![];
+    walk(schema, schema, value, "", &mut unknown);
+    unknown
+}
+
+fn resolve<'a>(root: &'a JsonValue, schema: &'a JsonValue) -> &'a JsonValue {
+    // a described reference is wrapped as `{ description, allOf: [{ $ref }] }`
+    if let Some([inner]) = schema
+        .get("allOf")
+        .and_then(JsonValue::as_array)
+        .map(Vec::as_slice)
+    {
+        return resolve(root, inner);
+    }
+    match schema.get("$ref").and_then(JsonValue::as_str) {
+        Some(reference) => reference
+            .strip_prefix("#/definitions/")
+            .and_then(|name| root["definitions"].get(name))
+            .map(|v| resolve(root, v))
+            .unwrap_or(schema),
+        None => schema,
+    }
+}
+
+fn walk(
+    root: &JsonValue,
+    schema: &JsonValue,
+    value: &Value,
+    path: &str,
+    unknown: &mut Vec<UnknownField>,
+) {
+    let schema = resolve(root, schema);
+
+    // for a union go with the variant that fits best
+    if let Some(variants) = schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))
+        .and_then(JsonValue::as_array)
+    {
+        let best = variants
+            .iter()
+            .map(|variant| {
+                let mut variant_unknown = vec![];
+                walk(root, variant, value, path, &mut variant_unknown);
+                let mismatch = !type_matches(resolve(root, variant), value);
+                (mismatch, variant_unknown)
+            })
+            .min_by_key(|(mismatch, v)| (*mismatch, v.len()));
+        if let Some((_, best)) = best {
+            unknown.extend(best);
+        }
+        return;
+    }
+
+    match value {
+        Value::Mapping(map) => {
+            let properties = schema.get("properties").and_then(JsonValue::as_object);
+            let additional = schema.get("additionalProperties");
+            for (key, child) in map {
+                let key = match key {
+                    Value::String(v) => v.clone(),
+                    other => serde_yaml::to_string(other)
+                        .unwrap_or_default()
+                        .trim()
+                        .to_string(),
+                };
+                let child_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                if let Some(property) = properties.and_then(|v| v.get(&key)) {
+                    walk(root, property, child, &child_path, unknown);
+                } else if let Some(additional) = additional.filter(|v| v.is_object()) {
+                    walk(root, additional, child, &child_path, unknown);
+                } else if additional == Some(&JsonValue::Bool(false)) {
+                    unknown.push(UnknownField {
+                        path: path.to_string(),
+                        suggestion: properties.and_then(|v| suggest(&key, v.keys())),
+                        field: key,
+                    });
+                }
+            }
+        }
+        Value::Sequence(seq) => {
+            if let Some(items) = schema.get("items") {
+                for (i, child) in seq.iter().enumerate() {
+                    let child_path = if path.is_empty() {
+                        i.to_string()
+                    } else {
+                        format!("{}.{}", path, i)
+                    };
+                    walk(root, items, child, &child_path, unknown);
+                }
+            }
+        }
+        Value::Tagged(tagged) => walk(root, schema, &tagged.value, path, unknown),
+        _ => {}
+    }
+}
+
+/// Whether the value is of the schema's type, and of its tag for tagged variants
+fn type_matches(schema: &JsonValue, value: &Value) -> bool {
+    let types = match schema.get("type") {
+        Some(JsonValue::String(v)) => vec![v.as_str()],
+        Some(JsonValue::Array(v)) => v.iter().filter_map(JsonValue::as_str).collect(),
+        _ => return true,
+    };
+    let type_matches = types.iter().any(|t| {
+        matches!(
+            (*t, value),
+            ("object", Value::Mapping(_))
+                | ("array", Value::Sequence(_))
+                | ("string", Value::String(_))
+                | ("integer", Value::Number(_))
+                | ("boolean", Value::Bool(_))
+        )
+    });
+    let tag_matches = match (schema.pointer("/properties/type/enum"), value) {
+        (Some(JsonValue::Array(tags)), Value::Mapping(map)) => map
+            .get("type")
+            .and_then(Value::as_str)
+            .is_some_and(|v| tags.iter().any(|tag| tag.as_str() == Some(v))),
+        _ => true,
+    };
+    type_matches && tag_matches
+}
+
+/// The closest candidate to the key, if it is close enough to be a typo
+fn suggest<'a>(key: &str, candidates: impl Iterator<Item = &'a String>) -> Option<String> {
+    let normalized = key.to_lowercase().replace('_', "-");
+    candidates
+        .map(|v| (edit_distance(&normalized, v), v))
+        .filter(|(distance, v)| *distance <= (v.len() / 3).max(1))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, v)| v.clone())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded_schema_is_up_to_date() {
+        let embedded: JsonValue = serde_json::from_str(CONFIG_SCHEMA_JSON).unwrap();
+        assert_eq!(
+            embedded,
+            config_schema(),
+            "schema/config.schema.json is outdated, regenerate it with `config schema`"
+        );
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("chain-id", "chain-id"), 0);
+        assert_eq!(edit_distance("chainid", "chain-id"), 1);
+        assert_eq!(edit_distance("netwrks", "networks"), 1);
+        assert_eq!(edit_distance("", "rpc"), 3);
+    }
+
+    #[test]
+    fn test_find_unknown_fields() {
+        let value: Value = serde_yaml::from_str(
+            r#"
+netwroks:
+    mainnet:
+        rpc: https://mainnet.node
+        chain-id: 1
+networks:
+    mainnet:
+        rpc: https://mainnet.node
+        chain_id: 1
+        something: else
+orders:
+    order1:
+        inputs:
+            - token: weth
+              vaultid: 1
+        outputs: []
+charts:
+    chart1:
+        plots:
+            plot1:
+                marks:
+                    - type: dot
+                      options:
+                        x: "0.1"
+                        colour: red
+"#,
+        )
+        .unwrap();
+        let unknown = find_unknown_fields(&config_schema(), &value);
+        assert_eq!(
+            unknown,
+            vec![
+                UnknownField {
+                    path: "".to_string(),
+                    field: "netwroks".to_string(),
+                    suggestion: Some("networks".to_string()),
+                },
+                UnknownField {
+                    path: "networks.mainnet".to_string(),
+                    field: "chain_id".to_string(),
+                    suggestion: Some("chain-id".to_string()),
+                },
+                UnknownField {
+                    path: "networks.mainnet".to_string(),
+                    field: "something".to_string(),
+                    suggestion: None,
+                },
+                UnknownField {
+                    path: "orders.order1.inputs.0".to_string(),
+                    field: "vaultid".to_string(),
+                    suggestion: Some("vault-id".to_string()),
+                },
+                UnknownField {
+                    path: "charts.chart1.plots.plot1.marks.0.options".to_string(),
+                    field: "colour".to_string(),
+                    suggestion: None,
+                },
+            ]
+        );
+        assert_eq!(
+            unknown[1].to_string(),
+            "Unknown field 'chain_id', did you mean 'chain-id'?"
+        );
+    }
+
+    #[test]
+    fn test_valid_config_has_no_unknown_fields() {
+        let value: Value = serde_yaml::from_str(
+            r#"
+imports:
+    - url: https://example.com/tokens.yml
+networks:
+    mainnet:
+        rpc: https://mainnet.node
+        chain-id: 1
+scenarios:
+    scenario1:
+        bindings:
+            key: value
+        blocks:
+            range: [0..100]
+            interval: 5
+        matrix:
+            spread: [0.01, 0.02]
+        scenarios:
+            child:
+                runs: 10
+                blocks: [0..]
+gui:
+    name: Gui
+    description: Description
+    deployments:
+        - deployment: deployment1
+          name: Deployment
+          description: Description
+          deposits:
+              - token: weth
+                presets: ["0"]
+          fields:
+              - binding: key
+                name: Key
+                presets:
+                    - value: "1"
+"#,
+        )
+        .unwrap();
+        assert_eq!(find_unknown_fields(&config_schema(), &value), vec![]);
+
+        let value: Value = serde_yaml::from_str(
+            "test:\n    scenario-name: s\n    scenario:\n        bindings:\n            a: b\n",
+        )
+        .unwrap();
+        assert_eq!(find_unknown_fields(&unit_test_schema(), &value), vec![]);
+    }
+}
@@ -7,6 +7,7 @@ pub fn mock_network() -> Arc<Network> {
     Arc::new(Network {
         name: "local".into(),
         rpc: ("http://127.0.0.1:8545").parse().unwrap(),
+        rpcs: vec![],
         chain_id: 1,
         label: Some("Local Testnet".into()),
         network_id: Some(1),
@@ -1,19 +1,22 @@
 use crate::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use typeshare::typeshare;
 
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct UnitTestConfigSource {
     pub test: TestConfigSource,
 }
 
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
+#[schemars(deny_unknown_fields)]
 pub struct TestConfigSource {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub calculate_entrypoint: Option<String>,
@@ -47,6 +50,7 @@ impl TestConfigSource {
             runs: self.scenario.runs,
             blocks: self.scenario.blocks.clone(),
             deployer: Arc::new(Deployer::dummy()),
+            parameters: HashMap::new(),
         });
 
         let config = TestConfig {
@@ -3,7 +3,7 @@ use alloy::{
     hex::decode,
     network::{Ethereum, EthereumWallet, TransactionBuilder},
     node_bindings::{Anvil, AnvilInstance},
-    primitives::{utils::parse_units, Address, Bytes, U256},
+    primitives::{utils::parse_units, Address, Bytes, B256, U256},
     providers::{
         ext::AnvilApi,
         fillers::{FillProvider, JoinFill, RecommendedFiller, WalletFiller},
@@ -37,6 +37,13 @@ sol!(
     "../../lib/rain.interpreter/lib/rain.interpreter.interface/lib/forge-std/src/interfaces//IMulticall3.sol"
 );
 
+sol!(
+    #![sol(all_derives = true)]
+    interface IParserV2 {
+        function parse2(bytes memory data) external view returns (bytes memory bytecode);
+    }
+);
+
 // type aliases for LocalEvm provider type
 pub type LocalEvmFillers = JoinFill<RecommendedFiller, WalletFiller<EthereumWallet>>;
 pub type LocalEvmProvider =
@@ -247,6 +254,59 @@ impl LocalEvm {
         self.provider.call(tx).await
     }
 
+    /// Builds the addOrder2() calldata of an order evaluating the given rainlang, parsed by the
+    /// deployer, with the given (token, decimals, vault id) inputs and outputs, no meta and no
+    /// post tasks, so orders can be added without composing a dotrain
+    pub async fn add_order_calldata(
+        &self,
+        rainlang: &str,
+        inputs: &[(Address, u8, U256)],
+        outputs: &[(Address, u8, U256)],
+    ) -> Vec<u8> {
+        let parse = IParserV2::parse2Call {
+            data: rainlang.as_bytes().to_vec().into(),
+        };
+        let bytecode = IParserV2::parse2Call::abi_decode_returns(
+            &self
+                .call(
+                    &TransactionRequest::default()
+                        .with_to(*self.deployer.address())
+                        .with_input(parse.abi_encode()),
+                )
+                .await
+                .unwrap(),
+            true,
+        )
+        .unwrap()
+        .bytecode;
+
+        let ios = |ios: &[(Address, u8, U256)]| {
+            ios.iter()
+                .map(|(token, decimals, vault_id)| Orderbook::IO {
+                    token: *token,
+                    decimals: *decimals,
+                    vaultId: *vault_id,
+                })
+                .collect::<Vec<_>>()
+        };
+        Orderbook::addOrder2Call {
+            config: Orderbook::OrderConfigV3 {
+                evaluable: Orderbook::EvaluableV3 {
+                    interpreter: *self.interpreter.address(),
+                    store: *self.store.address(),
+                    bytecode,
+                },
+                validInputs: ios(inputs),
+                validOutputs: ios(outputs),
+                nonce: B256::random(),
+                secret: B256::random(),
+                meta: Bytes::new(),
+            },
+            tasks: vec![],
+        }
+        .abi_encode()
+    }
+
     /// Adds an order with given calldata and deposit the specified amount into the given token vault,
     /// returns the AddOrder event and addOrder2() and deposit2() transaction receipts
     pub async fn add_order_and_deposit(
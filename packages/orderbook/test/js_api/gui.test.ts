@@ -15,6 +15,7 @@ import {
   SelectTokens,
   TokenDeposit,
   TokenInfos,
+  TokenMetadataWarnings,
 } from "../../dist/types/js_api.js";
 import { getLocal } from "mockttp";
 
@@ -349,6 +350,46 @@ describe("Rain Orderbook JS API Package Bindgen Tests - Gui", async function ()
     assert.equal(tokenInfos.get(token2Address)?.symbol, "T2");
   });
 
+  it("should get the token metadata warnings of the deployment's network", async () => {
+    // a token on another network, whose rpc is never read
+    const dotrainWithOtherNetwork = `
+    ${guiConfig3}
+
+    ${dotrain
+      .replace(
+        "networks:\n",
+        `networks:
+    other-network:
+        rpc: http://localhost:8085/other-rpc-url
+        chain-id: 456
+        network-id: 456
+        currency: ETH
+`
+      )
+      .replace(
+        "tokens:\n",
+        `tokens:
+    token3:
+        network: other-network
+        address: 0x1234567890abcdef1234567890abcdef12345678
+`
+      )}
+    `;
+    // the rpc of the deployment's network fails the token infos multicall
+    const gui = await DotrainOrderGui.chooseDeployment(
+      dotrainWithOtherNetwork,
+      "other-deployment"
+    );
+
+    const warnings: TokenMetadataWarnings = gui.getTokenMetadataWarnings();
+    assert.equal(warnings.length, 1);
+    assert.equal(warnings[0].type, "FetchFailed");
+    assert.equal(
+      warnings[0].type === "FetchFailed" && warnings[0].data.network,
+      "some-network"
+    );
+  });
+
   describe("deposit tests", async () => {
     let gui: DotrainOrderGui;
     beforeAll(async () => {
@@ -893,6 +934,50 @@ ${dotrain}
       );
     });
 
+    it("generates the same add order calldata for the same derivation", async () => {
+      await mockServer
+        .forPost("/rpc-url")
+        .withBodyIncluding("0xf0cfdd37")
+        .thenSendJsonRpcResult(`0x${"0".repeat(24) + "1".repeat(40)}`);
+      // iStore() call
+      await mockServer
+        .forPost("/rpc-url")
+        .withBodyIncluding("0xc19423bc")
+        .thenSendJsonRpcResult(`0x${"0".repeat(24) + "2".repeat(40)}`);
+      // iParser() call
+      await mockServer
+        .forPost("/rpc-url")
+        .withBodyIncluding("0x24376855")
+        .thenSendJsonRpcResult(`0x${"0".repeat(24) + "3".repeat(40)}`);
+      // parse2() call
+      await mockServer
+        .forPost("/rpc-url")
+        .withBodyIncluding("0xa3869e14")
+        // 0x1234 encoded bytes
+        .thenSendJsonRpcResult(
+          "0x000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000021234000000000000000000000000000000000000000000000000000000000000"
+        );
+
+      gui.saveFieldValue("test-binding", {
+        isPreset: false,
+        value: "10",
+      });
+      gui.setOrderDerivation(
+        "0x1234567890abcdef1234567890abcdef12345678",
+        "salt"
+      );
+
+      const addOrderCalldata: AddOrderCalldataResult =
+        await gui.generateAddOrderCalldata();
+      assert.equal(await gui.generateAddOrderCalldata(), addOrderCalldata);
+
+      gui.setOrderDerivation(
+        "0x1234567890abcdef1234567890abcdef12345678",
+        "other-salt"
+      );
+      assert.notEqual(await gui.generateAddOrderCalldata(), addOrderCalldata);
+    });
+
     it("should generate multicalldata for deposit and add order", async () => {
       await mockServer
         .forPost("/rpc-url")
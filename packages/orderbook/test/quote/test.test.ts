@@ -304,6 +304,11 @@ describe("Rain Orderbook Quote Package Bindgen Tests", async function () {
           ratio:
             "0x0000000000000000000000000000000000000000000000000000000000000002",
         },
+        formatted: {
+          maxOutput: "0.000000000000000001",
+          ratio: "0.000000000000000002",
+          inverseRatio: "500000000000000000.000000000000000000",
+        },
         success: true,
         error: undefined,
       },
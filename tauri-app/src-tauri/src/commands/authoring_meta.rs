@@ -1,12 +1,17 @@
 use crate::error::CommandResult;
+use rain_orderbook_app_settings::{imports::ImportLoaders, interpolation::EnvResolver};
 use rain_orderbook_common::dotrain_order::{DotrainOrder, ScenarioWords};
+use std::path::PathBuf;
 
 #[tauri::command]
 pub async fn get_authoring_meta_v2_for_scenarios(
     dotrain: String,
     settings: Option<String>,
+    dotrain_path: Option<PathBuf>,
+    settings_path: Option<PathBuf>,
 ) -> CommandResult<Vec<ScenarioWords>> {
-    let order = DotrainOrder::new(dotrain, settings).await?;
+    let loaders = ImportLoaders::for_files(dotrain_path.as_deref(), settings_path.as_deref());
+    let order = DotrainOrder::new_with_loaders(dotrain, settings, &EnvResolver, &loaders).await?;
     Ok(order.get_all_scenarios_all_words().await?)
 }
 
@@ -66,7 +71,7 @@ _ _: 0 0;
             deployer = encode_prefixed(deployer_address)
         );
 
-        let results = get_authoring_meta_v2_for_scenarios(dotrain, None)
+        let results = get_authoring_meta_v2_for_scenarios(dotrain, None, None, None)
             .await
             .unwrap();
 
@@ -134,7 +139,7 @@ _ _: 0 0;
             deployer = encode_prefixed(deployer_address)
         );
 
-        let results = get_authoring_meta_v2_for_scenarios(dotrain, None)
+        let results = get_authoring_meta_v2_for_scenarios(dotrain, None, None, None)
             .await
             .unwrap();
 
@@ -251,7 +256,7 @@ _ _: 0 0;
             deployer = encode_prefixed(deployer_address)
         );
 
-        let results = get_authoring_meta_v2_for_scenarios(dotrain, None)
+        let results = get_authoring_meta_v2_for_scenarios(dotrain, None, None, None)
             .await
             .unwrap();
 
@@ -1,9 +1,10 @@
-use crate::error::CommandResult;
+use crate::commands::config::{resolve_env, resolve_rpc_provider};
+use crate::error::{CommandError, CommandResult};
 use alloy_ethers_typecast::transaction::ReadableClientHttp;
 
 #[tauri::command]
 pub async fn get_chainid(rpc_url: String) -> CommandResult<u64> {
-    let chain_id = ReadableClientHttp::new_from_url(rpc_url)?
+    let chain_id = ReadableClientHttp::new_from_url(resolve_env(rpc_url)?)?
         .get_chainid()
         .await?;
 
@@ -13,9 +14,14 @@ pub async fn get_chainid(rpc_url: String) -> CommandResult<u64> {
 }
 
 #[tauri::command]
-pub async fn get_block_number(rpc_url: String) -> CommandResult<u64> {
-    let block_number = ReadableClientHttp::new_from_url(rpc_url)?
-        .get_block_number()
-        .await?;
-    Ok(block_number)
+pub async fn get_block_number(network: serde_yaml::Value) -> CommandResult<u64> {
+    resolve_rpc_provider(network)?
+        .with_failover(|url| async move {
+            Ok::<_, CommandError>(
+                ReadableClientHttp::new_from_url(url.to_string())?
+                    .get_block_number()
+                    .await?,
+            )
+        })
+        .await
 }
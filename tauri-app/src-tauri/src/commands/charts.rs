@@ -1,11 +1,17 @@
-use crate::commands::config::merge_configstrings;
+use crate::commands::config::merge_configs;
 use crate::error::CommandResult;
 use rain_orderbook_app_settings::config::*;
 use rain_orderbook_common::fuzz::*;
+use std::path::PathBuf;
 
 #[tauri::command]
-pub async fn make_charts(dotrain: String, settings: String) -> CommandResult<ChartData> {
-    let config = merge_configstrings(dotrain.clone(), settings).await?;
+pub async fn make_charts(
+    dotrain: String,
+    settings: String,
+    dotrain_path: Option<PathBuf>,
+    settings_path: Option<PathBuf>,
+) -> CommandResult<ChartData> {
+    let config = merge_configs(dotrain.clone(), settings, dotrain_path, settings_path).await?;
     let final_config: Config = config.try_into()?;
     let fuzzer = FuzzRunner::new(dotrain.as_str(), final_config.clone(), None).await;
 
@@ -1,27 +1,101 @@
 use crate::error::CommandResult;
 use dotrain::RainDocument;
-use rain_orderbook_app_settings::{config::Config, config_source::ConfigSource};
+use rain_orderbook_app_settings::{
+    config::Config,
+    config_source::ConfigSource,
+    imports::ImportLoaders,
+    interpolation::{interpolate_str, interpolate_yaml, ConfigSecrets, EnvResolver, Redacted},
+    lint::{lint_config, ConfigLintDiagnostic},
+    network::Network,
+    rpc::RpcProvider,
+};
+use rain_orderbook_common::token_metadata::{
+    enrich_token_metadata, EnrichedConfigSource, TokenInfoCache,
+};
+use std::{path::PathBuf, sync::Arc};
 
 #[tauri::command]
-pub async fn parse_configstring(text: String) -> CommandResult<ConfigSource> {
-    Ok(ConfigSource::try_from_string(text, None).await?.0)
+pub async fn parse_configstring(
+    text: String,
+    path: Option<PathBuf>,
+) -> CommandResult<Redacted<ConfigSource>> {
+    let loaders = ImportLoaders::for_files(path.as_deref(), None);
+    let (config, _) =
+        ConfigSource::try_from_string_with_loaders(text, None, &EnvResolver, &loaders).await?;
+    Ok(config.secrets.clone().redacted(config))
 }
 
 #[tauri::command]
 pub async fn merge_configstrings(
     dotrain: String,
     config_text: String,
+    dotrain_path: Option<PathBuf>,
+    settings_path: Option<PathBuf>,
+) -> CommandResult<Redacted<ConfigSource>> {
+    let config = merge_configs(dotrain, config_text, dotrain_path, settings_path).await?;
+    Ok(config.secrets.clone().redacted(config))
+}
+
+/// Merges the frontmatter of a dotrain with the settings, keeping the
+/// resolved values for use in the backend
+pub async fn merge_configs(
+    dotrain: String,
+    config_text: String,
+    dotrain_path: Option<PathBuf>,
+    settings_path: Option<PathBuf>,
 ) -> CommandResult<ConfigSource> {
     let frontmatter = RainDocument::get_front_matter(dotrain.as_str())
         .unwrap_or("")
         .to_string();
-    let (mut dotrain_config, config) =
-        ConfigSource::try_from_string(frontmatter, Some(config_text)).await?;
+    let loaders = ImportLoaders::for_files(dotrain_path.as_deref(), settings_path.as_deref());
+    let (mut dotrain_config, config) = ConfigSource::try_from_string_with_loaders(
+        frontmatter,
+        Some(config_text),
+        &EnvResolver,
+        &loaders,
+    )
+    .await?;
     dotrain_config.merge(config)?;
     Ok(dotrain_config)
 }
 
 #[tauri::command]
-pub fn convert_configstring_to_config(config_string: ConfigSource) -> CommandResult<Config> {
-    Ok(config_string.try_into()?)
+pub async fn enrich_configstring(
+    config_string: serde_yaml::Value,
+) -> CommandResult<EnrichedConfigSource> {
+    let mut config = ConfigSource::try_from_value_with_resolver(config_string, &EnvResolver)?;
+    let warnings = enrich_token_metadata(&mut config, None, TokenInfoCache::global(), None).await;
+    Ok(EnrichedConfigSource { config, warnings })
+}
+
+#[tauri::command]
+pub fn convert_configstring_to_config(
+    config_string: serde_yaml::Value,
+) -> CommandResult<Redacted<Config>> {
+    let config = ConfigSource::try_from_value_with_resolver(config_string, &EnvResolver)?;
+    let secrets = config.secrets.clone();
+    Ok(secrets.redacted(config.try_into()?))
+}
+
+/// Resolves the `${VAR}` references of a value the frontend got from a
+/// config, as interpolated values are redacted before they reach it
+pub fn resolve_env(value: String) -> CommandResult<String> {
+    if !value.contains("${") {
+        return Ok(value);
+    }
+    Ok(interpolate_str(&value, &EnvResolver)?.0)
+}
+
+/// Resolves a network the frontend got from a config into the rpc provider
+/// shared by the backend, so its requests fail over between the network's
+/// rpcs
+pub fn resolve_rpc_provider(mut network: serde_yaml::Value) -> CommandResult<Arc<RpcProvider>> {
+    interpolate_yaml(&mut network, &EnvResolver, &mut ConfigSecrets::default())?;
+    let network: Network = serde_yaml::from_value(network)?;
+    Ok(RpcProvider::for_network(&network))
+}
+
+#[tauri::command]
+pub fn lint_configstring(text: String) -> Vec<ConfigLintDiagnostic> {
+    lint_config(&text, None)
 }
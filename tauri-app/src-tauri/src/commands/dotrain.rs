@@ -1,3 +1,4 @@
+use crate::commands::config::resolve_env;
 use crate::error::CommandResult;
 use alloy::primitives::{bytes::Bytes, Address};
 use rain_orderbook_common::rainlang::parse_rainlang_on_fork;
@@ -9,5 +10,6 @@ pub async fn parse_dotrain(
     block_number: u64,
     deployer: Address,
 ) -> CommandResult<Bytes> {
-    Ok(parse_rainlang_on_fork(rainlang, rpc_url, Some(block_number), deployer).await?)
+    let rpc_url = resolve_env(rpc_url.to_string())?;
+    Ok(parse_rainlang_on_fork(rainlang, &rpc_url, Some(block_number), deployer).await?)
 }
@@ -1,3 +1,4 @@
+use crate::commands::config::resolve_env;
 use crate::error::CommandResult;
 use alloy::primitives::Address;
 use rain_orderbook_common::{
@@ -32,8 +33,10 @@ pub async fn call_lsp_problems(
     block_number: Option<u64>,
     bindings: HashMap<String, String>,
     deployer: Option<Address>,
+    config_text: Option<String>,
 ) -> CommandResult<Vec<Problem>> {
+    let rpc_url = resolve_env(rpc_url.to_string())?;
     Ok(DotrainAddOrderLsp::new(text_document, bindings)
-        .problems(rpc_url, block_number, deployer)
+        .problems(&rpc_url, block_number, deployer, config_text.as_deref())
         .await)
 }
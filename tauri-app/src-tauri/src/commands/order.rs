@@ -1,7 +1,12 @@
+use crate::commands::config::resolve_env;
 use crate::error::CommandResult;
-use crate::{toast::toast_error, transaction_status::TransactionStatusNoticeRwLock};
+use crate::{
+    journal::AppJournal, toast::toast_error, transaction_status::TransactionStatusNoticeRwLock,
+};
 use alloy::primitives::Bytes;
-use rain_orderbook_app_settings::{deployment::Deployment, scenario::Scenario};
+use rain_orderbook_app_settings::{
+    deployment::Deployment, imports::ImportLoaders, interpolation::EnvResolver, scenario::Scenario,
+};
 use rain_orderbook_common::{
     add_order::AddOrderArgs, csv::TryIntoCsv, dotrain_order::DotrainOrder,
     remove_order::RemoveOrderArgs, subgraph::SubgraphArgs, transaction::TransactionArgs,
@@ -9,7 +14,7 @@ use rain_orderbook_common::{
 };
 use std::fs;
 use std::path::PathBuf;
-use tauri::AppHandle;
+use tauri::{AppHandle, State};
 
 #[tauri::command]
 pub async fn orders_list_write_csv(
@@ -49,11 +54,18 @@ pub async fn order_detail(
 #[tauri::command]
 pub async fn order_add(
     app_handle: AppHandle,
+    journal: State<'_, AppJournal>,
     dotrain: String,
     deployment: Deployment,
     transaction_args: TransactionArgs,
 ) -> CommandResult<()> {
-    let tx_status_notice = TransactionStatusNoticeRwLock::new("Add order".into());
+    let transaction_args = TransactionArgs {
+        rpc_url: resolve_env(transaction_args.rpc_url)?,
+        journal: journal.flow(),
+        ..transaction_args
+    };
+    let tx_status_notice = TransactionStatusNoticeRwLock::new("Add order".into())
+        .with_journal(transaction_args.journal.clone());
     let add_order_args = AddOrderArgs::new_from_deployment(dotrain, deployment).await?;
     add_order_args
         .execute(transaction_args, |status| {
@@ -71,10 +83,16 @@ pub async fn order_add(
 #[tauri::command]
 pub async fn order_remove(
     app_handle: AppHandle,
+    journal: State<'_, AppJournal>,
     id: String,
     transaction_args: TransactionArgs,
     subgraph_args: SubgraphArgs,
 ) -> CommandResult<()> {
+    let transaction_args = TransactionArgs {
+        rpc_url: resolve_env(transaction_args.rpc_url)?,
+        journal: journal.flow(),
+        ..transaction_args
+    };
     let order = subgraph_args
         .to_subgraph_client()
         .await
@@ -90,7 +108,8 @@ pub async fn order_remove(
         })?;
     let remove_order_args: RemoveOrderArgs = order.into();
 
-    let tx_status_notice = TransactionStatusNoticeRwLock::new("Remove order".into());
+    let tx_status_notice = TransactionStatusNoticeRwLock::new("Remove order".into())
+        .with_journal(transaction_args.journal.clone());
     let _ = remove_order_args
         .execute(transaction_args.clone(), |status| {
             tx_status_notice.update_status_and_emit(app_handle.clone(), status);
@@ -112,7 +131,10 @@ pub async fn order_add_calldata(
 ) -> CommandResult<Bytes> {
     let add_order_args = AddOrderArgs::new_from_deployment(dotrain, deployment).await?;
     let calldata = add_order_args
-        .get_add_order_calldata(transaction_args)
+        .get_add_order_calldata(TransactionArgs {
+            rpc_url: resolve_env(transaction_args.rpc_url)?,
+            ..transaction_args
+        })
         .await
         .map_err(|e| {
             toast_error(app_handle.clone(), e.to_string());
@@ -158,13 +180,25 @@ pub async fn compose_from_scenario(
     dotrain: String,
     settings: Option<String>,
     scenario: Scenario,
+    dotrain_path: Option<PathBuf>,
+    settings_path: Option<PathBuf>,
 ) -> CommandResult<String> {
-    let order = DotrainOrder::new(dotrain.clone(), settings).await?;
+    let loaders = ImportLoaders::for_files(dotrain_path.as_deref(), settings_path.as_deref());
+    let order =
+        DotrainOrder::new_with_loaders(dotrain.clone(), settings, &EnvResolver, &loaders).await?;
     Ok(order.compose_scenario_to_rainlang(scenario.name).await?)
 }
 
 #[tauri::command]
-pub async fn validate_raindex_version(dotrain: String, settings: String) -> CommandResult<()> {
-    let order = DotrainOrder::new(dotrain.clone(), Some(settings)).await?;
+pub async fn validate_raindex_version(
+    dotrain: String,
+    settings: String,
+    dotrain_path: Option<PathBuf>,
+    settings_path: Option<PathBuf>,
+) -> CommandResult<()> {
+    let loaders = ImportLoaders::for_files(dotrain_path.as_deref(), settings_path.as_deref());
+    let order =
+        DotrainOrder::new_with_loaders(dotrain.clone(), Some(settings), &EnvResolver, &loaders)
+            .await?;
     Ok(order.validate_raindex_version().await?)
 }
@@ -1,3 +1,4 @@
+use crate::commands::config::resolve_rpc_provider;
 use crate::error::CommandResult;
 use alloy::primitives::{Address, U256};
 use rain_orderbook_bindings::IOrderBookV4::Quote;
@@ -11,10 +12,11 @@ use rain_orderbook_subgraph_client::types::common::*;
 pub async fn batch_order_quotes(
     orders: Vec<Order>,
     block_number: Option<u64>,
-    rpc_url: String,
+    network: serde_yaml::Value,
     gas: Option<U256>,
 ) -> CommandResult<Vec<BatchOrderQuotesResponse>> {
-    Ok(get_order_quotes(orders, block_number, rpc_url, gas).await?)
+    let rpc = resolve_rpc_provider(network)?;
+    Ok(get_order_quotes(orders, block_number, &rpc, gas).await?)
 }
 
 #[tauri::command]
@@ -23,7 +25,7 @@ pub async fn debug_order_quote(
     input_io_index: u32,
     output_io_index: u32,
     orderbook: Address,
-    rpc_url: String,
+    network: serde_yaml::Value,
     block_number: Option<u32>,
 ) -> CommandResult<(RainEvalResultsTable, Option<String>)> {
     let quote_target = QuoteTarget {
@@ -37,7 +39,7 @@ pub async fn debug_order_quote(
     };
 
     let mut debugger = QuoteDebugger::new(NewQuoteDebugger {
-        fork_url: rpc_url.parse()?,
+        rpc: resolve_rpc_provider(network)?,
         fork_block_number: block_number.map(|s| s.into()),
     })
     .await?;
@@ -62,6 +64,7 @@ mod tests {
         primitives::utils::parse_ether,
         sol_types::{SolCall, SolValue},
     };
+    use rain_orderbook_app_settings::network::Network;
     use rain_orderbook_common::{add_order::AddOrderArgs, dotrain_order::DotrainOrder};
     use rain_orderbook_test_fixtures::LocalEvm;
 
@@ -171,14 +174,18 @@ amount price: 16 52;
         let input_io_index = 0;
         let output_io_index = 0;
 
-        let rpc_url = local_evm.url();
+        let network = serde_yaml::to_value(Network {
+            rpc: local_evm.url().parse().unwrap(),
+            ..Network::dummy()
+        })
+        .unwrap();
 
         let result = debug_order_quote(
             order,
             input_io_index,
             output_io_index,
             *orderbook.address(),
-            rpc_url,
+            network,
             None,
         )
         .await;
@@ -4,12 +4,16 @@ use rain_orderbook_common::{
     replays::{NewTradeReplayer, TradeReplayer},
 };
 
+use crate::commands::config::resolve_rpc_provider;
 use crate::error::CommandResult;
 
 #[tauri::command]
-pub async fn debug_trade(tx_hash: String, rpc_url: String) -> CommandResult<RainEvalResultsTable> {
+pub async fn debug_trade(
+    tx_hash: String,
+    network: serde_yaml::Value,
+) -> CommandResult<RainEvalResultsTable> {
     let mut replayer: TradeReplayer = TradeReplayer::new(NewTradeReplayer {
-        fork_url: rpc_url.parse()?,
+        rpc: resolve_rpc_provider(network)?,
     })
     .await?;
     let tx_hash = tx_hash.parse::<B256>()?;
@@ -27,6 +31,7 @@ mod tests {
         },
         sol_types::SolCall,
     };
+    use rain_orderbook_app_settings::network::Network;
     use rain_orderbook_common::{add_order::AddOrderArgs, dotrain_order::DotrainOrder};
     use rain_orderbook_test_fixtures::{ContractTxHandler, LocalEvm, Orderbook};
 
@@ -162,7 +167,12 @@ amount price: 7 4;
             .await
             .unwrap();
 
-        let res = debug_trade(tx.transaction_hash.to_string(), local_evm.url())
+        let network = serde_yaml::to_value(Network {
+            rpc: local_evm.url().parse().unwrap(),
+            ..Network::dummy()
+        })
+        .unwrap();
+        let res = debug_trade(tx.transaction_hash.to_string(), network)
             .await
             .unwrap();
 
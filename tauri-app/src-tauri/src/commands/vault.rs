@@ -1,4 +1,6 @@
+use crate::commands::config::resolve_env;
 use crate::error::CommandResult;
+use crate::journal::AppJournal;
 use crate::toast::toast_error;
 use crate::transaction_status::TransactionStatusNoticeRwLock;
 use alloy::primitives::{Bytes, U256};
@@ -16,7 +18,7 @@ use rain_orderbook_subgraph_client::{
 };
 use std::fs;
 use std::path::PathBuf;
-use tauri::AppHandle;
+use tauri::{AppHandle, State};
 
 #[tauri::command]
 pub async fn vaults_list(
@@ -99,11 +101,18 @@ pub async fn vault_detail(id: String, subgraph_args: SubgraphArgs) -> CommandRes
 #[tauri::command]
 pub async fn vault_deposit(
     app_handle: AppHandle,
+    journal: State<'_, AppJournal>,
     deposit_args: DepositArgs,
     transaction_args: TransactionArgs,
 ) -> CommandResult<()> {
+    let transaction_args = TransactionArgs {
+        rpc_url: resolve_env(transaction_args.rpc_url)?,
+        journal: journal.flow(),
+        ..transaction_args
+    };
     let tx_status_notice =
-        TransactionStatusNoticeRwLock::new("Approve ERC20 token transfer".into());
+        TransactionStatusNoticeRwLock::new("Approve ERC20 token transfer".into())
+            .with_journal(transaction_args.journal.clone());
     let _ = deposit_args
         .execute_approve(transaction_args.clone(), |status| {
             tx_status_notice.update_status_and_emit(app_handle.clone(), status);
@@ -113,7 +122,8 @@ pub async fn vault_deposit(
             tx_status_notice.set_failed_status_and_emit(app_handle.clone(), e.to_string());
         });
 
-    let tx_status_notice = TransactionStatusNoticeRwLock::new("Deposit tokens into vault".into());
+    let tx_status_notice = TransactionStatusNoticeRwLock::new("Deposit tokens into vault".into())
+        .with_journal(transaction_args.journal.clone());
     let _ = deposit_args
         .execute_deposit(transaction_args.clone(), |status| {
             tx_status_notice.update_status_and_emit(app_handle.clone(), status);
@@ -134,7 +144,13 @@ pub async fn vault_deposit_approve_calldata(
     current_allowance: U256,
 ) -> CommandResult<Bytes> {
     let calldata = deposit_args
-        .get_approve_calldata(transaction_args, current_allowance)
+        .get_approve_calldata(
+            TransactionArgs {
+                rpc_url: resolve_env(transaction_args.rpc_url)?,
+                ..transaction_args
+            },
+            current_allowance,
+        )
         .await
         .map_err(|e| {
             toast_error(app_handle.clone(), e.to_string());
@@ -159,10 +175,17 @@ pub async fn vault_deposit_calldata(
 #[tauri::command]
 pub async fn vault_withdraw(
     app_handle: AppHandle,
+    journal: State<'_, AppJournal>,
     withdraw_args: WithdrawArgs,
     transaction_args: TransactionArgs,
 ) -> CommandResult<()> {
-    let tx_status_notice = TransactionStatusNoticeRwLock::new("Withdraw tokens from vault".into());
+    let transaction_args = TransactionArgs {
+        rpc_url: resolve_env(transaction_args.rpc_url)?,
+        journal: journal.flow(),
+        ..transaction_args
+    };
+    let tx_status_notice = TransactionStatusNoticeRwLock::new("Withdraw tokens from vault".into())
+        .with_journal(transaction_args.journal.clone());
     let _ = withdraw_args
         .execute(transaction_args.clone(), |status| {
             tx_status_notice.update_status_and_emit(app_handle.clone(), status);
@@ -1,6 +1,7 @@
+use crate::commands::config::resolve_env;
 use crate::error::CommandResult;
-use alloy_ethers_typecast::{client::LedgerClient, ethers_address_to_alloy, client::HDPath};
 use alloy::primitives::Address;
+use alloy_ethers_typecast::{client::HDPath, client::LedgerClient, ethers_address_to_alloy};
 
 #[tauri::command]
 pub async fn get_address_from_ledger(
@@ -8,8 +9,14 @@ pub async fn get_address_from_ledger(
     chain_id: u64,
     rpc_url: String,
 ) -> CommandResult<Address> {
-    let ledger_client =
-        LedgerClient::new(derivation_index.map(HDPath::LedgerLive), chain_id, rpc_url.clone(), None).await?;
+    let rpc_url = resolve_env(rpc_url)?;
+    let ledger_client = LedgerClient::new(
+        derivation_index.map(HDPath::LedgerLive),
+        chain_id,
+        rpc_url,
+        None,
+    )
+    .await?;
     let ledger_address = ethers_address_to_alloy(ledger_client.client.address());
 
     Ok(ledger_address)
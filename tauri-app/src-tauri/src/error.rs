@@ -4,6 +4,7 @@ use alloy_ethers_typecast::{client::LedgerClientError, transaction::ReadableClie
 use dotrain::error::ComposeError;
 use rain_orderbook_app_settings::config::ParseConfigSourceError;
 use rain_orderbook_app_settings::config_source::ConfigSourceError;
+use rain_orderbook_app_settings::interpolation::InterpolationError;
 use rain_orderbook_app_settings::merge::MergeError;
 use rain_orderbook_common::dotrain_order::DotrainOrderError;
 use rain_orderbook_common::fuzz::FuzzRunnerError;
@@ -78,6 +79,9 @@ pub enum CommandError {
     #[error(transparent)]
     ConfigSourceError(#[from] ConfigSourceError),
 
+    #[error(transparent)]
+    InterpolationError(#[from] InterpolationError),
+
     #[error(transparent)]
     DotrainOrderError(#[from] DotrainOrderError),
 
@@ -109,6 +113,9 @@ pub enum CommandError {
 
     #[error(transparent)]
     RainEvalResultError(#[from] rain_orderbook_common::fuzz::RainEvalResultError),
+
+    #[error(transparent)]
+    RpcProviderError(#[from] rain_orderbook_app_settings::rpc::RpcProviderError),
 }
 
 impl Serialize for CommandError {
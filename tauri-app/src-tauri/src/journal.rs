@@ -0,0 +1,22 @@
+use rain_orderbook_common::journal::{JournalFlow, TransactionJournal};
+use std::sync::Arc;
+use tauri::AppHandle;
+
+/// Transaction journal of the app, kept in its data dir. The app still sends
+/// transactions without a journal if it cannot be opened
+pub struct AppJournal(Option<Arc<TransactionJournal>>);
+
+impl AppJournal {
+    pub fn open(app_handle: &AppHandle) -> Self {
+        let journal = app_handle
+            .path_resolver()
+            .app_data_dir()
+            .and_then(|dir| TransactionJournal::open(dir.join("journal.json")).ok());
+        Self(journal.map(Arc::new))
+    }
+
+    /// A new flow for the transactions sent by a command
+    pub fn flow(&self) -> Option<JournalFlow> {
+        self.0.clone().map(JournalFlow::new)
+    }
+}
@@ -2,6 +2,7 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 pub mod error;
+pub mod journal;
 pub mod toast;
 pub mod transaction_status;
 
@@ -10,7 +11,10 @@ use commands::app::get_app_commit_sha;
 use commands::authoring_meta::get_authoring_meta_v2_for_scenarios;
 use commands::chain::{get_block_number, get_chainid};
 use commands::charts::make_charts;
-use commands::config::{convert_configstring_to_config, merge_configstrings, parse_configstring};
+use commands::config::{
+    convert_configstring_to_config, enrich_configstring, lint_configstring, merge_configstrings,
+    parse_configstring,
+};
 use commands::dotrain::parse_dotrain;
 use commands::dotrain_add_order_lsp::{call_lsp_completion, call_lsp_hover, call_lsp_problems};
 use commands::order::{
@@ -28,6 +32,8 @@ use commands::vault::{
     vault_withdraw_calldata, vaults_list, vaults_list_write_csv,
 };
 use commands::wallet::get_address_from_ledger;
+use journal::AppJournal;
+use tauri::Manager;
 
 fn main() {
     if std::env::consts::OS == "linux" {
@@ -43,6 +49,10 @@ fn main() {
 
 fn run_tauri_app() {
     tauri::Builder::default()
+        .setup(|app| {
+            app.manage(AppJournal::open(&app.handle()));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             vaults_list,
             vaults_list_write_csv,
@@ -66,7 +76,9 @@ fn run_tauri_app() {
             call_lsp_problems,
             parse_configstring,
             merge_configstrings,
+            enrich_configstring,
             convert_configstring_to_config,
+            lint_configstring,
             make_charts,
             order_add_calldata,
             order_remove_calldata,
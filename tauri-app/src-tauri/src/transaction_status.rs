@@ -1,6 +1,7 @@
 use alloy::sol_types::SolCall;
 use alloy_ethers_typecast::transaction::WriteTransactionStatus;
 use chrono::{DateTime, Utc};
+use rain_orderbook_common::journal::JournalFlow;
 use serde::{Deserialize, Serialize};
 use std::sync::RwLock;
 use tauri::{AppHandle, Manager};
@@ -41,9 +42,18 @@ pub struct TransactionStatusNotice {
 
     /// Human-readable label to display in the UI, describing the transaction i.e. "Approving ERC20 Token Spend"
     pub label: String,
+
+    /// Id of the journal flow the transaction is recorded in
+    pub flow: Option<String>,
+
+    /// Nonce the transaction is sent at, once the journal assigned it
+    pub nonce: Option<String>,
 }
 
-pub struct TransactionStatusNoticeRwLock(RwLock<TransactionStatusNotice>);
+pub struct TransactionStatusNoticeRwLock {
+    notice: RwLock<TransactionStatusNotice>,
+    journal: Option<JournalFlow>,
+}
 
 impl TransactionStatusNoticeRwLock {
     pub fn new(label: String) -> Self {
@@ -52,8 +62,18 @@ impl TransactionStatusNoticeRwLock {
             status: TransactionStatus::Initialized,
             created_at: Utc::now(),
             label,
+            flow: None,
+            nonce: None,
         };
-        Self(RwLock::new(notice))
+        Self {
+            notice: RwLock::new(notice),
+            journal: None,
+        }
+    }
+
+    /// Shows the flow and nonce of the journal entry of the transaction
+    pub fn with_journal(self, journal: Option<JournalFlow>) -> Self {
+        Self { journal, ..self }
     }
 
     pub fn update_status_and_emit<T: SolCall + Clone>(
@@ -71,18 +91,25 @@ impl TransactionStatusNoticeRwLock {
     }
 
     fn update_status<T: SolCall + Clone>(&self, status: WriteTransactionStatus<T>) {
-        let mut notice = self.0.write().unwrap();
+        let mut notice = self.notice.write().unwrap();
         notice.status = status.into();
+        if let Some(entry) = self.journal.as_ref().and_then(|flow| flow.last_entry()) {
+            notice.flow = Some(entry.flow);
+            notice.nonce = Some(entry.nonce.to_string());
+        }
     }
 
     fn set_failed_status(&self, message: String) {
-        let mut notice = self.0.write().unwrap();
+        let mut notice = self.notice.write().unwrap();
         notice.status = TransactionStatus::Failed(message);
     }
 
     fn emit(&self, app_handle: AppHandle) {
         app_handle
-            .emit_all("transaction_status_notice", self.0.read().unwrap().clone())
+            .emit_all(
+                "transaction_status_notice",
+                self.notice.read().unwrap().clone(),
+            )
             .unwrap();
     }
 }
@@ -33,7 +33,7 @@ test('renders table with the correct data', async () => {
         timestampAdded: '123',
         trades: [],
       },
-      rpcUrl: 'https://rpc-url.com',
+      network: { name: 'mainnet', rpc: 'https://rpc-url.com', rpcs: [], 'chain-id': 1 },
       inputIOIndex: 0,
       outputIOIndex: 0,
       orderbook: '0x123',
@@ -18,7 +18,11 @@ test('renders table with the correct data', async () => {
 
   render(ModalTradeDebug, {
     context: new Map([['$$_queryClient', queryClient]]),
-    props: { open: true, txHash: '0x123', rpcUrl: 'https://rpc-url.com' },
+    props: {
+      open: true,
+      txHash: '0x123',
+      network: { name: 'mainnet', rpc: 'https://rpc-url.com', rpcs: [], 'chain-id': 1 },
+    },
   });
 
   expect(await screen.findByText('Debug trade')).toBeInTheDocument();
@@ -1,11 +1,11 @@
 import { get } from 'svelte/store';
 import { invoke } from '@tauri-apps/api';
-import { rpcUrl } from '$lib/stores/settings';
+import { activeNetwork } from '$lib/stores/settings';
 import type { Order } from '$lib/typeshare/subgraphTypes';
 import type { BatchOrderQuotesResponse } from '$lib/typeshare/orderQuote';
 import type { Hex } from 'viem';
 import { mockIPC } from '@tauri-apps/api/mocks';
-import type { RainEvalResultsTable } from '$lib/typeshare/config';
+import type { Network, RainEvalResultsTable } from '$lib/typeshare/config';
 
 export async function batchOrderQuotes(
   orders: Order[],
@@ -14,7 +14,7 @@ export async function batchOrderQuotes(
   return invoke('batch_order_quotes', {
     orders,
     blockNumber,
-    rpcUrl: get(rpcUrl),
+    network: get(activeNetwork),
   });
 }
 
@@ -23,7 +23,7 @@ export async function debugOrderQuote(
   inputIOIndex: number,
   outputIOIndex: number,
   orderbook: Hex,
-  rpcUrl: string,
+  network: Network,
   blockNumber?: number,
 ) {
   return await invoke<[RainEvalResultsTable, string | undefined]>('debug_order_quote', {
@@ -31,7 +31,7 @@ export async function debugOrderQuote(
     inputIoIndex: inputIOIndex,
     outputIoIndex: outputIOIndex,
     orderbook,
-    rpcUrl,
+    network,
     blockNumber,
   });
 }
@@ -71,7 +71,7 @@ if (import.meta.vitest) {
       0,
       0,
       '0x123',
-      'https://rpc-url.com',
+      { name: 'mainnet', rpc: 'https://rpc-url.com', rpcs: [], 'chain-id': 1 },
     );
     expect(result).toEqual(mockQuoteDebug);
   });
@@ -1,11 +1,11 @@
-import type { RainEvalResultsTable } from '$lib/typeshare/config';
+import type { Network, RainEvalResultsTable } from '$lib/typeshare/config';
 import { invoke } from '@tauri-apps/api';
 import { mockIPC } from '@tauri-apps/api/mocks';
 
-export const tradeDebug = async (txHash: string, rpcUrl: string) => {
+export const tradeDebug = async (txHash: string, network: Network) => {
   return await invoke<RainEvalResultsTable>('debug_trade', {
     txHash,
-    rpcUrl,
+    network,
   });
 };
 
@@ -24,7 +24,12 @@ if (import.meta.vitest) {
       }
     });
 
-    const result = await tradeDebug('0x123', 'https://rpc-url.com');
+    const result = await tradeDebug('0x123', {
+      name: 'mainnet',
+      rpc: 'https://rpc-url.com',
+      rpcs: [],
+      'chain-id': 1,
+    });
     expect(result).toEqual(mockTradeDebug);
   });
 }
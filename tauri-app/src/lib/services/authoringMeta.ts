@@ -1,7 +1,9 @@
 import type { ScenarioWords } from '$lib/typeshare/authoringMeta';
 import { invoke } from '@tauri-apps/api';
+import { configFilePaths } from '$lib/services/config';
 
 export const getAuthoringMetaV2ForScenarios = async (
   dotrain: string,
   settings?: string,
-): Promise<ScenarioWords[]> => invoke('get_authoring_meta_v2_for_scenarios', { dotrain, settings });
+): Promise<ScenarioWords[]> =>
+  invoke('get_authoring_meta_v2_for_scenarios', { dotrain, settings, ...configFilePaths() });
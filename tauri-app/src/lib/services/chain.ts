@@ -1,7 +1,8 @@
 import { invoke } from '@tauri-apps/api';
+import type { Network } from '$lib/typeshare/config';
 
 export const getChainIdFromRpc = async (rpcUrl: string): Promise<number> =>
   invoke('get_chainid', { rpcUrl });
 
-export const getBlockNumberFromRpc = async (rpcUrl: string): Promise<number> =>
-  invoke('get_block_number', { rpcUrl });
+export const getBlockNumber = async (network: Network): Promise<number> =>
+  invoke('get_block_number', { network });
@@ -1,5 +1,6 @@
 import type { ChartData } from '$lib/typeshare/config';
 import { invoke } from '@tauri-apps/api';
+import { configFilePaths } from '$lib/services/config';
 
 export const makeChartData = async (dotrain: string, settings: string): Promise<ChartData> =>
-  invoke('make_charts', { dotrain, settings });
+  invoke('make_charts', { dotrain, settings, ...configFilePaths() });
@@ -1,13 +1,33 @@
-import { settingsText } from '$lib/stores/settings';
-import type { Config, ConfigSource } from '$lib/typeshare/config';
+import { settingsFile, settingsText } from '$lib/stores/settings';
+import { globalDotrainFile } from '$lib/storesGeneric/textFileStore';
+import type {
+  Config,
+  ConfigLintDiagnostic,
+  ConfigSource,
+  EnrichedConfigSource,
+} from '$lib/typeshare/config';
 import { invoke } from '@tauri-apps/api';
 import { get } from 'svelte/store';
 
+/// Paths of the loaded .rain and settings files, the relative imports of
+/// their configs are resolved from them
+export const configFilePaths = () => ({
+  dotrainPath: get(globalDotrainFile).path,
+  settingsPath: get(settingsFile).path,
+});
+
 export const parseConfigSource = async (text: string): Promise<ConfigSource> =>
-  invoke('parse_configstring', { text });
+  invoke('parse_configstring', { text, path: get(settingsFile).path });
 
 export const mergeDotrainConfigWithSettings = async (dotrain: string): Promise<ConfigSource> =>
-  invoke('merge_configstrings', { dotrain, configText: get(settingsText) });
+  invoke('merge_configstrings', { dotrain, configText: get(settingsText), ...configFilePaths() });
+
+export const enrichConfigSource = async (
+  configString: ConfigSource,
+): Promise<EnrichedConfigSource> => invoke('enrich_configstring', { configString });
 
 export const convertConfigstringToConfig = async (configString: ConfigSource): Promise<Config> =>
   invoke('convert_configstring_to_config', { configString });
+
+export const lintConfigSource = async (text: string): Promise<ConfigLintDiagnostic[]> =>
+  invoke('lint_configstring', { text });
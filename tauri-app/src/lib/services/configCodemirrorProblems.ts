@@ -1,12 +1,18 @@
 import { ErrorCode, type Problem } from 'codemirror-rainlang';
 import { reportErrorToSentry, SentrySeverityLevel } from '$lib/services/sentry';
-import { mergeDotrainConfigWithSettings, parseConfigSource } from './config';
+import type { ConfigLintDiagnostic } from '$lib/typeshare/config';
+import { lintConfigSource, mergeDotrainConfigWithSettings, parseConfigSource } from './config';
 
 export async function parseConfigSourceProblems(text: string) {
   const problems: Problem[] = [];
 
   try {
-    await parseConfigSource(text);
+    const diagnostics = await lintConfigSource(text);
+    problems.push(...diagnostics.map(convertDiagnosticToProblem));
+    // remote networks are only resolved when parsing
+    if (diagnostics.every((d) => d.severity !== 'Error')) {
+      await parseConfigSource(text);
+    }
   } catch (e) {
     reportErrorToSentry(e, SentrySeverityLevel.Info);
     problems.push(convertErrorToProblem(e));
@@ -35,3 +41,13 @@ function convertErrorToProblem(e: unknown) {
     code: ErrorCode.InvalidRainDocument,
   } as Problem;
 }
+
+function convertDiagnosticToProblem(diagnostic: ConfigLintDiagnostic) {
+  return {
+    msg: `${diagnostic.severity === 'Error' ? 'error' : 'warning'}: ${diagnostic.message}${
+      diagnostic.path ? ` at ${diagnostic.path}` : ''
+    }`,
+    position: diagnostic.span ? [diagnostic.span.start, diagnostic.span.end] : [0, 0],
+    code: ErrorCode.InvalidRainDocument,
+  } as Problem;
+}
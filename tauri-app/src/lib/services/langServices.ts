@@ -7,7 +7,7 @@ import {
   Hover,
   CompletionItem,
 } from 'codemirror-rainlang';
-import { rpcUrl } from '$lib/stores/settings';
+import { rpcUrl, settingsText } from '$lib/stores/settings';
 import { get } from 'svelte/store';
 import { forkBlockNumber } from '$lib/stores/forkBlockNumber';
 import { reportErrorToSentry, SentrySeverityLevel } from '$lib/services/sentry';
@@ -27,6 +27,7 @@ export async function problemsCallback(
       blockNumber: get(forkBlockNumber).value,
       bindings,
       deployer: deployerAddress,
+      configText: get(settingsText),
     });
   } catch (err) {
     reportErrorToSentry(err, SentrySeverityLevel.Info);
@@ -8,6 +8,7 @@ import type { Order as OrderListOrder } from '$lib/typeshare/subgraphTypes';
 import ModalTradeDebug from '$lib/components/modal/ModalTradeDebug.svelte';
 import type { Hex } from 'viem';
 import ModalQuoteDebug from '$lib/components/modal/ModalQuoteDebug.svelte';
+import type { Network } from '$lib/typeshare/config';
 
 export const handleDepositGenericModal = () => {
   new ModalVaultDepositGeneric({ target: document.body, props: { open: true } });
@@ -28,13 +29,13 @@ export const handleOrderRemoveModal = (
   new ModalOrderRemove({ target: document.body, props: { order, onOrderRemoved } });
 };
 
-export const handleDebugTradeModal = (txHash: string, rpcUrl: string) => {
-  new ModalTradeDebug({ target: document.body, props: { open: true, txHash, rpcUrl } });
+export const handleDebugTradeModal = (txHash: string, network: Network) => {
+  new ModalTradeDebug({ target: document.body, props: { open: true, txHash, network } });
 };
 
 export const handleQuoteDebugModal = (
   order: OrderDetailOrder,
-  rpcUrl: string,
+  network: Network,
   orderbook: string,
   inputIOIndex: number,
   outputIOIndex: number,
@@ -46,7 +47,7 @@ export const handleQuoteDebugModal = (
     props: {
       open: true,
       order,
-      rpcUrl,
+      network,
       orderbook: orderbook as Hex,
       inputIOIndex,
       outputIOIndex,
@@ -3,6 +3,7 @@ import { invoke } from '@tauri-apps/api';
 import { rpcUrl, orderbookAddress, chainId, subgraphUrl } from '$lib/stores/settings';
 import { ledgerWalletDerivationIndex } from '$lib/stores/wallets';
 import type { Deployment, Scenario } from '$lib/typeshare/config';
+import { configFilePaths } from '$lib/services/config';
 
 export async function orderAdd(dotrain: string, deployment: Deployment) {
   await invoke('order_add', {
@@ -63,6 +64,7 @@ export async function orderAddComposeRainlang(
     dotrain,
     settings,
     scenario,
+    ...configFilePaths(),
   });
 }
 
@@ -73,5 +75,6 @@ export async function validateRaindexVersion(
   return await invoke('validate_raindex_version', {
     dotrain,
     settings,
+    ...configFilePaths(),
   });
 }
@@ -1,17 +1,17 @@
-import { getBlockNumberFromRpc } from '$lib/services/chain';
+import { getBlockNumber } from '$lib/services/chain';
 import { fetchableIntStore } from '$lib/storesGeneric/fetchableStore';
 import { get } from 'svelte/store';
-import { rpcUrl } from './settings';
+import { activeNetwork } from './settings';
 
 export const forkBlockNumber = fetchableIntStore('forkBlockNumber', async () => {
-  const $rpcUrl = get(rpcUrl);
-  if (!$rpcUrl) return 0;
+  const $activeNetwork = get(activeNetwork);
+  if (!$activeNetwork) return 0;
 
-  return getBlockNumberFromRpc($rpcUrl);
+  return getBlockNumber($activeNetwork);
 });
 
 // When active chain updated, reset active orderbook
-rpcUrl.subscribe(async () => {
-  await rpcUrl.load();
+activeNetwork.subscribe(async () => {
+  await activeNetwork.load();
   forkBlockNumber.fetch();
 });
@@ -11,7 +11,7 @@ import {
   type OrderbookRef,
   type OrderbookConfigSource,
 } from '$lib/typeshare/config';
-import { getBlockNumberFromRpc } from '$lib/services/chain';
+import { getBlockNumber } from '$lib/services/chain';
 import { pickBy } from 'lodash';
 
 // general
@@ -61,7 +61,7 @@ export const activeChainHasBlockExplorer = derived(activeChain, ($activeChain) =
   return $activeChain && $activeChain?.blockExplorers?.default !== undefined;
 });
 export const activeChainLatestBlockNumber = derived(activeNetwork, ($activeNetwork) =>
-  $activeNetwork !== undefined ? getBlockNumberFromRpc($activeNetwork.rpc) : 0,
+  $activeNetwork !== undefined ? getBlockNumber($activeNetwork) : 0,
 );
 
 // orderbook
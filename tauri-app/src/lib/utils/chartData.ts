@@ -32,6 +32,16 @@ export const transformDataForPlot = (fuzzResult: FuzzResultFlat): TransformedPlo
   });
 };
 
+// Transform the results of a matrix sweep into a single plot dataset, with the
+// swept parameters of each scenario as columns to plot the metrics against
+export const transformSweepForPlot = (fuzzResults: FuzzResultFlat[]): TransformedPlotData[] =>
+  fuzzResults.flatMap((fuzzResult) => {
+    const parameters = Object.fromEntries(
+      Object.entries(fuzzResult.parameters).map(([name, value]) => [name, Number(value)]),
+    );
+    return transformDataForPlot(fuzzResult).map((row) => ({ ...row, ...parameters }));
+  });
+
 if (import.meta.vitest) {
   const { it, expect } = import.meta.vitest;
 
@@ -81,4 +91,33 @@ if (import.meta.vitest) {
       'Number of column names does not match data length',
     );
   });
+
+  it('sweep results are concatenated with their parameters', () => {
+    const sweep = [
+      {
+        data: {
+          block_number: '0x1234',
+          rows: [['0xDE0B6B3A7640000'], ['0x1BC16D674EC80000']],
+          column_names: ['col1'],
+        },
+        parameters: { spread: '0.01' },
+        scenario: 'test.spread=0%2E01',
+      },
+      {
+        data: {
+          block_number: '0x1234',
+          rows: [['0x29A2241AF62C0000']],
+          column_names: ['col1'],
+        },
+        parameters: { spread: '0.02' },
+        scenario: 'test.spread=0%2E02',
+      },
+    ];
+
+    expect(transformSweepForPlot(sweep)).toEqual([
+      { col1: 1, spread: 0.01 },
+      { col1: 2, spread: 0.01 },
+      { col1: 3, spread: 0.02 },
+    ]);
+  });
 }